@@ -0,0 +1,52 @@
+//! Outlines the meshes of a 2D camera. The 2D views have no prepass, so the edges are detected in the colors.
+
+use bevy::prelude::*;
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, rotate)
+        .run();
+}
+
+#[derive(Component)]
+struct Shape;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.spawn((
+        Camera2d,
+        EdgeDetection {
+            enable_color: true,
+            color_thickness: 2.0,
+            ..default()
+        },
+    ));
+
+    let shapes = [
+        meshes.add(Circle::new(50.0)),
+        meshes.add(Rectangle::new(100.0, 80.0)),
+        meshes.add(RegularPolygon::new(55.0, 6)),
+        meshes.add(Annulus::new(30.0, 55.0)),
+    ];
+    for (i, shape) in shapes.into_iter().enumerate() {
+        commands.spawn((
+            Mesh2d(shape),
+            MeshMaterial2d(materials.add(Color::hsl(i as f32 * 90.0, 0.6, 0.7))),
+            Transform::from_xyz(i as f32 * 160.0 - 240.0, 0.0, 0.0),
+            Shape,
+        ));
+    }
+}
+
+fn rotate(mut query: Query<&mut Transform, With<Shape>>, time: Res<Time>) {
+    for mut transform in &mut query {
+        transform.rotate_z(time.delta_secs() / 2.);
+    }
+}
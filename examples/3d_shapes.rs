@@ -9,10 +9,11 @@ use bevy::{
     prelude::*,
     render::{
         render_asset::RenderAssetUsages,
+        render_graph::RenderLabel,
         render_resource::{Extent3d, TextureDimension, TextureFormat},
     },
 };
-use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin, PosterizeSettings};
 use bevy_egui::{egui, EguiContexts, EguiPlugin};
 use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin};
 
@@ -22,7 +23,8 @@ fn main() {
         .add_plugins(EdgeDetectionPlugin {
             // If you wish to apply Smaa anti-aliasing after edge detection,
             // please ensure that the rendering order of [`EdgeDetectionNode`] is set before [`SmaaNode`].
-            before: Node3d::Smaa,
+            before: vec![Node3d::Smaa.intern()],
+            ..default()
         })
         .add_plugins(EguiPlugin {
             enable_multipass_for_primary_context: false,
@@ -242,6 +244,12 @@ fn edge_detection_ui(mut ctx: EguiContexts, mut edge_detection: Single<&mut Edge
                 egui::Slider::new(&mut edge_detection.color_thickness, 0.0..=8.0)
                     .text("color_thickness"),
             );
+            ui.add(
+                egui::Slider::new(&mut edge_detection.corner_rounding, 0.0..=8.0)
+                    .text("corner_rounding"),
+            );
+
+            ui.add(egui::Checkbox::new(&mut edge_detection.edge_aa, "edge_aa"));
 
             ui.add(
                 egui::Slider::new(&mut edge_detection.steep_angle_threshold, 0.0..=1.0)
@@ -251,6 +259,10 @@ fn edge_detection_ui(mut ctx: EguiContexts, mut edge_detection: Single<&mut Edge
                 egui::Slider::new(&mut edge_detection.steep_angle_multiplier, 0.0..=1.0)
                     .text("steep_angle_multiplier"),
             );
+            ui.add(
+                egui::Slider::new(&mut edge_detection.slope_thickness_boost, 0.0..=4.0)
+                    .text("slope_thickness_boost"),
+            );
 
             ui.horizontal(|ui| {
                 ui.add(
@@ -278,6 +290,41 @@ fn edge_detection_ui(mut ctx: EguiContexts, mut edge_detection: Single<&mut Edge
                 ui.label("uv_distortion_strength");
             });
 
+            ui.add(
+                egui::Slider::new(&mut edge_detection.distortion_octaves, 1..=4)
+                    .text("distortion_octaves"),
+            );
+
+            ui.add(egui::Slider::new(&mut edge_detection.strokes, 1..=3).text("strokes"));
+            ui.add(
+                egui::Slider::new(&mut edge_detection.stroke_scatter, 0.0..=8.0)
+                    .text("stroke_scatter"),
+            );
+
+            ui.add(egui::Checkbox::new(
+                &mut edge_detection.aspect_corrected,
+                "aspect_corrected",
+            ));
+
+            ui.add(egui::Slider::new(&mut edge_detection.boil_fps, 0.0..=24.0).text("boil_fps"));
+
+            let mut posterize = edge_detection.posterize.is_some();
+            ui.add(egui::Checkbox::new(&mut posterize, "posterize"));
+            match (posterize, &mut edge_detection.posterize) {
+                (true, Some(settings)) => {
+                    ui.add(
+                        egui::Slider::new(&mut settings.levels, 2..=16).text("posterize_levels"),
+                    );
+                    ui.add(egui::Checkbox::new(
+                        &mut settings.preserve_hue,
+                        "posterize_preserve_hue",
+                    ));
+                }
+                (true, None) => edge_detection.posterize = Some(PosterizeSettings::default()),
+                (false, Some(_)) => edge_detection.posterize = None,
+                (false, None) => {}
+            }
+
             let mut color = edge_detection.edge_color.to_srgba().to_f32_array_no_alpha();
             ui.horizontal(|ui| {
                 egui::color_picker::color_edit_button_rgb(ui, &mut color);
@@ -0,0 +1,101 @@
+//! A heavy scene with every expensive edge detection setting turned on and a tight GPU budget.
+//! The adaptive quality controller steps the settings down until the edge detection fits, logging its decisions.
+//!
+//! GPU timings are only available on Vulkan and DX12, elsewhere the quality stays on its first step.
+
+use bevy::{prelude::*, render::diagnostic::RenderDiagnosticsPlugin};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionAdaptiveQuality, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(RenderDiagnosticsPlugin)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (rotate, update_text))
+        .run();
+}
+
+#[derive(Component)]
+struct Shape;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Sphere::new(0.4).mesh().ico(3).unwrap());
+    for x in -20..20 {
+        for z in -20..20 {
+            commands.spawn((
+                Shape,
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(materials.add(Color::hsl((x * 20 + z) as f32 * 3.0, 0.6, 0.6))),
+                Transform::from_xyz(x as f32, 0.4, z as f32),
+            ));
+        }
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(50.0, 50.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    let edge_detection = EdgeDetection {
+        enable_color: true,
+        all_samples: true,
+        edge_aa: true,
+        strokes: 3,
+        distortion_octaves: 4,
+        ..default()
+    };
+
+    commands.spawn((
+        Camera3d::default(),
+        Msaa::Sample4,
+        Transform::from_xyz(0.0, 6.0, 14.0).looking_at(Vec3::ZERO, Vec3::Y),
+        EdgeDetectionAdaptiveQuality::from_settings(0.5, &edge_detection),
+        edge_detection,
+    ));
+
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+fn rotate(mut query: Query<&mut Transform, With<Shape>>, time: Res<Time>) {
+    for mut transform in &mut query {
+        transform.rotate_y(time.delta_secs() / 2.);
+    }
+}
+
+fn update_text(
+    camera: Single<(&EdgeDetection, &EdgeDetectionAdaptiveQuality)>,
+    mut text: Single<&mut Text>,
+) {
+    let (edge_detection, quality) = *camera;
+    text.0 = format!(
+        "quality step: {} / {}\nenable_color: {}\nall_samples: {}\nedge_aa: {}\nstrokes: {}\ndistortion_octaves: {}",
+        quality.step(),
+        quality.ladder.len() - 1,
+        edge_detection.enable_color,
+        edge_detection.all_samples,
+        edge_detection.edge_aa,
+        edge_detection.strokes,
+        edge_detection.distortion_octaves,
+    );
+}
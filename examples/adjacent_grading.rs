@@ -0,0 +1,82 @@
+//! Desaturates and lightens the scene in a thin halo beside the edges, like ink bleeding into paper,
+//! on a close-up of saturated objects.
+//!
+//! Press space to toggle the grading.
+
+use bevy::prelude::*;
+use bevy_edge_detection::{AdjacentGrading, EdgeDetection, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, toggle_grading)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (mesh, color, position) in [
+        (
+            meshes.add(Cuboid::default()),
+            Color::srgb(0.9, 0.15, 0.1),
+            Vec3::new(-0.8, 0.5, 0.0),
+        ),
+        (
+            meshes.add(Sphere::new(0.6)),
+            Color::srgb(0.1, 0.35, 0.9),
+            Vec3::new(0.7, 0.6, -0.4),
+        ),
+        (
+            meshes.add(Torus::new(0.2, 0.45)),
+            Color::srgb(0.95, 0.75, 0.1),
+            Vec3::new(0.1, 0.2, 0.9),
+        ),
+    ] {
+        commands.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(materials.add(color)),
+            Transform::from_translation(position),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(10.0, 10.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.2, 0.6, 0.3))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(3.0, 6.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    // A close-up, so the halo of a few pixels is easy to see.
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 1.6, 3.2).looking_at(Vec3::new(0.0, 0.4, 0.0), Vec3::Y),
+        EdgeDetection {
+            edge_adjacent_grading: Some(AdjacentGrading::default()),
+            ..default()
+        },
+    ));
+}
+
+fn toggle_grading(
+    mut edge_detection: Single<&mut EdgeDetection>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard.just_pressed(KeyCode::Space) {
+        edge_detection.edge_adjacent_grading = match edge_detection.edge_adjacent_grading {
+            Some(_) => None,
+            None => Some(AdjacentGrading::default()),
+        };
+        info!(
+            "edge_adjacent_grading: {:?}",
+            edge_detection.edge_adjacent_grading
+        );
+    }
+}
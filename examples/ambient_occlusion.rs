@@ -0,0 +1,103 @@
+//! Weighs the edges by the screen space ambient occlusion, in a Cornell box.
+//! The creases in the occluded corners keep their full strength, while the open edges are lightened.
+
+use bevy::{pbr::ScreenSpaceAmbientOcclusion, prelude::*};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, toggle_influence)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let white = materials.add(Color::srgb(0.8, 0.8, 0.8));
+    let red = materials.add(Color::srgb(0.8, 0.1, 0.1));
+    let green = materials.add(Color::srgb(0.1, 0.8, 0.1));
+
+    let wall = meshes.add(Cuboid::new(4.0, 4.0, 0.1));
+    let walls = [
+        // floor, ceiling and back
+        (white.clone(), Vec3::new(0.0, 0.0, 0.0), Vec3::X),
+        (white.clone(), Vec3::new(0.0, 4.0, 0.0), Vec3::X),
+        (white.clone(), Vec3::new(0.0, 2.0, -2.0), Vec3::ZERO),
+        // left and right
+        (red, Vec3::new(-2.0, 2.0, 0.0), Vec3::Y),
+        (green, Vec3::new(2.0, 2.0, 0.0), Vec3::Y),
+    ];
+    for (material, translation, axis) in walls {
+        let rotation = if axis == Vec3::ZERO {
+            Quat::IDENTITY
+        } else {
+            Quat::from_axis_angle(axis, std::f32::consts::FRAC_PI_2)
+        };
+        commands.spawn((
+            Mesh3d(wall.clone()),
+            MeshMaterial3d(material),
+            Transform::from_translation(translation).with_rotation(rotation),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(1.2, 2.4, 1.2))),
+        MeshMaterial3d(white.clone()),
+        Transform::from_xyz(-0.7, 1.2, -0.6).with_rotation(Quat::from_rotation_y(0.3)),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(1.2, 1.2, 1.2))),
+        MeshMaterial3d(white),
+        Transform::from_xyz(0.7, 0.6, 0.5).with_rotation(Quat::from_rotation_y(-0.3)),
+    ));
+
+    commands.spawn((
+        PointLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 3.5, 0.0),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 2.0, 7.0).looking_at(Vec3::new(0.0, 2.0, 0.0), Vec3::Y),
+        // SSAO doesn't support MSAA.
+        Msaa::Off,
+        ScreenSpaceAmbientOcclusion::default(),
+        EdgeDetection {
+            ao_influence: 1.0,
+            ..default()
+        },
+    ));
+
+    commands.spawn((
+        Text::new("Press space to cycle the ambient occlusion influence"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+/// Cycles the influence between the corners, no influence and the open edges.
+fn toggle_influence(mut query: Query<&mut EdgeDetection>, keys: Res<ButtonInput<KeyCode>>) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    for mut edge_detection in &mut query {
+        edge_detection.ao_influence = match edge_detection.ao_influence {
+            1.0 => 0.0,
+            0.0 => -1.0,
+            _ => 1.0,
+        };
+    }
+}
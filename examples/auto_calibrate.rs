@@ -0,0 +1,163 @@
+//! Walks the camera between a small room and an open field, with the thresholds calibrated automatically
+//! so the edge density stays about the same in both areas.
+//!
+//! Press space to toggle the calibration, the edge coverage is shown in the corner.
+
+use bevy::prelude::*;
+use bevy_edge_detection::{
+    EdgeCoverage, EdgeDetection, EdgeDetectionAutoCalibrate, EdgeDetectionPlugin,
+    EdgeThresholdSuggestion,
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (walk, toggle_calibration, log_suggestions, update_text),
+        )
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let wall = materials.add(Color::srgb(0.8, 0.75, 0.7));
+    let furniture = materials.add(Color::srgb(0.6, 0.4, 0.3));
+    let grass = materials.add(Color::srgb(0.3, 0.5, 0.3));
+
+    // The room, open toward the field.
+    for (size, position) in [
+        (Vec3::new(8.0, 0.2, 8.0), Vec3::new(0.0, 3.0, 0.0)),
+        (Vec3::new(0.2, 3.0, 8.0), Vec3::new(-4.0, 1.5, 0.0)),
+        (Vec3::new(0.2, 3.0, 8.0), Vec3::new(4.0, 1.5, 0.0)),
+        (Vec3::new(8.0, 3.0, 0.2), Vec3::new(0.0, 1.5, -4.0)),
+    ] {
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::from_size(size))),
+            MeshMaterial3d(wall.clone()),
+            Transform::from_translation(position),
+        ));
+    }
+    for (size, position) in [
+        (Vec3::new(1.6, 0.8, 0.9), Vec3::new(-1.5, 0.4, -2.5)),
+        (Vec3::new(0.5, 1.0, 0.5), Vec3::new(1.0, 0.5, -1.0)),
+        (Vec3::new(0.6, 2.0, 1.2), Vec3::new(3.5, 1.0, -3.0)),
+    ] {
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::from_size(size))),
+            MeshMaterial3d(furniture.clone()),
+            Transform::from_translation(position),
+        ));
+    }
+
+    // The field, with a few far away trees.
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(200.0, 200.0))),
+        MeshMaterial3d(grass.clone()),
+    ));
+    let trunk = meshes.add(Cylinder::new(0.3, 4.0));
+    for i in 0..24 {
+        let angle = i as f32 * 2.4;
+        let distance = 20.0 + (i % 5) as f32 * 8.0;
+        commands.spawn((
+            Mesh3d(trunk.clone()),
+            MeshMaterial3d(furniture.clone()),
+            Transform::from_xyz(
+                angle.cos() * distance,
+                2.0,
+                10.0 + angle.sin().abs() * distance,
+            ),
+        ));
+    }
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Msaa::Off,
+        Transform::from_xyz(0.0, 1.6, 2.0),
+        EdgeDetection::default(),
+        EdgeDetectionAutoCalibrate {
+            target_coverage: 4.0,
+            apply: true,
+            ..default()
+        },
+        EdgeCoverage::default(),
+    ));
+
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+/// Walks back and forth between the room and the field, looking into the room then out to the field.
+fn walk(mut camera: Single<&mut Transform, With<Camera3d>>, time: Res<Time>) {
+    let t = 0.5 - 0.5 * (time.elapsed_secs() * 0.3).cos();
+    let position = Vec3::new(0.0, 1.6, -1.0).lerp(Vec3::new(0.0, 1.6, 12.0), t);
+    let target = Vec3::new(0.0, 1.2, -4.0).lerp(Vec3::new(0.0, 1.6, 40.0), t);
+    **camera = Transform::from_translation(position).looking_at(target, Vec3::Y);
+}
+
+fn toggle_calibration(
+    mut commands: Commands,
+    camera: Single<(Entity, Has<EdgeDetectionAutoCalibrate>), With<Camera3d>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+) {
+    if !keyboard.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    let (entity, calibrated) = *camera;
+    if calibrated {
+        commands
+            .entity(entity)
+            .remove::<EdgeDetectionAutoCalibrate>();
+    } else {
+        commands.entity(entity).insert(EdgeDetectionAutoCalibrate {
+            target_coverage: 4.0,
+            apply: true,
+            ..default()
+        });
+    }
+}
+
+fn log_suggestions(mut suggestions: EventReader<EdgeThresholdSuggestion>) {
+    for suggestion in suggestions.read() {
+        info!(
+            "Suggested thresholds: depth {:?}, normal {:?}",
+            suggestion.depth_threshold, suggestion.normal_threshold
+        );
+    }
+}
+
+fn update_text(
+    camera: Single<(
+        &EdgeDetection,
+        &EdgeCoverage,
+        Has<EdgeDetectionAutoCalibrate>,
+    )>,
+    mut text: Single<&mut Text>,
+) {
+    let (edge_detection, coverage, calibrated) = *camera;
+    text.0 = format!(
+        "calibration: {}\ncoverage: {:.1}%\ndepth_threshold: {:.3}\nnormal_threshold: {:.3}",
+        if calibrated { "on" } else { "off" },
+        coverage.percent,
+        edge_detection.depth_threshold,
+        edge_detection.normal_threshold,
+    );
+}
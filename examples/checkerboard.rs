@@ -0,0 +1,105 @@
+//! Renders the edges of half of the pixels each frame, and shows the GPU time of the edge detection.
+//!
+//! Press space to toggle the checkerboard rendering, and enter to teleport the camera, resetting the history.
+//! GPU timings are only available on Vulkan and DX12.
+
+use bevy::{diagnostic::DiagnosticsStore, prelude::*, render::diagnostic::RenderDiagnosticsPlugin};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionAdaptiveQuality, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(RenderDiagnosticsPlugin)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (controls, update_text))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Cuboid::new(0.6, 0.6, 0.6));
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+    for x in -10..10 {
+        for z in -10..10 {
+            commands.spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(x as f32, 0.3, z as f32)
+                    .with_rotation(Quat::from_rotation_y((x * z) as f32 * 0.3)),
+            ));
+        }
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(30.0, 30.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 6.0, 12.0).looking_at(Vec3::ZERO, Vec3::Y),
+        EdgeDetection {
+            checkerboard: true,
+            ..default()
+        },
+    ));
+
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+fn controls(
+    mut camera: Single<(&mut EdgeDetection, &mut Transform)>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut teleported: Local<bool>,
+) {
+    let (edge_detection, transform) = &mut *camera;
+
+    if keyboard.just_pressed(KeyCode::Space) {
+        edge_detection.checkerboard = !edge_detection.checkerboard;
+    }
+
+    if keyboard.just_pressed(KeyCode::Enter) {
+        *teleported = !*teleported;
+        let position = if *teleported {
+            Vec3::new(10.0, 4.0, -8.0)
+        } else {
+            Vec3::new(0.0, 6.0, 12.0)
+        };
+        **transform = Transform::from_translation(position).looking_at(Vec3::ZERO, Vec3::Y);
+        // The edges of the previous point of view would linger on every other pixel for a frame.
+        edge_detection.reset_history = true;
+    }
+}
+
+fn update_text(
+    edge_detection: Single<&EdgeDetection>,
+    diagnostics: Res<DiagnosticsStore>,
+    mut text: Single<&mut Text>,
+) {
+    let gpu_time = diagnostics
+        .get(&EdgeDetectionAdaptiveQuality::GPU_TIME)
+        .and_then(|diagnostic| diagnostic.smoothed());
+
+    text.0 = format!(
+        "checkerboard: {}\nGPU time: {}\nspace: toggle, enter: teleport",
+        edge_detection.checkerboard,
+        gpu_time.map_or("unavailable".to_string(), |ms| format!("{ms:.3}ms")),
+    );
+}
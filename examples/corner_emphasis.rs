@@ -0,0 +1,77 @@
+//! A close-up of the corners of a cube, drawn heavier than the straight edges meeting at them.
+//!
+//! Press space to toggle the corner emphasis.
+
+use bevy::prelude::*;
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+const CORNER_EMPHASIS: f32 = 4.0;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, toggle_emphasis)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::from_length(2.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+        Transform::from_xyz(0.0, 1.0, 0.0),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    // Close to the top corner facing the camera, so the three creases and the silhouette meet in the view.
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(2.6, 3.4, 2.6).looking_at(Vec3::new(1.0, 2.0, 1.0), Vec3::Y),
+        EdgeDetection {
+            normal_thickness: 1.5,
+            corner_emphasis: CORNER_EMPHASIS,
+            ..default()
+        },
+    ));
+
+    commands.spawn((
+        Text::new(format!("corner_emphasis: {CORNER_EMPHASIS}")),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+fn toggle_emphasis(
+    mut edge_detection: Single<&mut EdgeDetection>,
+    mut text: Single<&mut Text>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+) {
+    if !keyboard.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    edge_detection.corner_emphasis = if edge_detection.corner_emphasis > 0.0 {
+        0.0
+    } else {
+        CORNER_EMPHASIS
+    };
+    text.0 = format!("corner_emphasis: {}", edge_detection.corner_emphasis);
+}
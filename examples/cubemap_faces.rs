@@ -0,0 +1,212 @@
+//! Bakes an outlined cubemap with six cameras rendering into the faces of a cube texture, e.g. a toon skybox or
+//! reflection probe, and views it as the skybox of another camera. The edges line up across the seams of the faces.
+
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    core_pipeline::Skybox,
+    prelude::*,
+    render::{
+        camera::{ManualTextureView, ManualTextureViewHandle, ManualTextureViews, RenderTarget},
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
+        render_asset::RenderAssets,
+        render_resource::{
+            Extent3d, TextureDimension, TextureFormat, TextureUsages, TextureView,
+            TextureViewDescriptor, TextureViewDimension,
+        },
+        texture::GpuImage,
+        view::RenderLayers,
+        Render, RenderApp, RenderSet,
+    },
+};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+/// The size of each face, in pixels.
+const FACE_SIZE: u32 = 256;
+
+/// The direction and up vector of the camera of each face, in the order of the layers of a cube texture.
+const FACES: [(Vec3, Vec3); 6] = [
+    (Vec3::X, Vec3::Y),
+    (Vec3::NEG_X, Vec3::Y),
+    (Vec3::Y, Vec3::Z),
+    (Vec3::NEG_Y, Vec3::NEG_Z),
+    (Vec3::NEG_Z, Vec3::Y),
+    (Vec3::Z, Vec3::Y),
+];
+
+/// The layer of the scene baked into the cubemap.
+const PROBE_LAYER: usize = 1;
+
+#[derive(Resource, Clone, ExtractResource)]
+struct Cubemap(Handle<Image>);
+
+/// The views of the faces of the cubemap, created in the render world once its texture is prepared.
+#[derive(Resource, Clone, Default)]
+struct FaceViews(Arc<Mutex<Option<Vec<TextureView>>>>);
+
+fn main() {
+    let face_views = FaceViews::default();
+
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins)
+        .add_plugins((
+            EdgeDetectionPlugin::default(),
+            ExtractResourcePlugin::<Cubemap>::default(),
+        ))
+        .insert_resource(face_views.clone())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (spawn_face_cameras, rotate));
+
+    app.sub_app_mut(RenderApp)
+        .insert_resource(face_views)
+        .add_systems(
+            Render,
+            create_face_views.in_set(RenderSet::PrepareResources),
+        );
+
+    app.run();
+}
+
+#[derive(Component)]
+struct Shape;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+
+    // Shapes all around the probe, some of them straddling the seams of the faces.
+    let shapes = [
+        meshes.add(Cuboid::new(1.0, 1.0, 1.0)),
+        meshes.add(Sphere::new(0.6).mesh().uv(32, 18)),
+        meshes.add(Torus::new(0.4, 0.7)),
+    ];
+    for i in 0..12 {
+        let angle = i as f32 / 12.0 * std::f32::consts::TAU;
+        let height = (i % 3) as f32 * 2.0 - 2.0;
+        commands.spawn((
+            Shape,
+            Mesh3d(shapes[i % shapes.len()].clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(angle.cos() * 4.0, height, angle.sin() * 4.0),
+            RenderLayers::layer(PROBE_LAYER),
+        ));
+    }
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+        RenderLayers::layer(PROBE_LAYER),
+    ));
+
+    let mut cubemap = Image::new_fill(
+        Extent3d {
+            width: FACE_SIZE,
+            height: FACE_SIZE,
+            depth_or_array_layers: 6,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::bevy_default(),
+        RenderAssetUsages::default(),
+    );
+    cubemap.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    cubemap.texture_view_descriptor = Some(TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::Cube),
+        ..default()
+    });
+    let cubemap = images.add(cubemap);
+    commands.insert_resource(Cubemap(cubemap.clone()));
+
+    // Only sees the baked cubemap.
+    commands.spawn((
+        Camera3d::default(),
+        Transform::default().looking_to(Vec3::NEG_Z, Vec3::Y),
+        Skybox {
+            image: cubemap,
+            brightness: 1000.0,
+            ..default()
+        },
+    ));
+}
+
+/// Creates a 2D view of each layer of the cube texture for the cameras of the faces to render into.
+fn create_face_views(
+    cubemap: Option<Res<Cubemap>>,
+    images: Res<RenderAssets<GpuImage>>,
+    face_views: Res<FaceViews>,
+    mut created: Local<bool>,
+) {
+    if *created {
+        return;
+    }
+    let Some(gpu_image) = cubemap.and_then(|cubemap| images.get(&cubemap.0)) else {
+        return;
+    };
+
+    *created = true;
+    *face_views.0.lock().unwrap() = Some(
+        (0..6)
+            .map(|layer| {
+                gpu_image.texture.create_view(&TextureViewDescriptor {
+                    dimension: Some(TextureViewDimension::D2),
+                    base_array_layer: layer,
+                    array_layer_count: Some(1),
+                    ..default()
+                })
+            })
+            .collect(),
+    );
+}
+
+fn spawn_face_cameras(
+    mut commands: Commands,
+    face_views: Res<FaceViews>,
+    mut manual_texture_views: ResMut<ManualTextureViews>,
+) {
+    let Some(views) = face_views.0.lock().unwrap().take() else {
+        return;
+    };
+
+    for (layer, (texture_view, (direction, up))) in views.into_iter().zip(FACES).enumerate() {
+        let handle = ManualTextureViewHandle(layer as u32);
+        manual_texture_views.insert(
+            handle,
+            ManualTextureView {
+                texture_view,
+                size: UVec2::splat(FACE_SIZE),
+                format: TextureFormat::bevy_default(),
+            },
+        );
+
+        commands.spawn((
+            Camera3d::default(),
+            Camera {
+                target: RenderTarget::TextureView(handle),
+                // Before the camera viewing the cubemap.
+                order: -1,
+                ..default()
+            },
+            // A 90° square frustum, so the faces cover the whole sphere of directions.
+            Projection::Perspective(PerspectiveProjection {
+                fov: std::f32::consts::FRAC_PI_2,
+                aspect_ratio: 1.0,
+                ..default()
+            }),
+            Transform::default().looking_to(direction, up),
+            RenderLayers::layer(PROBE_LAYER),
+            EdgeDetection::default(),
+        ));
+    }
+}
+
+fn rotate(mut query: Query<&mut Transform, With<Shape>>, time: Res<Time>) {
+    for mut transform in &mut query {
+        transform.rotate_y(time.delta_secs() / 2.);
+    }
+}
@@ -0,0 +1,171 @@
+//! Outlines the puddles of a scene with a custom edge source, detecting edges in a wetness texture.
+//!
+//! A second camera renders the wetness of each object, as a gray level, into the texture the source samples.
+//! The wetness edges are drawn in blue over the regular edges.
+//! The window isn't resizable so the wetness texture keeps lining up with the screen.
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::RenderLayers,
+    },
+    window::WindowResolution,
+};
+use bevy_edge_detection::{
+    EdgeDetection, EdgeDetectionPlugin, EdgeSource, EdgeSourcePlugin, EdgeSourceSettings,
+};
+
+const WIDTH: u32 = 1280;
+const HEIGHT: u32 = 720;
+
+/// The render layer of the wetness of the objects.
+const WETNESS_LAYER: usize = 1;
+
+const WETNESS_SHADER: &str = "
+fn wetness_gradient(source: texture_2d<f32>, source_sampler: sampler, uv: vec2f, offset: vec2f) -> f32 {
+    let left = textureSample(source, source_sampler, uv - vec2f(offset.x, 0.0)).r;
+    let right = textureSample(source, source_sampler, uv + vec2f(offset.x, 0.0)).r;
+    let down = textureSample(source, source_sampler, uv - vec2f(0.0, offset.y)).r;
+    let up = textureSample(source, source_sampler, uv + vec2f(0.0, offset.y)).r;
+    return max(abs(right - left), abs(up - down));
+}
+";
+
+/// Detects the edges between areas of different wetness.
+#[derive(Component)]
+struct WetnessEdges {
+    image: Handle<Image>,
+    settings: EdgeSourceSettings,
+}
+
+impl EdgeSource for WetnessEdges {
+    const FUNCTION: &'static str = "wetness_gradient";
+    const SHADER: &'static str = WETNESS_SHADER;
+
+    fn image(&self) -> &Handle<Image> {
+        &self.image
+    }
+
+    fn settings(&self) -> EdgeSourceSettings {
+        self.settings
+    }
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                resolution: WindowResolution::new(WIDTH as f32, HEIGHT as f32),
+                resizable: false,
+                ..default()
+            }),
+            ..default()
+        }))
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_plugins(EdgeSourcePlugin::<WetnessEdges>::default())
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let mut wetness = Image::new_fill(
+        Extent3d {
+            width: WIDTH,
+            height: HEIGHT,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0; 4],
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::default(),
+    );
+    wetness.texture_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT;
+    let wetness = images.add(wetness);
+
+    let mut spawn_object = |mesh: Mesh, color: Color, wetness: f32, transform: Transform| {
+        let mesh = meshes.add(mesh);
+        commands
+            .spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(materials.add(color)),
+                transform,
+            ))
+            .with_child((
+                Mesh3d(mesh),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: Color::linear_rgb(wetness, wetness, wetness),
+                    unlit: true,
+                    ..default()
+                })),
+                RenderLayers::layer(WETNESS_LAYER),
+            ));
+    };
+
+    spawn_object(
+        Plane3d::default().mesh().size(20.0, 20.0).into(),
+        Color::srgb(0.6, 0.55, 0.5),
+        0.0,
+        Transform::default(),
+    );
+    for (radius, x, z) in [(1.5, -2.0, 1.0), (0.8, 2.5, -1.0), (1.1, 1.0, 3.0)] {
+        spawn_object(
+            Circle::new(radius).into(),
+            Color::srgb(0.3, 0.35, 0.4),
+            1.0,
+            Transform::from_xyz(x, 0.01, z).with_rotation(Quat::from_rotation_x(-FRAC_PI_2)),
+        );
+    }
+    spawn_object(
+        Cuboid::from_length(1.0).into(),
+        Color::srgb(0.8, 0.7, 0.6),
+        0.0,
+        Transform::from_xyz(0.0, 0.5, -2.0),
+    );
+    spawn_object(
+        Sphere::new(0.6).into(),
+        Color::srgb(0.7, 0.6, 0.5),
+        0.5,
+        Transform::from_xyz(-3.0, 0.6, -1.5),
+    );
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands
+        .spawn((
+            Camera3d::default(),
+            Transform::from_xyz(0.0, 6.0, 9.0).looking_at(Vec3::ZERO, Vec3::Y),
+            EdgeDetection::default(),
+            WetnessEdges {
+                image: wetness.clone(),
+                settings: EdgeSourceSettings {
+                    threshold: 0.2,
+                    thickness: 1.5,
+                    color: Color::srgb(0.1, 0.3, 0.9),
+                },
+            },
+        ))
+        .with_child((
+            // Renders the wetness from the same point of view, before the main camera.
+            Camera3d::default(),
+            Camera {
+                order: -1,
+                target: wetness.into(),
+                clear_color: Color::BLACK.into(),
+                ..default()
+            },
+            Msaa::Off,
+            RenderLayers::layer(WETNESS_LAYER),
+        ));
+}
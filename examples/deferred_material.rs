@@ -0,0 +1,105 @@
+//! Outlines the material changes of a flat wall on the deferred path, where the depth and normals are continuous.
+//!
+//! The wall is covered by decal-like patches of metal, polished tiles and paint, flush with it.
+//! Press space to toggle the material edges.
+
+use bevy::{
+    core_pipeline::prepass::{DeferredPrepass, DepthPrepass},
+    pbr::DefaultOpaqueRendererMethod,
+    prelude::*,
+};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .insert_resource(DefaultOpaqueRendererMethod::deferred())
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, toggle_material_edges)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let wall_color = Color::srgb(0.75, 0.72, 0.68);
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(8.0, 4.0, 0.2))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: wall_color,
+            perceptual_roughness: 0.9,
+            ..default()
+        })),
+        Transform::from_xyz(0.0, 2.0, -0.1),
+    ));
+
+    // The patches are a hair in front of the wall, so their depth steps stay far below the depth threshold.
+    let trim = materials.add(StandardMaterial {
+        base_color: wall_color,
+        metallic: 1.0,
+        perceptual_roughness: 0.3,
+        ..default()
+    });
+    let tiles = materials.add(StandardMaterial {
+        base_color: wall_color,
+        perceptual_roughness: 0.1,
+        ..default()
+    });
+    let paint = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.7, 0.2, 0.15),
+        perceptual_roughness: 0.9,
+        ..default()
+    });
+
+    for (material, size, position) in [
+        (trim.clone(), Vec2::new(8.0, 0.25), Vec2::new(0.0, 1.0)),
+        (trim, Vec2::new(8.0, 0.1), Vec2::new(0.0, 3.2)),
+        (tiles, Vec2::new(2.5, 1.5), Vec2::new(-2.0, 2.2)),
+        (paint, Vec2::new(1.5, 1.5), Vec2::new(2.0, 2.2)),
+    ] {
+        commands.spawn((
+            Mesh3d(meshes.add(Rectangle::from_size(size))),
+            MeshMaterial3d(material),
+            Transform::from_translation(position.extend(0.001)),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.5, 0.5, 0.5))),
+    ));
+
+    commands.spawn((
+        DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(3.0, 6.0, 6.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(1.5, 2.5, 7.0).looking_at(Vec3::new(0.0, 2.0, 0.0), Vec3::Y),
+        // The deferred path doesn't support MSAA.
+        Msaa::Off,
+        DepthPrepass,
+        DeferredPrepass,
+        EdgeDetection {
+            enable_material: true,
+            ..default()
+        },
+    ));
+}
+
+fn toggle_material_edges(
+    mut edge_detection: Single<&mut EdgeDetection>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard.just_pressed(KeyCode::Space) {
+        edge_detection.enable_material = !edge_detection.enable_material;
+        info!("enable_material: {}", edge_detection.enable_material);
+    }
+}
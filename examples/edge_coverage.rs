@@ -0,0 +1,104 @@
+//! Prints how much of the screen is covered by edges, and warns when it exceeds 20%.
+//!
+//! Move the camera closer with the up and down arrow keys to change the coverage.
+
+use bevy::{
+    diagnostic::{DiagnosticsStore, LogDiagnosticsPlugin},
+    prelude::*,
+};
+use bevy_edge_detection::{EdgeCoverage, EdgeDetection, EdgeDetectionPlugin};
+
+const COVERAGE_WARNING_PERCENT: f32 = 20.0;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_plugins(LogDiagnosticsPlugin::filtered(vec![
+            EdgeCoverage::COVERAGE_PERCENT,
+        ]))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (zoom, warn_coverage))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+
+    for x in -3..=3 {
+        for z in -3..=3 {
+            commands.spawn((
+                Mesh3d(meshes.add(Cuboid::from_length(0.5))),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(x as f32, 0.25, z as f32),
+            ));
+        }
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.5, 0.3))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 6.0, 9.0).looking_at(Vec3::ZERO, Vec3::Y),
+        Msaa::Off,
+        EdgeDetection::default(),
+        // Opts in to the edge coverage measurement.
+        EdgeCoverage::default(),
+    ));
+}
+
+fn zoom(
+    mut query: Query<&mut Transform, With<Camera3d>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+) {
+    let mut direction = 0.0;
+    if keyboard.pressed(KeyCode::ArrowUp) {
+        direction -= 1.0;
+    }
+    if keyboard.pressed(KeyCode::ArrowDown) {
+        direction += 1.0;
+    }
+
+    for mut transform in &mut query {
+        let distance =
+            (transform.translation.length() + direction * 5.0 * time.delta_secs()).clamp(2.0, 30.0);
+        transform.translation = transform.translation.normalize() * distance;
+    }
+}
+
+fn warn_coverage(
+    query: Query<&EdgeCoverage, Changed<EdgeCoverage>>,
+    diagnostics: Res<DiagnosticsStore>,
+    mut warned: Local<bool>,
+) {
+    for coverage in &query {
+        let too_many_edges = coverage.percent > COVERAGE_WARNING_PERCENT;
+        if too_many_edges && !*warned {
+            warn!(
+                "Edges cover {:.1}% of the screen, more than {COVERAGE_WARNING_PERCENT}%",
+                coverage.percent
+            );
+        }
+        *warned = too_many_edges;
+    }
+
+    if let Some(average) = diagnostics
+        .get_measurement(&EdgeCoverage::COVERAGE_PERCENT)
+        .map(|measurement| measurement.value)
+    {
+        debug!("Average edge coverage: {average:.1}%");
+    }
+}
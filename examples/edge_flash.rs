@@ -0,0 +1,82 @@
+//! Flashes the edges red for 0.3 seconds when space is pressed, like a hit taken by the player.
+//!
+//! Press enter for a longer, slower flash to white. A flash started mid-flash is blended over the running one.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin, EdgeFlash, EdgeFlashEvent};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, flash_edges)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+    for (i, mesh) in [
+        meshes.add(Cuboid::default()),
+        meshes.add(Sphere::default()),
+        meshes.add(Torus::default()),
+        meshes.add(Cylinder::default()),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        commands.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(i as f32 * 2.0 - 3.0, 0.5, 0.0),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.4, 0.4, 0.45))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 4.0, 8.0).looking_at(Vec3::ZERO, Vec3::Y),
+        EdgeDetection {
+            depth_thickness: 1.5,
+            normal_thickness: 1.5,
+            ..default()
+        },
+    ));
+}
+
+fn flash_edges(
+    camera: Single<Entity, With<EdgeDetection>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut flashes: EventWriter<EdgeFlashEvent>,
+) {
+    let flash = if keyboard.just_pressed(KeyCode::Space) {
+        EdgeFlash::new(Color::srgb(1.0, 0.05, 0.05), Duration::from_millis(300))
+    } else if keyboard.just_pressed(KeyCode::Enter) {
+        EdgeFlash {
+            curve: EaseFunction::SineInOut,
+            ..EdgeFlash::new(Color::WHITE, Duration::from_secs(2))
+        }
+    } else {
+        return;
+    };
+
+    flashes.write(EdgeFlashEvent {
+        camera: *camera,
+        flash,
+    });
+}
@@ -0,0 +1,84 @@
+//! Animates the FOV of the camera from 60° to 20° and back, keeping the relative line weight through the zoom.
+//!
+//! Press space to toggle the FOV compensation, without it the lines stay as thin as at 60° while the objects grow.
+
+use bevy::prelude::*;
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+const WIDE_FOV: f32 = 60.0;
+const NARROW_FOV: f32 = 20.0;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (zoom, toggle_compensation))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+    for (i, mesh) in [
+        meshes.add(Cuboid::default()),
+        meshes.add(Sphere::default()),
+        meshes.add(Torus::default()),
+        meshes.add(Cylinder::default()),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        commands.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(i as f32 * 2.0 - 3.0, 0.5, 0.0),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Projection::Perspective(PerspectiveProjection {
+            fov: WIDE_FOV.to_radians(),
+            ..default()
+        }),
+        Transform::from_xyz(0.0, 4.0, 8.0).looking_at(Vec3::ZERO, Vec3::Y),
+        EdgeDetection {
+            fov_compensation: true,
+            reference_fov: WIDE_FOV.to_radians(),
+            ..default()
+        },
+    ));
+}
+
+fn zoom(mut projection: Single<&mut Projection>, time: Res<Time>) {
+    let Projection::Perspective(perspective) = &mut **projection else {
+        return;
+    };
+
+    let t = 0.5 - 0.5 * (time.elapsed_secs() * 0.8).cos();
+    perspective.fov = WIDE_FOV.lerp(NARROW_FOV, t).to_radians();
+}
+
+fn toggle_compensation(
+    mut edge_detection: Single<&mut EdgeDetection>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard.just_pressed(KeyCode::Space) {
+        edge_detection.fov_compensation = !edge_detection.fov_compensation;
+        info!("fov_compensation: {}", edge_detection.fov_compensation);
+    }
+}
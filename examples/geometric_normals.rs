@@ -0,0 +1,139 @@
+//! Outlines a normal-mapped brick wall with the geometric normals, so only the wall's silhouette and corners
+//! are drawn instead of every groove of the bricks.
+//!
+//! Press space to toggle between the geometric normals and the normals of the normal prepass.
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, toggle_geometric_normals)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let bricks = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.7, 0.35, 0.25),
+        normal_map_texture: Some(images.add(brick_normal_map())),
+        perceptual_roughness: 0.9,
+        ..default()
+    });
+
+    // The normal map needs the tangents of the mesh.
+    let wall = Mesh::from(Cuboid::new(6.0, 3.0, 0.5))
+        .with_generated_tangents()
+        .unwrap();
+    commands.spawn((
+        Mesh3d(meshes.add(wall)),
+        MeshMaterial3d(bricks.clone()),
+        Transform::from_xyz(0.0, 1.5, 0.0),
+    ));
+
+    let pillar = Mesh::from(Cuboid::new(1.0, 4.0, 1.0))
+        .with_generated_tangents()
+        .unwrap();
+    commands.spawn((
+        Mesh3d(meshes.add(pillar)),
+        MeshMaterial3d(bricks),
+        Transform::from_xyz(3.5, 2.0, 0.0),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.5, 0.5, 0.5))),
+    ));
+
+    commands.spawn((
+        DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(-3.0, 6.0, 6.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(-3.0, 2.5, 7.0).looking_at(Vec3::new(0.5, 1.5, 0.0), Vec3::Y),
+        EdgeDetection {
+            geometric_normals_only: true,
+            ..default()
+        },
+    ));
+}
+
+/// A tiling normal map of bricks, beveled into the mortar.
+fn brick_normal_map() -> Image {
+    const SIZE: u32 = 256;
+    const BRICK: UVec2 = UVec2::new(64, 32);
+    const BEVEL: u32 = 4;
+
+    let mut data = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            // Every other row is offset by half a brick.
+            let x = x + (y / BRICK.y % 2) * BRICK.x / 2;
+            let (u, v) = (x % BRICK.x, y % BRICK.y);
+
+            let mut normal = Vec3::Z;
+            if u < BEVEL {
+                normal.x = -1.0;
+            } else if u >= BRICK.x - BEVEL {
+                normal.x = 1.0;
+            }
+            if v < BEVEL {
+                normal.y = 1.0;
+            } else if v >= BRICK.y - BEVEL {
+                normal.y = -1.0;
+            }
+
+            let packed = normal.normalize() * 0.5 + 0.5;
+            data.extend(
+                (packed * 255.0)
+                    .round()
+                    .as_uvec3()
+                    .to_array()
+                    .map(|c| c as u8),
+            );
+            data.push(255);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        // Normal maps are linear.
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+fn toggle_geometric_normals(
+    mut edge_detection: Single<&mut EdgeDetection>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard.just_pressed(KeyCode::Space) {
+        edge_detection.geometric_normals_only = !edge_detection.geometric_normals_only;
+        info!(
+            "geometric_normals_only: {}",
+            edge_detection.geometric_normals_only
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Glitches the edges for one second, like a corrupted signal, when space is pressed.
+//!
+//! Each glitch uses the next seed, so the sequence of glitches is the same on every run.
+
+use bevy::prelude::*;
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin, EdgeGlitch};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, trigger_glitch)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+    for (i, mesh) in [
+        meshes.add(Cuboid::default()),
+        meshes.add(Sphere::default()),
+        meshes.add(Torus::default()),
+        meshes.add(Cylinder::default()),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        commands.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(i as f32 * 2.0 - 3.0, 0.5, 0.0),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 4.0, 8.0).looking_at(Vec3::ZERO, Vec3::Y),
+        EdgeDetection::default(),
+    ));
+}
+
+fn trigger_glitch(
+    mut commands: Commands,
+    camera: Single<Entity, With<EdgeDetection>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut seed: Local<u64>,
+) {
+    if !keyboard.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    *seed += 1;
+    commands.entity(*camera).insert(EdgeGlitch {
+        intensity: 1.0,
+        seed: *seed,
+        decay: 1.0,
+    });
+}
@@ -0,0 +1,116 @@
+//! Detects the edges every other frame and reprojects them on the others, and shows the GPU time of the edge detection.
+//!
+//! Press space to toggle the half rate detection, and enter to teleport the camera, resetting the history.
+//! GPU timings are only available on Vulkan and DX12.
+
+use bevy::{
+    core_pipeline::prepass::MotionVectorPrepass, diagnostic::DiagnosticsStore, prelude::*,
+    render::diagnostic::RenderDiagnosticsPlugin,
+};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionAdaptiveQuality, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(RenderDiagnosticsPlugin)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (orbit, controls, update_text))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Cuboid::new(0.6, 0.6, 0.6));
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+    for x in -10..10 {
+        for z in -10..10 {
+            commands.spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(x as f32, 0.3, z as f32)
+                    .with_rotation(Quat::from_rotation_y((x * z) as f32 * 0.3)),
+            ));
+        }
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(30.0, 30.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 6.0, 12.0).looking_at(Vec3::ZERO, Vec3::Y),
+        // The edges are reprojected along the motion vectors.
+        MotionVectorPrepass,
+        EdgeDetection {
+            half_rate: true,
+            ..default()
+        },
+    ));
+
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+/// Slowly orbits the camera, so the edges are reprojected.
+fn orbit(mut transform: Single<&mut Transform, With<Camera3d>>, time: Res<Time>) {
+    let rotation = Quat::from_rotation_y(time.delta_secs() * 0.2);
+    transform.rotate_around(Vec3::ZERO, rotation);
+}
+
+fn controls(
+    mut camera: Single<(&mut EdgeDetection, &mut Transform)>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut teleported: Local<bool>,
+) {
+    let (edge_detection, transform) = &mut *camera;
+
+    if keyboard.just_pressed(KeyCode::Space) {
+        edge_detection.half_rate = !edge_detection.half_rate;
+    }
+
+    if keyboard.just_pressed(KeyCode::Enter) {
+        *teleported = !*teleported;
+        let position = if *teleported {
+            Vec3::new(10.0, 4.0, -8.0)
+        } else {
+            Vec3::new(0.0, 6.0, 12.0)
+        };
+        **transform = Transform::from_translation(position).looking_at(Vec3::ZERO, Vec3::Y);
+        // The edges of the previous point of view would be reprojected for a frame.
+        edge_detection.reset_history = true;
+    }
+}
+
+fn update_text(
+    edge_detection: Single<&EdgeDetection>,
+    diagnostics: Res<DiagnosticsStore>,
+    mut text: Single<&mut Text>,
+) {
+    let gpu_time = diagnostics
+        .get(&EdgeDetectionAdaptiveQuality::GPU_TIME)
+        .and_then(|diagnostic| diagnostic.smoothed());
+
+    text.0 = format!(
+        "half rate: {}\nGPU time: {}\nspace: toggle, enter: teleport",
+        edge_detection.half_rate,
+        gpu_time.map_or("unavailable".to_string(), |ms| format!("{ms:.3}ms")),
+    );
+}
@@ -0,0 +1,73 @@
+//! Only draws the edges inside a box volume, drawn with gizmos.
+
+use bevy::{math::bounding::Aabb3d, prelude::*};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (move_volume, draw_volume))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+
+    for x in -4..=4 {
+        for z in -4..=4 {
+            commands.spawn((
+                Mesh3d(meshes.add(Sphere::new(0.5).mesh().uv(32, 18))),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(x as f32 * 1.5, 0.5, z as f32 * 1.5),
+            ));
+        }
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 9.0, 12.0).looking_at(Vec3::ZERO, Vec3::Y),
+        Msaa::Off,
+        EdgeDetection {
+            mask_volume: Some(Aabb3d::new(Vec3::ZERO, Vec3::new(2.5, 1.5, 2.5))),
+            mask_volume_margin: 0.5,
+            ..default()
+        },
+    ));
+}
+
+/// Slides the volume back and forth across the spheres.
+fn move_volume(mut query: Query<&mut EdgeDetection>, time: Res<Time>) {
+    for mut edge_detection in &mut query {
+        let center = Vec3::new(time.elapsed_secs().sin() * 4.0, 0.0, 0.0);
+        edge_detection.mask_volume = Some(Aabb3d::new(center, Vec3::new(2.5, 1.5, 2.5)));
+    }
+}
+
+fn draw_volume(query: Query<&EdgeDetection>, mut gizmos: Gizmos) {
+    for edge_detection in &query {
+        if let Some(volume) = edge_detection.mask_volume {
+            let center = Vec3::from(volume.min + volume.max) / 2.0;
+            let size = Vec3::from(volume.max - volume.min);
+            gizmos.cuboid(
+                Transform::from_translation(center).with_scale(size),
+                Color::srgb(1.0, 0.3, 0.3),
+            );
+        }
+    }
+}
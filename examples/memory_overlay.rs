@@ -0,0 +1,177 @@
+//! Draws the outlines of a "memory", a room seen by another camera, over the current view.
+//!
+//! The memory camera renders only its edges into an image, with `OutputMode::EdgesOnly`,
+//! and the main camera composites them over its own output with an `EdgeOverlay`.
+//!
+//! Press space to cycle the blend modes of the overlay.
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::RenderLayers,
+    },
+};
+use bevy_edge_detection::{
+    EdgeDetection, EdgeDetectionPlugin, EdgeOverlay, EdgeOverlayBlend, OutputMode,
+};
+
+/// The render layer of the memory, only seen by the memory camera.
+const MEMORY_LAYER: usize = 1;
+
+/// The size of the image of the memory, smaller than the window, it's stretched over it.
+const MEMORY_SIZE: UVec2 = UVec2::new(640, 360);
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (cycle_blend, drift_memory))
+        .run();
+}
+
+#[derive(Component)]
+struct Memory;
+
+#[derive(Component)]
+struct BlendText;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+
+    // The world, on the default layer.
+    for (i, mesh) in [
+        meshes.add(Cuboid::default()),
+        meshes.add(Sphere::default()),
+        meshes.add(Cylinder::default()),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        commands.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(i as f32 * 2.5 - 2.5, 0.5, 0.0),
+        ));
+    }
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.3, 0.35))),
+    ));
+
+    // The memory, a room full of pillars only the memory camera sees.
+    let pillar = meshes.add(Cuboid::new(0.4, 3.0, 0.4));
+    for x in -2..=2 {
+        for z in -2..=0 {
+            commands.spawn((
+                Mesh3d(pillar.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(x as f32 * 2.0, 1.5, z as f32 * 2.0 - 2.0),
+                RenderLayers::layer(MEMORY_LAYER),
+                Memory,
+            ));
+        }
+    }
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(12.0, 12.0))),
+        MeshMaterial3d(material),
+        RenderLayers::layer(MEMORY_LAYER),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+        RenderLayers::from_layers(&[0, MEMORY_LAYER]),
+    ));
+
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: MEMORY_SIZE.x,
+            height: MEMORY_SIZE.y,
+            ..default()
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    let image = images.add(image);
+
+    // The memory camera renders first, only its edges, transparent everywhere else.
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            order: -1,
+            target: RenderTarget::Image(image.clone().into()),
+            clear_color: ClearColorConfig::Custom(Color::NONE),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 2.0, 6.0).looking_at(Vec3::new(0.0, 1.0, 0.0), Vec3::Y),
+        EdgeDetection {
+            output_mode: OutputMode::EdgesOnly,
+            edge_color: Color::srgb(0.2, 0.9, 1.0),
+            ..default()
+        },
+        RenderLayers::layer(MEMORY_LAYER),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 3.0, 8.0).looking_at(Vec3::ZERO, Vec3::Y),
+        EdgeDetection::default(),
+        EdgeOverlay {
+            blend: EdgeOverlayBlend::Screen,
+            opacity: 0.8,
+            ..EdgeOverlay::new(image)
+        },
+    ));
+
+    commands.spawn((
+        Text::new(format!("blend: {:?}", EdgeOverlayBlend::Screen)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        BlendText,
+    ));
+}
+
+fn cycle_blend(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut overlays: Query<&mut EdgeOverlay>,
+    mut text: Single<&mut Text, With<BlendText>>,
+) {
+    if !keyboard.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    for mut overlay in &mut overlays {
+        overlay.blend = match overlay.blend {
+            EdgeOverlayBlend::Alpha => EdgeOverlayBlend::Add,
+            EdgeOverlayBlend::Add => EdgeOverlayBlend::Multiply,
+            EdgeOverlayBlend::Multiply => EdgeOverlayBlend::Screen,
+            EdgeOverlayBlend::Screen => EdgeOverlayBlend::Alpha,
+        };
+        text.0 = format!("blend: {:?}", overlay.blend);
+    }
+}
+
+/// Sways the pillars of the memory, so it doesn't look like part of the world.
+fn drift_memory(mut query: Query<&mut Transform, With<Memory>>, time: Res<Time>) {
+    let sway = (time.elapsed_secs() * 0.7).sin() * 0.1;
+    for mut transform in &mut query {
+        transform.rotation = Quat::from_rotation_z(sway);
+    }
+}
@@ -0,0 +1,58 @@
+//! Smears the lines along the motion of a rapidly strafing camera, like a dash effect.
+
+use bevy::{core_pipeline::prepass::MotionVectorPrepass, prelude::*};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, strafe)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+
+    for x in -5..=5 {
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::new(0.6, 1.0 + (x as f32).abs() * 0.3, 0.6))),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(x as f32 * 1.5, 0.5, 0.0),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(30.0, 10.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 3.0, 8.0).looking_to(Vec3::new(0.0, -0.3, -1.0), Vec3::Y),
+        Msaa::Off,
+        // The motion vector prepass is required for the motion smear.
+        MotionVectorPrepass,
+        EdgeDetection {
+            motion_smear_strength: 4.0,
+            ..default()
+        },
+    ));
+}
+
+/// Dashes the camera left and right.
+fn strafe(mut query: Query<&mut Transform, With<Camera3d>>, time: Res<Time>) {
+    for mut transform in &mut query {
+        transform.translation.x = (time.elapsed_secs() * 2.0).sin().powi(3) * 6.0;
+    }
+}
@@ -0,0 +1,72 @@
+//! Sweeps the uv distortion frequency from 1 to 100.
+//! The noise is sampled from its mip chain, so high frequencies fade into a fine wobble instead of sparkling.
+
+use bevy::prelude::*;
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, sweep_frequency)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+
+    for x in -2..=2 {
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::new(1.0, 1.0, 1.0))),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(x as f32 * 2.0, 0.5, 0.0).with_rotation(Quat::from_rotation_y(0.6)),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 4.0, 8.0).looking_at(Vec3::ZERO, Vec3::Y),
+        EdgeDetection {
+            uv_distortion_strength: Vec2::splat(0.004),
+            ..default()
+        },
+    ));
+
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+/// Sweeps the frequency back and forth on a log scale.
+fn sweep_frequency(
+    mut edge_detection: Single<&mut EdgeDetection>,
+    mut text: Single<&mut Text>,
+    time: Res<Time>,
+) {
+    let t = 0.5 - 0.5 * (time.elapsed_secs() * 0.3).cos();
+    let frequency = 100.0_f32.powf(t);
+
+    edge_detection.uv_distortion_frequency = Vec2::splat(frequency);
+    text.0 = format!("uv_distortion_frequency: {frequency:.1}");
+}
@@ -0,0 +1,65 @@
+//! Tints the outline of each object by a darkened version of its own color.
+
+use bevy::prelude::*;
+use bevy_edge_detection::{EdgeColorMode, EdgeDetection, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let colors = [
+        Color::srgb(0.9, 0.3, 0.3),
+        Color::srgb(0.3, 0.8, 0.4),
+        Color::srgb(0.3, 0.5, 0.9),
+        Color::srgb(0.9, 0.8, 0.3),
+        Color::srgb(0.7, 0.4, 0.9),
+    ];
+
+    let shapes = [
+        meshes.add(Cuboid::default()),
+        meshes.add(Sphere::default().mesh().uv(32, 18)),
+        meshes.add(Torus::default()),
+        meshes.add(Cylinder::default()),
+        meshes.add(Cone::default()),
+    ];
+
+    for (i, (shape, color)) in shapes.into_iter().zip(colors).enumerate() {
+        commands.spawn((
+            Mesh3d(shape),
+            MeshMaterial3d(materials.add(color)),
+            Transform::from_xyz(i as f32 * 2.0 - 4.0, 1.0, 0.0),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.9, 0.9, 0.85))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 5.0, 9.0).looking_at(Vec3::new(0.0, 1.0, 0.0), Vec3::Y),
+        Msaa::Off,
+        EdgeDetection {
+            edge_color_mode: EdgeColorMode::FromObject {
+                darken: 0.6,
+                saturate: 0.5,
+            },
+            ..default()
+        },
+    ));
+}
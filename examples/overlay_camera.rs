@@ -0,0 +1,91 @@
+//! Stacks an overlay camera drawing first-person "arms" over the world camera.
+//! Each camera has its own `EdgeDetection` and only outlines its own geometry.
+
+use bevy::{prelude::*, render::view::RenderLayers};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+/// The render layer of the arms, only seen by the overlay camera.
+const ARMS_LAYER: usize = 1;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, look_around)
+        .run();
+}
+
+#[derive(Component)]
+struct Player;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+
+    for x in -3..=3 {
+        for z in -3..=3 {
+            commands.spawn((
+                Mesh3d(meshes.add(Cuboid::new(1.0, 1.0, 1.0))),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(x as f32 * 3.0, 0.5, z as f32 * 3.0),
+            ));
+        }
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(30.0, 30.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+        RenderLayers::from_layers(&[0, ARMS_LAYER]),
+    ));
+
+    let arms_material = materials.add(Color::srgb(0.9, 0.6, 0.5));
+    commands
+        .spawn((
+            Player,
+            Transform::from_xyz(0.0, 1.6, 8.0),
+            Visibility::default(),
+        ))
+        .with_children(|player| {
+            player.spawn((Camera3d::default(), Msaa::Off, EdgeDetection::default()));
+
+            player.spawn((
+                Camera3d::default(),
+                Camera {
+                    order: 1,
+                    clear_color: ClearColorConfig::None,
+                    ..default()
+                },
+                Msaa::Off,
+                EdgeDetection {
+                    edge_color: Color::srgb(0.6, 0.1, 0.1),
+                    ..default()
+                },
+                RenderLayers::layer(ARMS_LAYER),
+            ));
+
+            for x in [-0.35, 0.35] {
+                player.spawn((
+                    Mesh3d(meshes.add(Capsule3d::new(0.08, 0.6))),
+                    MeshMaterial3d(arms_material.clone()),
+                    Transform::from_xyz(x, -0.35, -0.6).with_rotation(Quat::from_rotation_x(-1.2)),
+                    RenderLayers::layer(ARMS_LAYER),
+                ));
+            }
+        });
+}
+
+/// Turns the player around, so the world moves behind the arms.
+fn look_around(mut query: Query<&mut Transform, With<Player>>, time: Res<Time>) {
+    for mut transform in &mut query {
+        transform.rotation = Quat::from_rotation_y((time.elapsed_secs() * 0.3).sin() * 0.8);
+    }
+}
@@ -0,0 +1,86 @@
+//! Draws the edges in over three seconds, like linework inking itself for an intro.
+//! Press space to replay the reveal, and tab to switch between the noise and radial orders.
+
+use bevy::prelude::*;
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin, RevealMode, RevealSettings};
+
+/// The duration of the reveal, in seconds.
+const REVEAL_DURATION: f32 = 3.0;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (controls, animate_reveal))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+
+    for x in -4..=4 {
+        for z in -4..=4 {
+            commands.spawn((
+                Mesh3d(meshes.add(Cuboid::from_length(0.8))),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(x as f32 * 2.0, 0.4, z as f32 * 2.0),
+            ));
+        }
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(40.0, 40.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 10.0, 16.0).looking_at(Vec3::ZERO, Vec3::Y),
+        EdgeDetection {
+            reveal: Some(RevealSettings {
+                progress: 0.0,
+                ..default()
+            }),
+            ..default()
+        },
+    ));
+}
+
+fn controls(mut edge_detection: Single<&mut EdgeDetection>, keyboard: Res<ButtonInput<KeyCode>>) {
+    let Some(reveal) = &mut edge_detection.reveal else {
+        return;
+    };
+
+    if keyboard.just_pressed(KeyCode::Space) {
+        reveal.progress = 0.0;
+    }
+
+    if keyboard.just_pressed(KeyCode::Tab) {
+        reveal.mode = match reveal.mode {
+            RevealMode::Noise => RevealMode::Radial {
+                origin: Vec2::new(0.5, 0.5),
+            },
+            RevealMode::Radial { .. } => RevealMode::Noise,
+        };
+        reveal.progress = 0.0;
+    }
+}
+
+fn animate_reveal(mut edge_detection: Single<&mut EdgeDetection>, time: Res<Time>) {
+    let Some(reveal) = &mut edge_detection.reveal else {
+        return;
+    };
+
+    // Once complete, the edges are the same as without the reveal.
+    reveal.progress = (reveal.progress + time.delta_secs() / REVEAL_DURATION).min(1.0);
+}
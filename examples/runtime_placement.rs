@@ -0,0 +1,102 @@
+//! Moves the edge detection before or after the chromatic aberration of the post-processing at runtime.
+//!
+//! The edge detection node is added at both placements up front, and the camera picks the one processing it.
+//! Press space to switch: after the post-processing the edges are crisp, before it they're split into fringes
+//! like the rest of the scene.
+
+use bevy::{
+    core_pipeline::{core_3d::graph::Node3d, post_process::ChromaticAberration},
+    prelude::*,
+    render::render_graph::RenderLabel,
+};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlacement, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin {
+            additional_placements: vec![before_post_processing()],
+            ..default()
+        })
+        .add_systems(Startup, setup)
+        .add_systems(Update, switch_placement)
+        .run();
+}
+
+/// After the main passes, before the chromatic aberration.
+fn before_post_processing() -> EdgeDetectionPlacement {
+    EdgeDetectionPlacement::Between {
+        after: Node3d::EndMainPass.intern(),
+        before: Node3d::PostProcessing.intern(),
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+    for (i, mesh) in [
+        meshes.add(Cuboid::default()),
+        meshes.add(Sphere::default()),
+        meshes.add(Torus::default()),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        commands.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(i as f32 * 2.0 - 2.0, 0.5, 0.0),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 3.0, 6.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ChromaticAberration {
+            intensity: 0.05,
+            ..default()
+        },
+        EdgeDetection::default(),
+    ));
+
+    commands.spawn((
+        Text::new("placement: after the post-processing"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+fn switch_placement(
+    mut edge_detection: Single<&mut EdgeDetection>,
+    mut text: Single<&mut Text>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+) {
+    if !keyboard.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    // `None` is the default placement of the plugin, after the post-processing.
+    let (placement, description) = match edge_detection.placement {
+        None => (Some(before_post_processing()), "before"),
+        Some(_) => (None, "after"),
+    };
+    edge_detection.placement = placement;
+    text.0 = format!("placement: {description} the post-processing");
+}
@@ -0,0 +1,81 @@
+//! Reveals the edges inside an expanding sphere, like a detective scan. Press space to trigger a pulse.
+
+use bevy::prelude::*;
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin, ScanSettings};
+
+/// The speed at which the scan sphere expands, in world units per second.
+const SCAN_SPEED: f32 = 8.0;
+/// The radius at which the scan stops expanding.
+const SCAN_MAX_RADIUS: f32 = 30.0;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (trigger_scan, expand_scan))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+
+    for x in -4..=4 {
+        for z in -4..=4 {
+            commands.spawn((
+                Mesh3d(meshes.add(Cuboid::from_length(0.8))),
+                MeshMaterial3d(material.clone()),
+                Transform::from_xyz(x as f32 * 2.0, 0.4, z as f32 * 2.0),
+            ));
+        }
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(40.0, 40.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 10.0, 16.0).looking_at(Vec3::ZERO, Vec3::Y),
+        Msaa::Off,
+        EdgeDetection {
+            edge_color: Color::srgb(0.2, 0.9, 1.0),
+            scan: Some(ScanSettings {
+                center: Vec3::ZERO,
+                radius: 0.0,
+                edge_falloff: 2.0,
+            }),
+            ..default()
+        },
+    ));
+}
+
+fn trigger_scan(mut query: Query<&mut EdgeDetection>, keyboard: Res<ButtonInput<KeyCode>>) {
+    if !keyboard.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    for mut edge_detection in &mut query {
+        if let Some(scan) = &mut edge_detection.scan {
+            scan.radius = 0.0;
+        }
+    }
+}
+
+fn expand_scan(mut query: Query<&mut EdgeDetection>, time: Res<Time>) {
+    for mut edge_detection in &mut query {
+        if let Some(scan) = &mut edge_detection.scan {
+            scan.radius = (scan.radius + SCAN_SPEED * time.delta_secs()).min(SCAN_MAX_RADIUS);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Outlines the shadows cast by a sun on the ground, with a screen-space shadow mask.
+//!
+//! A second camera renders the visibility of the sun at each pixel, from the same point of view,
+//! into the mask the edge detection samples. The shadow edges are drawn in dark blue over the regular edges.
+//! The window isn't resizable so the mask keeps lining up with the screen.
+//!
+//! Press space to toggle the shadow edges.
+
+use bevy::{
+    asset::weak_handle,
+    core_pipeline::tonemapping::{DebandDither, Tonemapping},
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{
+            AsBindGroup, Extent3d, ShaderRef, TextureDimension, TextureFormat, TextureUsages,
+        },
+        view::RenderLayers,
+    },
+    window::WindowResolution,
+};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+const WIDTH: u32 = 1280;
+const HEIGHT: u32 = 720;
+
+/// The render layer of the shadow mask.
+const SHADOW_MASK_LAYER: usize = 1;
+
+const SHADOW_MASK_SHADER_HANDLE: Handle<Shader> =
+    weak_handle!("5b0e4c1d-8f3a-4e62-9d7b-2a61c4f0e913");
+
+const SHADOW_MASK_SHADER: &str = "
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    mesh_view_bindings::{lights, view},
+    shadows::fetch_directional_shadow,
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let normal = normalize(in.world_normal);
+    let view_z = dot(vec4<f32>(
+        view.view_from_world[0].z,
+        view.view_from_world[1].z,
+        view.view_from_world[2].z,
+        view.view_from_world[3].z,
+    ), in.world_position);
+
+    // Faces turned away from the sun are in their own shadow.
+    let facing = step(0.0, dot(normal, lights.directional_lights[0].direction_to_light));
+    let visibility = fetch_directional_shadow(0u, in.world_position, normal, view_z) * facing;
+    return vec4<f32>(visibility, visibility, visibility, 1.0);
+}
+";
+
+/// Writes the visibility of the first directional light, 0.0 in shadow and 1.0 lit.
+#[derive(Asset, AsBindGroup, TypePath, Clone, Default)]
+struct ShadowMaskMaterial {}
+
+impl Material for ShadowMaskMaterial {
+    fn fragment_shader() -> ShaderRef {
+        SHADOW_MASK_SHADER_HANDLE.into()
+    }
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                resolution: WindowResolution::new(WIDTH as f32, HEIGHT as f32),
+                resizable: false,
+                ..default()
+            }),
+            ..default()
+        }))
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_plugins(MaterialPlugin::<ShadowMaskMaterial>::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, toggle_shadow_edges)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut shadow_mask_materials: ResMut<Assets<ShadowMaskMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    mut shaders: ResMut<Assets<Shader>>,
+) {
+    shaders.insert(
+        &SHADOW_MASK_SHADER_HANDLE,
+        Shader::from_wgsl(SHADOW_MASK_SHADER, file!()),
+    );
+
+    let mut shadow_mask = Image::new_fill(
+        Extent3d {
+            width: WIDTH,
+            height: HEIGHT,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[255; 4],
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::default(),
+    );
+    shadow_mask.texture_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT;
+    let shadow_mask = images.add(shadow_mask);
+
+    let shadow_mask_material = shadow_mask_materials.add(ShadowMaskMaterial::default());
+    let mut spawn_object = |mesh: Mesh, color: Color, transform: Transform| {
+        let mesh = meshes.add(mesh);
+        commands
+            .spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(materials.add(color)),
+                transform,
+            ))
+            .with_child((
+                Mesh3d(mesh),
+                MeshMaterial3d(shadow_mask_material.clone()),
+                RenderLayers::layer(SHADOW_MASK_LAYER),
+            ));
+    };
+
+    spawn_object(
+        Plane3d::default().mesh().size(20.0, 20.0).into(),
+        Color::srgb(0.75, 0.72, 0.65),
+        Transform::default(),
+    );
+    spawn_object(
+        Cuboid::new(1.0, 2.0, 1.0).into(),
+        Color::srgb(0.8, 0.5, 0.4),
+        Transform::from_xyz(-2.0, 1.0, 0.0),
+    );
+    spawn_object(
+        Sphere::new(0.7).into(),
+        Color::srgb(0.4, 0.6, 0.8),
+        Transform::from_xyz(1.0, 0.7, -1.0),
+    );
+    spawn_object(
+        Torus::new(0.3, 0.6).into(),
+        Color::srgb(0.9, 0.8, 0.3),
+        Transform::from_xyz(2.0, 1.2, 1.5).with_rotation(Quat::from_rotation_x(1.0)),
+    );
+
+    // The sun lights both layers, so the shadow maps of the mask camera hold the same casters.
+    commands.spawn((
+        DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(-4.0, 6.0, 2.0).looking_at(Vec3::ZERO, Vec3::Y),
+        RenderLayers::from_layers(&[0, SHADOW_MASK_LAYER]),
+    ));
+
+    commands
+        .spawn((
+            Camera3d::default(),
+            Transform::from_xyz(0.0, 6.0, 9.0).looking_at(Vec3::ZERO, Vec3::Y),
+            EdgeDetection {
+                shadow_mask: Some(shadow_mask.clone()),
+                shadow_edge_color: Color::srgb(0.05, 0.1, 0.35),
+                shadow_thickness: 1.5,
+                ..default()
+            },
+        ))
+        .with_child((
+            // Renders the shadow mask from the same point of view, before the main camera.
+            Camera3d::default(),
+            Camera {
+                order: -1,
+                target: shadow_mask.into(),
+                clear_color: Color::WHITE.into(),
+                ..default()
+            },
+            // The visibility is written to the mask as is.
+            Tonemapping::None,
+            DebandDither::Disabled,
+            Msaa::Off,
+            RenderLayers::layer(SHADOW_MASK_LAYER),
+        ));
+}
+
+fn toggle_shadow_edges(
+    mut edge_detection: Single<&mut EdgeDetection>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut shadow_mask: Local<Option<Handle<Image>>>,
+) {
+    if keyboard.just_pressed(KeyCode::Space) {
+        // Keeps the mask aside while the shadow edges are off.
+        std::mem::swap(&mut edge_detection.shadow_mask, &mut *shadow_mask);
+        info!("shadow edges: {}", edge_detection.shadow_mask.is_some());
+    }
+}
@@ -0,0 +1,115 @@
+//! Uses the detected edges to sharpen the scene instead of drawing them.
+//!
+//! Press space to toggle the sharpening, and enter to save a screenshot of the current mode,
+//! so a pair of screenshots can be compared.
+
+use bevy::{
+    prelude::*,
+    render::view::screenshot::{save_to_disk, Screenshot},
+};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin, OutputMode};
+
+const SHARPEN: OutputMode = OutputMode::Sharpen { amount: 1.5 };
+// A zero amount leaves the scene as it is, for the comparison.
+const NO_SHARPEN: OutputMode = OutputMode::Sharpen { amount: 0.0 };
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (toggle_sharpening, save_screenshot))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let shapes = [
+        meshes.add(Cuboid::new(1.0, 1.0, 1.0)),
+        meshes.add(Sphere::new(0.6).mesh().uv(32, 18)),
+        meshes.add(Torus::new(0.4, 0.7)),
+        meshes.add(Cylinder::new(0.5, 1.2)),
+    ];
+
+    for (i, shape) in shapes.into_iter().enumerate() {
+        commands.spawn((
+            Mesh3d(shape),
+            MeshMaterial3d(materials.add(Color::hsl(i as f32 * 80.0, 0.5, 0.6))),
+            Transform::from_xyz(i as f32 * 2.0 - 3.0, 0.7, 0.0)
+                .with_rotation(Quat::from_rotation_x(0.4) * Quat::from_rotation_y(0.6)),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 4.0, 8.0).looking_at(Vec3::ZERO, Vec3::Y),
+        EdgeDetection {
+            output_mode: SHARPEN,
+            // Only the geometric edges are sharpened, not the shading.
+            enable_color: false,
+            ..default()
+        },
+    ));
+
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+fn toggle_sharpening(
+    mut edge_detection: Single<&mut EdgeDetection>,
+    mut text: Single<&mut Text>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard.just_pressed(KeyCode::Space) {
+        edge_detection.output_mode = if edge_detection.output_mode == SHARPEN {
+            NO_SHARPEN
+        } else {
+            SHARPEN
+        };
+    }
+
+    text.0 = format!(
+        "output_mode: {:?}\nspace: toggle, enter: screenshot",
+        edge_detection.output_mode
+    );
+}
+
+fn save_screenshot(
+    mut commands: Commands,
+    edge_detection: Single<&EdgeDetection>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard.just_pressed(KeyCode::Enter) {
+        let path = if edge_detection.output_mode == SHARPEN {
+            "./sharpen_on.png"
+        } else {
+            "./sharpen_off.png"
+        };
+        commands
+            .spawn(Screenshot::primary_window())
+            .observe(save_to_disk(path));
+    }
+}
@@ -3,6 +3,7 @@
 use bevy::{
     core_pipeline::{core_3d::graph::Node3d, smaa::Smaa},
     prelude::*,
+    render::render_graph::RenderLabel,
 };
 use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
 use bevy_egui::{egui, EguiContexts, EguiPlugin};
@@ -14,7 +15,8 @@ fn main() {
         .add_plugins(EdgeDetectionPlugin {
             // If you wish to apply Smaa anti-aliasing after edge detection,
             // please ensure that the rendering order of [`EdgeDetectionNode`] is set before [`SmaaNode`].
-            before: Node3d::Smaa,
+            before: vec![Node3d::Smaa.intern()],
+            ..default()
         })
         .add_plugins(EguiPlugin {
             enable_multipass_for_primary_context: false,
@@ -91,6 +93,10 @@ fn edge_detection_ui(mut ctx: EguiContexts, mut edge_detection: Single<&mut Edge
                     egui::Slider::new(&mut edge_detection.normal_threshold, 0.0..=8.0)
                         .text("normal_threshold"),
                 );
+                ui.add(egui::Checkbox::new(
+                    &mut edge_detection.geometric_normals_only,
+                    "geometric_normals_only",
+                ));
             });
 
             ui.horizontal(|ui| {
@@ -116,6 +122,12 @@ fn edge_detection_ui(mut ctx: EguiContexts, mut edge_detection: Single<&mut Edge
                 egui::Slider::new(&mut edge_detection.color_thickness, 0.0..=8.0)
                     .text("color_thickness"),
             );
+            ui.add(
+                egui::Slider::new(&mut edge_detection.corner_rounding, 0.0..=8.0)
+                    .text("corner_rounding"),
+            );
+
+            ui.add(egui::Checkbox::new(&mut edge_detection.edge_aa, "edge_aa"));
 
             ui.add(
                 egui::Slider::new(&mut edge_detection.steep_angle_threshold, 0.0..=1.0)
@@ -125,6 +137,10 @@ fn edge_detection_ui(mut ctx: EguiContexts, mut edge_detection: Single<&mut Edge
                 egui::Slider::new(&mut edge_detection.steep_angle_multiplier, 0.0..=1.0)
                     .text("steep_angle_multiplier"),
             );
+            ui.add(
+                egui::Slider::new(&mut edge_detection.slope_thickness_boost, 0.0..=4.0)
+                    .text("slope_thickness_boost"),
+            );
 
             ui.horizontal(|ui| {
                 ui.add(
@@ -152,6 +168,18 @@ fn edge_detection_ui(mut ctx: EguiContexts, mut edge_detection: Single<&mut Edge
                 ui.label("uv_distortion_strength");
             });
 
+            ui.add(
+                egui::Slider::new(&mut edge_detection.distortion_octaves, 1..=4)
+                    .text("distortion_octaves"),
+            );
+
+            ui.add(egui::Checkbox::new(
+                &mut edge_detection.aspect_corrected,
+                "aspect_corrected",
+            ));
+
+            ui.add(egui::Slider::new(&mut edge_detection.boil_fps, 0.0..=24.0).text("boil_fps"));
+
             let mut color = edge_detection.edge_color.to_srgba().to_f32_array_no_alpha();
             ui.horizontal(|ui| {
                 egui::color_picker::color_edit_button_rgb(ui, &mut color);
@@ -0,0 +1,66 @@
+//! Streaks the edges of a fast-orbiting object along its motion, like anime speed lines.
+
+use bevy::{core_pipeline::prepass::MotionVectorPrepass, prelude::*};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, orbit)
+        .run();
+}
+
+/// A marker component for the orbiting object.
+#[derive(Component)]
+struct Orbiting;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::default())),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.3, 0.3))),
+        Transform::from_xyz(4.0, 1.0, 0.0),
+        Orbiting,
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(1.0).mesh().uv(32, 18))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.5, 0.8))),
+        Transform::from_xyz(0.0, 1.0, 0.0),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 8.0, 12.0).looking_at(Vec3::ZERO, Vec3::Y),
+        Msaa::Off,
+        // The motion vector prepass is required for the motion emphasis.
+        MotionVectorPrepass,
+        EdgeDetection {
+            motion_emphasis: 2.0,
+            ..default()
+        },
+    ));
+}
+
+fn orbit(mut query: Query<&mut Transform, With<Orbiting>>, time: Res<Time>) {
+    for mut transform in &mut query {
+        let angle = time.elapsed_secs() * 4.0;
+        transform.translation = Vec3::new(angle.cos() * 4.0, 1.0, angle.sin() * 4.0);
+        transform.rotation = Quat::from_rotation_y(-angle);
+    }
+}
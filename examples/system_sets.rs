@@ -0,0 +1,113 @@
+//! Orders your own systems against the edge detection ones with [`EdgeDetectionSet`].
+//!
+//! The edge thickness is animated in the main world after the crate's own changes to the settings,
+//! and the line boil of the camera is paused in the render world once its uniform is prepared.
+//! Press space to pause or resume the line boil.
+
+use bevy::{
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        Render, RenderApp, RenderSet,
+    },
+};
+use bevy_edge_detection::{
+    EdgeDetection, EdgeDetectionPlugin, EdgeDetectionSet, EdgeDetectionUniform,
+};
+
+/// Pauses the line boil of a camera.
+#[derive(Component, ExtractComponent, Clone, Copy)]
+struct PausedBoil {
+    /// The time the line boil was paused at.
+    time: f32,
+}
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_plugins(ExtractComponentPlugin::<PausedBoil>::default())
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                toggle_boil,
+                // Runs after the crate's changes, e.g. the adaptive quality, so it takes precedence.
+                animate_thickness.after(EdgeDetectionSet::Configure),
+            ),
+        );
+
+    app.sub_app_mut(RenderApp).add_systems(
+        Render,
+        // The uniform is extracted and prepared by the crate, then uploaded in `RenderSet::PrepareResources`.
+        pause_boil
+            .in_set(RenderSet::Prepare)
+            .after(EdgeDetectionSet::Prepare),
+    );
+
+    app.run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Torus::default())),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+        Transform::from_xyz(0.0, 0.5, 0.0),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(10.0, 10.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 3.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+        EdgeDetection {
+            boil_fps: 8.0,
+            ..default()
+        },
+    ));
+}
+
+fn toggle_boil(
+    mut commands: Commands,
+    camera: Single<(Entity, Has<PausedBoil>), With<EdgeDetection>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+) {
+    if !keyboard.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    let (entity, paused) = *camera;
+    if paused {
+        commands.entity(entity).remove::<PausedBoil>();
+    } else {
+        commands.entity(entity).insert(PausedBoil {
+            time: time.elapsed_secs_wrapped(),
+        });
+    }
+}
+
+fn animate_thickness(mut edge_detection: Single<&mut EdgeDetection>, time: Res<Time>) {
+    let thickness = 1.0 + 0.5 * time.elapsed_secs().sin();
+    edge_detection.depth_thickness = thickness;
+    edge_detection.normal_thickness = thickness;
+    edge_detection.color_thickness = thickness;
+}
+
+fn pause_boil(mut views: Query<(&PausedBoil, &mut EdgeDetectionUniform)>) {
+    for (paused, mut uniform) in views.iter_mut() {
+        uniform.time = paused.time;
+    }
+}
@@ -0,0 +1,52 @@
+//! Draws a thick, pulsing outline around the edges, like a selection highlight.
+
+use bevy::prelude::*;
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin, ThickOutline};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, pulse)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(1.0, 2.0, 1.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
+        Transform::from_xyz(0.0, 1.0, 0.0),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(3.0, 3.0, 5.0).looking_at(Vec3::new(0.0, 1.0, 0.0), Vec3::Y),
+        Msaa::Off,
+        EdgeDetection {
+            thick_outline: Some(ThickOutline {
+                radius_px: 16.0,
+                color: Color::srgb(1.0, 0.8, 0.2),
+            }),
+            ..default()
+        },
+    ));
+}
+
+/// Animates the outline radius, which doesn't recompile the shaders.
+fn pulse(mut query: Query<&mut EdgeDetection>, time: Res<Time>) {
+    for mut edge_detection in &mut query {
+        if let Some(thick_outline) = &mut edge_detection.thick_outline {
+            thick_outline.radius_px = 16.0 + 8.0 * (time.elapsed_secs() * 3.0).sin();
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Draws the edges of the same scene in two windows, with an LDR camera in the primary window
+//! and an HDR camera in the secondary one, each specialized for the format of its own window.
+//!
+//! Press enter in the primary window to close and reopen the secondary window, or close it directly.
+
+use bevy::{
+    prelude::*,
+    render::camera::RenderTarget,
+    window::{WindowRef, WindowResolution},
+};
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlugin};
+
+/// Marks the camera of the secondary window.
+#[derive(Component)]
+struct SecondaryCamera;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, toggle_secondary_window)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.7, 0.6));
+    for (i, mesh) in [
+        meshes.add(Cuboid::default()),
+        meshes.add(Sphere::default()),
+        meshes.add(Torus::default()),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        commands.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(i as f32 * 2.0 - 2.0, 0.5, 0.0),
+        ));
+    }
+
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.7, 0.7, 0.7))),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 3.0, 6.0).looking_at(Vec3::ZERO, Vec3::Y),
+        EdgeDetection::default(),
+    ));
+
+    let secondary_window = spawn_secondary_window(&mut commands);
+    commands.spawn((
+        Camera3d::default(),
+        Camera {
+            target: RenderTarget::Window(WindowRef::Entity(secondary_window)),
+            hdr: true,
+            ..default()
+        },
+        Transform::from_xyz(-5.0, 4.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+        EdgeDetection {
+            edge_color: Color::srgb(0.1, 0.2, 0.8),
+            ..default()
+        },
+        SecondaryCamera,
+    ));
+}
+
+fn spawn_secondary_window(commands: &mut Commands) -> Entity {
+    commands
+        .spawn(Window {
+            title: "Secondary window (HDR)".to_string(),
+            resolution: WindowResolution::new(640.0, 480.0),
+            ..default()
+        })
+        .id()
+}
+
+fn toggle_secondary_window(
+    mut commands: Commands,
+    mut camera: Single<&mut Camera, With<SecondaryCamera>>,
+    windows: Query<(), With<Window>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+) {
+    if !keyboard.just_pressed(KeyCode::Enter) {
+        return;
+    }
+
+    let RenderTarget::Window(WindowRef::Entity(window)) = camera.target else {
+        return;
+    };
+
+    if windows.contains(window) {
+        commands.entity(window).despawn();
+    } else {
+        // The edge detection of the camera is prepared again for the new window.
+        let window = spawn_secondary_window(&mut commands);
+        camera.target = RenderTarget::Window(WindowRef::Entity(window));
+    }
+}
@@ -0,0 +1,71 @@
+//! Runs the edge detection before the transparent pass, so the edges of a seabed are dimmed by the water above it
+//! like the rest of the scene, while the rocks sticking out of the water keep their full outlines.
+
+use bevy::prelude::*;
+use bevy_edge_detection::{EdgeDetection, EdgeDetectionPlacement, EdgeDetectionPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EdgeDetectionPlugin {
+            placement: EdgeDetectionPlacement::BeforeTransparent,
+            ..default()
+        })
+        .add_systems(Startup, setup)
+        .add_systems(Update, orbit)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.76, 0.7, 0.5))),
+    ));
+
+    let rock = materials.add(Color::srgb(0.5, 0.5, 0.45));
+    for (size, position) in [
+        (0.8, Vec3::new(-2.0, 0.4, 1.0)),
+        (1.2, Vec3::new(2.5, 0.6, -1.5)),
+        (0.5, Vec3::new(0.5, 0.25, 2.5)),
+        (2.4, Vec3::new(-3.0, 1.2, -3.0)),
+    ] {
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::from_length(size))),
+            MeshMaterial3d(rock.clone()),
+            Transform::from_translation(position).with_rotation(Quat::from_rotation_y(size)),
+        ));
+    }
+
+    // The water surface, alpha blended over the seabed and its edges.
+    commands.spawn((
+        Mesh3d(meshes.add(Plane3d::default().mesh().size(20.0, 20.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgba(0.1, 0.35, 0.5, 0.7),
+            alpha_mode: AlphaMode::Blend,
+            perceptual_roughness: 0.1,
+            ..default()
+        })),
+        Transform::from_xyz(0.0, 1.5, 0.0),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 7.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+        EdgeDetection::default(),
+    ));
+}
+
+fn orbit(mut camera: Single<&mut Transform, With<Camera3d>>, time: Res<Time>) {
+    let angle = time.elapsed_secs() * 0.2;
+    **camera = Transform::from_xyz(angle.sin() * 10.0, 7.0, angle.cos() * 10.0)
+        .looking_at(Vec3::ZERO, Vec3::Y);
+}
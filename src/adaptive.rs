@@ -0,0 +1,245 @@
+use bevy::{
+    diagnostic::{DiagnosticPath, DiagnosticsStore},
+    prelude::*,
+};
+
+use crate::{EdgeDetection, EdgeDetectionSet};
+
+/// Steps the [`EdgeDetection`] of a camera down a ladder of cheaper settings when its passes take
+/// longer than `budget_ms` on the GPU, and back up when there's headroom.
+///
+/// The GPU time is read from the [`EdgeDetectionAdaptiveQuality::GPU_TIME`] diagnostic, which needs the
+/// [`RenderDiagnosticsPlugin`](bevy::render::diagnostic::RenderDiagnosticsPlugin) and a backend with timestamp
+/// queries (Vulkan or DX12). Without it, the camera stays on its current step. The diagnostic is shared by all
+/// the cameras with edge detection, so their budgets are compared to the average time of a view.
+///
+/// The time has to stay over the budget, or under `headroom` times the budget, for `hold_secs` before
+/// the quality changes, so it doesn't oscillate every frame. Each time a step back up ends up over the budget again,
+/// the hold before the next step up doubles, up to 32 times `hold_secs`. The steps changing the pipeline key of the view
+/// respecialize its pipelines, so they're also at least `respecialization_cooldown_secs` apart.
+///
+/// The fields of the [`EdgeQualityStep`]s are owned by the controller: the ones of the current step
+/// overwrite the settings of the [`EdgeDetection`] whenever the step changes.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct EdgeDetectionAdaptiveQuality {
+    /// The GPU time the edge detection passes of a view may take, in milliseconds.
+    pub budget_ms: f32,
+    /// The settings to step through, from the highest quality to the cheapest.
+    pub ladder: Vec<EdgeQualityStep>,
+    /// The fraction of the budget the time has to drop under before stepping back up.
+    ///
+    /// Range: (0.0, 1.0]
+    pub headroom: f32,
+    /// How long the time has to stay over the budget, or under the headroom, before the quality changes.
+    pub hold_secs: f32,
+    /// The minimum time between two steps respecializing the pipelines.
+    pub respecialization_cooldown_secs: f32,
+
+    /// The index of the current step in the `ladder`.
+    step: usize,
+    applied: bool,
+    stepped_up: bool,
+    failed_step_ups: u32,
+    over_budget_secs: f32,
+    under_headroom_secs: f32,
+    since_respecialization_secs: f32,
+}
+
+impl EdgeDetectionAdaptiveQuality {
+    /// The GPU time of the edge detection passes of a view, in milliseconds.
+    pub const GPU_TIME: DiagnosticPath =
+        DiagnosticPath::const_new("render/edge_detection/elapsed_gpu");
+
+    /// Creates a controller stepping through `ladder`, starting at its first step.
+    pub fn new(budget_ms: f32, ladder: Vec<EdgeQualityStep>) -> Self {
+        Self {
+            budget_ms,
+            ladder,
+            headroom: 0.7,
+            hold_secs: 1.0,
+            respecialization_cooldown_secs: 3.0,
+            step: 0,
+            applied: false,
+            stepped_up: false,
+            failed_step_ups: 0,
+            over_budget_secs: 0.0,
+            under_headroom_secs: 0.0,
+            since_respecialization_secs: f32::INFINITY,
+        }
+    }
+
+    /// Creates a controller starting from the current settings of `edge_detection`, then dropping the
+    /// fBm octaves and extra strokes, then the anti-aliasing and per-sample detection, then the color source.
+    pub fn from_settings(budget_ms: f32, edge_detection: &EdgeDetection) -> Self {
+        let full = EdgeQualityStep::from(edge_detection);
+        let single_stroke = EdgeQualityStep {
+            distortion_octaves: 1,
+            strokes: 1,
+            ..full
+        };
+        let aliased = EdgeQualityStep {
+            edge_aa: false,
+            all_samples: false,
+            ..single_stroke
+        };
+        let no_color = EdgeQualityStep {
+            enable_color: false,
+            ..aliased
+        };
+
+        let mut ladder = vec![full, single_stroke, aliased, no_color];
+        ladder.dedup();
+        Self::new(budget_ms, ladder)
+    }
+
+    /// The index of the current step in the `ladder`.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+}
+
+/// A rung of the [`EdgeDetectionAdaptiveQuality::ladder`], overriding the settings of [`EdgeDetection`] that matter for performance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub struct EdgeQualityStep {
+    /// See [`EdgeDetection::enable_color`].
+    pub enable_color: bool,
+    /// See [`EdgeDetection::all_samples`].
+    pub all_samples: bool,
+    /// See [`EdgeDetection::edge_aa`].
+    pub edge_aa: bool,
+    /// See [`EdgeDetection::strokes`].
+    pub strokes: u32,
+    /// See [`EdgeDetection::distortion_octaves`].
+    pub distortion_octaves: u32,
+}
+
+impl EdgeQualityStep {
+    /// Overwrites the settings of `edge_detection` with this step.
+    pub fn apply(&self, edge_detection: &mut EdgeDetection) {
+        edge_detection.enable_color = self.enable_color;
+        edge_detection.all_samples = self.all_samples;
+        edge_detection.edge_aa = self.edge_aa;
+        edge_detection.strokes = self.strokes;
+        edge_detection.distortion_octaves = self.distortion_octaves;
+    }
+
+    /// Returns whether going from this step to `other` changes the pipeline key of the view.
+    pub fn respecializes(&self, other: &EdgeQualityStep) -> bool {
+        self.enable_color != other.enable_color
+            || self.all_samples != other.all_samples
+            || self.edge_aa != other.edge_aa
+            || (self.strokes > 1) != (other.strokes > 1)
+    }
+}
+
+impl From<&EdgeDetection> for EdgeQualityStep {
+    fn from(edge_detection: &EdgeDetection) -> Self {
+        Self {
+            enable_color: edge_detection.enable_color,
+            all_samples: edge_detection.all_samples,
+            edge_aa: edge_detection.edge_aa,
+            strokes: edge_detection.strokes,
+            distortion_octaves: edge_detection.distortion_octaves,
+        }
+    }
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.register_type::<EdgeDetectionAdaptiveQuality>()
+        .add_systems(
+            Update,
+            adapt_edge_detection_quality.in_set(EdgeDetectionSet::Configure),
+        );
+}
+
+/// Steps the quality of the cameras with [`EdgeDetectionAdaptiveQuality`] up or down their ladder.
+pub fn adapt_edge_detection_quality(
+    mut cameras: Query<(
+        Entity,
+        &mut EdgeDetection,
+        &mut EdgeDetectionAdaptiveQuality,
+    )>,
+    diagnostics: Res<DiagnosticsStore>,
+    time: Res<Time>,
+) {
+    let gpu_time = diagnostics
+        .get(&EdgeDetectionAdaptiveQuality::GPU_TIME)
+        .and_then(|diagnostic| diagnostic.smoothed())
+        .map(|ms| ms as f32);
+    let delta = time.delta_secs();
+
+    for (entity, mut edge_detection, mut quality) in cameras.iter_mut() {
+        let quality = quality.as_mut();
+        if quality.ladder.is_empty() {
+            continue;
+        }
+
+        quality.step = quality.step.min(quality.ladder.len() - 1);
+        if !quality.applied {
+            quality.ladder[quality.step].apply(&mut edge_detection);
+            quality.applied = true;
+        }
+
+        quality.since_respecialization_secs += delta;
+
+        let Some(gpu_time) = gpu_time else {
+            continue;
+        };
+
+        if gpu_time > quality.budget_ms {
+            quality.over_budget_secs += delta;
+            quality.under_headroom_secs = 0.0;
+        } else if gpu_time < quality.budget_ms * quality.headroom {
+            quality.under_headroom_secs += delta;
+            quality.over_budget_secs = 0.0;
+        } else {
+            quality.over_budget_secs = 0.0;
+            quality.under_headroom_secs = 0.0;
+        }
+
+        let next = if quality.over_budget_secs >= quality.hold_secs
+            && quality.step + 1 < quality.ladder.len()
+        {
+            quality.step + 1
+        } else if quality.under_headroom_secs
+            >= quality.hold_secs * (1 << quality.failed_step_ups) as f32
+            && quality.step > 0
+        {
+            quality.step - 1
+        } else {
+            continue;
+        };
+
+        let current = quality.ladder[quality.step];
+        let respecializes = current.respecializes(&quality.ladder[next]);
+        if respecializes
+            && quality.since_respecialization_secs < quality.respecialization_cooldown_secs
+        {
+            continue;
+        }
+
+        info!(
+            "Edge detection of {entity} takes {gpu_time:.2}ms for a budget of {:.2}ms, stepping {} to quality step {next}",
+            quality.budget_ms,
+            if next > quality.step { "down" } else { "up" },
+        );
+
+        let step_up = next < quality.step;
+        if step_up && quality.stepped_up {
+            // The previous step up held, the next one doesn't need to wait longer.
+            quality.failed_step_ups = 0;
+        } else if !step_up && quality.stepped_up {
+            quality.failed_step_ups = (quality.failed_step_ups + 1).min(5);
+        }
+        quality.stepped_up = step_up;
+
+        quality.ladder[next].apply(&mut edge_detection);
+        quality.step = next;
+        quality.over_budget_secs = 0.0;
+        quality.under_headroom_secs = 0.0;
+        if respecializes {
+            quality.since_respecialization_secs = 0.0;
+        }
+    }
+}
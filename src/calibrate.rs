@@ -0,0 +1,247 @@
+use bevy::{
+    diagnostic::FrameCount,
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        gpu_readback::{Readback, ReadbackComplete},
+        render_asset::RenderAssetUsages,
+        render_resource::BufferUsages,
+        storage::ShaderStorageBuffer,
+    },
+};
+
+use crate::{depth_range, EdgeDetection, EdgeDetectionSet, EdgeDetectionUniform};
+
+/// The number of bins of the depth and normal gradient histograms.
+/// It must match `CALIBRATION_BINS` in the shader.
+pub const CALIBRATION_BINS: usize = 64;
+/// The bins are spaced on a log scale, this many per doubling of the gradient.
+const BINS_PER_OCTAVE: f32 = 4.0;
+/// The gradient at the bottom of the second bin, as a power of two.
+/// The first bin holds all the gradients below it, including the flat areas.
+const MIN_GRADIENT_LOG2: f32 = -8.0;
+
+/// Calibrates the depth and normal thresholds of a camera so their edges cover `target_coverage` percent of the viewport.
+///
+/// Add it to a camera with [`EdgeDetection`] to opt in. Every `interval` frames, the edge pass builds histograms
+/// of the depth and normal gradients it compares to the thresholds, which are read back asynchronously.
+/// The thresholds hitting the target coverage are sent as an [`EdgeThresholdSuggestion`] event a few frames later,
+/// and written to the [`EdgeDetection`] of the camera if `apply` is set.
+///
+/// Each source is calibrated on its own, so with both of them enabled the edges cover up to twice the target.
+/// The gradients are binned on a log scale, four bins per doubling, so the suggestions move in steps of about 19%.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct EdgeDetectionAutoCalibrate {
+    /// The percentage of the viewport the edges of each source should cover, in `[0.0, 100.0]`.
+    pub target_coverage: f32,
+    /// Whether to write the suggested thresholds to the [`EdgeDetection`] of the camera.
+    /// If `false`, they're only sent as [`EdgeThresholdSuggestion`] events, e.g. for a tool to display them.
+    pub apply: bool,
+    /// The number of frames between two calibrations. The histograms are only built on these frames.
+    pub interval: u32,
+}
+
+impl Default for EdgeDetectionAutoCalibrate {
+    fn default() -> Self {
+        Self {
+            target_coverage: 5.0,
+            apply: false,
+            interval: 30,
+        }
+    }
+}
+
+/// The thresholds hitting the [`EdgeDetectionAutoCalibrate::target_coverage`] of a camera.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct EdgeThresholdSuggestion {
+    /// The calibrated camera.
+    pub camera: Entity,
+    /// The suggested [`EdgeDetection::depth_threshold`], or `None` if the depth source is disabled.
+    pub depth_threshold: Option<f32>,
+    /// The suggested [`EdgeDetection::normal_threshold`], or `None` if the normal source is disabled.
+    pub normal_threshold: Option<f32>,
+}
+
+/// The storage buffer the gradient histograms of a camera are built into,
+/// the depth bins followed by the normal bins.
+#[derive(Component, ExtractComponent, Clone, Debug)]
+pub struct EdgeCalibrationBuffer {
+    pub buffer: Handle<ShaderStorageBuffer>,
+    /// Whether the histograms are built this frame.
+    pub sample: bool,
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.register_type::<EdgeDetectionAutoCalibrate>()
+        .add_event::<EdgeThresholdSuggestion>()
+        .add_plugins(ExtractComponentPlugin::<EdgeCalibrationBuffer>::default())
+        .add_systems(
+            PostUpdate,
+            (
+                prepare_edge_calibration_buffers,
+                schedule_edge_calibration_readbacks,
+            )
+                .chain()
+                .in_set(EdgeDetectionSet::Configure),
+        );
+}
+
+/// Creates the histogram buffers of the calibrated cameras, and cleans them up when the calibration stops.
+#[allow(clippy::type_complexity)]
+pub fn prepare_edge_calibration_buffers(
+    mut commands: Commands,
+    mut buffers: ResMut<Assets<ShaderStorageBuffer>>,
+    added: Query<
+        Entity,
+        (
+            With<EdgeDetectionAutoCalibrate>,
+            Without<EdgeCalibrationBuffer>,
+        ),
+    >,
+    mut removed: RemovedComponents<EdgeDetectionAutoCalibrate>,
+) {
+    for entity in added.iter() {
+        let mut buffer = ShaderStorageBuffer::with_size(
+            2 * CALIBRATION_BINS * size_of::<u32>(),
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        buffer.buffer_description.label = Some("edge_detection_calibration_buffer");
+        buffer.buffer_description.usage |= BufferUsages::COPY_SRC | BufferUsages::COPY_DST;
+
+        commands.entity(entity).insert(EdgeCalibrationBuffer {
+            buffer: buffers.add(buffer),
+            sample: false,
+        });
+    }
+
+    for entity in removed.read() {
+        if let Ok(mut entity_commands) = commands.get_entity(entity) {
+            entity_commands.remove::<EdgeCalibrationBuffer>();
+        }
+    }
+}
+
+/// Builds the histograms of the calibrated cameras every [`EdgeDetectionAutoCalibrate::interval`] frames,
+/// and reads them back.
+pub fn schedule_edge_calibration_readbacks(
+    mut commands: Commands,
+    mut cameras: Query<(
+        Entity,
+        &EdgeDetectionAutoCalibrate,
+        &mut EdgeCalibrationBuffer,
+    )>,
+    frame_count: Res<FrameCount>,
+) {
+    for (entity, calibrate, mut buffer) in cameras.iter_mut() {
+        buffer.sample = frame_count.0.is_multiple_of(calibrate.interval.max(1));
+        if !buffer.sample {
+            continue;
+        }
+
+        // The camera may already read back its edge coverage, so the readback gets its own entity.
+        commands
+            .spawn(Readback::buffer(buffer.buffer.clone()))
+            .observe(
+                move |trigger: Trigger<ReadbackComplete>,
+                      mut commands: Commands,
+                      cameras: Query<(
+                    &EdgeDetectionAutoCalibrate,
+                    &mut EdgeDetection,
+                    Option<&Projection>,
+                )>,
+                      suggestions: EventWriter<EdgeThresholdSuggestion>| {
+                    commands.entity(trigger.target()).despawn();
+                    calibrate_edge_thresholds(entity, &trigger.event().0, cameras, suggestions);
+                },
+            );
+    }
+}
+
+fn calibrate_edge_thresholds(
+    camera: Entity,
+    data: &[u8],
+    mut cameras: Query<(
+        &EdgeDetectionAutoCalibrate,
+        &mut EdgeDetection,
+        Option<&Projection>,
+    )>,
+    mut suggestions: EventWriter<EdgeThresholdSuggestion>,
+) {
+    let Ok((calibrate, mut edge_detection, projection)) = cameras.get_mut(camera) else {
+        return;
+    };
+
+    let histograms: Vec<u32> = data
+        .chunks_exact(size_of::<u32>())
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect();
+    if histograms.len() < 2 * CALIBRATION_BINS {
+        return;
+    }
+    let (depth_histogram, normal_histogram) = histograms.split_at(CALIBRATION_BINS);
+
+    let suggestion = EdgeThresholdSuggestion {
+        camera,
+        depth_threshold: edge_detection
+            .enable_depth
+            .then(|| suggest_threshold(depth_histogram, calibrate.target_coverage))
+            .flatten()
+            .map(|threshold| {
+                // The gradients are measured in world units, a normalized threshold is a fraction of the range.
+                match edge_detection.normalized_depth_threshold {
+                    true => threshold / projection.and_then(depth_range).unwrap_or(1.0),
+                    false => threshold,
+                }
+            }),
+        normal_threshold: edge_detection
+            .enable_normal
+            .then(|| suggest_threshold(normal_histogram, calibrate.target_coverage))
+            .flatten(),
+    };
+
+    if calibrate.apply {
+        if let Some(depth_threshold) = suggestion.depth_threshold {
+            edge_detection.depth_threshold = depth_threshold;
+        }
+        if let Some(normal_threshold) = suggestion.normal_threshold {
+            edge_detection.normal_threshold = normal_threshold;
+        }
+    }
+
+    suggestions.write(suggestion);
+}
+
+/// Returns the threshold above which `target_coverage` percent of the pixels of `histogram` are,
+/// or `None` if the histogram is empty.
+fn suggest_threshold(histogram: &[u32], target_coverage: f32) -> Option<f32> {
+    let total: u64 = histogram.iter().map(|&count| count as u64).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let target = total as f64 * (target_coverage as f64 / 100.0).clamp(0.0, 1.0);
+    let mut above = 0;
+    for (bin, &count) in histogram.iter().enumerate().rev() {
+        above += count as u64;
+        if above as f64 >= target {
+            // The pixels of this bin are at least at its lower bound.
+            return Some(bin_lower_bound(bin.max(1)));
+        }
+    }
+
+    Some(bin_lower_bound(1))
+}
+
+fn bin_lower_bound(bin: usize) -> f32 {
+    2.0_f32.powf(MIN_GRADIENT_LOG2 + (bin - 1) as f32 / BINS_PER_OCTAVE)
+}
+
+/// Tells the edge pass of the calibrated views whether to build the histograms this frame.
+pub fn prepare_edge_calibration(
+    mut views: Query<(&EdgeCalibrationBuffer, &mut EdgeDetectionUniform)>,
+) {
+    for (buffer, mut uniform) in views.iter_mut() {
+        uniform.calibrate = buffer.sample.into();
+    }
+}
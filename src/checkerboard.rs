@@ -0,0 +1,99 @@
+use bevy::{
+    diagnostic::FrameCount,
+    prelude::*,
+    render::{
+        render_resource::*, renderer::RenderDevice, sync_world::RenderEntity,
+        texture::CachedTexture, view::ViewTarget, MainWorld,
+    },
+};
+
+use crate::{
+    EdgeDetection, EdgeDetectionMaskTexture, EdgeDetectionPipelineId, EdgeDetectionUniform,
+    EDGE_MASK_TEXTURE_FORMAT,
+};
+
+/// The [`EdgeDetectionUniform::checkerboard_phase`] rendering all the pixels.
+pub const CHECKERBOARD_ALL_PIXELS: u32 = 2;
+
+/// Marks an [`EdgeDetectionMaskTexture`] as the history of a view with [`EdgeDetection::checkerboard`].
+///
+/// The texture isn't taken from the texture cache, so it keeps its content between frames:
+/// the pixels skipped by the edge detection pass of a frame keep the edges of the previous one.
+#[derive(Component)]
+pub struct EdgeDetectionCheckerboardHistory;
+
+/// Creates the history of the views with [`EdgeDetection::checkerboard`], and picks the half of the pixels
+/// rendered this frame. All the pixels are rendered when the history is (re)created, e.g. on resize,
+/// or reset with [`EdgeDetection::reset_history`].
+#[allow(clippy::type_complexity)]
+pub fn prepare_edge_detection_checkerboard(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    frame_count: Res<FrameCount>,
+    mut views: Query<(
+        Entity,
+        &ViewTarget,
+        &EdgeDetection,
+        &EdgeDetectionPipelineId,
+        &mut EdgeDetectionUniform,
+        Option<&EdgeDetectionMaskTexture>,
+        Has<EdgeDetectionCheckerboardHistory>,
+    )>,
+) {
+    for (entity, view_target, edge_detection, pipeline_id, mut uniform, mask, has_history) in
+        views.iter_mut()
+    {
+        if !pipeline_id.key.checkerboard {
+            // The history is replaced, or removed, by the regular edge mask.
+            if has_history {
+                commands
+                    .entity(entity)
+                    .remove::<EdgeDetectionCheckerboardHistory>();
+            }
+            continue;
+        }
+
+        let size = view_target.main_texture().size();
+        let history_valid = has_history && mask.is_some_and(|mask| mask.0.texture.size() == size);
+
+        if history_valid && !edge_detection.reset_history {
+            uniform.checkerboard_phase = frame_count.0 & 1;
+            continue;
+        }
+
+        uniform.checkerboard_phase = CHECKERBOARD_ALL_PIXELS;
+        if history_valid {
+            continue;
+        }
+
+        let texture = render_device.create_texture(&TextureDescriptor {
+            label: Some("edge_detection_checkerboard_history"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: EDGE_MASK_TEXTURE_FORMAT,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let default_view = texture.create_view(&TextureViewDescriptor::default());
+
+        commands.entity(entity).insert((
+            EdgeDetectionMaskTexture(CachedTexture {
+                texture,
+                default_view,
+            }),
+            EdgeDetectionCheckerboardHistory,
+        ));
+    }
+}
+
+/// Clears the [`EdgeDetection::reset_history`] flags once they've been extracted.
+pub fn clear_edge_detection_history_resets(mut main_world: ResMut<MainWorld>) {
+    let mut query = main_world.query_filtered::<&mut EdgeDetection, With<RenderEntity>>();
+    for mut edge_detection in query.iter_mut(&mut main_world) {
+        if edge_detection.reset_history {
+            edge_detection.bypass_change_detection().reset_history = false;
+        }
+    }
+}
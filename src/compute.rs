@@ -0,0 +1,79 @@
+use bevy::{
+    prelude::*,
+    render::{camera::CameraMainTextureUsages, render_resource::*, view::ViewTarget, RenderApp},
+};
+
+use crate::{
+    EdgeDetection, EdgeDetectionKey, EdgeDetectionPipeline, EdgeDetectionSet, EdgeDetectionSupport,
+};
+
+/// The side of the square tile of pixels processed by a workgroup of the compute variant.
+/// It must match the workgroup size of the `compute` entry point of the shader.
+pub const EDGE_DETECTION_WORKGROUP_SIZE: u32 = 8;
+
+/// The binding of the output of the compute variant, the main texture of the view.
+pub const EDGE_DETECTION_OUTPUT_BINDING: u32 = 22;
+
+/// Inserted when the edge detection runs as a compute pass, see [`EdgeDetectionPlugin::use_compute`](crate::EdgeDetectionPlugin::use_compute).
+/// The render world has the [`SpecializedComputePipelines`] of the [`EdgeDetectionPipeline`] instead.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct EdgeDetectionCompute;
+
+impl SpecializedComputePipeline for EdgeDetectionPipeline {
+    type Key = EdgeDetectionKey;
+
+    fn specialize(&self, key: Self::Key) -> ComputePipelineDescriptor {
+        ComputePipelineDescriptor {
+            label: Some("edge_detection: compute_pipeline".into()),
+            layout: vec![self.bind_group_layout(key.layout_key()).clone()],
+            push_constant_ranges: vec![],
+            shader: self.shader.clone(),
+            shader_defs: self.shader_defs(key),
+            entry_point: "compute".into(),
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
+
+/// Lets the compute variant write to the main textures of the HDR cameras, by adding the storage usage to them.
+///
+/// The LDR formats can't be written as storage textures, their cameras keep the fragment pass,
+/// and so do all the cameras if the adapter can't write the HDR format from a compute shader.
+pub fn configure_compute_output(
+    support: Option<Res<EdgeDetectionSupport>>,
+    mut cameras: Query<(&Camera, &mut CameraMainTextureUsages), With<EdgeDetection>>,
+) {
+    if !support.is_some_and(|support| support.compute_output) {
+        return;
+    }
+
+    for (camera, mut usages) in cameras.iter_mut() {
+        if camera.hdr && !usages.0.contains(TextureUsages::STORAGE_BINDING) {
+            usages.0 |= TextureUsages::STORAGE_BINDING;
+        }
+    }
+}
+
+/// Returns whether the compute variant writes the output of a view with `key`, whose main texture has `usages`.
+pub(crate) fn compute_output(key: &EdgeDetectionKey, usages: TextureUsages) -> bool {
+    key.target_format == ViewTarget::TEXTURE_FORMAT_HDR
+        && usages.contains(TextureUsages::STORAGE_BINDING)
+        && key.compute_supported()
+}
+
+pub(crate) fn build(app: &mut App, use_compute: bool) {
+    if !use_compute {
+        return;
+    }
+
+    app.insert_resource(EdgeDetectionCompute).add_systems(
+        PostUpdate,
+        configure_compute_output.in_set(EdgeDetectionSet::Configure),
+    );
+
+    let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+
+    render_app.init_resource::<SpecializedComputePipelines<EdgeDetectionPipeline>>();
+}
@@ -0,0 +1,76 @@
+use bevy::{
+    core_pipeline::{
+        core_2d::{
+            graph::{Core2d, Node2d},
+            CORE_2D_DEPTH_FORMAT,
+        },
+        prepass::NORMAL_PREPASS_FORMAT,
+    },
+    prelude::*,
+    render::{
+        render_graph::{InternedRenderLabel, RenderGraphApp, RenderLabel, ViewNodeRunner},
+        render_resource::{Texture, TextureView},
+        texture::FallbackImageMsaa,
+        view::ViewTarget,
+        Render,
+    },
+};
+
+use crate::{EdgeDetection, EdgeDetectionNode, EdgeDetectionSet};
+
+/// The edge detection node of the 2D cameras, see [`EdgeDetectionPlugin::before_2d`](crate::EdgeDetectionPlugin::before_2d).
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+pub struct EdgeDetection2dLabel;
+
+/// The textures bound in place of the prepass textures of a 2D view, which has none.
+///
+/// The depth texture of the 2D views can't be sampled either, so the depth and normal sources are compiled out
+/// of their pipelines, and these textures only fill the bindings.
+#[derive(Component)]
+pub struct EdgeDetection2dTextures {
+    pub depth_texture: Texture,
+    pub depth: TextureView,
+    pub normal: TextureView,
+}
+
+/// Adds the edge detection node to the [`Core2d`] render graph, after the tonemapping and before `before`.
+pub(crate) fn build(render_app: &mut SubApp, before: InternedRenderLabel) {
+    // The node runs the 2D views, which are always at `EdgeDetectionPlacement::PostProcess`.
+    render_app
+        .add_render_graph_node::<ViewNodeRunner<EdgeDetectionNode>>(Core2d, EdgeDetection2dLabel)
+        .add_render_graph_edges(
+            Core2d,
+            (
+                Node2d::Tonemapping.intern(),
+                EdgeDetection2dLabel.intern(),
+                before,
+            ),
+        )
+        .add_systems(
+            Render,
+            prepare_edge_detection_2d_textures.in_set(EdgeDetectionSet::PrepareResources),
+        );
+}
+
+/// Prepares the [`EdgeDetection2dTextures`] of the 2D views with an [`EdgeDetection`].
+#[allow(clippy::type_complexity)]
+pub fn prepare_edge_detection_2d_textures(
+    mut commands: Commands,
+    mut fallback_images: FallbackImageMsaa,
+    views: Query<(Entity, &Msaa), (With<EdgeDetection>, With<Camera2d>, With<ViewTarget>)>,
+) {
+    for (entity, msaa) in views.iter() {
+        let depth = fallback_images.image_for_samplecount(msaa.samples(), CORE_2D_DEPTH_FORMAT);
+        let (depth_texture, depth) = (depth.texture.clone(), depth.texture_view.clone());
+        let normal = fallback_images
+            .image_for_samplecount(msaa.samples(), NORMAL_PREPASS_FORMAT)
+            .texture_view
+            .clone();
+
+        commands.entity(entity).insert(EdgeDetection2dTextures {
+            depth_texture,
+            depth,
+            normal,
+        });
+    }
+}
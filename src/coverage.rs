@@ -0,0 +1,118 @@
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        gpu_readback::{Readback, ReadbackComplete},
+        render_asset::RenderAssetUsages,
+        render_resource::BufferUsages,
+        storage::ShaderStorageBuffer,
+    },
+};
+
+use crate::{EdgeDetection, EdgeDetectionMaskOutput, EdgeDetectionSet};
+
+/// Measures how much of the screen is covered by edges.
+///
+/// Add it to a camera with [`EdgeDetection`](crate::EdgeDetection) to opt in. The edge pixels are
+/// counted on the GPU and read back asynchronously, so [`EdgeCoverage::percent`] lags a few frames behind.
+/// The average coverage of all the measured cameras is also published as the
+/// [`EdgeCoverage::COVERAGE_PERCENT`] diagnostic.
+///
+/// Without this component, the edge pass doesn't count anything.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct EdgeCoverage {
+    /// The percentage of the viewport covered by edges, in `[0.0, 100.0]`.
+    pub percent: f32,
+}
+
+impl EdgeCoverage {
+    pub const COVERAGE_PERCENT: DiagnosticPath =
+        DiagnosticPath::const_new("edge_detection/coverage_percent");
+}
+
+/// The storage buffer the edge pixels of a camera are counted into.
+#[derive(Component, ExtractComponent, Clone, Debug)]
+pub struct EdgeCoverageBuffer(pub Handle<ShaderStorageBuffer>);
+
+pub(crate) fn build(app: &mut App) {
+    app.register_type::<EdgeCoverage>()
+        .register_diagnostic(Diagnostic::new(EdgeCoverage::COVERAGE_PERCENT).with_suffix("%"))
+        .add_plugins(ExtractComponentPlugin::<EdgeCoverageBuffer>::default())
+        .add_systems(
+            PostUpdate,
+            prepare_edge_coverage_buffers.in_set(EdgeDetectionSet::Configure),
+        )
+        .add_observer(read_edge_coverage);
+}
+
+/// Creates the counter buffers of the cameras measuring their edge coverage,
+/// and cleans them up when the measurement stops.
+#[allow(clippy::type_complexity)]
+pub fn prepare_edge_coverage_buffers(
+    mut commands: Commands,
+    mut buffers: ResMut<Assets<ShaderStorageBuffer>>,
+    added: Query<Entity, (With<EdgeCoverage>, Without<EdgeCoverageBuffer>)>,
+    mut removed: RemovedComponents<EdgeCoverage>,
+) {
+    for entity in added.iter() {
+        let mut buffer =
+            ShaderStorageBuffer::with_size(size_of::<u32>(), RenderAssetUsages::RENDER_WORLD);
+        buffer.buffer_description.label = Some("edge_detection_coverage_buffer");
+        buffer.buffer_description.usage |= BufferUsages::COPY_SRC | BufferUsages::COPY_DST;
+        let buffer = buffers.add(buffer);
+
+        commands
+            .entity(entity)
+            .insert((EdgeCoverageBuffer(buffer.clone()), Readback::buffer(buffer)));
+    }
+
+    for entity in removed.read() {
+        if let Ok(mut entity_commands) = commands.get_entity(entity) {
+            entity_commands.remove::<(EdgeCoverageBuffer, Readback)>();
+        }
+    }
+}
+
+fn read_edge_coverage(
+    trigger: Trigger<ReadbackComplete>,
+    mut cameras: Query<(
+        &Camera,
+        &mut EdgeCoverage,
+        Option<&EdgeDetection>,
+        Has<EdgeDetectionMaskOutput>,
+    )>,
+    mut diagnostics: Diagnostics,
+) {
+    let Ok((camera, mut coverage, edge_detection, mask_output)) = cameras.get_mut(trigger.target())
+    else {
+        return;
+    };
+
+    let Some(size) = camera.physical_viewport_size() else {
+        return;
+    };
+
+    // The edges are counted in the pixels they're detected at.
+    let scale = edge_detection.map_or(1.0, |edge_detection| {
+        edge_detection.detection_scale(mask_output)
+    });
+    let size = (size.as_vec2() * scale).ceil().as_uvec2();
+    let pixels = size.x * size.y;
+    if pixels == 0 {
+        return;
+    }
+
+    let edge_pixels: u32 = trigger.event().to_shader_type();
+    coverage.percent = (edge_pixels as f32 / pixels as f32 * 100.0).min(100.0);
+
+    let (sum, count) = cameras
+        .iter()
+        .fold((0.0, 0), |(sum, count), (_, coverage, ..)| {
+            (sum + coverage.percent, count + 1)
+        });
+    diagnostics.add_measurement(&EdgeCoverage::COVERAGE_PERCENT, || {
+        (sum / count as f32) as f64
+    });
+}
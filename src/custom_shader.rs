@@ -0,0 +1,69 @@
+use bevy::{
+    asset::{load_internal_asset, weak_handle},
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        render_resource::{
+            CachedPipelineState, CachedRenderPipelineId, PipelineCache, PipelineCacheError,
+        },
+        RenderApp,
+    },
+};
+
+/// The `bevy_edge_detection::bindings` import of the custom edge detection shaders.
+pub const EDGE_DETECTION_BINDINGS_SHADER_HANDLE: Handle<Shader> =
+    weak_handle!("2f0c9d51-7a43-4e8b-9b6e-5c1d3e8a4f27");
+
+/// Replaces the built-in edge detection shader of a camera, overriding [`EdgeDetectionPlugin::shader`](crate::EdgeDetectionPlugin::shader).
+///
+/// The shader is a fullscreen fragment shader with a `fragment` entry point, taking a
+/// `bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput`. It's specialized with the same
+/// bind group layout and shader defs as the built-in one, whose bindings it imports from
+/// `bevy_edge_detection::bindings`, and the view from `bevy_pbr::mesh_view_bindings::view`. The views with a custom
+/// shader never use the compute variant of [`EdgeDetectionPlugin::use_compute`](crate::EdgeDetectionPlugin::use_compute).
+///
+/// If the shader fails to compile, the camera falls back to the built-in shader until it's fixed and reloaded.
+#[derive(Component, ExtractComponent, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct EdgeDetectionShader(pub Handle<Shader>);
+
+/// [`EdgeDetectionPlugin::shader`](crate::EdgeDetectionPlugin::shader), in the render world.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct DefaultEdgeDetectionShader(pub Option<Handle<Shader>>);
+
+pub(crate) fn build(app: &mut App, shader: Option<Handle<Shader>>) {
+    load_internal_asset!(
+        app,
+        EDGE_DETECTION_BINDINGS_SHADER_HANDLE,
+        "edge_detection_bindings.wgsl",
+        Shader::from_wgsl
+    );
+
+    app.register_type::<EdgeDetectionShader>()
+        .add_plugins(ExtractComponentPlugin::<EdgeDetectionShader>::default());
+
+    if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+        render_app.insert_resource(DefaultEdgeDetectionShader(shader));
+    }
+}
+
+impl DefaultEdgeDetectionShader {
+    /// Returns the shader of a view with the given [`EdgeDetectionShader`], `None` for the built-in one.
+    pub fn resolve(&self, shader: Option<&EdgeDetectionShader>) -> Option<AssetId<Shader>> {
+        shader
+            .map(|shader| &shader.0)
+            .or(self.0.as_ref())
+            .map(Handle::id)
+    }
+}
+
+/// Whether the pipeline failed to compile, as opposed to waiting for its shader or its imports to load.
+pub(crate) fn pipeline_failed(pipeline_cache: &PipelineCache, id: CachedRenderPipelineId) -> bool {
+    matches!(
+        pipeline_cache.get_render_pipeline_state(id),
+        CachedPipelineState::Err(error) if !matches!(
+            error,
+            PipelineCacheError::ShaderNotLoaded(_) | PipelineCacheError::ShaderImportNotYetAvailable
+        )
+    )
+}
@@ -0,0 +1,163 @@
+use bevy::{
+    asset::weak_handle,
+    core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    prelude::*,
+    render::{
+        diagnostic::RecordDiagnostics,
+        render_resource::{
+            binding_types::{texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::{CachedTexture, TextureCache},
+        view::ViewTarget,
+    },
+};
+
+use crate::{EdgeDetectionPipelineId, EdgeDetectionUniform, EDGE_MASK_TEXTURE_FORMAT};
+
+pub const EDGE_DILATION_SHADER_HANDLE: Handle<Shader> =
+    weak_handle!("8c1e4b7a-3f92-4d05-a6e8-5b2d9f0c7e41");
+
+/// The largest [`EdgeDetection::dilation`](crate::EdgeDetection::dilation), which bounds the search of the dilation pass.
+pub const MAX_DILATION: u32 = 8;
+
+/// The texture the dilated edge mask of a view is written to.
+#[derive(Component)]
+pub struct EdgeDetectionDilationTexture(pub CachedTexture);
+
+/// Creates the dilated edge mask texture of the views with [`EdgeDetection::dilation`](crate::EdgeDetection::dilation).
+pub fn prepare_edge_detection_dilation_textures(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    render_device: Res<RenderDevice>,
+    views: Query<(Entity, &ViewTarget, &EdgeDetectionPipelineId)>,
+) {
+    for (entity, view_target, pipeline_id) in views.iter() {
+        if !pipeline_id.key.dilation {
+            commands
+                .entity(entity)
+                .remove::<EdgeDetectionDilationTexture>();
+            continue;
+        }
+
+        let texture = texture_cache.get(
+            &render_device,
+            TextureDescriptor {
+                label: Some("edge_detection_dilation_texture"),
+                size: pipeline_id.mask_size(view_target),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: EDGE_MASK_TEXTURE_FORMAT,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+        );
+
+        commands
+            .entity(entity)
+            .insert(EdgeDetectionDilationTexture(texture));
+    }
+}
+
+/// The pipeline dilating the edge mask. It isn't specialized, the radius is read from the [`EdgeDetectionUniform`].
+#[derive(Resource)]
+pub struct EdgeDilationPipeline {
+    pub layout: BindGroupLayout,
+    pub pipeline: CachedRenderPipelineId,
+}
+
+impl FromWorld for EdgeDilationPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(
+            "edge_detection: dilation_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    // edge mask
+                    texture_2d(TextureSampleType::Float { filterable: false }),
+                    // The uniform holding the radius
+                    uniform_buffer::<EdgeDetectionUniform>(true),
+                ),
+            ),
+        );
+
+        let pipeline =
+            world
+                .resource::<PipelineCache>()
+                .queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("edge_detection: dilation_pipeline".into()),
+                    layout: vec![layout.clone()],
+                    vertex: fullscreen_shader_vertex_state(),
+                    fragment: Some(FragmentState {
+                        shader: EDGE_DILATION_SHADER_HANDLE,
+                        shader_defs: vec![],
+                        entry_point: "fragment".into(),
+                        targets: vec![Some(ColorTargetState {
+                            format: EDGE_MASK_TEXTURE_FORMAT,
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: default(),
+                    depth_stencil: None,
+                    multisample: default(),
+                    push_constant_ranges: vec![],
+                    zero_initialize_workgroup_memory: false,
+                });
+
+        Self { layout, pipeline }
+    }
+}
+
+impl EdgeDilationPipeline {
+    /// Returns the compiled pipeline, or `None` if it isn't ready to run yet.
+    pub fn get_pipeline<'w>(
+        &self,
+        pipeline_cache: &'w PipelineCache,
+    ) -> Option<&'w RenderPipeline> {
+        pipeline_cache.get_render_pipeline(self.pipeline)
+    }
+
+    /// Dilates the edge mask into `output` with the pipeline from [`Self::get_pipeline`] and returns its view.
+    pub fn run<'a>(
+        &self,
+        render_context: &mut RenderContext,
+        pipeline: &RenderPipeline,
+        mask_view: &TextureView,
+        ed_uniforms: BindingResource,
+        uniform_offset: u32,
+        output: &'a EdgeDetectionDilationTexture,
+    ) -> &'a TextureView {
+        let bind_group = render_context.render_device().create_bind_group(
+            "edge_detection_dilation_bind_group",
+            &self.layout,
+            &BindGroupEntries::sequential((mask_view, ed_uniforms)),
+        );
+
+        let output_view = &output.0.default_view;
+        let diagnostics = render_context.diagnostic_recorder();
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("edge_detection_dilation_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: output_view,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        let pass_span = diagnostics.pass_span(&mut render_pass, "dilation");
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[uniform_offset]);
+        render_pass.draw(0..3, 0..1);
+        pass_span.end(&mut render_pass);
+
+        output_view
+    }
+}
@@ -0,0 +1,175 @@
+use bevy::{
+    asset::weak_handle,
+    core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    prelude::*,
+    render::{
+        diagnostic::RecordDiagnostics,
+        render_resource::{
+            binding_types::{sampler, texture_2d},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::{CachedTexture, TextureCache},
+        view::ViewTarget,
+    },
+};
+
+use crate::{EdgeDetectionPipelineId, EdgeDetectionSupport, EDGE_MASK_TEXTURE_FORMAT};
+
+pub const EDGE_AA_SHADER_HANDLE: Handle<Shader> =
+    weak_handle!("2f6b8d41-7c3e-4a90-b5d2-e18c4f07a6b3");
+
+/// The texture the anti-aliased edge mask of a view is written to.
+#[derive(Component)]
+pub struct EdgeDetectionAaTexture(pub CachedTexture);
+
+/// Creates the anti-aliased edge mask texture of the views with [`EdgeDetection::edge_aa`](crate::EdgeDetection::edge_aa).
+pub fn prepare_edge_detection_aa_textures(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    render_device: Res<RenderDevice>,
+    views: Query<(Entity, &ViewTarget, &EdgeDetectionPipelineId)>,
+) {
+    for (entity, view_target, pipeline_id) in views.iter() {
+        if !pipeline_id.key.edge_aa {
+            commands.entity(entity).remove::<EdgeDetectionAaTexture>();
+            continue;
+        }
+
+        let texture = texture_cache.get(
+            &render_device,
+            TextureDescriptor {
+                label: Some("edge_detection_aa_texture"),
+                size: pipeline_id.mask_size(view_target),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: EDGE_MASK_TEXTURE_FORMAT,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+        );
+
+        commands
+            .entity(entity)
+            .insert(EdgeDetectionAaTexture(texture));
+    }
+}
+
+/// The pipeline anti-aliasing the edge mask. It isn't specialized.
+#[derive(Resource)]
+pub struct EdgeAaPipeline {
+    pub layout: BindGroupLayout,
+    pub sampler: Sampler,
+    pub pipeline: CachedRenderPipelineId,
+}
+
+impl FromWorld for EdgeAaPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let filterable = world.resource::<EdgeDetectionSupport>().float_filtering;
+        let render_device = world.resource::<RenderDevice>();
+
+        // Without float filtering, the edge mask is sampled at the nearest texels.
+        let (sampler_binding, filter) = match filterable {
+            true => (SamplerBindingType::Filtering, FilterMode::Linear),
+            false => (SamplerBindingType::NonFiltering, FilterMode::Nearest),
+        };
+
+        let layout = render_device.create_bind_group_layout(
+            "edge_detection: aa_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    // edge mask
+                    texture_2d(TextureSampleType::Float { filterable }),
+                    // edge mask sampler
+                    sampler(sampler_binding),
+                ),
+            ),
+        );
+
+        let sampler = render_device.create_sampler(&SamplerDescriptor {
+            label: Some("edge detection aa sampler"),
+            mag_filter: filter,
+            min_filter: filter,
+            ..default()
+        });
+
+        let pipeline =
+            world
+                .resource::<PipelineCache>()
+                .queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("edge_detection: aa_pipeline".into()),
+                    layout: vec![layout.clone()],
+                    vertex: fullscreen_shader_vertex_state(),
+                    fragment: Some(FragmentState {
+                        shader: EDGE_AA_SHADER_HANDLE,
+                        shader_defs: vec![],
+                        entry_point: "fragment".into(),
+                        targets: vec![Some(ColorTargetState {
+                            format: EDGE_MASK_TEXTURE_FORMAT,
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: default(),
+                    depth_stencil: None,
+                    multisample: default(),
+                    push_constant_ranges: vec![],
+                    zero_initialize_workgroup_memory: false,
+                });
+
+        Self {
+            layout,
+            sampler,
+            pipeline,
+        }
+    }
+}
+
+impl EdgeAaPipeline {
+    /// Returns the compiled pipeline, or `None` if it isn't ready to run yet.
+    pub fn get_pipeline<'w>(
+        &self,
+        pipeline_cache: &'w PipelineCache,
+    ) -> Option<&'w RenderPipeline> {
+        pipeline_cache.get_render_pipeline(self.pipeline)
+    }
+
+    /// Anti-aliases the edge mask into `output` with the pipeline from [`Self::get_pipeline`] and returns its view.
+    pub fn run<'a>(
+        &self,
+        render_context: &mut RenderContext,
+        pipeline: &RenderPipeline,
+        mask_view: &TextureView,
+        output: &'a EdgeDetectionAaTexture,
+    ) -> &'a TextureView {
+        let bind_group = render_context.render_device().create_bind_group(
+            "edge_detection_aa_bind_group",
+            &self.layout,
+            &BindGroupEntries::sequential((mask_view, &self.sampler)),
+        );
+
+        let output_view = &output.0.default_view;
+        let diagnostics = render_context.diagnostic_recorder();
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("edge_detection_aa_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: output_view,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        let pass_span = diagnostics.pass_span(&mut render_pass, "aa");
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+        pass_span.end(&mut render_pass);
+
+        output_view
+    }
+}
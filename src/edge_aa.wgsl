@@ -0,0 +1,64 @@
+//! Anti-aliasing of the edge mask, before it's composited onto the screen.
+//!
+//! A small FXAA-style filter blurring the mask along the direction of its contours, with the opacity
+//! of the edges as luma. Only the mask is filtered, so the scene stays sharp.
+
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+// rgb: edge color; a: edge opacity
+@group(0) @binding(0) var edge_mask_texture: texture_2d<f32>;
+@group(0) @binding(1) var edge_mask_sampler: sampler;
+
+// Bounds the blur along the contours, in pixels.
+const SPAN_MAX: f32 = 8.0;
+// Reduces the blur along the contours in low contrast areas.
+const REDUCE_MUL: f32 = 1.0 / 8.0;
+const REDUCE_MIN: f32 = 1.0 / 128.0;
+
+/// Samples the mask with the edge color premultiplied by its opacity, so the blur doesn't bleed
+/// the color of the transparent texels into the edges.
+fn sample_premultiplied(uv: vec2f) -> vec4f {
+    let edges = textureSampleLevel(edge_mask_texture, edge_mask_sampler, uv, 0.0);
+    return vec4f(edges.rgb * edges.a, edges.a);
+}
+
+fn opacity_at(uv: vec2f, offset: vec2f, texel_size: vec2f) -> f32 {
+    return textureSampleLevel(edge_mask_texture, edge_mask_sampler, uv + offset * texel_size, 0.0).a;
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4f {
+    let texel_size = 1.0 / vec2f(textureDimensions(edge_mask_texture));
+    let uv = in.uv;
+
+    let center = opacity_at(uv, vec2f(0.0), texel_size);
+    let nw = opacity_at(uv, vec2f(-1.0, -1.0), texel_size);
+    let ne = opacity_at(uv, vec2f(1.0, -1.0), texel_size);
+    let sw = opacity_at(uv, vec2f(-1.0, 1.0), texel_size);
+    let se = opacity_at(uv, vec2f(1.0, 1.0), texel_size);
+
+    let opacity_min = min(center, min(min(nw, ne), min(sw, se)));
+    let opacity_max = max(center, max(max(nw, ne), max(sw, se)));
+
+    // The direction of the contour, perpendicular to the opacity gradient.
+    var direction = vec2f(-((nw + ne) - (sw + se)), (nw + sw) - (ne + se));
+    let reduce = max((nw + ne + sw + se) * 0.25 * REDUCE_MUL, REDUCE_MIN);
+    let scale = 1.0 / (min(abs(direction.x), abs(direction.y)) + reduce);
+    direction = clamp(direction * scale, vec2f(-SPAN_MAX), vec2f(SPAN_MAX)) * texel_size;
+
+    let inner = 0.5 * (
+        sample_premultiplied(uv + direction * (1.0 / 3.0 - 0.5)) +
+        sample_premultiplied(uv + direction * (2.0 / 3.0 - 0.5))
+    );
+    let outer = inner * 0.5 + 0.25 * (
+        sample_premultiplied(uv - direction * 0.5) +
+        sample_premultiplied(uv + direction * 0.5)
+    );
+
+    // The wider blur overshoots when it crosses another contour, the narrower one is kept then.
+    let filtered = select(outer, inner, outer.a < opacity_min || outer.a > opacity_max);
+    if filtered.a <= 0.0 {
+        return vec4f(0.0);
+    }
+    return vec4f(filtered.rgb / filtered.a, filtered.a);
+}
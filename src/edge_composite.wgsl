@@ -0,0 +1,326 @@
+//! Composites the edge mask written by the edge detection pass onto the screen.
+//!
+//! The mask is post-filtered here, e.g. to round the corners of thick edges.
+
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+#import bevy_edge_detection::common::{
+    EdgeDetectionUniform, blend_edge, edge_over, in_viewport, luminance, output_alpha, posterize, sharpen,
+}
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+// rgb: edge color; a: edge opacity
+@group(0) @binding(1) var edge_mask_texture: texture_2d<f32>;
+@group(0) @binding(2) var<uniform> ed_uniform: EdgeDetectionUniform;
+#ifdef THICK_OUTLINE
+// xy: pixel coordinates of the nearest edge pixel, 0xffff if there's none in range
+@group(0) @binding(3) var jump_flood_texture: texture_2d<u32>;
+#endif
+#ifdef REPROJECT
+#ifdef MULTISAMPLED
+@group(0) @binding(4) var motion_vector_prepass_texture: texture_multisampled_2d<f32>;
+#else
+@group(0) @binding(4) var motion_vector_prepass_texture: texture_2d<f32>;
+#endif
+#endif
+
+// The size of a pixel of the edge mask in pixels of the screen, below 1.0 when the edges are detected
+// at a lower resolution. The radii of the post-filters are in pixels of the screen.
+var<private> mask_scale: f32 = 1.0;
+
+// -----------------------
+// Upsampling ------------
+// -----------------------
+
+#ifdef SCALED
+/// Returns the pixel of the edge mask covering the pixel of the screen.
+fn mask_pixel_coord(pixel_coord: vec2i) -> vec2i {
+    let ratio = vec2f(textureDimensions(edge_mask_texture)) / vec2f(textureDimensions(screen_texture));
+    return vec2i((vec2f(pixel_coord) + 0.5) * ratio);
+}
+
+/// Interpolates the edge mask bilinearly at the pixel of the screen. The colors are weighted by
+/// the opacities, so the transparent pixels around the edges don't darken them.
+fn upsample_edges(pixel_coord: vec2i) -> vec4f {
+    let mask_size = vec2i(textureDimensions(edge_mask_texture));
+    let ratio = vec2f(mask_size) / vec2f(textureDimensions(screen_texture));
+    let position = (vec2f(pixel_coord) + 0.5) * ratio - 0.5;
+    let origin = vec2i(floor(position));
+    let weights = position - floor(position);
+
+    var color = vec3f(0.0);
+    var opacity = 0.0;
+    for (var y = 0; y <= 1; y++) {
+        for (var x = 0; x <= 1; x++) {
+            let neighbor = textureLoad(edge_mask_texture, clamp(origin + vec2i(x, y), vec2i(0), mask_size - 1), 0);
+            let weight = mix(1.0 - weights.x, weights.x, f32(x)) * mix(1.0 - weights.y, weights.y, f32(y));
+            color += neighbor.rgb * neighbor.a * weight;
+            opacity += neighbor.a * weight;
+        }
+    }
+
+    return vec4f(color / max(opacity, 1e-4), opacity);
+}
+#endif
+
+// -----------------------
+// Reprojection ----------
+// -----------------------
+
+#ifdef REPROJECT
+// The distance in pixels from which the reprojected edges fade out, they're dropped at twice this distance,
+// so fast motion doesn't smear them across the screen.
+const REPROJECTION_FADE_DISTANCE: f32 = 32.0;
+
+struct Reprojection {
+    // The pixel of the edge mask in the previous frame.
+    coord: vec2i,
+    // The opacity of the reprojected edges.
+    weight: f32,
+}
+
+/// Follows the motion vector of the pixel back to the edge mask of the previous frame,
+/// on the frames the edges aren't detected.
+fn reproject(pixel_coord: vec2i) -> Reprojection {
+    if ed_uniform.reproject == 0u {
+        return Reprojection(pixel_coord, 1.0);
+    }
+
+    let size = vec2f(textureDimensions(edge_mask_texture));
+    // The motion vectors are in uv units, from the previous position to the current one.
+    let motion = textureLoad(motion_vector_prepass_texture, pixel_coord, 0).xy * size;
+    let previous = vec2f(pixel_coord) + 0.5 - motion;
+
+    // The ghosting is clamped: there's no history outside the screen, and fast motion fades the edges.
+    let on_screen = all(previous >= vec2f(0.0)) && all(previous < size);
+    let weight = 1.0 - smoothstep(REPROJECTION_FADE_DISTANCE, 2.0 * REPROJECTION_FADE_DISTANCE, length(motion));
+    return Reprojection(vec2i(floor(previous)), select(0.0, weight, on_screen));
+}
+#endif
+
+// -----------------------
+// Corner Rounding -------
+// -----------------------
+
+#ifdef CORNER_ROUNDING
+// Bounds the radius of the disc the edges are blurred by, in pixels.
+const MAX_CORNER_ROUNDING: f32 = 8.0;
+const PI: f32 = 3.141592653589793;
+
+/// Antialiased coverage of the pixel at `offset` by a disc of `radius` centered on the pixel being shaded.
+fn disc_weight(offset: vec2i, radius: f32) -> f32 {
+    return saturate(radius + 0.5 - length(vec2f(offset)));
+}
+
+/// Blurs the edge mask by a disc and re-thresholds it at half its opacity, which rounds the convex corners
+/// and fills the concave joins, while the straight sides of the edges, and so their width, stay in place.
+/// A disc wider than an edge would thin it, so its radius is at most half the local width of the edges,
+/// estimated from their area in the disc of the rounding radius.
+fn round_corners(pixel_coord: vec2i) -> vec4f {
+    let max_radius = min(ed_uniform.corner_rounding * mask_scale, MAX_CORNER_ROUNDING);
+    let extent = i32(ceil(max_radius));
+    let max_coord = vec2i(textureDimensions(edge_mask_texture)) - 1;
+    let edges = textureLoad(edge_mask_texture, pixel_coord, 0);
+
+    var area = 0.0;
+    var max_opacity = 0.0;
+    for (var y = -extent; y <= extent; y++) {
+        for (var x = -extent; x <= extent; x++) {
+            let weight = disc_weight(vec2i(x, y), max_radius);
+            if weight <= 0.0 {
+                continue;
+            }
+
+            let opacity = textureLoad(edge_mask_texture, clamp(pixel_coord + vec2i(x, y), vec2i(0), max_coord), 0).a;
+            area += opacity * weight;
+            max_opacity = max(max_opacity, opacity);
+        }
+    }
+    if max_opacity <= 0.0 {
+        return edges;
+    }
+
+    // An edge of width w crosses the disc over about 2 * max_radius * w pixels.
+    let radius = min(max_radius, area / max_opacity / (4.0 * max_radius));
+    // The edges of a pixel or two are already as round as they can be.
+    if radius < 1.0 {
+        return edges;
+    }
+
+    var color = vec3f(0.0);
+    var coverage = 0.0;
+    var disc_area = 0.0;
+    let radius_extent = i32(ceil(radius));
+    for (var y = -radius_extent; y <= radius_extent; y++) {
+        for (var x = -radius_extent; x <= radius_extent; x++) {
+            let weight = disc_weight(vec2i(x, y), radius);
+            if weight <= 0.0 {
+                continue;
+            }
+
+            let neighbor = textureLoad(edge_mask_texture, clamp(pixel_coord + vec2i(x, y), vec2i(0), max_coord), 0);
+            color += neighbor.rgb * neighbor.a * weight;
+            coverage += neighbor.a * weight;
+            disc_area += weight;
+        }
+    }
+
+    // Across a straight side, the coverage of the disc changes by 4 / (PI * radius) per pixel,
+    // so the threshold is antialiased over a pixel.
+    let threshold = (coverage / (disc_area * max_opacity) - 0.5) * PI * radius / 4.0;
+    return vec4f(color / max(coverage, 1e-4), saturate(0.5 + threshold) * max_opacity);
+}
+#endif
+
+// -----------------------
+// Thick Outline ---------
+// -----------------------
+
+#ifdef THICK_OUTLINE
+/// Covers the pixels within the outline radius of the nearest edge pixel.
+fn thick_outline(pixel_coord: vec2i) -> vec4f {
+    let nearest = textureLoad(jump_flood_texture, pixel_coord, 0).xy;
+    // No edge pixel was found, see `JUMP_FLOOD_NO_SEED`.
+    if nearest.x == 0xffffu {
+        return vec4f(0.0);
+    }
+
+    let distance = length(vec2f(nearest) - vec2f(pixel_coord)) / mask_scale;
+    let coverage = saturate(ed_uniform.thick_outline_radius + 0.5 - distance);
+    return vec4f(ed_uniform.thick_outline_color.rgb, coverage * ed_uniform.thick_outline_color.a);
+}
+#endif
+
+// -----------------------
+// Adjacent Grading ------
+// -----------------------
+
+#ifdef ADJACENT_GRADING
+// Bounds the search of the edges next to a pixel, in pixels.
+const MAX_ADJACENT_GRADING_WIDTH: f32 = 8.0;
+
+/// Dilates the edge mask over the grading width, with a linear falloff. Only the strongest neighbor is kept,
+/// so the halos of close edges don't add up.
+fn dilate_edges(pixel_coord: vec2i) -> f32 {
+    let width = min(ed_uniform.adjacent_grading.x * mask_scale, MAX_ADJACENT_GRADING_WIDTH);
+    let extent = i32(ceil(width));
+    let max_coord = vec2i(textureDimensions(edge_mask_texture)) - 1;
+
+    var dilated = 0.0;
+    for (var y = -extent; y <= extent; y++) {
+        for (var x = -extent; x <= extent; x++) {
+            let falloff = 1.0 - length(vec2f(f32(x), f32(y))) / (width + 1.0);
+            if falloff <= 0.0 {
+                continue;
+            }
+
+            let neighbor = textureLoad(edge_mask_texture, clamp(pixel_coord + vec2i(x, y), vec2i(0), max_coord), 0);
+            dilated = max(dilated, neighbor.a * falloff);
+        }
+    }
+
+    return dilated;
+}
+
+/// Desaturates and lightens the scene color by the `halo` beside the edges.
+/// Bright HDR colors are lightened towards white without being darkened.
+fn grade_adjacent(color: vec3f, halo: f32) -> vec3f {
+    let desaturated = mix(color, vec3f(luminance(color)), ed_uniform.adjacent_grading.y * halo);
+    return mix(desaturated, max(desaturated, vec3f(1.0)), ed_uniform.adjacent_grading.z * halo);
+}
+#endif
+
+// -----------------------
+// Sharpening ------------
+// -----------------------
+
+#ifdef SHARPEN
+/// Sharpens the scene color by the strength of the edges, instead of drawing them.
+fn sharpen_scene(pixel_coord: vec2i, color: vec3f, strength: f32) -> vec3f {
+    let max_coord = vec2i(textureDimensions(screen_texture)) - 1;
+    let north = textureLoad(screen_texture, clamp(pixel_coord - vec2i(0, 1), vec2i(0), max_coord), 0).rgb;
+    let south = textureLoad(screen_texture, clamp(pixel_coord + vec2i(0, 1), vec2i(0), max_coord), 0).rgb;
+    let east = textureLoad(screen_texture, clamp(pixel_coord + vec2i(1, 0), vec2i(0), max_coord), 0).rgb;
+    let west = textureLoad(screen_texture, clamp(pixel_coord - vec2i(1, 0), vec2i(0), max_coord), 0).rgb;
+    return sharpen(color, north, south, east, west, strength * ed_uniform.sharpen_amount);
+}
+#endif
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4f {
+    let pixel_coord = vec2i(in.position.xy);
+    let scene = textureLoad(screen_texture, pixel_coord, 0);
+#ifdef BLUEPRINT
+    // The scene is replaced by a flat background, only its edges are drawn.
+    var color = ed_uniform.blueprint_background.rgb;
+#else
+    var color = scene.rgb;
+#endif
+
+    // The pixels of the other cameras drawing to the target, e.g. in split-screen, are left as is.
+    if ed_uniform.viewport.z > 0.0 && !in_viewport(in.position.xy, ed_uniform.viewport) {
+        return scene;
+    }
+
+#ifdef REPROJECT
+    let reprojection = reproject(pixel_coord);
+    let mask_coord = reprojection.coord;
+#else ifdef SCALED
+    mask_scale = f32(textureDimensions(edge_mask_texture).x) / f32(textureDimensions(screen_texture).x);
+    let mask_coord = mask_pixel_coord(pixel_coord);
+#else
+    let mask_coord = pixel_coord;
+#endif
+
+#ifdef CORNER_ROUNDING
+    var edges = round_corners(mask_coord);
+#else ifdef SCALED
+    var edges = upsample_edges(pixel_coord);
+#else
+    var edges = textureLoad(edge_mask_texture, mask_coord, 0);
+#endif
+
+#ifdef THICK_OUTLINE
+    // The thick outline is laid under the edges.
+    edges = edge_over(edges, thick_outline(mask_coord));
+#endif
+
+#ifdef REPROJECT
+    let mask_weight = reprojection.weight;
+#else
+    let mask_weight = 1.0;
+#endif
+    edges.a *= mask_weight;
+
+#ifdef DEBUG_VIEW
+    // The mask holds the debug view of `EdgeDetection::debug_view`, which replaces the scene.
+    return vec4f(edges.rgb, 1.0);
+#else ifdef EDGES_ONLY
+    // The scene is dropped, e.g. for the edges to be composited over another camera.
+    return vec4f(edges.rgb, edges.a * ed_uniform.intensity);
+#else
+#ifdef SHARPEN
+    color = sharpen_scene(pixel_coord, color, edges.a);
+#endif
+
+#ifdef POSTERIZE
+    color = posterize(color, ed_uniform.posterize_levels, ed_uniform.posterize_preserve_hue != 0u);
+#endif
+
+#ifdef ADJACENT_GRADING
+    // The halo is the dilated edges minus the edges themselves, the line is drawn over the graded color.
+    let halo = saturate(dilate_edges(mask_coord) * mask_weight - edges.a);
+    color = grade_adjacent(color, halo);
+#endif
+
+#ifdef INVERTED
+    // The edge color is laid everywhere but on the edges, where the scene shows through.
+    let fill = ed_uniform.edge_color;
+    color = blend_edge(color, fill.rgb, (1.0 - edges.a) * fill.a, ed_uniform.blend_mode);
+#else ifndef SHARPEN
+    color = blend_edge(color, edges.rgb, edges.a, ed_uniform.blend_mode);
+#endif
+    // The whole effect fades in from the untouched scene.
+    let intensity = ed_uniform.intensity;
+    return vec4f(mix(scene.rgb, color, intensity), output_alpha(scene.a, edges.a * intensity));
+#endif
+}
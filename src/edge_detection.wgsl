@@ -1,85 +1,142 @@
 //! Edge Detection using 3x3 Sobel Filter
 //!
-//! This shader implements edge detection based on depth, normal, and color gradients using a 3x3 Sobel filter.
+//! This shader implements edge detection based on depth, normal, and color gradients using a 3x3 Sobel filter,
+//! measured at up to `KERNEL_RADIUS` thicknesses.
 //! It combines the results of depth, normal, and color edge detection to produce a final edge map.
 
 #import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
-#import bevy_render::view::View
-#import bevy_pbr::view_transformations::uv_to_ndc
-
-@group(0) @binding(0) var screen_texture: texture_2d<f32>;
-
-#ifdef MULTISAMPLED
-@group(0) @binding(1) var depth_prepass_texture: texture_depth_multisampled_2d;
-#else
-@group(0) @binding(1) var depth_prepass_texture: texture_depth_2d;
+#import bevy_pbr::{
+    mesh_view_bindings::view,
+    view_transformations::{depth_ndc_to_view_z, position_ndc_to_world, uv_to_ndc},
+}
+#ifdef ENABLE_MATERIAL
+#import bevy_pbr::pbr_deferred_types::unpack_unorm4x8_
 #endif
-
-#ifdef MULTISAMPLED
-@group(0) @binding(2) var normal_prepass_texture: texture_multisampled_2d<f32>;
-#else
-@group(0) @binding(2) var normal_prepass_texture: texture_2d<f32>;
+#ifdef DEFERRED_NORMALS
+#import bevy_pbr::{pbr_deferred_types::unpack_24bit_normal, utils::octahedral_decode}
+#endif
+#import bevy_edge_detection::common::{
+    F32_MAX, blend_edge, edge_over, finite_or, in_viewport, is_finite, luminance, output_alpha, posterize,
+    sanitize_depth, sanitize_normal, sharpen, srgb_to_linear,
+}
+#ifdef EDGE_SOURCES
+#import bevy_edge_detection::edge_sources
 #endif
 
-@group(0) @binding(3) var texture_sampler: sampler;
-
-@group(0) @binding(4) var noise_texture: texture_2d<f32>;
-@group(0) @binding(5) var noise_sampler: sampler;
-
-@group(0) @binding(6) var<uniform> view: View;
-@group(0) @binding(7) var<uniform> ed_uniform: EdgeDetectionUniform;
-
-struct EdgeDetectionUniform {
-    depth_threshold: f32,
-    normal_threshold: f32,
-    color_threshold: f32,
-    depth_thickness: f32,
-    normal_thickness: f32,
-    color_thickness: f32,
-    steep_angle_threshold: f32,
-    steep_angle_multiplier: f32,
-
-    // xy: distortion frequency; zw: distortion strength
-    uv_distortion: vec4f,
-    edge_color: vec4f,
+#import bevy_edge_detection::bindings::{
+    ambient_occlusion_texture, calibration_histograms, deferred_prepass_texture, depth_prepass_texture, ed_uniform,
+    edge_coverage, mesh_tag_depth_texture, mesh_tag_texture, motion_vector_prepass_texture, noise_sampler,
+    noise_texture, normal_prepass_texture, screen_mask_texture, screen_sampler, screen_texture, shadow_mask_texture,
+    stencil_prepass_texture, texture_sampler,
 }
 
 // -----------------------
 // View Transformation ---
 // -----------------------
 
-/// Retrieve the perspective camera near clipping plane
-fn perspective_camera_near() -> f32 {
-    return view.clip_from_view[3][2];
+/// Returns the texel of the prepass textures at `uv`, clamped to the texture. Loads outside of it return zero
+/// on some backends, which would read as far depth and draw edges along the borders, e.g. on the seams
+/// of the faces of a cubemap rendered by six cameras.
+///
+/// The texel is also clamped to the viewport of the camera, so the kernels along its borders don't read the pixels
+/// of the other cameras drawing to the target, e.g. in split-screen.
+fn uv_to_pixel_coord(uv: vec2f) -> vec2i {
+    let viewport_min = max(vec2i(view.viewport.xy), vec2i(0));
+    let viewport_max = min(vec2i(view.viewport.xy + view.viewport.zw), vec2i(texture_size)) - 1;
+    return clamp(vec2i(floor(uv * texture_size)), viewport_min, viewport_max);
 }
 
-/// Convert ndc depth to linear view z. 
-/// Note: Depth values in front of the camera will be negative as -z is forward
-fn depth_ndc_to_view_z(ndc_depth: f32) -> f32 {
-#ifdef VIEW_PROJECTION_PERSPECTIVE
-    return -perspective_camera_near() / ndc_depth;
-#else ifdef VIEW_PROJECTION_ORTHOGRAPHIC
-    return -(view.clip_from_view[3][2] - ndc_depth) / view.clip_from_view[2][2];
-#else
-    let view_pos = view.view_from_clip * vec4(0.0, 0.0, ndc_depth, 1.0);
-    return view_pos.z / view_pos.w;
-#endif
+/// Clamps `uv` to the centers of the texels of the viewport, like `uv_to_pixel_coord`, for the filtered samples.
+fn clamp_to_viewport(uv: vec2f) -> vec2f {
+    return clamp(uv, (view.viewport.xy + 0.5) * texel_size, (view.viewport.xy + view.viewport.zw - 0.5) * texel_size);
 }
 
-/// Convert a ndc space position to world space
-fn position_ndc_to_world(ndc_pos: vec3<f32>) -> vec3<f32> {
-    let world_pos = view.world_from_clip * vec4(ndc_pos, 1.0);
-    return world_pos.xyz / world_pos.w;
+/// Moves `uv` to the prepass textures rendered with the TAA jitter of the projection, so the edges detected
+/// after the TAA resolve stay put instead of wobbling by a pixel every frame. The jitter is zero before the resolve.
+fn unjitter(uv: vec2f) -> vec2f {
+    return uv - ed_uniform.jitter * texel_size;
+}
+
+#ifdef NON_FILTERABLE
+/// Loads the nearest texel of `texture` at `uv`, on the platforms where the float textures can't be filtered.
+fn load_nearest(texture: texture_2d<f32>, uv: vec2f) -> vec4f {
+    let size = vec2i(textureDimensions(texture));
+    return textureLoad(texture, clamp(vec2i(floor(uv * vec2f(size))), vec2i(0), size - 1), 0);
+}
+#endif
+
+/// Whether the view uses an orthographic projection, inferred from the projection matrix.
+/// Only used when the projection type of the view is unknown on the CPU side.
+fn view_is_orthographic() -> bool {
+    return view.clip_from_view[3].w == 1.0;
 }
 
 fn calculate_view(world_position: vec3f) -> vec3f {
 #ifdef VIEW_PROJECTION_ORTHOGRAPHIC
         // Orthographic view vector
     return normalize(vec3f(view.clip_from_world[0].z, view.clip_from_world[1].z, view.clip_from_world[2].z));
-#else
+#else ifdef VIEW_PROJECTION_PERSPECTIVE
         // Only valid for a perspective projection
     return normalize(view.world_position.xyz - world_position.xyz);
+#else
+    if view_is_orthographic() {
+        return normalize(vec3f(view.clip_from_world[0].z, view.clip_from_world[1].z, view.clip_from_world[2].z));
+    }
+    return normalize(view.world_position.xyz - world_position.xyz);
+#endif
+}
+
+// -----------------------
+// Operators -------------
+// -----------------------
+
+// The weights of the side and center rows or columns of the 3x3 kernels, normalized to sum to 4.0
+// like the Sobel kernel, so a sharp step has the same gradient whatever the operator.
+#ifdef OPERATOR_PREWITT
+const KERNEL_SIDE: f32 = 4.0 / 3.0;
+const KERNEL_CENTER: f32 = 4.0 / 3.0;
+#else ifdef OPERATOR_SCHARR
+const KERNEL_SIDE: f32 = 0.75;
+const KERNEL_CENTER: f32 = 2.5;
+#else
+const KERNEL_SIDE: f32 = 1.0;
+const KERNEL_CENTER: f32 = 2.0;
+#endif
+
+#ifdef OPERATOR_ROBERTS_CROSS
+// The weight of the differences along the diagonals of the Roberts cross, normalized like the 3x3 kernels.
+const ROBERTS_CROSS_WEIGHT: f32 = 4.0;
+
+/// The offset of the ends of the diagonal of the Roberts cross from the pixel, in uv.
+fn diagonal_offset(thickness: f32) -> vec2f {
+    return texel_size * thickness;
+}
+
+/// The offset of the ends of the anti-diagonal of the Roberts cross from the pixel, in uv.
+fn anti_diagonal_offset(thickness: f32) -> vec2f {
+    return texel_size * vec2f(-thickness, thickness);
+}
 #endif
+
+// The number of rings of samples of the kernels, `EdgeDetection::kernel_radius`. The 3x3 kernels are measured
+// at evenly spaced thicknesses up to the thickness of the source, one per ring, so the pixels between
+// the center and the outer samples are sampled too and the thick lines stay solid.
+const KERNEL_RADIUS: u32 = #{KERNEL_RADIUS}u;
+
+/// The thickness of the ring `ring` of the kernel, from `thickness / KERNEL_RADIUS` for the innermost ring
+/// to `thickness` for the outermost, without going below one texel unless it was already thinner.
+fn ring_thickness(thickness: f32, ring: u32) -> f32 {
+    return scaled_thickness(thickness, f32(ring) / f32(KERNEL_RADIUS));
+}
+
+/// Returns the edge intensity of `gradient`: 1.0 above `threshold` without `softness`, or a smoothstep
+/// from `threshold - softness` to `threshold + softness` otherwise, so the edges fade in instead of popping.
+/// The band starts at zero at the lowest, so the flat areas don't get faint edges from a wide softness.
+fn threshold_edge(gradient: f32, threshold: f32, softness: f32) -> f32 {
+    if softness <= 0.0 {
+        return f32(gradient > threshold);
+    }
+    return smoothstep(max(threshold - softness, 0.0), threshold + softness, gradient);
 }
 
 // -----------------------
@@ -87,20 +144,121 @@ fn calculate_view(world_position: vec3f) -> vec3f {
 // -----------------------
 
 fn prepass_depth(uv: vec2f) -> f32 {
-#ifdef MULTISAMPLED
-    let pixel_coord = vec2i(uv * texture_size);
-    let depth = textureLoad(depth_prepass_texture, pixel_coord, sample_index_i);
+#ifdef COMPUTE
+#ifdef DEPTH_TEXTURE
+    let cached = cache_index(uv);
+    if cached >= 0 {
+        return sanitize_depth(depth_cache[cached], center_depth);
+    }
+#endif
+#endif
+    // A NaN written by a broken shader would poison the whole kernel around it, so it's replaced by the center.
+    return sanitize_depth(sample_depth(uv), center_depth);
+}
+
+fn sample_depth(frame_uv: vec2f) -> f32 {
+    let uv = clamp_to_viewport(unjitter(frame_uv));
+#ifndef DEPTH_TEXTURE
+    let depth = 0.0;
+#else ifdef ALL_SAMPLES
+    // Use the nearest depth across the samples (reverse z), so thin features covering only some samples are kept.
+    let pixel_coord = uv_to_pixel_coord(uv);
+    var depth = 0.0;
+    for (var i = 0; i < i32(#{SAMPLE_COUNT}); i++) {
+        depth = max(depth, load_depth_sample(pixel_coord, i));
+    }
+#else ifdef MULTISAMPLED
+    let pixel_coord = uv_to_pixel_coord(uv);
+    let depth = load_depth_sample(pixel_coord, sample_index_i);
+#else ifdef NON_FILTERABLE
+    let depth = load_nearest(depth_prepass_texture, uv).r;
 #else
-    let depth = textureSample(depth_prepass_texture, texture_sampler, uv);
+    let depth = textureSampleLevel(depth_prepass_texture, texture_sampler, uv, 0);
 #endif
     return depth;
 }
 
+#ifdef MULTISAMPLED
+#ifdef DEPTH_TEXTURE
+fn load_depth_sample(pixel_coord: vec2i, sample_index: i32) -> f32 {
+#ifdef NON_FILTERABLE
+    return textureLoad(depth_prepass_texture, pixel_coord, sample_index).r;
+#else
+    return textureLoad(depth_prepass_texture, pixel_coord, sample_index);
+#endif
+}
+#endif
+#endif
+
+/// The linear view z of the surface at `uv`, negative in front of the camera.
+/// The VIEW_PROJECTION_* shader defs pick the fast path of the projection, otherwise the depth is unprojected
+/// with the inverse projection matrix, which is valid for any projection.
 fn prepass_view_z(uv: vec2f) -> f32 {
     let depth = prepass_depth(uv);
     return depth_ndc_to_view_z(depth);
 }
 
+/// The view distance of the nearest surface in the 3x3 footprint of the depth kernel around `uv`,
+/// so the silhouette of a near object against a far background is at the distance of the object on both sides.
+fn nearest_view_distance(uv: vec2f) -> f32 {
+    let offset = texel_size * max(ed_uniform.depth_thickness, 1.0);
+    // View z is negative in front of the camera, the nearest surface has the largest.
+    var view_z = -F32_MAX;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            view_z = max(view_z, prepass_view_z(uv + vec2f(f32(x), f32(y)) * offset));
+        }
+    }
+    return -view_z;
+}
+
+/// The height of the view at the view distance `distance`, in world units: it grows with the distance under
+/// perspective, and is the height of the view volume under orthographic projections, whatever the distance.
+/// The depth differences between the neighboring pixels of a slanted surface grow with it.
+fn view_height_at(distance: f32) -> f32 {
+    // 1 / tan(fov / 2) under perspective, 2 / height under orthographic projections.
+    let scale = view.clip_from_view[1][1];
+#ifdef VIEW_PROJECTION_ORTHOGRAPHIC
+    return 2.0 / scale;
+#else ifdef VIEW_PROJECTION_PERSPECTIVE
+    return 2.0 * distance / scale;
+#else
+    if view_is_orthographic() {
+        return 2.0 / scale;
+    }
+    return 2.0 * distance / scale;
+#endif
+}
+
+/// Whether the pixel at `uv` is the background, at the far plane of the reverse-Z depth under any projection.
+/// Without a depth texture, nothing is known to be background.
+fn is_background(uv: vec2f) -> bool {
+#ifdef DEPTH_TEXTURE
+    return prepass_depth(uv) == 0.0;
+#else
+    return false;
+#endif
+}
+
+#ifdef NO_BACKGROUND_EDGES
+/// Whether the background is within `reach` pixels of `uv`, in the footprint of the kernel.
+fn near_background(uv: vec2f, reach: f32) -> bool {
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            if is_background(uv + vec2f(f32(x), f32(y)) * texel_size * reach) {
+                return true;
+            }
+        }
+    }
+    return false;
+}
+#endif
+
+/// Reconstructs the world position of the surface at `uv` from its depth, for any projection.
+fn prepass_world_position(uv: vec2f) -> vec3f {
+    return position_ndc_to_world(vec3f(uv_to_ndc(uv), prepass_depth(uv)));
+}
+
 fn view_z_gradient_x(uv: vec2f, y: f32, thickness: f32) -> f32 {
     let l_coord = uv + texel_size * vec2f(-thickness, y);    // left  coordinate
     let r_coord = uv + texel_size * vec2f(thickness, y);    // right coordinate
@@ -115,42 +273,176 @@ fn view_z_gradient_y(uv: vec2f, x: f32, thickness: f32) -> f32 {
     return prepass_view_z(t_coord) - prepass_view_z(d_coord);
 }
 
+/// The fraction of the depth threshold added on a surface at the view distance `distance`, seen at the `fresnel` angle.
+/// It's scaled by the size of the pixels on the surface rather than its distance, which the depth differences
+/// across a slanted orthographic surface don't depend on.
+fn steep_angle_adjustment(fresnel: f32, distance: f32) -> f32 {
+    return smoothstep(ed_uniform.steep_angle_threshold, 1.0, fresnel) * ed_uniform.steep_angle_multiplier * view_height_at(distance);
+}
+
 fn detect_edge_depth(uv: vec2f, thickness: f32, fresnel: f32) -> f32 {
-    let deri_x = view_z_gradient_x(uv, thickness, thickness) + 2.0 * view_z_gradient_x(uv, 0.0, thickness) + view_z_gradient_x(uv, -thickness, thickness);
+#ifdef OPERATOR_ROBERTS_CROSS
+    let deri_x = ROBERTS_CROSS_WEIGHT * (prepass_view_z(uv + diagonal_offset(thickness)) - prepass_view_z(uv - diagonal_offset(thickness)));
+    let deri_y = ROBERTS_CROSS_WEIGHT * (prepass_view_z(uv + anti_diagonal_offset(thickness)) - prepass_view_z(uv - anti_diagonal_offset(thickness)));
+#else
+    let deri_x = KERNEL_SIDE * view_z_gradient_x(uv, thickness, thickness) + KERNEL_CENTER * view_z_gradient_x(uv, 0.0, thickness) + KERNEL_SIDE * view_z_gradient_x(uv, -thickness, thickness);
 
-    let deri_y = view_z_gradient_y(uv, thickness, thickness) + 2.0 * view_z_gradient_y(uv, 0.0, thickness) + view_z_gradient_y(uv, -thickness, thickness);
+    let deri_y = KERNEL_SIDE * view_z_gradient_y(uv, thickness, thickness) + KERNEL_CENTER * view_z_gradient_y(uv, 0.0, thickness) + KERNEL_SIDE * view_z_gradient_y(uv, -thickness, thickness);
+#endif
 
     // why not `let grad = sqrt(deri_x * deri_x + deri_y * deri_y);`?
     //
     // Because ·deri_x· or ·deri_y· might be too large,
     // causing overflow in the calculation and resulting in incorrect results.
-    let grad = max(abs(deri_x), abs(deri_y));
+    //
+    // The background of a perspective view is at infinity, so the gradients across a silhouette
+    // are infinite or NaN. They're kept as the largest gradient instead of letting NaN fail the comparison.
+    let grad = max(finite_or(abs(deri_x), F32_MAX), finite_or(abs(deri_y), F32_MAX));
 
     let view_z = abs(prepass_view_z(uv));
+    // The background has no depth edges of its own, they're drawn on the side of the surfaces.
+    // It's at infinity under perspective, and at the far plane under orthographic projections.
+    if !is_finite(view_z) || is_background(uv) {
+        return 0.0;
+    }
+
+    let steep_angle = steep_angle_adjustment(fresnel, view_z);
+
+#ifdef AUTO_CALIBRATE
+    // The gradient compared to the depth threshold itself.
+    calibration_depth_gradient = grad / (1.0 + steep_angle);
+#endif
+
+#ifdef ONE_SIDED_DEPTH
+    // The surface behind doesn't get a mirrored line.
+    if !is_nearer_side(uv, thickness, view_z) {
+        return 0.0;
+    }
+#endif
+
+    let steep_angle_scale = 1.0 + steep_angle;
+    let edge = threshold_edge(grad, pixel_thresholds.x * steep_angle_scale, ed_uniform.depth_threshold_softness * steep_angle_scale);
+#ifdef HATCHING
+    if edge > 0.0 {
+        // The infinite gradients across the silhouettes are bounded, keeping their sign, and scaled down
+        // so their squares don't overflow.
+        let gradient = clamp(vec2f(deri_x, deri_y), vec2f(-F32_MAX), vec2f(F32_MAX)) / grad;
+        record_hatch_gradient(vec3f(gradient.x, 0.0, 0.0), vec3f(gradient.y, 0.0, 0.0));
+    }
+#endif
+    return edge;
+}
 
-    let steep_angle_adjustment = smoothstep(ed_uniform.steep_angle_threshold, 1.0, fresnel) * ed_uniform.steep_angle_multiplier * view_z;
+#ifdef ONE_SIDED_DEPTH
+/// The fraction of its distance by which a pixel may be behind the middle of a discontinuity and still be drawn,
+/// so the pixels of coplanar surfaces don't flicker between the sides.
+const ONE_SIDED_DEPTH_BIAS: f32 = 0.01;
 
-    return f32(grad > ed_uniform.depth_threshold * (1.0 + steep_angle_adjustment));
+/// Whether the pixel at `uv`, at `distance` from the camera, is on the nearer side of the depth discontinuity
+/// in the kernel around it, closer than the middle of its nearest and farthest samples.
+fn is_nearer_side(uv: vec2f, thickness: f32, distance: f32) -> bool {
+    var nearest = F32_MAX;
+    var farthest = 0.0;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            // The background at infinity is the farthest there is.
+            let sample = finite_or(abs(prepass_view_z(uv + vec2f(f32(x), f32(y)) * texel_size * thickness)), F32_MAX);
+            nearest = min(nearest, sample);
+            farthest = max(farthest, sample);
+        }
+    }
+    return distance <= mix(nearest, farthest, 0.5) * (1.0 + ONE_SIDED_DEPTH_BIAS);
 }
+#endif
 
 // -----------------------
 // Normal Detection ------
 // -----------------------
 
-fn prepass_normal_unpack(uv: vec2f) -> vec3f {
-    let normal_packed = prepass_normal(uv);
-    return normalize(normal_packed.xyz * 2.0 - vec3(1.0));
+fn prepass_normal(uv: vec2f) -> vec3f {
+#ifndef NORMALS
+    // Neither the normal source nor the steep angle adjustment read the normals.
+    return vec3f(0.0);
+#else
+    // The normals of the background are cleared, or left over, which would draw a second line along
+    // the silhouettes. The neighbors in the background take the normal of the center, like the broken samples.
+    if is_background(uv) {
+        return center_normal;
+    }
+#ifdef GEOMETRIC_NORMALS
+    return geometric_normal(uv);
+#else
+#ifdef COMPUTE
+    let cached = cache_index(uv);
+    if cached >= 0 {
+        return sanitize_normal(normal_cache[cached], center_normal);
+    }
+#endif
+    return sanitize_normal(sample_normal(uv), center_normal);
+#endif
+#endif
 }
 
-fn prepass_normal(uv: vec2f) -> vec3f {
-#ifdef MULTISAMPLED
-    let pixel_coord = vec2i(uv * texture_size);
+#ifdef NORMALS
+#ifndef GEOMETRIC_NORMALS
+fn sample_normal(frame_uv: vec2f) -> vec3f {
+    let uv = clamp_to_viewport(unjitter(frame_uv));
+#ifdef DEFERRED_NORMALS
+    // The G-buffer packs the octahedral encoding of the world normal in the low 24 bits of w,
+    // decoded like the deferred lighting pass does, and encoded like the normal prepass.
+    let gbuffer = textureLoad(deferred_prepass_texture, uv_to_pixel_coord(uv), 0);
+    let normal = vec4f(octahedral_decode(unpack_24bit_normal(gbuffer.a)) * 0.5 + 0.5, 0.0);
+#else ifdef ALL_SAMPLES
+    // Use the normal diverging the most from the first sample, so creases covering only some samples are kept.
+    let pixel_coord = uv_to_pixel_coord(uv);
+    let first = textureLoad(normal_prepass_texture, pixel_coord, 0).xyz;
+    var normal = first;
+    var min_similarity = 2.0;
+    for (var i = 1; i < i32(#{SAMPLE_COUNT}); i++) {
+        let sample = textureLoad(normal_prepass_texture, pixel_coord, i).xyz;
+        let similarity = dot(sample * 2.0 - 1.0, first * 2.0 - 1.0);
+        if similarity < min_similarity {
+            min_similarity = similarity;
+            normal = sample;
+        }
+    }
+#else ifdef MULTISAMPLED
+    let pixel_coord = uv_to_pixel_coord(uv);
     let normal = textureLoad(normal_prepass_texture, pixel_coord, sample_index_i);
+#else ifdef NON_FILTERABLE
+    let normal = load_nearest(normal_prepass_texture, uv);
 #else
-    let normal = textureSample(normal_prepass_texture, texture_sampler, uv);
+    let normal = textureSampleLevel(normal_prepass_texture, texture_sampler, uv, 0.0);
 #endif
     return normal.xyz;
 }
+#endif
+#endif
+
+#ifdef GEOMETRIC_NORMALS
+/// Reconstructs the normal of the geometry at `uv` from the depth of its neighbors, ignoring the detail
+/// of the normal maps in the prepass normals. On each axis, the neighbor closest to the surface is used,
+/// so the normals of the pixels along a silhouette aren't bent towards the background.
+///
+/// The normal is in world space and packed like the prepass normals, so the normal threshold keeps its meaning.
+fn geometric_normal(uv: vec2f) -> vec3f {
+    let center = prepass_world_position(uv);
+    let previous_x = center - prepass_world_position(uv - vec2f(texel_size.x, 0.0));
+    let next_x = prepass_world_position(uv + vec2f(texel_size.x, 0.0)) - center;
+    let previous_y = center - prepass_world_position(uv - vec2f(0.0, texel_size.y));
+    let next_y = prepass_world_position(uv + vec2f(0.0, texel_size.y)) - center;
+
+    let tangent_x = select(next_x, previous_x, dot(previous_x, previous_x) < dot(next_x, next_x));
+    let tangent_y = select(next_y, previous_y, dot(previous_y, previous_y) < dot(next_y, next_y));
+
+    // Faces the camera, like the normals of the rendered surfaces.
+    var normal = normalize(cross(tangent_x, tangent_y));
+    if dot(normal, calculate_view(center)) < 0.0 {
+        normal = -normal;
+    }
+    return normal * 0.5 + 0.5;
+}
+#endif
 
 fn normal_gradient_x(uv: vec2f, y: f32, thickness: f32) -> vec3f {
     let l_coord = uv + texel_size * vec2f(-thickness, y);    // left  coordinate
@@ -166,104 +458,1480 @@ fn normal_gradient_y(uv: vec2f, x: f32, thickness: f32) -> vec3f {
     return prepass_normal(t_coord) - prepass_normal(d_coord);
 }
 
+/// Whether the surface folds outward at `uv`, like the outer edges of a cube, from the normal gradients
+/// `gradient_x` and `gradient_y` measured across `thickness`. The normal turns toward the direction of travel
+/// across a convex crease, and away from it across a concave one.
+fn is_convex(uv: vec2f, gradient_x: vec3f, gradient_y: vec3f, thickness: f32) -> bool {
+#ifdef OPERATOR_ROBERTS_CROSS
+    let offset_x = diagonal_offset(thickness);
+    let offset_y = anti_diagonal_offset(thickness);
+#else
+    let offset_x = texel_size * vec2f(thickness, 0.0);
+    let offset_y = texel_size * vec2f(0.0, thickness);
+#endif
+    let position_x = prepass_world_position(uv + offset_x) - prepass_world_position(uv - offset_x);
+    let position_y = prepass_world_position(uv + offset_y) - prepass_world_position(uv - offset_y);
+    return dot(gradient_x, position_x) + dot(gradient_y, position_y) > 0.0;
+}
+
 fn detect_edge_normal(uv: vec2f, thickness: f32) -> f32 {
-    let deri_x = abs(
-        normal_gradient_x(uv, thickness, thickness) + 2.0 * normal_gradient_x(uv, 0.0, thickness) + normal_gradient_x(uv, -thickness, thickness)
-    );
+#ifdef OPERATOR_ROBERTS_CROSS
+    let kernel_x = ROBERTS_CROSS_WEIGHT * (prepass_normal(uv + diagonal_offset(thickness)) - prepass_normal(uv - diagonal_offset(thickness)));
+    let kernel_y = ROBERTS_CROSS_WEIGHT * (prepass_normal(uv + anti_diagonal_offset(thickness)) - prepass_normal(uv - anti_diagonal_offset(thickness)));
+#else
+    let kernel_x = KERNEL_SIDE * normal_gradient_x(uv, thickness, thickness) + KERNEL_CENTER * normal_gradient_x(uv, 0.0, thickness) + KERNEL_SIDE * normal_gradient_x(uv, -thickness, thickness);
+    let kernel_y = KERNEL_SIDE * normal_gradient_y(uv, thickness, thickness) + KERNEL_CENTER * normal_gradient_y(uv, 0.0, thickness) + KERNEL_SIDE * normal_gradient_y(uv, -thickness, thickness);
+#endif
 
-    let deri_y = abs(
-        normal_gradient_y(uv, thickness, thickness) + 2.0 * normal_gradient_y(uv, 0.0, thickness) + normal_gradient_y(uv, -thickness, thickness)
-    );
+    let deri_x = abs(kernel_x);
+    let deri_y = abs(kernel_y);
 
     let x_max = max(deri_x.x, max(deri_x.y, deri_x.z));
     let y_max = max(deri_y.x, max(deri_y.y, deri_y.z));
 
     let grad = max(x_max, y_max);
 
-    return f32(grad > ed_uniform.normal_threshold);
+#ifdef AUTO_CALIBRATE
+    calibration_normal_gradient = grad;
+#endif
+
+    // The background has no normal edges, its neighbors in the kernel already take the normal of the center.
+    if is_background(uv) {
+        return 0.0;
+    }
+
+    var threshold = pixel_thresholds.y;
+    // The convexity is only measured when the convex creases have their own threshold.
+    if pixel_normal_threshold_convex != threshold && is_convex(uv, kernel_x, kernel_y, thickness) {
+        threshold = pixel_normal_threshold_convex;
+    }
+#ifdef CORNER_EMPHASIS
+    threshold = emphasize_corner(threshold, grad, kernel_x, kernel_y);
+#endif
+
+    let edge = threshold_edge(grad, threshold, ed_uniform.normal_threshold_softness);
+#ifdef HATCHING
+    if edge > 0.0 {
+        record_hatch_gradient(kernel_x, kernel_y);
+    }
+#endif
+    return edge;
+}
+
+#ifdef CORNER_EMPHASIS
+// ----------------------
+// Corner Emphasis ------
+// ----------------------
+
+/// Bounds the corner emphasis, so the corners don't grow into blobs.
+const MAX_CORNER_EMPHASIS: f32 = 8.0;
+
+/// How much the gradients in x and y point in different directions, from 0.0 along straight edges,
+/// where they're parallel, to 1.0 at corners. It's the determinant of the structure tensor
+/// of the gradients over its squared trace, so it doesn't depend on the edge strength.
+fn cornerness(gradient_x: vec3f, gradient_y: vec3f) -> f32 {
+    let xx = dot(gradient_x, gradient_x);
+    let yy = dot(gradient_y, gradient_y);
+    let xy = dot(gradient_x, gradient_y);
+    let trace = xx + yy;
+    return saturate(4.0 * (xx * yy - xy * xy) / max(trace * trace, 1e-12));
+}
+
+/// Lowers `threshold` at the corners, which thickens the edges there.
+/// Only pixels with a gradient close to the threshold are emphasized, so smoothly curved surfaces,
+/// whose normals turn in every direction, aren't outlined.
+fn emphasize_corner(threshold: f32, grad: f32, gradient_x: vec3f, gradient_y: vec3f) -> f32 {
+    let near_edge = smoothstep(0.25, 1.0, grad / max(threshold, 1e-6));
+    let emphasis = min(ed_uniform.corner_emphasis, MAX_CORNER_EMPHASIS) * cornerness(gradient_x, gradient_y) * near_edge;
+    return threshold / (1.0 + emphasis);
 }
+#endif
 
 // ----------------------
 // Color Detection ------
 // ----------------------
 
 fn prepass_color(uv: vec2f) -> vec3f {
-    return textureSample(screen_texture, texture_sampler, uv).rgb;
+#ifdef COMPUTE
+    let cached = cache_index(uv);
+    if cached >= 0 {
+        return color_cache[cached];
+    }
+#endif
+    return sample_color(uv);
+}
+
+fn sample_color(frame_uv: vec2f) -> vec3f {
+    let uv = clamp_to_viewport(frame_uv);
+#ifdef NON_FILTERABLE
+    return load_nearest(screen_texture, uv).rgb;
+#else
+    return textureSampleLevel(screen_texture, screen_sampler, uv, 0.0).rgb;
+#endif
+}
+
+/// Returns the color of the scene under the pixel at `uv`.
+fn load_scene(uv: vec2f) -> vec4f {
+#ifdef NON_FILTERABLE
+    return load_nearest(screen_texture, uv);
+#else
+    return textureSampleLevel(screen_texture, screen_sampler, uv, 0.0);
+#endif
+}
+
+// The color in the space the gradients are measured in, see `ColorEdgeSpace`.
+#ifdef COLOR_SPACE_LUMINANCE
+alias EdgeColor = f32;
+#else
+alias EdgeColor = vec3f;
+#endif
+
+fn edge_color_sample(uv: vec2f) -> EdgeColor {
+#ifdef COLOR_SPACE_LUMINANCE
+    return luminance(linear_color(uv));
+#else ifdef COLOR_SPACE_OKLAB
+    return linear_to_oklab(linear_color(uv));
+#else
+    return prepass_color(uv);
+#endif
+}
+
+/// The scene color at `uv`, decoded to linear values on gamma encoded targets.
+fn linear_color(uv: vec2f) -> vec3f {
+#ifdef GAMMA_ENCODED_TARGET
+    return srgb_to_linear(prepass_color(uv));
+#else
+    return prepass_color(uv);
+#endif
+}
+
+#ifdef COLOR_SPACE_OKLAB
+/// Converts a linear sRGB color to OkLab, see https://bottosson.github.io/posts/oklab/.
+fn linear_to_oklab(color: vec3f) -> vec3f {
+    let lms = mat3x3f(
+        0.4122214708, 0.2119034982, 0.0883024619,
+        0.5363325363, 0.6806995451, 0.2817188376,
+        0.0514459929, 0.1073969566, 0.6299787005,
+    ) * max(color, vec3f(0.0));
+    // `pow` is undefined for zero.
+    let lms_root = pow(max(lms, vec3f(1e-12)), vec3f(1.0 / 3.0));
+    return mat3x3f(
+        0.2104542553, 1.9779984951, 0.0259040371,
+        0.7936177850, -2.4285922050, 0.7827717662,
+        -0.0040720468, 0.4505937099, -0.8086757660,
+    ) * lms_root;
 }
+#endif
 
-fn color_gradient_x(uv: vec2f, y: f32, thickness: f32) -> vec3f {
+fn color_gradient_x(uv: vec2f, y: f32, thickness: f32) -> EdgeColor {
     let l_coord = uv + texel_size * vec2f(-thickness, y);    // left  coordinate
     let r_coord = uv + texel_size * vec2f(thickness, y);    // right coordinate
 
-    return prepass_color(r_coord) - prepass_color(l_coord);
+    return edge_color_sample(r_coord) - edge_color_sample(l_coord);
 }
 
-fn color_gradient_y(uv: vec2f, x: f32, thickness: f32) -> vec3f {
+fn color_gradient_y(uv: vec2f, x: f32, thickness: f32) -> EdgeColor {
     let d_coord = uv + texel_size * vec2f(x, -thickness);    // down coordinate
     let t_coord = uv + texel_size * vec2f(x, thickness);    // top  coordinate
 
-    return prepass_color(t_coord) - prepass_color(d_coord);
+    return edge_color_sample(t_coord) - edge_color_sample(d_coord);
 }
 
 fn detect_edge_color(uv: vec2f, thickness: f32) -> f32 {
-    let deri_x = color_gradient_x(uv, thickness, thickness) + 2.0 * color_gradient_x(uv, 0.0, thickness) + color_gradient_x(uv, -thickness, thickness);
+#ifdef OPERATOR_ROBERTS_CROSS
+    let deri_x = ROBERTS_CROSS_WEIGHT * (edge_color_sample(uv + diagonal_offset(thickness)) - edge_color_sample(uv - diagonal_offset(thickness)));
+    let deri_y = ROBERTS_CROSS_WEIGHT * (edge_color_sample(uv + anti_diagonal_offset(thickness)) - edge_color_sample(uv - anti_diagonal_offset(thickness)));
+#else
+    let deri_x = KERNEL_SIDE * color_gradient_x(uv, thickness, thickness) + KERNEL_CENTER * color_gradient_x(uv, 0.0, thickness) + KERNEL_SIDE * color_gradient_x(uv, -thickness, thickness);
 
-    let deri_y = color_gradient_y(uv, thickness, thickness) + 2.0 * color_gradient_y(uv, 0.0, thickness) + color_gradient_y(uv, -thickness, thickness);
+    let deri_y = KERNEL_SIDE * color_gradient_y(uv, thickness, thickness) + KERNEL_CENTER * color_gradient_y(uv, 0.0, thickness) + KERNEL_SIDE * color_gradient_y(uv, -thickness, thickness);
+#endif
 
     let grad = max(length(deri_x), length(deri_y));
 
-    return f32(grad > ed_uniform.color_threshold);
+    var threshold = pixel_thresholds.z;
+#ifdef CORNER_EMPHASIS
+    // A single channel has no corners, its gradients in x and y are always parallel.
+    threshold = emphasize_corner(threshold, grad, vec3f(deri_x), vec3f(deri_y));
+#endif
+
+    let edge = threshold_edge(grad, threshold, ed_uniform.color_threshold_softness);
+#ifdef HATCHING
+    if edge > 0.0 {
+        record_hatch_gradient(vec3f(deri_x), vec3f(deri_y));
+    }
+#endif
+    return edge;
 }
 
-var<private> texture_size: vec2f;
-var<private> texel_size: vec2f;
-var<private> sample_index_i: i32 = 0;
+#ifdef ENABLE_MATERIAL
+// ----------------------
+// Material Detection ---
+// ----------------------
 
-@fragment
-fn fragment(
-#ifdef MULTISAMPLED
-    @builtin(sample_index) sample_index: u32,
+/// The attributes of the material at `uv` compared by `ed_uniform.material_attributes`, the others are zero:
+/// the base color in the first column, the roughness, metallic and reflectance in the second one.
+fn deferred_material(uv: vec2f) -> mat2x3f {
+    let gbuffer = textureLoad(deferred_prepass_texture, uv_to_pixel_coord(uv), 0);
+    // x: base color (sRGB) and perceptual roughness; z: reflectance, metallic and diffuse occlusion.
+    let base_color_roughness = unpack_unorm4x8_(gbuffer.x);
+    let properties = unpack_unorm4x8_(gbuffer.z);
+
+    let attributes = ed_uniform.material_attributes;
+    return mat2x3f(
+        base_color_roughness.rgb * attributes.x,
+        vec3f(base_color_roughness.a * attributes.y, properties.g * attributes.z, properties.r * attributes.w),
+    );
+}
+
+fn material_gradient_x(uv: vec2f, y: f32, thickness: f32) -> mat2x3f {
+    let l_coord = uv + texel_size * vec2f(-thickness, y);    // left  coordinate
+    let r_coord = uv + texel_size * vec2f(thickness, y);    // right coordinate
+
+    return deferred_material(r_coord) - deferred_material(l_coord);
+}
+
+fn material_gradient_y(uv: vec2f, x: f32, thickness: f32) -> mat2x3f {
+    let d_coord = uv + texel_size * vec2f(x, -thickness);    // down coordinate
+    let t_coord = uv + texel_size * vec2f(x, thickness);    // top  coordinate
+
+    return deferred_material(t_coord) - deferred_material(d_coord);
+}
+
+fn detect_edge_material(uv: vec2f, thickness: f32) -> f32 {
+#ifdef OPERATOR_ROBERTS_CROSS
+    let deri_x = ROBERTS_CROSS_WEIGHT * (deferred_material(uv + diagonal_offset(thickness)) - deferred_material(uv - diagonal_offset(thickness)));
+    let deri_y = ROBERTS_CROSS_WEIGHT * (deferred_material(uv + anti_diagonal_offset(thickness)) - deferred_material(uv - anti_diagonal_offset(thickness)));
+#else
+    let deri_x = KERNEL_SIDE * material_gradient_x(uv, thickness, thickness) + KERNEL_CENTER * material_gradient_x(uv, 0.0, thickness) + KERNEL_SIDE * material_gradient_x(uv, -thickness, thickness);
+
+    let deri_y = KERNEL_SIDE * material_gradient_y(uv, thickness, thickness) + KERNEL_CENTER * material_gradient_y(uv, 0.0, thickness) + KERNEL_SIDE * material_gradient_y(uv, -thickness, thickness);
 #endif
-    in: FullscreenVertexOutput
-) -> @location(0) vec4f {
-#ifdef MULTISAMPLED
-    sample_index_i = i32(sample_index);
+
+    let grad_x = dot(deri_x[0], deri_x[0]) + dot(deri_x[1], deri_x[1]);
+    let grad_y = dot(deri_y[0], deri_y[0]) + dot(deri_y[1], deri_y[1]);
+    let grad = sqrt(max(grad_x, grad_y));
+
+    return f32(grad > ed_uniform.material_threshold);
+}
 #endif
 
-    texture_size = vec2f(textureDimensions(screen_texture));
-    texel_size = 1.0 / texture_size;
+#ifdef ENABLE_SHADOW
+// ----------------------
+// Shadow Detection -----
+// ----------------------
 
-    let near_ndc_pos = vec3f(uv_to_ndc(in.uv), 1.0);
-    let near_world_pos = position_ndc_to_world(near_ndc_pos);
+fn shadow_visibility(uv: vec2f) -> f32 {
+#ifdef NON_FILTERABLE
+    return load_nearest(shadow_mask_texture, uv).r;
+#else
+    return textureSampleLevel(shadow_mask_texture, texture_sampler, uv, 0.0).r;
+#endif
+}
 
-    let view_direction = calculate_view(near_world_pos);
+fn shadow_gradient_x(uv: vec2f, y: f32, thickness: f32) -> f32 {
+    let l_coord = uv + texel_size * vec2f(-thickness, y);    // left  coordinate
+    let r_coord = uv + texel_size * vec2f(thickness, y);    // right coordinate
 
-    let normal = prepass_normal_unpack(in.uv);
-    let fresnel = 1.0 - saturate(dot(normal, view_direction));;
+    return shadow_visibility(r_coord) - shadow_visibility(l_coord);
+}
+
+fn shadow_gradient_y(uv: vec2f, x: f32, thickness: f32) -> f32 {
+    let d_coord = uv + texel_size * vec2f(x, -thickness);    // down coordinate
+    let t_coord = uv + texel_size * vec2f(x, thickness);    // top  coordinate
+
+    return shadow_visibility(t_coord) - shadow_visibility(d_coord);
+}
+
+/// Detects the transitions between lit and shadowed pixels of the shadow mask.
+fn detect_edge_shadow(uv: vec2f, thickness: f32) -> f32 {
+#ifdef OPERATOR_ROBERTS_CROSS
+    let deri_x = ROBERTS_CROSS_WEIGHT * (shadow_visibility(uv + diagonal_offset(thickness)) - shadow_visibility(uv - diagonal_offset(thickness)));
+    let deri_y = ROBERTS_CROSS_WEIGHT * (shadow_visibility(uv + anti_diagonal_offset(thickness)) - shadow_visibility(uv - anti_diagonal_offset(thickness)));
+#else
+    let deri_x = KERNEL_SIDE * shadow_gradient_x(uv, thickness, thickness) + KERNEL_CENTER * shadow_gradient_x(uv, 0.0, thickness) + KERNEL_SIDE * shadow_gradient_x(uv, -thickness, thickness);
+    let deri_y = KERNEL_SIDE * shadow_gradient_y(uv, thickness, thickness) + KERNEL_CENTER * shadow_gradient_y(uv, 0.0, thickness) + KERNEL_SIDE * shadow_gradient_y(uv, -thickness, thickness);
+#endif
+
+    // The kernels weigh 4 across a sharp transition, normalized so a full one is 1.0.
+    let grad = max(abs(deri_x), abs(deri_y)) * 0.25;
+
+    return f32(grad > ed_uniform.shadow_threshold);
+}
+#endif
+
+// ----------------------
+// Stencil Detection ----
+// ----------------------
+
+#ifdef ENABLE_STENCIL
+fn prepass_stencil(pixel_coord: vec2i) -> u32 {
+    let coord = clamp(pixel_coord, vec2i(0), vec2i(texture_size) - 1);
+#ifdef MULTISAMPLED
+    return textureLoad(stencil_prepass_texture, coord, sample_index_i).r;
+#else
+    return textureLoad(stencil_prepass_texture, coord, 0).r;
+#endif
+}
 
-    let sample_uv = in.position.xy * min(texel_size.x, texel_size.y);
-    let noise = textureSample(noise_texture, noise_sampler, sample_uv * ed_uniform.uv_distortion.xy);
-    let uv = in.uv + noise.xy * ed_uniform.uv_distortion.zw;
+fn detect_edge_stencil(uv: vec2f, thickness: f32) -> f32 {
+    // Stencil values are integers and can't be filtered,
+    // so neighbors are compared directly instead of computing a gradient.
+    let center = vec2i(uv * texture_size);
+    let offset = i32(max(round(thickness), 1.0));
+    let stencil = prepass_stencil(center);
 
     var edge = 0.0;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            if prepass_stencil(center + vec2i(x, y) * offset) != stencil {
+                edge = 1.0;
+            }
+        }
+    }
 
-#ifdef ENABLE_DEPTH
-    let edge_depth = detect_edge_depth(uv, ed_uniform.depth_thickness, fresnel);
-    edge = max(edge, edge_depth);
+    return edge;
+}
 #endif
 
-#ifdef ENABLE_NORMAL
-    let edge_normal = detect_edge_normal(uv, ed_uniform.normal_thickness);
-    edge = max(edge, edge_normal);
+// ----------------------
+// Custom Sources -------
+// ----------------------
+
+#ifdef EDGE_SOURCES
+/// The distance between the samples of the custom edge source `index`.
+fn edge_source_offset(index: u32) -> vec2f {
+    return texel_size * ed_uniform.edge_sources[index].y;
+}
+
+fn detect_edge_source(index: u32, gradient: f32) -> f32 {
+    return f32(gradient > ed_uniform.edge_sources[index].x);
+}
+
+/// The edges of the custom edge source `index`, in its own color.
+fn edge_source_edges(index: u32, edge: f32) -> vec4f {
+    let color = ed_uniform.edge_source_colors[index];
+    return vec4f(color.rgb, edge * color.a);
+}
 #endif
 
-#ifdef ENABLE_COLOR
-    let edge_color = detect_edge_color(uv, ed_uniform.color_thickness);
-    edge = max(edge, edge_color);
+// ----------------------
+// Motion Vectors -------
+// ----------------------
+
+#ifdef MOTION_VECTOR_PREPASS
+fn prepass_motion_vector(uv: vec2f) -> vec2f {
+#ifdef MULTISAMPLED
+    let pixel_coord = uv_to_pixel_coord(uv);
+    let motion_vector = textureLoad(motion_vector_prepass_texture, pixel_coord, sample_index_i);
+#else ifdef NON_FILTERABLE
+    let motion_vector = load_nearest(motion_vector_prepass_texture, uv);
+#else
+    let motion_vector = textureSampleLevel(motion_vector_prepass_texture, texture_sampler, uv, 0.0);
+#endif
+    return motion_vector.xy;
+}
+#endif
+
+// ----------------------
+// Motion Emphasis ------
+// ----------------------
+
+#ifdef ENABLE_MOTION_EMPHASIS
+/// The number of samples taken along the motion direction to streak the edges.
+const MOTION_EMPHASIS_SAMPLES: i32 = 4;
+
+/// Streaks the edges along the screen-space motion of the pixel and boosts them by its speed.
+fn emphasize_motion(uv: vec2f, fresnel: f32, edge: f32) -> f32 {
+    // The motion vector is the uv offset from the previous frame to the current frame.
+    let motion_vector = prepass_motion_vector(uv);
+    let speed = length(motion_vector * texture_size);
+
+    // Static pixels are left untouched.
+    if speed < 0.5 {
+        return edge;
+    }
+
+    var streak = edge;
+    for (var i = 1; i <= MOTION_EMPHASIS_SAMPLES; i++) {
+        let t = f32(i) / f32(MOTION_EMPHASIS_SAMPLES);
+        let trail_uv = uv - motion_vector * ed_uniform.motion_emphasis * t;
+        streak = max(streak, detect_edge(trail_uv, fresnel) * (1.0 - t));
+    }
+
+    // Boost by the speed in pixels per frame.
+    return saturate(streak * (1.0 + speed * ed_uniform.motion_emphasis * 0.1));
+}
+#endif
+
+// ----------------------
+// Edge Detection -------
+// ----------------------
+
+/// Combines the edges of the enabled sources at `uv`.
+#ifdef SLOPE_THICKNESS
+/// The relative depth change per pixel at which the thickness boost is fully applied.
+const FULL_BOOST_SLOPE: f32 = 0.02;
+/// Bounds the thickness boost, so the kernel offsets stay local.
+const MAX_SLOPE_THICKNESS_BOOST: f32 = 4.0;
+
+/// Scales the thickness up where the depth slope is steep, so the line weight stays even
+/// around curved silhouettes instead of thinning where surfaces are viewed edge-on.
+fn slope_thickness_scale(uv: vec2f) -> f32 {
+    let view_z = abs(prepass_view_z(uv));
+    let gradient = max(abs(view_z_gradient_x(uv, 0.0, 1.0)), abs(view_z_gradient_y(uv, 0.0, 1.0)));
+    // Silhouettes against the background at infinity are as steep as it gets.
+    let slope = finite_or(gradient / max(view_z, 1e-4), FULL_BOOST_SLOPE);
+    let boost = min(ed_uniform.slope_thickness_boost, MAX_SLOPE_THICKNESS_BOOST);
+    return 1.0 + boost * saturate(slope / FULL_BOOST_SLOPE);
+}
 #endif
 
-    var color = textureSample(screen_texture, texture_sampler, in.uv).rgb;
-    color = mix(color, ed_uniform.edge_color.rgb, edge * ed_uniform.edge_color.a);
+/// Scales the thickness down beyond `thickness_reference_distance`, like ink lines under perspective.
+/// The objects of orthographic views don't shrink with their distance, so their thickness is kept.
+fn distance_thickness_scale(uv: vec2f) -> f32 {
+#ifdef VIEW_PROJECTION_ORTHOGRAPHIC
+    return 1.0;
+#else
+#ifndef VIEW_PROJECTION_PERSPECTIVE
+    if view_is_orthographic() {
+        return 1.0;
+    }
+#endif
+    // The background at infinity shrinks to the minimum thickness.
+    let scale = saturate(ed_uniform.thickness_reference_distance / nearest_view_distance(uv));
+    return pow(max(scale, 1e-4), ed_uniform.thickness_attenuation);
+#endif
+}
 
-    return vec4f(color, 1.0);
-}
\ No newline at end of file
+/// Scales `thickness`, without going below one texel unless it was already thinner.
+fn scaled_thickness(thickness: f32, scale: f32) -> f32 {
+    return max(thickness * scale, min(thickness, 1.0));
+}
+
+fn detect_edge(uv: vec2f, fresnel: f32) -> f32 {
+    var edge = 0.0;
+
+#ifdef SLOPE_THICKNESS
+    var thickness_scale = slope_thickness_scale(uv);
+#else
+    var thickness_scale = 1.0;
+#endif
+    if ed_uniform.thickness_attenuation > 0.0 {
+        thickness_scale *= distance_thickness_scale(uv);
+    }
+
+#ifdef NO_BACKGROUND_EDGES
+    // Nothing is drawn against the background, up to the reach of the thickest source.
+    let thickest = max(max(ed_uniform.depth_thickness, ed_uniform.normal_thickness), max(ed_uniform.color_thickness, ed_uniform.material_thickness));
+    if near_background(uv, ring_thickness(scaled_thickness(thickest, thickness_scale), KERNEL_RADIUS)) {
+        return 0.0;
+    }
+#endif
+
+    // The outermost ring is measured last, so it's the one recorded by the calibration.
+    for (var ring = 1u; ring <= KERNEL_RADIUS; ring++) {
+#ifdef ENABLE_DEPTH
+        let edge_depth = detect_edge_depth(uv, ring_thickness(scaled_thickness(ed_uniform.depth_thickness, thickness_scale), ring), fresnel);
+        edge = max(edge, edge_depth);
+#ifdef CHANNEL_EDGES
+        channel_edges.x = max(channel_edges.x, edge_depth);
+#endif
+#endif
+
+#ifdef ENABLE_NORMAL
+        let edge_normal = detect_edge_normal(uv, ring_thickness(scaled_thickness(ed_uniform.normal_thickness, thickness_scale), ring));
+        edge = max(edge, edge_normal);
+#ifdef CHANNEL_EDGES
+        channel_edges.y = max(channel_edges.y, edge_normal);
+#endif
+#endif
+
+#ifdef ENABLE_COLOR
+        let edge_color = detect_edge_color(uv, ring_thickness(scaled_thickness(ed_uniform.color_thickness, thickness_scale), ring));
+        edge = max(edge, edge_color);
+#ifdef CHANNEL_EDGES
+        channel_edges.z = max(channel_edges.z, edge_color);
+#endif
+#endif
+
+#ifdef ENABLE_MATERIAL
+        let edge_material = detect_edge_material(uv, ring_thickness(scaled_thickness(ed_uniform.material_thickness, thickness_scale), ring));
+        edge = max(edge, edge_material);
+#ifdef CHANNEL_EDGES
+        channel_edges.w = max(channel_edges.w, edge_material);
+#endif
+#endif
+    }
+
+    return edge;
+}
+
+// ----------------------
+// Compositing ----------
+// ----------------------
+
+#ifdef CHANNEL_EDGE_COLORS
+/// Layers the edges of the sources with a color of their own over the others, drawn together in `edge_color`:
+/// the depth edges over the normal edges over the color edges.
+/// `edge` is the strength of the edges once modulated, e.g. by the ambient occlusion, which scales all the sources.
+fn channel_edges_over(edge_color: vec4f, edge: f32) -> vec4f {
+    let detected = max(max(channel_edges.x, channel_edges.y), max(channel_edges.z, channel_edges.w));
+    // The edges added after the detection, e.g. by the motion emphasis, are drawn in the edge color.
+    let strengths = select(vec4f(0.0, 0.0, 0.0, edge), saturate(channel_edges * (edge / detected)), detected > 0.0);
+    let own = (vec3u(ed_uniform.channel_edge_colors) & vec3u(1u, 2u, 4u)) != vec3u(0u);
+
+    let shared = select(strengths.xyz, vec3f(0.0), own);
+    var edges = vec4f(edge_color.rgb, max(max(shared.x, shared.y), max(shared.z, strengths.w)) * edge_color.a);
+    if own.z {
+        edges = edge_over(vec4f(ed_uniform.color_edge_color.rgb, strengths.z * ed_uniform.color_edge_color.a), edges);
+    }
+    if own.y {
+        edges = edge_over(vec4f(ed_uniform.normal_edge_color.rgb, strengths.y * ed_uniform.normal_edge_color.a), edges);
+    }
+    if own.x {
+        edges = edge_over(vec4f(ed_uniform.depth_edge_color.rgb, strengths.x * ed_uniform.depth_edge_color.a), edges);
+    }
+    return edges;
+}
+#endif
+
+#ifdef DEBUG_VIEW
+/// The color of the pixel in the debug view of `EdgeDetection::debug_view`: the strength of a source in grayscale,
+/// or the steep angle adjustment as a heatmap. `edge` is the strength of all the edges, once modulated.
+fn debug_view_color(uv: vec2f, fresnel: f32, edge: f32) -> vec3f {
+#ifdef DEBUG_VIEW_DEPTH_EDGES
+    return vec3f(channel_edges.x);
+#else ifdef DEBUG_VIEW_NORMAL_EDGES
+    return vec3f(channel_edges.y);
+#else ifdef DEBUG_VIEW_COLOR_EDGES
+    return vec3f(channel_edges.z);
+#else ifdef DEBUG_VIEW_STEEP_ANGLE_FACTOR
+    return steep_angle_heatmap(uv, fresnel);
+#else
+    return vec3f(saturate(edge));
+#endif
+}
+
+#ifdef DEBUG_VIEW_STEEP_ANGLE_FACTOR
+/// The steep angle adjustment of the depth threshold at `uv`, from blue where the threshold is kept
+/// to red where it's doubled or more. Black on the background, and without the depth source.
+fn steep_angle_heatmap(uv: vec2f, fresnel: f32) -> vec3f {
+#ifdef ENABLE_DEPTH
+    let view_z = abs(prepass_view_z(uv));
+    if is_finite(view_z) && !is_background(uv) {
+        let value = saturate(steep_angle_adjustment(fresnel, view_z));
+        return saturate(1.5 - abs(4.0 * value - vec3f(3.0, 2.0, 1.0)));
+    }
+#endif
+    return vec3f(0.0);
+}
+#endif
+#endif
+
+#ifdef EDGE_COLOR_FROM_OBJECT
+/// Derives the edge color from the color of the object on the nearer side of the depth discontinuity.
+fn object_edge_color(uv: vec2f) -> vec3f {
+    // View z increases toward the camera, so the gradient points to the nearer side.
+    let gradient = vec2f(
+        prepass_view_z(uv + vec2f(texel_size.x, 0.0)) - prepass_view_z(uv - vec2f(texel_size.x, 0.0)),
+        prepass_view_z(uv + vec2f(0.0, texel_size.y)) - prepass_view_z(uv - vec2f(0.0, texel_size.y)),
+    );
+
+    var sample_uv = uv;
+    if dot(gradient, gradient) > 0.0 {
+        sample_uv += normalize(gradient) * texel_size * 2.0;
+    }
+    // Never sample outside of the viewport.
+    sample_uv = clamp(sample_uv, vec2f(0.0), vec2f(1.0));
+
+    let object_color = prepass_color(sample_uv);
+
+    let luminance = dot(object_color, vec3f(0.2126, 0.7152, 0.0722));
+    let saturated = max(mix(vec3f(luminance), object_color, 1.0 + ed_uniform.object_color_adjustment.y), vec3f(0.0));
+    return saturated * (1.0 - saturate(ed_uniform.object_color_adjustment.x));
+}
+#endif
+
+#ifdef SHARPEN
+/// Sharpens the scene color by the strength of the edges, instead of drawing them.
+fn sharpen_scene(pixel_coord: vec2i, color: vec3f, strength: f32) -> vec3f {
+    let max_coord = vec2i(texture_size) - 1;
+    let north = textureLoad(screen_texture, clamp(pixel_coord - vec2i(0, 1), vec2i(0), max_coord), 0).rgb;
+    let south = textureLoad(screen_texture, clamp(pixel_coord + vec2i(0, 1), vec2i(0), max_coord), 0).rgb;
+    let east = textureLoad(screen_texture, clamp(pixel_coord + vec2i(1, 0), vec2i(0), max_coord), 0).rgb;
+    let west = textureLoad(screen_texture, clamp(pixel_coord - vec2i(1, 0), vec2i(0), max_coord), 0).rgb;
+    return sharpen(color, north, south, east, west, strength * ed_uniform.sharpen_amount);
+}
+#endif
+
+// -----------------------
+// Ambient Occlusion -----
+// -----------------------
+
+#ifdef AMBIENT_OCCLUSION
+/// Returns the factor the edge strength is scaled by. Positive influences lighten the unoccluded edges,
+/// negative ones lighten the occluded edges.
+fn ambient_occlusion_scale(uv: vec2f) -> f32 {
+    let size = textureDimensions(ambient_occlusion_texture);
+    let pixel_coord = clamp(vec2i(uv * vec2f(size)), vec2i(0), vec2i(size) - 1);
+    let visibility = textureLoad(ambient_occlusion_texture, pixel_coord, 0).r;
+
+    let influence = ed_uniform.ao_influence;
+    return mix(1.0, select(visibility, 1.0 - visibility, influence > 0.0), abs(influence));
+}
+#endif
+
+// -----------------------
+// Distortion ------------
+// -----------------------
+
+/// Returns the uv of the noise lookup, scrolling at `uv_distortion_speed`. With `BOIL`, it jumps to a new
+/// random offset `boil_fps` times per second, like hand-drawn lines redrawn on every drawing.
+fn noise_lookup_uv(sample_uv: vec2f) -> vec2f {
+    let noise_uv = sample_uv * ed_uniform.uv_distortion.xy + ed_uniform.noise_scroll;
+#ifdef BOIL
+    // The index of the current drawing, so the offset only depends on the time and not on the frame rate.
+    let drawing = floor(ed_uniform.time * ed_uniform.boil_fps);
+    // The noise texture repeats, so any offset in [0, 1) is seamless.
+    let offset = fract(sin(vec2f(drawing * 12.9898, drawing * 78.233)) * 43758.5453);
+    return noise_uv + offset;
+#else
+    return noise_uv;
+#endif
+}
+
+/// The rotation and scale applied to the noise lookup uv.
+fn noise_uv_transform() -> mat2x2f {
+    let columns = ed_uniform.noise_uv_transform;
+    return mat2x2f(columns.xy, columns.zw);
+}
+
+/// Returns the mip level of the noise lookup at `frequency` times the distortion frequency,
+/// from the number of noise texels covered by a pixel.
+///
+/// The derivatives of the noise uv are useless in a fullscreen pass, so the level is computed explicitly.
+fn noise_lod(frequency: f32) -> f32 {
+    let transform = noise_uv_transform();
+    let transform_scale = max(length(transform[0]), length(transform[1]));
+    let noise_size = vec2f(textureDimensions(noise_texture));
+    let footprint = noise_footprint * transform_scale * frequency * abs(ed_uniform.uv_distortion.xy) * noise_size;
+    return log2(max(max(footprint.x, footprint.y), 1e-6)) + ed_uniform.noise_lod_bias;
+}
+
+/// Bounds the number of noise octaves.
+const MAX_DISTORTION_OCTAVES: u32 = 4u;
+
+/// Accumulates the noise octaves at increasing frequency and decreasing amplitude (fBm).
+/// The sum is normalized by the total amplitude, so a single octave is the plain noise lookup.
+fn distortion_noise(sample_uv: vec2f) -> vec2f {
+    let noise_uv = noise_lookup_uv(sample_uv);
+    var noise = textureSampleLevel(noise_texture, noise_sampler, noise_uv, noise_lod(1.0)).xy;
+
+    var frequency = 1.0;
+    var amplitude = 1.0;
+    var total_amplitude = 1.0;
+    let octaves = min(ed_uniform.distortion_octaves, MAX_DISTORTION_OCTAVES);
+    for (var i = 1u; i < octaves; i++) {
+        frequency *= ed_uniform.distortion_lacunarity;
+        amplitude *= ed_uniform.distortion_gain;
+        let octave = textureSampleLevel(noise_texture, noise_sampler, noise_uv * frequency, noise_lod(frequency));
+        noise += octave.xy * amplitude;
+        total_amplitude += amplitude;
+    }
+
+    return noise / total_amplitude;
+}
+
+// -----------------------
+// Strokes ---------------
+// -----------------------
+
+#ifdef MULTI_STROKE
+/// Bounds the number of strokes.
+const MAX_STROKES: u32 = 3u;
+/// Opacity of each stroke, so the strokes get darker where they overlap.
+const STROKE_OPACITY: f32 = 0.7;
+/// Offset between the noise lookups of the strokes, so each stroke wanders on its own.
+const STROKE_NOISE_OFFSET: vec2f = vec2f(0.37, 0.71);
+
+/// Draws the contour again with noise-driven offsets and composites the strokes over each other.
+fn multi_stroke_edge(uv: vec2f, sample_uv: vec2f, fresnel: f32, edge: f32) -> f32 {
+    let noise_uv = noise_lookup_uv(sample_uv);
+    let lod = noise_lod(1.0);
+
+    // The transparency left by the strokes drawn so far.
+    var transparency = 1.0 - edge * STROKE_OPACITY;
+    let strokes = min(ed_uniform.strokes, MAX_STROKES);
+    for (var i = 1u; i < strokes; i++) {
+        let noise = textureSampleLevel(noise_texture, noise_sampler, noise_uv + STROKE_NOISE_OFFSET * f32(i), lod).xy;
+        let offset = (noise * 2.0 - 1.0) * ed_uniform.stroke_scatter * texel_size;
+        transparency *= 1.0 - detect_edge(uv + offset, fresnel) * STROKE_OPACITY;
+    }
+
+    return 1.0 - transparency;
+}
+#endif
+
+// -----------------------
+// Hatching --------------
+// -----------------------
+
+#ifdef HATCHING
+/// Adds the gradients of a source detecting an edge to the structure tensor of the pixel, normalized by its
+/// trace so each source weighs the same whatever its strength.
+fn record_hatch_gradient(gradient_x: vec3f, gradient_y: vec3f) {
+    let xx = dot(gradient_x, gradient_x);
+    let yy = dot(gradient_y, gradient_y);
+    let trace = xx + yy;
+    if trace > 0.0 {
+        hatch_tensor += vec3f(xx, yy, dot(gradient_x, gradient_y)) / trace;
+    }
+}
+
+/// Breaks the edges into strokes along them, across the direction of their gradients. The noise offsets
+/// and lengthens or shortens each stroke, so they don't line up like a dashed line.
+fn hatch_mask(sample_uv: vec2f) -> f32 {
+    // The angle of the dominant gradient, `atan2(gy, gx)` for a single scalar gradient, from the structure tensor
+    // of all of them so the gradients of opposite signs don't cancel out.
+#ifdef OPERATOR_ROBERTS_CROSS
+    // The gradients are measured along the diagonals, rotated by 45 degrees from the axes.
+    let angle = 0.5 * atan2(2.0 * hatch_tensor.z, hatch_tensor.x - hatch_tensor.y) + 0.7853982;
+#else
+    let angle = 0.5 * atan2(2.0 * hatch_tensor.z, hatch_tensor.x - hatch_tensor.y);
+#endif
+    let tangent = vec2f(-sin(angle), cos(angle));
+
+    let noise = textureSampleLevel(noise_texture, noise_sampler, noise_lookup_uv(sample_uv), noise_lod(1.0)).xy;
+    let stroke = dot(frame_position, tangent) / ed_uniform.hatching.x + noise.x;
+    let coverage = ed_uniform.hatching.y * mix(0.75, 1.25, noise.y);
+    return f32(fract(stroke) < coverage);
+}
+#endif
+
+// -----------------------
+// Scan ------------------
+// -----------------------
+
+#ifdef SCAN
+/// Returns 1.0 inside the scan sphere, fading to 0.0 over the falloff width at its surface.
+fn scan_mask(uv: vec2f) -> f32 {
+    let distance = length(prepass_world_position(uv) - ed_uniform.scan.xyz);
+    let radius = ed_uniform.scan.w;
+    // smoothstep is undefined for equal edges, so a zero falloff cuts the edges sharply.
+    let falloff = max(ed_uniform.scan_falloff, 1e-4);
+    return 1.0 - smoothstep(radius - falloff, radius, distance);
+}
+#endif
+
+// -----------------------
+// Reveal ----------------
+// -----------------------
+
+#ifdef REVEAL
+// The scale of the noise ordering the reveal, in repetitions per viewport height.
+const REVEAL_NOISE_SCALE: f32 = 2.0;
+
+/// Returns how much the edges of this pixel are drawn in, which is 1.0 everywhere once the progress reaches 1.0.
+fn reveal_mask() -> f32 {
+    let viewport_uv = frame_position / frame_size;
+    let aspect = vec2f(frame_size.x / frame_size.y, 1.0);
+
+    // The order in which the pixel is revealed, in [0.0, 1.0].
+    var key: f32;
+    if ed_uniform.reveal_mode == 1u {
+        // Normalized by the distance to the farthest corner.
+        let origin = ed_uniform.reveal.zw;
+        let farthest = max(origin, 1.0 - origin) * aspect;
+        key = saturate(length((viewport_uv - origin) * aspect) / max(length(farthest), 1e-4));
+    } else {
+        key = textureSampleLevel(noise_texture, noise_sampler, viewport_uv * aspect * REVEAL_NOISE_SCALE, 0.0).x;
+    }
+
+    let progress = ed_uniform.reveal.x;
+    let softness = ed_uniform.reveal.y;
+    if softness <= 0.0 {
+        return f32(key <= progress);
+    }
+    // The fade is stretched so it's over when the progress reaches 1.0, whatever the softness.
+    return saturate((progress * (1.0 + softness) - key) / softness);
+}
+#endif
+
+// -----------------------
+// Distance Fade ---------
+// -----------------------
+
+/// Returns 1.0 up to `fade_start` from the camera, fading to 0.0 at `fade_end`.
+fn distance_fade(uv: vec2f) -> f32 {
+    // The background at infinity is faded out, like anything beyond `fade_end`.
+    let width = max(ed_uniform.fade_end - ed_uniform.fade_start, 1e-4);
+    return saturate((ed_uniform.fade_end - nearest_view_distance(uv)) / width);
+}
+
+/// Returns 1.0 between `depth_range_min` and `depth_range_max` from the camera, fading to 0.0 over
+/// `depth_range_feather` outside of them.
+fn depth_window(uv: vec2f) -> f32 {
+    let distance = nearest_view_distance(uv);
+    let feather = max(ed_uniform.depth_range_feather, 1e-4);
+    var window = saturate((distance - ed_uniform.depth_range_min) / feather + 1.0);
+    if ed_uniform.depth_range_max > 0.0 {
+        // The background at infinity is outside of the window.
+        window *= saturate((ed_uniform.depth_range_max - distance) / feather + 1.0);
+    }
+    return window;
+}
+
+// -----------------------
+// Edge Color Gradient ---
+// -----------------------
+
+/// Returns the edge color up to `color_gradient_start` from the camera, shading into `edge_color_far`
+/// at `color_gradient_end`.
+fn edge_color_gradient(uv: vec2f) -> vec4f {
+    // The background at infinity takes the far color, like anything beyond `color_gradient_end`.
+    let gradient = ed_uniform.edge_color_gradient;
+    let width = max(gradient.y - gradient.x, 1e-4);
+    let t = saturate((nearest_view_distance(uv) - gradient.x) / width);
+    return mix(ed_uniform.edge_color, ed_uniform.edge_color_far, t);
+}
+
+// -----------------------
+// Mask Volume -----------
+// -----------------------
+
+#ifdef MASK_VOLUME
+/// Returns 1.0 inside the mask volume, fading to 0.0 over the soft margin outside of it.
+fn mask_volume(uv: vec2f) -> f32 {
+    let world_position = prepass_world_position(uv);
+    // Signed distance to the box, euclidean outside of it so the fade is rounded around the edges and corners.
+    let q = abs(world_position - ed_uniform.mask_volume_center.xyz) - ed_uniform.mask_volume_half_size.xyz;
+    let distance = length(max(q, vec3f(0.0))) + min(max(q.x, max(q.y, q.z)), 0.0);
+
+    // smoothstep is undefined for equal edges, so a zero margin cuts the edges sharply.
+    let margin = max(ed_uniform.mask_volume_margin, 1e-4);
+    return 1.0 - smoothstep(0.0, margin, distance);
+}
+#endif
+
+// -----------------------
+// Overlay Camera --------
+// -----------------------
+
+#ifdef OVERLAY_CAMERA
+/// Returns 1.0 where the camera drew its own geometry, or close enough to it for its edges to reach.
+///
+/// The depth of an overlay camera is cleared to the far plane (0.0, reverse z), so the pixels of the cameras
+/// below it don't have any depth and aren't outlined twice.
+fn overlay_mask(uv: vec2f) -> f32 {
+    let reach = max(max(ed_uniform.depth_thickness, ed_uniform.normal_thickness), ed_uniform.color_thickness);
+    let offset = reach * texel_size;
+
+    let drawn = prepass_depth(uv) > 0.0
+        || prepass_depth(uv + vec2f(offset.x, 0.0)) > 0.0
+        || prepass_depth(uv - vec2f(offset.x, 0.0)) > 0.0
+        || prepass_depth(uv + vec2f(0.0, offset.y)) > 0.0
+        || prepass_depth(uv - vec2f(0.0, offset.y)) > 0.0;
+    return f32(drawn);
+}
+#endif
+
+// -----------------------
+// Mesh Tags -------------
+// -----------------------
+
+#ifdef MESH_TAGS
+// Must match the constants of `mesh_tag.rs`.
+const MESH_TAG_EXCLUDED: u32 = 1u;
+const MESH_TAG_FIRST_COLOR: u32 = 2u;
+
+/// The relative depth difference under which a tagged mesh is the surface of the scene,
+/// as both passes rasterize it with the same transforms but not bit for bit.
+const MESH_TAG_DEPTH_TOLERANCE: f32 = 1e-4;
+
+/// The tag of the surface of the scene at `uv`, 0 if it isn't a tagged mesh, or if it's hidden behind another object.
+fn mesh_tag(uv: vec2f) -> u32 {
+    let pixel_coord = uv_to_pixel_coord(uv);
+    let depth = textureLoad(mesh_tag_depth_texture, pixel_coord, 0).r;
+    let visible = depth > 0.0 && depth >= prepass_depth(uv) * (1.0 - MESH_TAG_DEPTH_TOLERANCE);
+    return select(0u, textureLoad(mesh_tag_texture, pixel_coord, 0).r, visible);
+}
+
+/// The offset between the samples of the footprint of the pixel, as far as its edges reach.
+/// The kernels sample the diagonals too, so the whole 3x3 footprint is checked.
+fn mesh_tag_offset() -> vec2f {
+    let reach = max(max(ed_uniform.depth_thickness, ed_uniform.normal_thickness), ed_uniform.color_thickness);
+    return reach * texel_size;
+}
+#endif
+
+#ifdef EXCLUDED_MESHES
+/// Returns 0.0 on the excluded meshes, or close enough to them for the edges of their silhouette to reach.
+fn excluded_mesh_mask(uv: vec2f) -> f32 {
+    let offset = mesh_tag_offset();
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            if mesh_tag(uv + vec2f(f32(x), f32(y)) * offset) == MESH_TAG_EXCLUDED {
+                return 0.0;
+            }
+        }
+    }
+    return 1.0;
+}
+#endif
+
+#ifdef EDGE_COLOR_OVERRIDES
+/// Returns the color of the nearest surface in the footprint of the pixel if it's an `EdgeColorOverride` mesh,
+/// `color` otherwise. The first of the equally near samples wins, so the silhouettes between two overrides
+/// are resolved the same way every frame.
+fn edge_color_override(uv: vec2f, color: vec4f) -> vec4f {
+    let offset = mesh_tag_offset();
+    var nearest_depth = -1.0;
+    var nearest_color = color;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            let sample_uv = uv + vec2f(f32(x), f32(y)) * offset;
+            let depth = prepass_depth(sample_uv);
+            if depth > nearest_depth {
+                nearest_depth = depth;
+                let tag = mesh_tag(sample_uv);
+                let slot = max(tag, MESH_TAG_FIRST_COLOR) - MESH_TAG_FIRST_COLOR;
+                nearest_color = select(color, ed_uniform.edge_color_overrides[slot], tag >= MESH_TAG_FIRST_COLOR);
+            }
+        }
+    }
+    return nearest_color;
+}
+#endif
+
+// -----------------------
+// Auto Calibration ------
+// -----------------------
+
+#ifdef AUTO_CALIBRATE
+// Must match the constants of `calibrate.rs`.
+const CALIBRATION_BINS: u32 = 64u;
+const CALIBRATION_BINS_PER_OCTAVE: f32 = 4.0;
+const CALIBRATION_MIN_GRADIENT_LOG2: f32 = -8.0;
+
+// The gradients of the pixel, recorded by the depth and normal detection. Negative if the source is disabled.
+var<private> calibration_depth_gradient: f32 = -1.0;
+var<private> calibration_normal_gradient: f32 = -1.0;
+
+/// The first bin holds the gradients below the minimum, the others are spaced on a log scale.
+fn calibration_bin(gradient: f32) -> u32 {
+    if gradient < exp2(CALIBRATION_MIN_GRADIENT_LOG2) {
+        return 0u;
+    }
+    let bin = 1.0 + floor((log2(gradient) - CALIBRATION_MIN_GRADIENT_LOG2) * CALIBRATION_BINS_PER_OCTAVE);
+    return u32(min(bin, f32(CALIBRATION_BINS - 1u)));
+}
+
+/// Adds the gradients of the pixel to the histograms.
+fn record_calibration() {
+    if calibration_depth_gradient >= 0.0 {
+        atomicAdd(&calibration_histograms[calibration_bin(calibration_depth_gradient)], 1u);
+    }
+    if calibration_normal_gradient >= 0.0 {
+        atomicAdd(&calibration_histograms[CALIBRATION_BINS + calibration_bin(calibration_normal_gradient)], 1u);
+    }
+}
+#endif
+
+// -----------------------
+// Exclusion Rects -------
+// -----------------------
+
+#ifdef EXCLUSION_RECTS
+/// Returns 0.0 inside the exclusion rectangles, fading to 1.0 at their borders over the feather width.
+fn exclusion_mask() -> f32 {
+    // The rectangles are relative to the viewport of the camera, or to the whole frame of a sub view.
+    let pixel = frame_position;
+
+    var mask = 1.0;
+    for (var i = 0u; i < ed_uniform.exclusion_rect_count; i++) {
+        let rect = ed_uniform.exclusion_rects[i] * frame_size.xyxy;
+        // Signed distance to the border of the rectangle, negative inside.
+        let outside = max(rect.xy - pixel, pixel - rect.zw);
+        let distance = max(outside.x, outside.y);
+
+        let feather = ed_uniform.exclusion_feather;
+        let visible = select(f32(distance > 0.0), saturate(1.0 + distance / feather), feather > 0.0);
+        mask = min(mask, visible);
+    }
+
+    return mask;
+}
+#endif
+
+// -----------------------
+// Screen Mask -----------
+// -----------------------
+
+#ifdef SCREEN_MASK
+/// Returns the red channel of the screen mask, which spans the viewport of the camera, or the whole frame of a sub view.
+fn screen_mask() -> f32 {
+    let uv = frame_position / frame_size;
+#ifdef NON_FILTERABLE
+    return load_nearest(screen_mask_texture, uv).r;
+#else
+    return textureSampleLevel(screen_mask_texture, texture_sampler, uv, 0.0).r;
+#endif
+}
+#endif
+
+// -----------------------
+// Region Overrides ------
+// -----------------------
+
+#ifdef REGION_OVERRIDES
+/// Returns the index of the last region override containing the pixel, or -1 outside of them.
+fn region_override() -> i32 {
+    // The rectangles are relative to the viewport of the camera, or to the whole frame of a sub view.
+    let pixel = frame_position;
+
+    var region = -1;
+    for (var i = 0u; i < ed_uniform.region_count; i++) {
+        let rect = ed_uniform.region_rects[i] * frame_size.xyxy;
+        if all(pixel >= rect.xy) && all(pixel < rect.zw) {
+            region = i32(i);
+        }
+    }
+
+    return region;
+}
+#endif
+
+// The depth, normal and color thresholds, the normal threshold of the convex creases and the edge color
+// of the pixel, those of the view or of the region override containing it.
+var<private> pixel_thresholds: vec3f;
+var<private> pixel_normal_threshold_convex: f32;
+var<private> pixel_edge_color: vec4f;
+
+var<private> texture_size: vec2f;
+var<private> texel_size: vec2f;
+// The position of the pixel in the frame and the size of the frame, in pixels. The frame is the viewport,
+// or the whole image a sub view is a section of, so the screen space effects line up across the sub views.
+var<private> frame_position: vec2f;
+var<private> frame_size: vec2f;
+var<private> sample_index_i: i32 = 0;
+// The depth and packed normal of the pixel, replacing the infinite or NaN samples of its neighbors.
+// The background until they're loaded.
+var<private> center_depth: f32 = 0.0;
+var<private> center_normal: vec3f = vec3f(0.0);
+// The change of the noise lookup uv between neighboring pixels, before the distortion frequency.
+var<private> noise_footprint: f32;
+#ifdef CHANNEL_EDGES
+// The strengths of the depth, normal, color and material edges of the pixel, the strongest of their rings.
+var<private> channel_edges: vec4f = vec4f(0.0);
+#endif
+#ifdef HATCHING
+// xx, yy and xy: the structure tensor of the gradients of the sources detecting an edge at the pixel.
+var<private> hatch_tensor: vec3f = vec3f(0.0);
+#endif
+
+struct FragmentOutput {
+    @location(0) color: vec4f,
+#ifdef MASK_OUTPUT
+    // The strength of the edges, written to the `ViewEdgeMaskTexture`.
+    @location(1) mask: f32,
+#endif
+}
+
+@fragment
+fn fragment(
+#ifdef MULTISAMPLED
+    @builtin(sample_index) sample_index: u32,
+#endif
+    in: FullscreenVertexOutput
+) -> FragmentOutput {
+#ifdef MULTISAMPLED
+    sample_index_i = i32(sample_index);
+#endif
+    return detect_pixel(in);
+}
+
+/// Detects the edges of a pixel, shared by the fragment and compute entry points.
+fn detect_pixel(in: FullscreenVertexOutput) -> FragmentOutput {
+    texture_size = vec2f(textureDimensions(screen_texture));
+    texel_size = 1.0 / texture_size;
+
+#ifdef SCALED
+    // The edges are detected into a smaller mask, the pixel is placed at its position on the screen.
+    let position = in.uv * texture_size;
+#else
+    let position = in.position.xy;
+#endif
+    if ed_uniform.sub_view.z > 0.0 {
+        frame_position = position - view.viewport.xy + ed_uniform.sub_view.xy;
+        frame_size = ed_uniform.sub_view.zw;
+    } else {
+        frame_position = position - view.viewport.xy;
+        frame_size = view.viewport.zw;
+    }
+
+    pixel_thresholds = vec3f(ed_uniform.depth_threshold, ed_uniform.normal_threshold, ed_uniform.color_threshold);
+    pixel_normal_threshold_convex = ed_uniform.normal_threshold_convex;
+    pixel_edge_color = ed_uniform.edge_color;
+    if ed_uniform.edge_color_gradient.z > 0.0 {
+        pixel_edge_color = edge_color_gradient(in.uv);
+    }
+#ifdef REGION_OVERRIDES
+    let region = region_override();
+    if region >= 0 {
+        pixel_thresholds = ed_uniform.region_thresholds[region].xyz;
+        pixel_normal_threshold_convex = pixel_thresholds.y;
+        pixel_edge_color = ed_uniform.region_edge_colors[region];
+    }
+#endif
+
+#ifdef CHECKERBOARD
+    // Only half of the pixels are rendered each frame, the others keep the edges of the previous frame.
+    let parity = (u32(frame_position.x) + u32(frame_position.y)) & 1u;
+    if ed_uniform.checkerboard_phase < 2u && parity != ed_uniform.checkerboard_phase {
+        discard;
+    }
+#endif
+
+    center_depth = prepass_depth(in.uv);
+    center_normal = prepass_normal(in.uv);
+
+    let near_ndc_pos = vec3f(uv_to_ndc(in.uv), 1.0);
+    let near_world_pos = position_ndc_to_world(near_ndc_pos);
+
+    let view_direction = calculate_view(near_world_pos);
+
+    let normal = normalize(center_normal * 2.0 - vec3(1.0));
+    let fresnel = 1.0 - saturate(dot(normal, view_direction));;
+
+#ifdef ASPECT_CORRECTED
+    // Measure both the noise lookup and the distortion offsets in physical pixels, relative to the reference height,
+    // so the wobble is isotropic on screen and keeps its wavelength whatever the size of the frame is.
+    let reference_texel_size = 1.0 / ed_uniform.noise_reference_height;
+    let sample_uv = noise_uv_transform() * (frame_position * reference_texel_size);
+    noise_footprint = reference_texel_size;
+    let noise = distortion_noise(sample_uv);
+    var uv = in.uv + noise.xy * ed_uniform.uv_distortion.zw * ed_uniform.noise_reference_height * texel_size;
+#else
+    // The distortion offsets are relative to the frame, and converted to the uv of the texture.
+    let frame_to_uv = frame_size * texel_size;
+    let frame_texel_size = 1.0 / max(frame_size.x, frame_size.y);
+    let sample_uv = noise_uv_transform() * (frame_position * frame_texel_size);
+    noise_footprint = frame_texel_size;
+    let noise = distortion_noise(sample_uv);
+    var uv = in.uv + noise.xy * ed_uniform.uv_distortion.zw * frame_to_uv;
+#endif
+
+#ifdef ENABLE_MOTION_SMEAR
+    // Offset each pixel back by a noisy fraction of its motion, so fast motion smears the lines along it.
+    uv -= prepass_motion_vector(in.uv) * ed_uniform.motion_smear_strength * noise.x;
+#endif
+
+    var edge = detect_edge(uv, fresnel);
+
+#ifdef AUTO_CALIBRATE
+#ifdef MULTISAMPLED
+    let calibrated = ed_uniform.calibrate != 0u && sample_index_i == 0;
+#else
+    let calibrated = ed_uniform.calibrate != 0u;
+#endif
+    if calibrated {
+        record_calibration();
+    }
+#endif
+
+#ifdef MULTI_STROKE
+    edge = multi_stroke_edge(uv, sample_uv, fresnel, edge);
+#endif
+
+#ifdef ENABLE_MOTION_EMPHASIS
+    edge = emphasize_motion(uv, fresnel, edge);
+#endif
+
+#ifdef AMBIENT_OCCLUSION
+    edge *= ambient_occlusion_scale(uv);
+#endif
+
+#ifdef HATCHING
+    edge *= hatch_mask(sample_uv);
+#endif
+
+#ifdef EDGE_COLOR_FROM_OBJECT
+    var edge_color = vec4f(object_edge_color(uv), pixel_edge_color.a);
+#else
+    var edge_color = pixel_edge_color;
+#endif
+
+#ifdef EDGE_COLOR_OVERRIDES
+    edge_color = edge_color_override(uv, edge_color);
+#endif
+
+    // rgb: edge color; a: edge opacity
+#ifdef CHANNEL_EDGE_COLORS
+    var edges = channel_edges_over(edge_color, edge);
+#else
+    var edges = vec4f(edge_color.rgb, edge * edge_color.a);
+#endif
+
+#ifdef ENABLE_SHADOW
+    var edge_shadow = 0.0;
+    for (var ring = 1u; ring <= KERNEL_RADIUS; ring++) {
+        edge_shadow = max(edge_shadow, detect_edge_shadow(uv, ring_thickness(ed_uniform.shadow_thickness, ring)));
+    }
+    let shadow_edges = vec4f(ed_uniform.shadow_edge_color.rgb, edge_shadow * ed_uniform.shadow_edge_color.a);
+    edges = edge_over(shadow_edges, edges);
+    edge = max(edge, edge_shadow);
+#endif
+
+#ifdef ENABLE_STENCIL
+    var edge_stencil = 0.0;
+    for (var ring = 1u; ring <= KERNEL_RADIUS; ring++) {
+        edge_stencil = max(edge_stencil, detect_edge_stencil(uv, ring_thickness(ed_uniform.stencil_thickness, ring)));
+    }
+    let stencil_edges = vec4f(ed_uniform.stencil_edge_color.rgb, edge_stencil * ed_uniform.stencil_edge_color.a);
+    edges = edge_over(stencil_edges, edges);
+    edge = max(edge, edge_stencil);
+#endif
+
+    // The custom sources are drawn over the built-in ones, in registration order.
+#ifdef EDGE_SOURCE_0
+    let edge_source_0 = detect_edge_source(0u, edge_sources::edge_source_0_gradient(texture_sampler, uv, edge_source_offset(0u)));
+    edges = edge_over(edge_source_edges(0u, edge_source_0), edges);
+    edge = max(edge, edge_source_0);
+#endif
+#ifdef EDGE_SOURCE_1
+    let edge_source_1 = detect_edge_source(1u, edge_sources::edge_source_1_gradient(texture_sampler, uv, edge_source_offset(1u)));
+    edges = edge_over(edge_source_edges(1u, edge_source_1), edges);
+    edge = max(edge, edge_source_1);
+#endif
+#ifdef EDGE_SOURCE_2
+    let edge_source_2 = detect_edge_source(2u, edge_sources::edge_source_2_gradient(texture_sampler, uv, edge_source_offset(2u)));
+    edges = edge_over(edge_source_edges(2u, edge_source_2), edges);
+    edge = max(edge, edge_source_2);
+#endif
+#ifdef EDGE_SOURCE_3
+    let edge_source_3 = detect_edge_source(3u, edge_sources::edge_source_3_gradient(texture_sampler, uv, edge_source_offset(3u)));
+    edges = edge_over(edge_source_edges(3u, edge_source_3), edges);
+    edge = max(edge, edge_source_3);
+#endif
+
+    // Above 1.0, the edges glow through the bloom of the HDR targets. The LDR targets can't store them,
+    // and the over-bright colors would skew the blending, so they're clamped there.
+    edges = vec4f(edges.rgb * ed_uniform.edge_emissive_strength, edges.a);
+#ifndef HDR_TARGET
+    edges = vec4f(saturate(edges.rgb), edges.a);
+#endif
+
+#ifdef SCAN
+    let scan = scan_mask(in.uv);
+    edges.a *= scan;
+    edge *= scan;
+#endif
+
+#ifdef REVEAL
+    let reveal = reveal_mask();
+    edges.a *= reveal;
+    edge *= reveal;
+#endif
+
+#ifdef MASK_VOLUME
+    let volume = mask_volume(in.uv);
+    edges.a *= volume;
+    edge *= volume;
+#endif
+
+#ifdef OVERLAY_CAMERA
+    let overlay = overlay_mask(uv);
+    edges.a *= overlay;
+    edge *= overlay;
+#endif
+
+#ifdef EXCLUDED_MESHES
+    let included = excluded_mesh_mask(uv);
+    edges.a *= included;
+    edge *= included;
+#endif
+
+#ifdef EXCLUSION_RECTS
+    let exclusion = exclusion_mask();
+    edges.a *= exclusion;
+    edge *= exclusion;
+#endif
+
+#ifdef SCREEN_MASK
+    let masked = screen_mask();
+    edges.a *= masked;
+    edge *= masked;
+#endif
+
+    if ed_uniform.fade_end > 0.0 {
+        let fade = distance_fade(uv);
+        edges.a *= fade;
+        edge *= fade;
+    }
+
+    if ed_uniform.depth_range_min > 0.0 || ed_uniform.depth_range_max > 0.0 {
+        let window = depth_window(uv);
+        edges.a *= window;
+        edge *= window;
+    }
+
+    // The pixels of the other cameras drawing to the target, e.g. in split-screen, are left as is.
+    let viewport = select(0.0, 1.0, in_viewport(position, view.viewport));
+    edges.a *= viewport;
+    edge *= viewport;
+
+#ifdef EDGE_COVERAGE
+#ifdef MULTISAMPLED
+    let counted = sample_index_i == 0;
+#else
+    let counted = true;
+#endif
+    if counted && edge > 0.5 {
+        atomicAdd(&edge_coverage, 1u);
+    }
+#endif
+
+#ifdef SHARPEN
+    // The edge colors are ignored, the strength of the edges drives the sharpening.
+    edges.a = saturate(edge);
+#endif
+
+#ifdef DEBUG_VIEW
+    // The debug view replaces the output, with the composite pass drawing the mask as is.
+    var output = vec4f(debug_view_color(uv, fresnel, edge), 1.0);
+#ifndef EDGE_MASK
+    if viewport == 0.0 {
+        output = load_scene(in.uv);
+    }
+#endif
+#else ifdef EDGE_MASK
+    // The edges are composited onto the screen by the composite pass.
+    let output = edges;
+#else
+    let scene = load_scene(in.uv);
+#ifdef EDGES_ONLY
+    // The scene is dropped, e.g. for the edges to be composited over another camera.
+    var output = vec4f(edges.rgb, edges.a * ed_uniform.intensity);
+#else
+#ifdef BLUEPRINT
+    // The scene is replaced by a flat background, only its edges are drawn.
+    var color = ed_uniform.blueprint_background.rgb;
+#else
+    var color = scene.rgb;
+#endif
+#ifdef SHARPEN
+    color = sharpen_scene(uv_to_pixel_coord(in.uv), color, edges.a);
+#endif
+#ifdef POSTERIZE
+    color = posterize(color, ed_uniform.posterize_levels, ed_uniform.posterize_preserve_hue != 0u);
+#endif
+#ifdef INVERTED
+    // The edge color is laid everywhere but on the edges, where the scene shows through.
+    let fill = ed_uniform.edge_color;
+    color = blend_edge(color, fill.rgb, (1.0 - edges.a) * fill.a, ed_uniform.blend_mode);
+#else ifndef SHARPEN
+    color = blend_edge(color, edges.rgb, edges.a, ed_uniform.blend_mode);
+#endif
+    // The whole effect fades in from the untouched scene.
+    let intensity = ed_uniform.intensity;
+    var output = vec4f(mix(scene.rgb, color, intensity), output_alpha(scene.a, edges.a * intensity));
+#endif
+    if viewport == 0.0 {
+        output = scene;
+    }
+#endif
+
+#ifdef MASK_OUTPUT
+    return FragmentOutput(output, saturate(edge));
+#else
+    return FragmentOutput(output);
+#endif
+}
+
+// -----------------------
+// Compute Variant -------
+// -----------------------
+
+#ifdef COMPUTE
+// The output of the compute variant, the main texture of the view.
+@group(0) @binding(22) var output_texture: texture_storage_2d<rgba16float, write>;
+
+// The side of the square tile of pixels processed by a workgroup.
+const TILE_SIZE: u32 = 8u;
+// The side of the cache of a workgroup: its tile, and the border of one texel the 3x3 kernels read around it.
+const CACHE_SIZE: u32 = TILE_SIZE + 2u;
+
+// The texels around the tile of the workgroup, fetched once instead of once per kernel reading them.
+#ifdef DEPTH_TEXTURE
+var<workgroup> depth_cache: array<f32, CACHE_SIZE * CACHE_SIZE>;
+#endif
+#ifdef NORMALS
+#ifndef GEOMETRIC_NORMALS
+var<workgroup> normal_cache: array<vec3f, CACHE_SIZE * CACHE_SIZE>;
+#endif
+#endif
+var<workgroup> color_cache: array<vec3f, CACHE_SIZE * CACHE_SIZE>;
+// The texel of the first entry of the cache.
+var<private> cache_origin: vec2i;
+
+/// Returns the entry of the cache holding the texel centered at `uv`, or -1 if it isn't cached.
+///
+/// The filtering can't tell the offsets from the centers of the texels below its subtexel precision,
+/// of 1/256th of a texel at best, so the cached texels are identical to the samples they replace.
+fn cache_index(uv: vec2f) -> i32 {
+    let texel = uv * texture_size - 0.5;
+    let coord = round(texel);
+    if any(abs(texel - coord) > vec2f(1.0 / 256.0)) {
+        return -1;
+    }
+
+    let cache_coord = vec2i(coord) - cache_origin;
+    if any(cache_coord < vec2i(0)) || any(cache_coord >= vec2i(i32(CACHE_SIZE))) {
+        return -1;
+    }
+    return cache_coord.y * i32(CACHE_SIZE) + cache_coord.x;
+}
+
+/// Fetches the texels of the cache of the workgroup, spread over its invocations.
+/// The texels outside the texture are sampled like the kernels would, clamped to its border.
+fn fill_cache(local_index: u32) {
+    for (var index = local_index; index < CACHE_SIZE * CACHE_SIZE; index += TILE_SIZE * TILE_SIZE) {
+        let coord = cache_origin + vec2i(vec2u(index % CACHE_SIZE, index / CACHE_SIZE));
+        let uv = (vec2f(coord) + 0.5) * texel_size;
+#ifdef DEPTH_TEXTURE
+        depth_cache[index] = sample_depth(uv);
+#endif
+#ifdef NORMALS
+#ifndef GEOMETRIC_NORMALS
+        normal_cache[index] = sample_normal(uv);
+#endif
+#endif
+        color_cache[index] = sample_color(uv);
+    }
+}
+
+@compute @workgroup_size(TILE_SIZE, TILE_SIZE, 1)
+fn compute(
+    @builtin(global_invocation_id) global_id: vec3u,
+    @builtin(workgroup_id) workgroup_id: vec3u,
+    @builtin(local_invocation_index) local_index: u32,
+) {
+    texture_size = vec2f(textureDimensions(screen_texture));
+    texel_size = 1.0 / texture_size;
+    cache_origin = vec2i(workgroup_id.xy * TILE_SIZE) - 1;
+
+    // The whole workgroup fills the cache before any of its pixels is detected.
+    fill_cache(local_index);
+    workgroupBarrier();
+
+    let size = textureDimensions(output_texture);
+    if any(global_id.xy >= size) {
+        return;
+    }
+
+    let position = vec2f(global_id.xy) + 0.5;
+    let output = detect_pixel(FullscreenVertexOutput(vec4f(position, 0.0, 1.0), position / vec2f(size)));
+    textureStore(output_texture, global_id.xy, output.color);
+}
+#endif
@@ -0,0 +1,101 @@
+//! The bindings of the edge detection pass, for the custom shaders of `EdgeDetectionPlugin::shader`
+//! and `EdgeDetectionShader` to import, e.g. `#import bevy_edge_detection::bindings::{ed_uniform, screen_texture}`.
+//!
+//! They're declared with the same shader defs as the built-in shader, so only the textures the key of the view
+//! binds exist. The edge source textures are in `bevy_edge_detection::edge_sources`, with `EDGE_SOURCES`.
+
+#define_import_path bevy_edge_detection::bindings
+
+#import bevy_edge_detection::common::EdgeDetectionUniform
+
+// The view is bound at @group(0) @binding(0), like in the mesh view bindings the view transformations of bevy_pbr read.
+
+// Without depth texture sampling, e.g. on WebGL2, no depth is bound and every pixel reads as the background.
+#ifdef DEPTH_TEXTURE
+#ifdef MULTISAMPLED
+#ifdef NON_FILTERABLE
+@group(0) @binding(1) var depth_prepass_texture: texture_multisampled_2d<f32>;
+#else
+@group(0) @binding(1) var depth_prepass_texture: texture_depth_multisampled_2d;
+#endif
+#else ifdef NON_FILTERABLE
+@group(0) @binding(1) var depth_prepass_texture: texture_2d<f32>;
+#else
+@group(0) @binding(1) var depth_prepass_texture: texture_depth_2d;
+#endif
+#endif
+
+// The normals reconstructed from the depth don't read the normal prepass, which may not exist.
+#ifdef NORMALS
+#ifndef GEOMETRIC_NORMALS
+#ifdef MULTISAMPLED
+@group(0) @binding(2) var normal_prepass_texture: texture_multisampled_2d<f32>;
+#else
+@group(0) @binding(2) var normal_prepass_texture: texture_2d<f32>;
+#endif
+#endif
+#endif
+
+@group(0) @binding(3) var texture_sampler: sampler;
+
+@group(0) @binding(4) var noise_texture: texture_2d<f32>;
+@group(0) @binding(5) var noise_sampler: sampler;
+
+@group(0) @binding(6) var screen_texture: texture_2d<f32>;
+@group(0) @binding(7) var<uniform> ed_uniform: EdgeDetectionUniform;
+
+#ifdef ENABLE_STENCIL
+#ifdef MULTISAMPLED
+@group(0) @binding(8) var stencil_prepass_texture: texture_multisampled_2d<u32>;
+#else
+@group(0) @binding(8) var stencil_prepass_texture: texture_2d<u32>;
+#endif
+#endif
+
+#ifdef MOTION_VECTOR_PREPASS
+#ifdef MULTISAMPLED
+@group(0) @binding(9) var motion_vector_prepass_texture: texture_multisampled_2d<f32>;
+#else
+@group(0) @binding(9) var motion_vector_prepass_texture: texture_2d<f32>;
+#endif
+#endif
+
+#ifdef EDGE_COVERAGE
+// The number of edge pixels, read back by `EdgeCoverage`.
+@group(0) @binding(10) var<storage, read_write> edge_coverage: atomic<u32>;
+#endif
+
+// The sampler of the color attachment, linear or nearest depending on `EdgeDetection::color_filter`.
+@group(0) @binding(11) var screen_sampler: sampler;
+
+#ifdef AMBIENT_OCCLUSION
+// The ambient visibility computed by SSAO, 1.0 where unoccluded.
+@group(0) @binding(12) var ambient_occlusion_texture: texture_2d<f32>;
+#endif
+
+#ifdef AUTO_CALIBRATE
+// Log scale histograms of the depth then normal gradients, read back by `EdgeDetectionAutoCalibrate`.
+@group(0) @binding(13) var<storage, read_write> calibration_histograms: array<atomic<u32>, 128>;
+#endif
+
+#ifdef DEFERRED_PREPASS
+// The packed material attributes and normals of the deferred G-buffer, after the edge sources. Never multisampled.
+@group(0) @binding(18) var deferred_prepass_texture: texture_2d<u32>;
+#endif
+
+#ifdef ENABLE_SHADOW
+// r: visibility of the light, from 0.0 in shadow to 1.0 lit, supplied by `EdgeDetection::shadow_mask`.
+@group(0) @binding(19) var shadow_mask_texture: texture_2d<f32>;
+#endif
+
+#ifdef SCREEN_MASK
+// r: multiplies the edges, supplied by `EdgeDetection::screen_mask`. Spans the viewport, or the frame of a sub view.
+@group(0) @binding(23) var screen_mask_texture: texture_2d<f32>;
+#endif
+
+#ifdef MESH_TAGS
+// The depth of the `NoEdgeDetection` and `EdgeColorOverride` meshes, cleared to the far plane (0.0, reverse z),
+// and their tags, see `EDGE_MESH_TAG_FORMAT`. Never multisampled.
+@group(0) @binding(20) var mesh_tag_depth_texture: texture_2d<f32>;
+@group(0) @binding(21) var mesh_tag_texture: texture_2d<u32>;
+#endif
@@ -0,0 +1,298 @@
+//! Types and functions shared by the edge detection and composite shaders.
+
+#define_import_path bevy_edge_detection::common
+
+struct EdgeDetectionUniform {
+    depth_threshold: f32,
+    // of the concave creases
+    normal_threshold: f32,
+    color_threshold: f32,
+    material_threshold: f32,
+    shadow_threshold: f32,
+    normal_threshold_convex: f32,
+    // half the width of the smooth bands around the thresholds, 0.0 for the hard edges
+    depth_threshold_softness: f32,
+    normal_threshold_softness: f32,
+    color_threshold_softness: f32,
+    depth_thickness: f32,
+    normal_thickness: f32,
+    color_thickness: f32,
+    stencil_thickness: f32,
+    material_thickness: f32,
+    shadow_thickness: f32,
+    steep_angle_threshold: f32,
+    steep_angle_multiplier: f32,
+    slope_thickness_boost: f32,
+    thickness_attenuation: f32,
+    // view distance, in world units
+    thickness_reference_distance: f32,
+    corner_emphasis: f32,
+    ao_influence: f32,
+    motion_emphasis: f32,
+    motion_smear_strength: f32,
+    corner_rounding: f32,
+    // in pixels of the edge mask
+    dilation: f32,
+    // weight of the reprojected history, zero when there's none
+    temporal_blend: f32,
+    thick_outline_radius: f32,
+    boil_fps: f32,
+    // seconds since startup, wrapped
+    time: f32,
+    distortion_octaves: u32,
+    distortion_lacunarity: f32,
+    distortion_gain: f32,
+    strokes: u32,
+    // in pixels
+    stroke_scatter: f32,
+    noise_lod_bias: f32,
+    // viewport height the aspect corrected uv distortion is measured against, in pixels
+    noise_reference_height: f32,
+    // x: scale, in pixels; y: coverage
+    hatching: vec2f,
+
+    // xy: distortion frequency; zw: distortion strength
+    uv_distortion: vec4f,
+    // offset of the noise lookup uv, in repetitions of the noise texture
+    noise_scroll: vec2f,
+    // TAA jitter of the prepass textures, in pixels, zero when the edges are detected before the TAA resolve
+    jitter: vec2f,
+    // rotation and scale applied to the noise lookup uv, xy: first column; zw: second column
+    // (a mat2x2f has its columns padded to 16 bytes in uniforms on WebGL2, shifting the fields after it)
+    noise_uv_transform: vec4f,
+    edge_color: vec4f,
+    depth_edge_color: vec4f,
+    normal_edge_color: vec4f,
+    color_edge_color: vec4f,
+    // bits 0, 1 and 2: whether the depth, normal and color edges are drawn with their own color
+    channel_edge_colors: u32,
+    edge_color_far: vec4f,
+    // x: start; y: end, in view distances; z: 1.0 if the edge color shades into `edge_color_far`
+    edge_color_gradient: vec4f,
+    stencil_edge_color: vec4f,
+    shadow_edge_color: vec4f,
+    thick_outline_color: vec4f,
+    // replaces the scene with `OutputMode::Blueprint`
+    blueprint_background: vec4f,
+    // x: darken; y: saturate
+    object_color_adjustment: vec2f,
+
+    // xy: min; zw: max, in normalized viewport coordinates
+    exclusion_rects: array<vec4f, 8>,
+    exclusion_rect_count: u32,
+    // in pixels
+    exclusion_feather: f32,
+
+    // xy: min; zw: max, in normalized viewport coordinates
+    region_rects: array<vec4f, 4>,
+    // x: depth; y: normal; z: color
+    region_thresholds: array<vec4f, 4>,
+    region_edge_colors: array<vec4f, 4>,
+    region_count: u32,
+
+    // xyz: world space center; w: radius
+    scan: vec4f,
+    scan_falloff: f32,
+
+    // x: progress; y: softness; zw: origin, in normalized viewport coordinates
+    reveal: vec4f,
+    // 0: noise; 1: radial
+    reveal_mode: u32,
+
+    // world space
+    mask_volume_center: vec4f,
+    mask_volume_half_size: vec4f,
+    mask_volume_margin: f32,
+
+    // view distances, in world units
+    fade_start: f32,
+    // 0.0 if the distance fade is disabled
+    fade_end: f32,
+
+    // view distances, in world units
+    depth_range_min: f32,
+    // 0.0 if the depth window has no far end
+    depth_range_max: f32,
+    depth_range_feather: f32,
+
+    // number of bands, 0.0 if the posterization is disabled
+    posterize_levels: f32,
+    // 1 if only the luminance is posterized
+    posterize_preserve_hue: u32,
+    // x: width in pixels; y: desaturate; z: lighten
+    adjacent_grading: vec4f,
+
+    sharpen_amount: f32,
+    // fades the output in from the untouched scene
+    intensity: f32,
+    // multiplier of the linear edge colors, clamped on the LDR targets
+    edge_emissive_strength: f32,
+    // one of the EDGE_BLEND_* constants
+    blend_mode: u32,
+    // 0 or 1: parity of the pixels rendered this frame with the checkerboard; 2: all the pixels
+    checkerboard_phase: u32,
+    // 1 on the frames the gradient histograms are built
+    calibrate: u32,
+    // 1 on the frames the edges of the previous frame are reprojected instead of being detected
+    reproject: u32,
+
+    // x: base color; y: roughness; z: metallic; w: reflectance, 1.0 if compared
+    material_attributes: vec4f,
+
+    // x: threshold; y: thickness
+    edge_sources: array<vec4f, 4>,
+    edge_source_colors: array<vec4f, 4>,
+
+    // indexed by the tags of the `EdgeColorOverride` meshes
+    edge_color_overrides: array<vec4f, 8>,
+
+    // xy: offset of the viewport in the whole frame of a sub view; zw: size of the frame, zero without a sub view
+    sub_view: vec4f,
+    // xy: origin of the viewport of the camera in the target; zw: its size, in pixels, zero without a camera
+    viewport: vec4f,
+}
+
+fn srgb_to_linear(color: vec3f) -> vec3f {
+    return select(pow((color + 0.055) / 1.055, vec3f(2.4)), color / 12.92, color <= vec3f(0.04045));
+}
+
+fn linear_to_srgb(color: vec3f) -> vec3f {
+    return select(1.055 * pow(color, vec3f(1.0 / 2.4)) - 0.055, color * 12.92, color <= vec3f(0.0031308));
+}
+
+// The values of `EdgeBlendMode`.
+const EDGE_BLEND_ALPHA_OVER: u32 = 0u;
+const EDGE_BLEND_REPLACE: u32 = 1u;
+const EDGE_BLEND_MULTIPLY: u32 = 2u;
+const EDGE_BLEND_ADDITIVE: u32 = 3u;
+
+/// Blends `edge_color` with `color` in one of the `EDGE_BLEND_*` modes, weighted by the given opacity, in linear space.
+fn blend_edge(color: vec3f, edge_color: vec3f, opacity: f32, mode: u32) -> vec3f {
+    if mode == EDGE_BLEND_REPLACE {
+        return select(color, edge_color, opacity >= 0.5);
+    }
+#ifdef GAMMA_ENCODED_TARGET
+    // The target holds gamma encoded values, so the edge color is written as is at full opacity,
+    // but partial opacities and the other modes are blended in linear space.
+    let linear = blend_linear(srgb_to_linear(color), srgb_to_linear(edge_color), opacity, mode);
+    return select(linear_to_srgb(linear), edge_color, mode == EDGE_BLEND_ALPHA_OVER && opacity >= 1.0);
+#else
+    return blend_linear(color, edge_color, opacity, mode);
+#endif
+}
+
+fn blend_linear(color: vec3f, edge_color: vec3f, opacity: f32, mode: u32) -> vec3f {
+    var blended: vec3f;
+    switch mode {
+        case EDGE_BLEND_MULTIPLY: {
+            blended = color * edge_color;
+        }
+        case EDGE_BLEND_ADDITIVE: {
+            blended = color + edge_color;
+        }
+        default: {
+            blended = edge_color;
+        }
+    }
+    return mix(color, blended, opacity);
+}
+
+fn luminance(color: vec3f) -> f32 {
+    return dot(color, vec3f(0.2126, 0.7152, 0.0722));
+}
+
+/// Quantizes `value` in [0, 1] into `levels` bands, from 0.0 to 1.0.
+fn quantize(value: vec3f, levels: f32) -> vec3f {
+    return min(floor(saturate(value) * levels), vec3f(levels - 1.0)) / (levels - 1.0);
+}
+
+/// Quantizes the scene color into `levels` bands, either its luminance or each of its channels.
+fn posterize(color: vec3f, levels: f32, preserve_hue: bool) -> vec3f {
+#ifdef HDR_TARGET
+    // Reinhard tonemap on the luminance, so the bands are spread over the visible range
+    // instead of all the bright values collapsing into the top band.
+    let mapped = color / (1.0 + luminance(color));
+#else
+    let mapped = color;
+#endif
+
+    var posterized: vec3f;
+    if preserve_hue {
+        let mapped_luminance = luminance(mapped);
+        let banded_luminance = quantize(vec3f(mapped_luminance), levels).x;
+        posterized = mapped * select(banded_luminance / mapped_luminance, 0.0, mapped_luminance <= 0.0);
+    } else {
+        posterized = quantize(mapped, levels);
+    }
+
+#ifdef HDR_TARGET
+    // Inverse of the tonemap, the top band being bounded as it maps back to infinity.
+    return posterized / (1.0 - min(luminance(posterized), 0.999));
+#else
+    return posterized;
+#endif
+}
+
+/// Unsharp mask of `color` by `amount`, against the average of its four neighbors.
+/// The result is clamped to the range of the neighborhood, which prevents ringing, and negative HDR colors.
+fn sharpen(color: vec3f, north: vec3f, south: vec3f, east: vec3f, west: vec3f, amount: f32) -> vec3f {
+    let blurred = (north + south + east + west) * 0.25;
+    let sharpened = color + (color - blurred) * amount;
+
+    let neighborhood_min = min(color, min(min(north, south), min(east, west)));
+    let neighborhood_max = max(color, max(max(north, south), max(east, west)));
+    return clamp(sharpened, neighborhood_min, neighborhood_max);
+}
+
+/// Whether the pixel at `position` of the target is in `viewport`, see `EdgeDetectionUniform::viewport`.
+/// The other pixels belong to the other cameras drawing to the target, e.g. in split-screen.
+fn in_viewport(position: vec2f, viewport: vec4f) -> bool {
+    let viewport_position = position - viewport.xy;
+    return all(viewport_position >= vec2f(0.0)) && all(viewport_position < viewport.zw);
+}
+
+/// Returns the alpha written to the render target: the edge opacity with `WRITE_ALPHA_MASK`,
+/// the alpha of the scene with `PRESERVE_SCENE_ALPHA`, 1.0 otherwise.
+fn output_alpha(scene_alpha: f32, edge_opacity: f32) -> f32 {
+#ifdef WRITE_ALPHA_MASK
+    return edge_opacity;
+#else ifdef PRESERVE_SCENE_ALPHA
+    return scene_alpha;
+#else
+    return 1.0;
+#endif
+}
+
+/// Composites the `top` edge layer over the `bottom` one, the alpha of the layers being the opacity of the edges.
+fn edge_over(top: vec4f, bottom: vec4f) -> vec4f {
+    let alpha = top.a + bottom.a * (1.0 - top.a);
+    if alpha <= 0.0 {
+        return vec4f(0.0);
+    }
+    return vec4f((top.rgb * top.a + bottom.rgb * bottom.a * (1.0 - top.a)) / alpha, alpha);
+}
+
+/// The largest finite `f32`.
+const F32_MAX: f32 = 3.40282347e38;
+
+/// Whether `value` is neither infinite nor NaN. The exponent bits are compared, which fast math can't fold away
+/// like `value == value`.
+fn is_finite(value: f32) -> bool {
+    return (bitcast<u32>(value) & 0x7f800000u) != 0x7f800000u;
+}
+
+/// Returns `value`, or `fallback` if it's infinite or NaN.
+fn finite_or(value: f32, fallback: f32) -> f32 {
+    return select(fallback, value, is_finite(value));
+}
+
+/// Returns `depth` clamped to the [0, 1] range of the reverse-Z depth, or `fallback` if it's infinite or NaN.
+fn sanitize_depth(depth: f32, fallback: f32) -> f32 {
+    return select(fallback, saturate(depth), is_finite(depth));
+}
+
+/// Returns the packed `normal`, or `fallback` if any of its components is infinite or NaN.
+fn sanitize_normal(normal: vec3f, fallback: vec3f) -> vec3f {
+    let finite = (bitcast<vec3u>(normal) & vec3u(0x7f800000u)) != vec3u(0x7f800000u);
+    return select(fallback, normal, all(finite));
+}
@@ -0,0 +1,42 @@
+//! Dilation of the edge mask, before it's composited onto the screen.
+//!
+//! Grows the edges by a disc, which keeps the joins and corners of the lines closed. Each pixel takes
+//! the color of its strongest neighbor instead of an average, so the edge color stays crisp, and only
+//! the mask is dilated, so the scene isn't smeared.
+
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+#import bevy_edge_detection::common::EdgeDetectionUniform
+
+// rgb: edge color; a: edge opacity
+@group(0) @binding(0) var edge_mask_texture: texture_2d<f32>;
+@group(0) @binding(1) var<uniform> ed_uniform: EdgeDetectionUniform;
+
+// Bounds the search of the edges around a pixel, in pixels of the edge mask.
+const MAX_DILATION: f32 = 8.0;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4f {
+    let pixel_coord = vec2i(in.position.xy);
+    let radius = clamp(ed_uniform.dilation, 0.0, MAX_DILATION);
+    let extent = i32(ceil(radius));
+    let max_coord = vec2i(textureDimensions(edge_mask_texture)) - 1;
+
+    var edges = textureLoad(edge_mask_texture, pixel_coord, 0);
+    for (var y = -extent; y <= extent; y++) {
+        for (var x = -extent; x <= extent; x++) {
+            // Antialiased coverage of the disc at this distance.
+            let coverage = saturate(radius + 0.5 - length(vec2f(f32(x), f32(y))));
+            if coverage <= 0.0 {
+                continue;
+            }
+
+            let neighbor = textureLoad(edge_mask_texture, clamp(pixel_coord + vec2i(x, y), vec2i(0), max_coord), 0);
+            let opacity = neighbor.a * coverage;
+            if opacity > edges.a {
+                edges = vec4f(neighbor.rgb, opacity);
+            }
+        }
+    }
+
+    return edges;
+}
@@ -0,0 +1,48 @@
+// Draws the tags of the meshes excluded from the edge detection or outlined with their own color,
+// see `NoEdgeDetection` and `EdgeColorOverride`.
+
+#import bevy_pbr::{
+    mesh_bindings::mesh,
+    mesh_functions,
+    morph,
+    skinning,
+    view_transformations::position_world_to_clip,
+}
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+#ifdef MORPH_TARGETS
+    @builtin(vertex_index) index: u32,
+#endif
+    @location(0) position: vec3f,
+#ifdef SKINNED
+    @location(1) joint_indices: vec4<u32>,
+    @location(2) joint_weights: vec4f,
+#endif
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> @builtin(position) vec4f {
+    var position = vertex.position;
+#ifdef MORPH_TARGETS
+    let vertex_index = vertex.index - mesh[vertex.instance_index].first_vertex_index;
+    for (var i = 0u; i < morph::layer_count(); i++) {
+        position += morph::weight_at(i) * morph::morph(vertex_index, morph::position_offset, i);
+    }
+#endif
+
+#ifdef SKINNED
+    let world_from_local = skinning::skin_model(vertex.joint_indices, vertex.joint_weights, vertex.instance_index);
+#else
+    let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+#endif
+
+    let world_position = mesh_functions::mesh_position_local_to_world(world_from_local, vec4f(position, 1.0));
+    return position_world_to_clip(world_position.xyz);
+}
+
+// The tag is specialized per pipeline, so the draws of different tags aren't batched together.
+@fragment
+fn fragment() -> @location(0) u32 {
+    return #{MESH_TAG}u;
+}
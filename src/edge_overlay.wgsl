@@ -0,0 +1,48 @@
+//! Composites the edges rendered alone by another camera over the output of the view, see `EdgeOverlay`.
+
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct EdgeOverlayUniform {
+    opacity: f32,
+    // one of the BLEND_* constants
+    blend: u32,
+    _webgl2_padding: vec2f,
+}
+
+// The values of `EdgeOverlayBlend`.
+const BLEND_ALPHA: u32 = 0u;
+const BLEND_ADD: u32 = 1u;
+const BLEND_MULTIPLY: u32 = 2u;
+const BLEND_SCREEN: u32 = 3u;
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+// rgb: edge color; a: edge opacity
+@group(0) @binding(1) var overlay_texture: texture_2d<f32>;
+@group(0) @binding(2) var overlay_sampler: sampler;
+@group(0) @binding(3) var<uniform> overlay: EdgeOverlayUniform;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4f {
+    let scene = textureLoad(screen_texture, vec2i(in.position.xy), 0);
+    // The overlay is stretched over the view, whatever its resolution.
+    let edges = textureSampleLevel(overlay_texture, overlay_sampler, in.uv, 0.0);
+    let opacity = saturate(edges.a * overlay.opacity);
+
+    var blended: vec3f;
+    switch overlay.blend {
+        case BLEND_ADD: {
+            blended = scene.rgb + edges.rgb;
+        }
+        case BLEND_MULTIPLY: {
+            blended = scene.rgb * edges.rgb;
+        }
+        case BLEND_SCREEN: {
+            blended = scene.rgb + edges.rgb - scene.rgb * edges.rgb;
+        }
+        default: {
+            blended = edges.rgb;
+        }
+    }
+
+    return vec4f(mix(scene.rgb, blended, opacity), scene.a);
+}
@@ -0,0 +1,193 @@
+use std::marker::PhantomData;
+
+use bevy::{asset::weak_handle, ecs::world::EntityRef, prelude::*};
+
+use crate::EdgeDetectionUniform;
+
+pub const EDGE_SOURCES_SHADER_HANDLE: Handle<Shader> =
+    weak_handle!("6c1f9e27-4b8a-4d3e-a5f0-93d2b7e48c15");
+
+/// The maximum number of [`EdgeSource`]s registered with an [`EdgeSourcePlugin`].
+pub const MAX_EDGE_SOURCES: usize = 4;
+
+/// The binding of the texture of the first [`EdgeSource`], the others follow it.
+pub const EDGE_SOURCE_FIRST_BINDING: u32 = 14;
+
+/// A custom edge source, detecting edges in a texture rendered by your game, e.g. a wetness or material id prepass.
+///
+/// The source is a component added to the cameras with [`EdgeDetection`](crate::EdgeDetection), holding the texture
+/// of the camera and its settings. Register it with an [`EdgeSourcePlugin`]. Its edges are thresholded like
+/// the built-in sources and drawn over them with their own color, like the stencil edges.
+///
+/// The gradient is computed by the WGSL function [`EdgeSource::FUNCTION`], defined in [`EdgeSource::SHADER`]
+/// with the signature:
+///
+/// ```wgsl
+/// fn wetness_gradient(source: texture_2d<f32>, source_sampler: sampler, uv: vec2f, offset: vec2f) -> f32
+/// ```
+///
+/// where `offset` is the size of a texel scaled by [`EdgeSourceSettings::thickness`]. A pixel is an edge
+/// when the returned gradient is above [`EdgeSourceSettings::threshold`]. The texture is bound as a filterable
+/// float texture and `source_sampler` is a linear sampler clamping to the borders, unless the adapter can't filter
+/// float textures: they are then bound as non-filterable, with a nearest sampler.
+pub trait EdgeSource: Component {
+    /// The name of the WGSL function computing the gradient.
+    const FUNCTION: &'static str;
+    /// The WGSL source defining [`EdgeSource::FUNCTION`].
+    const SHADER: &'static str;
+
+    /// The texture the edges are detected in. It's replaced by a white texture until it's loaded.
+    fn image(&self) -> &Handle<Image>;
+
+    /// The threshold, thickness and color of the edges of this source.
+    fn settings(&self) -> EdgeSourceSettings;
+}
+
+/// The settings of the edges of an [`EdgeSource`], the same as the ones of the built-in sources.
+#[derive(Clone, Copy, Debug, Reflect)]
+#[reflect(Default)]
+pub struct EdgeSourceSettings {
+    /// Gradient above which a pixel is an edge.
+    pub threshold: f32,
+    /// Distance in pixels between the samples of the gradient.
+    pub thickness: f32,
+    /// Color of the edges.
+    pub color: Color,
+}
+
+impl Default for EdgeSourceSettings {
+    fn default() -> Self {
+        Self {
+            threshold: 0.1,
+            thickness: 1.0,
+            color: Color::BLACK,
+        }
+    }
+}
+
+/// Registers an [`EdgeSource`], at most [`MAX_EDGE_SOURCES`] of them.
+///
+/// Sources must be registered before [`EdgeDetectionPlugin`](crate::EdgeDetectionPlugin) is finished,
+/// which generates the shader module calling their functions.
+pub struct EdgeSourcePlugin<S: EdgeSource>(PhantomData<S>);
+
+impl<S: EdgeSource> Default for EdgeSourcePlugin<S> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<S: EdgeSource> Plugin for EdgeSourcePlugin<S> {
+    fn build(&self, app: &mut App) {
+        let mut sources = app.world_mut().get_resource_or_init::<EdgeSources>();
+        assert!(
+            sources.sources.len() < MAX_EDGE_SOURCES,
+            "At most {MAX_EDGE_SOURCES} edge sources can be registered"
+        );
+
+        sources.sources.push(RegisteredEdgeSource {
+            function: S::FUNCTION,
+            shader: S::SHADER,
+            extract: extract_edge_source::<S>,
+        });
+    }
+}
+
+/// The [`EdgeSource`]s registered with an [`EdgeSourcePlugin`], in registration order.
+#[derive(Resource, Default)]
+pub struct EdgeSources {
+    sources: Vec<RegisteredEdgeSource>,
+}
+
+struct RegisteredEdgeSource {
+    function: &'static str,
+    shader: &'static str,
+    extract: fn(EntityRef) -> Option<(AssetId<Image>, EdgeSourceSettings)>,
+}
+
+fn extract_edge_source<S: EdgeSource>(
+    entity: EntityRef,
+) -> Option<(AssetId<Image>, EdgeSourceSettings)> {
+    let source = entity.get::<S>()?;
+    Some((source.image().id(), source.settings()))
+}
+
+impl EdgeSources {
+    /// Whether no [`EdgeSource`] is registered.
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Extracts the sources of a camera, and writes their settings to its uniform.
+    pub fn extract(
+        &self,
+        camera: EntityRef,
+        uniform: &mut EdgeDetectionUniform,
+    ) -> ExtractedEdgeSources {
+        let mut extracted = ExtractedEdgeSources::default();
+
+        for (index, source) in self.sources.iter().enumerate() {
+            let Some((image, settings)) = (source.extract)(camera) else {
+                continue;
+            };
+
+            extracted.0[index] = Some(image);
+            uniform.edge_sources[index] =
+                Vec4::new(settings.threshold, settings.thickness, 0.0, 0.0);
+            uniform.edge_source_colors[index] = settings.color.into();
+        }
+
+        extracted
+    }
+
+    /// Generates the shader module binding the textures of the sources and calling their functions,
+    /// each behind the `EDGE_SOURCE_{index}` shader def.
+    fn shader(&self) -> String {
+        let mut shader = String::from("#define_import_path bevy_edge_detection::edge_sources\n");
+
+        for (index, source) in self.sources.iter().enumerate() {
+            let binding = EDGE_SOURCE_FIRST_BINDING + index as u32;
+            shader += &format!(
+                "
+#ifdef EDGE_SOURCE_{index}
+@group(0) @binding({binding}) var edge_source_{index}_texture: texture_2d<f32>;
+
+{}
+
+fn edge_source_{index}_gradient(source_sampler: sampler, uv: vec2f, offset: vec2f) -> f32 {{
+    return {}(edge_source_{index}_texture, source_sampler, uv, offset);
+}}
+#endif
+",
+                source.shader, source.function
+            );
+        }
+
+        shader
+    }
+}
+
+/// The texture of each [`EdgeSource`] of a view, indexed by registration order.
+#[derive(Component, Clone, Copy, Default, Debug)]
+pub struct ExtractedEdgeSources(pub [Option<AssetId<Image>>; MAX_EDGE_SOURCES]);
+
+impl ExtractedEdgeSources {
+    /// The bits of the sources of the view, see [`EdgeDetectionKey::edge_sources`](crate::EdgeDetectionKey::edge_sources).
+    pub fn mask(&self) -> u8 {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, image)| image.is_some())
+            .fold(0, |mask, (index, _)| mask | 1 << index)
+    }
+}
+
+pub(crate) fn finish(app: &mut App) {
+    // The module is inserted even without sources: the imports of the edge detection shader are resolved
+    // regardless of the shader defs around them.
+    let sources = app.world().resource::<EdgeSources>();
+    let shader = Shader::from_wgsl(sources.shader(), "bevy_edge_detection/edge_sources.wgsl");
+    app.world_mut()
+        .resource_mut::<Assets<Shader>>()
+        .insert(&EDGE_SOURCES_SHADER_HANDLE, shader);
+}
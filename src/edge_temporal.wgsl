@@ -0,0 +1,88 @@
+//! Temporal stabilization of the edge mask, before it's post-filtered and composited onto the screen.
+//!
+//! The stabilized edges of the previous frame are reprojected along the motion vectors and blended
+//! with the edges of this frame, so the pixels flipping on and off under slow motion don't shimmer.
+//! The history is rejected where it can't be trusted: outside the screen, under fast motion, and where
+//! the depth doesn't match the one of the previous frame, i.e. on the surfaces just disoccluded.
+
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+#import bevy_edge_detection::common::EdgeDetectionUniform
+
+// rgb: edge color; a: edge opacity
+@group(0) @binding(0) var edge_mask_texture: texture_2d<f32>;
+@group(0) @binding(1) var history_texture: texture_2d<f32>;
+@group(0) @binding(2) var history_depth_texture: texture_2d<f32>;
+#ifdef MULTISAMPLED
+#ifdef NON_FILTERABLE
+@group(0) @binding(3) var depth_texture: texture_multisampled_2d<f32>;
+#else
+@group(0) @binding(3) var depth_texture: texture_depth_multisampled_2d;
+#endif
+@group(0) @binding(4) var motion_vector_prepass_texture: texture_multisampled_2d<f32>;
+#else
+#ifdef NON_FILTERABLE
+@group(0) @binding(3) var depth_texture: texture_2d<f32>;
+#else
+@group(0) @binding(3) var depth_texture: texture_depth_2d;
+#endif
+@group(0) @binding(4) var motion_vector_prepass_texture: texture_2d<f32>;
+#endif
+@group(0) @binding(5) var<uniform> ed_uniform: EdgeDetectionUniform;
+
+// The distance in pixels from which the history fades out, it's rejected at twice this distance,
+// so fast motion doesn't leave trails.
+const HISTORY_FADE_DISTANCE: f32 = 32.0;
+// The relative difference with the depth of the previous frame beyond which the history is rejected.
+const HISTORY_DEPTH_TOLERANCE: f32 = 0.05;
+
+struct FragmentOutput {
+    @location(0) edges: vec4f,
+    // The depth of the pixel, compared by the next frame.
+    @location(1) depth: f32,
+}
+
+fn load_depth(pixel_coord: vec2i) -> f32 {
+#ifdef NON_FILTERABLE
+    return textureLoad(depth_texture, pixel_coord, 0).r;
+#else
+    return textureLoad(depth_texture, pixel_coord, 0);
+#endif
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> FragmentOutput {
+    let pixel_coord = vec2i(in.position.xy);
+    let size = vec2f(textureDimensions(edge_mask_texture));
+    // The edge mask is smaller than the prepass textures when the edges are detected at a lower resolution.
+    let view_size = vec2f(textureDimensions(depth_texture));
+    let view_coord = vec2i((vec2f(pixel_coord) + 0.5) * view_size / size);
+
+    let edges = textureLoad(edge_mask_texture, pixel_coord, 0);
+    let depth = load_depth(view_coord);
+
+    // The motion vectors are in uv units, from the previous position to the current one.
+    let motion = textureLoad(motion_vector_prepass_texture, view_coord, 0).xy;
+    let previous = vec2f(pixel_coord) + 0.5 - motion * size;
+    let previous_coord = vec2i(floor(previous));
+
+    var weight = ed_uniform.temporal_blend;
+    weight *= 1.0 - smoothstep(HISTORY_FADE_DISTANCE, 2.0 * HISTORY_FADE_DISTANCE, length(motion * view_size));
+    // There's no history outside the screen.
+    if any(previous < vec2f(0.0)) || any(previous >= size) {
+        weight = 0.0;
+    }
+
+    // The surfaces uncovered this frame had another depth in the previous one, their history belongs to the
+    // surface that moved away. The background at infinity is at zero on both sides.
+    let previous_depth = textureLoad(history_depth_texture, clamp(previous_coord, vec2i(0), vec2i(size) - 1), 0).r;
+    if abs(previous_depth - depth) > HISTORY_DEPTH_TOLERANCE * max(previous_depth, depth) {
+        weight = 0.0;
+    }
+
+    // The colors are weighted by the opacities, so the transparent pixels don't darken the edges.
+    let history = textureLoad(history_texture, clamp(previous_coord, vec2i(0), vec2i(size) - 1), 0);
+    let color = mix(edges.rgb * edges.a, history.rgb * history.a, weight);
+    let opacity = mix(edges.a, history.a, weight);
+
+    return FragmentOutput(vec4f(color / max(opacity, 1e-4), opacity), depth);
+}
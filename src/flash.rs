@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{EdgeDetection, EdgeDetectionSet};
+
+/// A flash of the edges of a camera toward a color, e.g. red for a moment when the player takes damage.
+///
+/// The edges jump to `color` and fade back to the color of the [`EdgeDetection`] over `duration`,
+/// following `curve`. The alpha of `color` is blended too, so an opaque flash also strengthens faint edges.
+/// Start one with an [`EdgeFlashEvent`].
+#[derive(Clone, Copy, Debug, Reflect)]
+pub struct EdgeFlash {
+    /// The color the edges flash to.
+    pub color: Color,
+    /// How long the edges take to fade back.
+    pub duration: Duration,
+    /// The easing of the fade, from 0.0 at the start of the flash to 1.0 once it's over.
+    pub curve: EaseFunction,
+}
+
+impl EdgeFlash {
+    /// A flash to `color`, fading back over `duration` with a quadratic ease out.
+    pub fn new(color: Color, duration: Duration) -> Self {
+        Self {
+            color,
+            duration,
+            curve: EaseFunction::QuadraticOut,
+        }
+    }
+}
+
+/// Starts an [`EdgeFlash`] on a camera with an [`EdgeDetection`].
+#[derive(Event, Clone, Copy, Debug)]
+pub struct EdgeFlashEvent {
+    /// The camera to flash the edges of.
+    pub camera: Entity,
+    pub flash: EdgeFlash,
+}
+
+/// The flashes running on a camera, added by the [`EdgeFlashEvent`]s and removed once they're all over.
+///
+/// Like the [`EdgeGlitch`](crate::EdgeGlitch), the flashes are blended into the copy of the [`EdgeDetection`]
+/// of the camera extracted to the render world, so the component itself is never changed and its edge color
+/// is back exactly once the flashes are over. A flash started mid-flash is blended over the running ones,
+/// which keep fading underneath it, so the edges never jump back to their own color in between.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct EdgeFlashes {
+    /// The running flashes, from the oldest, with the time elapsed since they started.
+    flashes: Vec<(EdgeFlash, Duration)>,
+}
+
+impl EdgeFlashes {
+    /// Returns `edge_detection` with the running flashes blended into its edge color.
+    pub fn apply(&self, edge_detection: &EdgeDetection) -> EdgeDetection {
+        let mut flashed = edge_detection.clone();
+        let mut color = LinearRgba::from(edge_detection.edge_color);
+        for (flash, elapsed) in &self.flashes {
+            let progress = elapsed.as_secs_f32() / flash.duration.as_secs_f32().max(f32::EPSILON);
+            let weight = 1.0 - flash.curve.sample_clamped(progress).clamp(0.0, 1.0);
+            color = color.mix(&flash.color.into(), weight);
+        }
+        flashed.edge_color = color.into();
+        flashed
+    }
+
+    /// Whether no flash is running.
+    pub fn is_empty(&self) -> bool {
+        self.flashes.is_empty()
+    }
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.register_type::<EdgeFlashes>()
+        .add_event::<EdgeFlashEvent>()
+        .add_systems(
+            Update,
+            (advance_edge_flashes, start_edge_flashes)
+                .chain()
+                .in_set(EdgeDetectionSet::Configure),
+        );
+}
+
+/// Adds the flashes of the [`EdgeFlashEvent`]s to the [`EdgeFlashes`] of their cameras.
+pub fn start_edge_flashes(
+    mut commands: Commands,
+    mut events: EventReader<EdgeFlashEvent>,
+    cameras: Query<(), With<EdgeDetection>>,
+) {
+    for &EdgeFlashEvent { camera, flash } in events.read() {
+        if !cameras.contains(camera) {
+            warn!("Can't flash the edges of {camera}, it has no `EdgeDetection`");
+            continue;
+        }
+
+        commands
+            .entity(camera)
+            .entry::<EdgeFlashes>()
+            .or_default()
+            .and_modify(move |mut flashes| flashes.flashes.push((flash, Duration::ZERO)));
+    }
+}
+
+/// Advances the [`EdgeFlashes`], dropping the flashes that are over and removing the component once they all are.
+pub fn advance_edge_flashes(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut EdgeFlashes)>,
+    time: Res<Time>,
+) {
+    for (entity, mut flashes) in query.iter_mut() {
+        flashes.flashes.retain_mut(|(flash, elapsed)| {
+            *elapsed += time.delta();
+            *elapsed < flash.duration
+        });
+        if flashes.is_empty() {
+            commands.entity(entity).remove::<EdgeFlashes>();
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::{EdgeDetection, EdgeDetectionSet};
+
+/// The factor the thresholds are multiplied by on the frames the edges drop out.
+const DROPOUT_THRESHOLD_SCALE: f32 = 1000.0;
+/// The distortion strength added by a spike at full intensity.
+const MAX_DISTORTION_SPIKE: f32 = 0.05;
+
+/// Jitters the edges of a camera like a corrupted signal: the edge color flickers, the distortion spikes
+/// and the edges drop out on some frames.
+///
+/// The jitter is applied to the copy of the [`EdgeDetection`] of the camera extracted to the render world,
+/// so the component itself is never changed. It's derived from the `seed` and the [`FrameCount`](bevy::diagnostic::FrameCount),
+/// so a replay with the same seed glitches the same way. The glitch fades out as its `intensity` decays,
+/// and is removed once it reaches zero. Only the settings of the uniform are jittered, so the pipeline of
+/// the camera isn't specialized again.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct EdgeGlitch {
+    /// The strength of the jitter, with 1.0 flickering on most frames.
+    pub intensity: f32,
+    /// The seed of the jitter.
+    pub seed: u64,
+    /// The intensity lost per second, e.g. 1.0 for a one-second glitch at full intensity.
+    pub decay: f32,
+}
+
+impl EdgeGlitch {
+    /// Returns `edge_detection` jittered for `frame`.
+    pub fn apply(&self, edge_detection: &EdgeDetection, frame: u32) -> EdgeDetection {
+        let chance = self.intensity.clamp(0.0, 1.0);
+        let mut state = self.seed ^ (frame as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        let mut random = || (splitmix64(&mut state) >> 40) as f32 / (1u32 << 24) as f32;
+
+        let mut glitched = edge_detection.clone();
+
+        // The color jumps to a random hue and the opacity flickers.
+        let alpha = edge_detection.edge_color.alpha();
+        if random() < chance {
+            glitched.edge_color = Color::hsla(random() * 360.0, 1.0, 0.5, alpha);
+        }
+        glitched
+            .edge_color
+            .set_alpha(alpha * (1.0 - chance * random()));
+
+        // Rare but strong distortion spikes.
+        let spike = random().powi(4) * self.intensity;
+        glitched.uv_distortion_strength += Vec2::splat(spike * MAX_DISTORTION_SPIKE);
+        glitched.noise_rotation += spike * TAU;
+
+        // The edges drop out.
+        if random() < chance * 0.3 {
+            glitched.depth_threshold *= DROPOUT_THRESHOLD_SCALE;
+            glitched.normal_threshold *= DROPOUT_THRESHOLD_SCALE;
+            glitched.normal_threshold_concave = glitched
+                .normal_threshold_concave
+                .map(|threshold| threshold * DROPOUT_THRESHOLD_SCALE);
+            glitched.normal_threshold_convex = glitched
+                .normal_threshold_convex
+                .map(|threshold| threshold * DROPOUT_THRESHOLD_SCALE);
+            glitched.color_threshold *= DROPOUT_THRESHOLD_SCALE;
+        }
+
+        glitched
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.register_type::<EdgeGlitch>().add_systems(
+        Update,
+        decay_edge_glitches.in_set(EdgeDetectionSet::Configure),
+    );
+}
+
+/// Decays the intensity of the [`EdgeGlitch`]es, and removes them once they've faded out.
+pub fn decay_edge_glitches(
+    mut commands: Commands,
+    mut glitches: Query<(Entity, &mut EdgeGlitch)>,
+    time: Res<Time>,
+) {
+    for (entity, mut glitch) in glitches.iter_mut() {
+        glitch.intensity -= glitch.decay * time.delta_secs();
+        if glitch.intensity <= 0.0 {
+            commands.entity(entity).remove::<EdgeGlitch>();
+        }
+    }
+}
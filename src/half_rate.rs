@@ -0,0 +1,86 @@
+use bevy::{
+    diagnostic::FrameCount,
+    prelude::*,
+    render::{
+        render_resource::*, renderer::RenderDevice, texture::CachedTexture, view::ViewTarget,
+    },
+};
+
+use crate::{
+    EdgeDetection, EdgeDetectionMaskTexture, EdgeDetectionPipelineId, EdgeDetectionUniform,
+    EDGE_MASK_TEXTURE_FORMAT,
+};
+
+/// Marks an [`EdgeDetectionMaskTexture`] as the history of a view with [`EdgeDetection::half_rate`].
+///
+/// The texture isn't taken from the texture cache, so it keeps its content between frames:
+/// on the frames the edge detection pass is skipped, the edges of the previous frame are reprojected from it.
+#[derive(Component)]
+pub struct EdgeDetectionHalfRateHistory {
+    /// Whether the edges of the previous frame are reprojected this frame, instead of being detected.
+    pub reproject: bool,
+}
+
+/// Creates the history of the views with [`EdgeDetection::half_rate`], and picks the frames the edges are detected.
+/// The edges are detected when the history is (re)created, e.g. on the first frame or on resize,
+/// or reset with [`EdgeDetection::reset_history`].
+#[allow(clippy::type_complexity)]
+pub fn prepare_edge_detection_half_rate(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    frame_count: Res<FrameCount>,
+    mut views: Query<(
+        Entity,
+        &ViewTarget,
+        &EdgeDetection,
+        &EdgeDetectionPipelineId,
+        &mut EdgeDetectionUniform,
+        Option<&EdgeDetectionMaskTexture>,
+        Option<&mut EdgeDetectionHalfRateHistory>,
+    )>,
+) {
+    for (entity, view_target, edge_detection, pipeline_id, mut uniform, mask, history) in
+        views.iter_mut()
+    {
+        if !pipeline_id.key.half_rate {
+            // The history is replaced, or removed, by the regular edge mask.
+            if history.is_some() {
+                commands
+                    .entity(entity)
+                    .remove::<EdgeDetectionHalfRateHistory>();
+            }
+            continue;
+        }
+
+        let size = view_target.main_texture().size();
+        let history_valid =
+            history.is_some() && mask.is_some_and(|mask| mask.0.texture.size() == size);
+
+        if let Some(mut history) = history.filter(|_| history_valid) {
+            history.reproject = !edge_detection.reset_history && frame_count.0 & 1 == 1;
+            uniform.reproject = history.reproject.into();
+            continue;
+        }
+
+        let texture = render_device.create_texture(&TextureDescriptor {
+            label: Some("edge_detection_half_rate_history"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: EDGE_MASK_TEXTURE_FORMAT,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let default_view = texture.create_view(&TextureViewDescriptor::default());
+
+        uniform.reproject = 0;
+        commands.entity(entity).insert((
+            EdgeDetectionMaskTexture(CachedTexture {
+                texture,
+                default_view,
+            }),
+            EdgeDetectionHalfRateHistory { reproject: false },
+        ));
+    }
+}
@@ -0,0 +1,342 @@
+use bevy::{
+    asset::weak_handle,
+    core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    prelude::*,
+    render::{
+        diagnostic::RecordDiagnostics,
+        render_resource::{
+            binding_types::{texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice, RenderQueue},
+        texture::{CachedTexture, TextureCache},
+        view::ViewTarget,
+    },
+};
+
+use crate::EdgeDetectionPipelineId;
+
+pub const JUMP_FLOOD_SHADER_HANDLE: Handle<Shader> =
+    weak_handle!("5d4a3f0e-2b8c-4e51-9d6a-7c1f0e2b3a94");
+
+/// The format of the jump flood textures, the rg channels hold the pixel coordinates of the nearest edge pixel,
+/// or [`JUMP_FLOOD_NO_SEED`] if none has been found yet.
+///
+/// Unlike the 32-bit float formats, the 16-bit integer ones are renderable on all the backends, WebGL2 included.
+pub const JUMP_FLOOD_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rg16Uint;
+
+/// The coordinates of the jump flood texels without a nearest edge pixel.
+pub const JUMP_FLOOD_NO_SEED: u32 = u16::MAX as u32;
+
+/// The largest radius of [`ThickOutline`], which bounds the number of jump flood passes.
+pub const MAX_THICK_OUTLINE_RADIUS: f32 = 256.0;
+
+/// A thick outline drawn around the detected edges, see [`EdgeDetection::thick_outline`](crate::EdgeDetection::thick_outline).
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ThickOutline {
+    /// Radius of the outline around the edges, in pixels.
+    ///
+    /// Range: [0.0, 256.0]
+    pub radius_px: f32,
+    /// Color of the outline. It's drawn under the edges.
+    pub color: Color,
+}
+
+impl Default for ThickOutline {
+    fn default() -> Self {
+        Self {
+            radius_px: 8.0,
+            color: Color::BLACK,
+        }
+    }
+}
+
+impl ThickOutline {
+    /// Returns the steps of the jump flood passes needed to cover the radius, from the largest to 1.
+    pub fn jump_flood_steps(&self) -> impl Iterator<Item = u32> {
+        let radius = self.radius_px.clamp(1.0, MAX_THICK_OUTLINE_RADIUS);
+        let passes = radius.log2().ceil() as u32 + 1;
+        (0..passes).rev().map(|exponent| 1 << exponent)
+    }
+}
+
+#[derive(Clone, Copy, ShaderType)]
+pub struct JumpFloodStep {
+    pub step: u32,
+}
+
+/// The ping-pong textures the edge mask of a view is jump flooded in.
+#[derive(Component)]
+pub struct EdgeDetectionJumpFloodTextures {
+    pub textures: [CachedTexture; 2],
+}
+
+/// Creates the jump flood textures of the views with a [`ThickOutline`].
+pub fn prepare_edge_detection_jump_flood_textures(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    render_device: Res<RenderDevice>,
+    views: Query<(Entity, &ViewTarget, &EdgeDetectionPipelineId)>,
+) {
+    for (entity, view_target, pipeline_id) in views.iter() {
+        if !pipeline_id.key.thick_outline {
+            commands
+                .entity(entity)
+                .remove::<EdgeDetectionJumpFloodTextures>();
+            continue;
+        }
+
+        let descriptor = |label| TextureDescriptor {
+            label: Some(label),
+            size: pipeline_id.mask_size(view_target),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: JUMP_FLOOD_TEXTURE_FORMAT,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        };
+
+        let textures = [
+            texture_cache.get(
+                &render_device,
+                descriptor("edge_detection_jump_flood_texture_a"),
+            ),
+            texture_cache.get(
+                &render_device,
+                descriptor("edge_detection_jump_flood_texture_b"),
+            ),
+        ];
+
+        commands
+            .entity(entity)
+            .insert(EdgeDetectionJumpFloodTextures { textures });
+    }
+}
+
+/// The pipelines jump flooding the edge mask. They aren't specialized,
+/// the number of passes is chosen each frame from the outline radius.
+#[derive(Resource)]
+pub struct JumpFloodPipeline {
+    /// The layout of the seed pass, reading the edge mask.
+    pub seed_layout: BindGroupLayout,
+    /// The layout of the jump passes, reading the previous jump flood texture.
+    pub layout: BindGroupLayout,
+    /// The seed and jump pipelines, only queued with the first view drawing a [`ThickOutline`],
+    /// see [`queue_jump_flood_pipelines`].
+    pub pipelines: Option<[CachedRenderPipelineId; 2]>,
+    /// The steps of all the jump flood passes, indexed by their exponent.
+    pub steps: DynamicUniformBuffer<JumpFloodStep>,
+    pub step_offsets: Vec<u32>,
+}
+
+impl FromWorld for JumpFloodPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let render_queue = world.resource::<RenderQueue>();
+
+        let layout = |label, sample_type| {
+            render_device.create_bind_group_layout(
+                label,
+                &BindGroupLayoutEntries::sequential(
+                    ShaderStages::FRAGMENT,
+                    (
+                        // edge mask or previous jump flood texture
+                        texture_2d(sample_type),
+                        // jump flood step
+                        uniform_buffer::<JumpFloodStep>(true),
+                    ),
+                ),
+            )
+        };
+        let seed_layout = layout(
+            "edge_detection: jump_flood_seed_bind_group_layout",
+            TextureSampleType::Float { filterable: false },
+        );
+        let layout = layout(
+            "edge_detection: jump_flood_bind_group_layout",
+            TextureSampleType::Uint,
+        );
+
+        let mut steps = DynamicUniformBuffer::default();
+        steps.set_label(Some("edge_detection_jump_flood_steps"));
+        let step_offsets = (0..=MAX_THICK_OUTLINE_RADIUS.log2() as u32)
+            .map(|exponent| {
+                steps.push(&JumpFloodStep {
+                    step: 1 << exponent,
+                })
+            })
+            .collect();
+        steps.write_buffer(render_device, render_queue);
+
+        Self {
+            seed_layout,
+            layout,
+            pipelines: None,
+            steps,
+            step_offsets,
+        }
+    }
+}
+
+impl JumpFloodPipeline {
+    /// Queues the seed and jump pipelines, if they aren't yet.
+    pub fn queue_pipelines(&mut self, pipeline_cache: &PipelineCache) {
+        if self.pipelines.is_some() {
+            return;
+        }
+
+        let pipeline = |label: &'static str, layout: &BindGroupLayout, seed: bool| {
+            pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
+                label: Some(label.into()),
+                layout: vec![layout.clone()],
+                vertex: fullscreen_shader_vertex_state(),
+                fragment: Some(FragmentState {
+                    shader: JUMP_FLOOD_SHADER_HANDLE,
+                    shader_defs: match seed {
+                        true => vec!["SEED".into()],
+                        false => vec![],
+                    },
+                    entry_point: match seed {
+                        true => "seed".into(),
+                        false => "jump".into(),
+                    },
+                    targets: vec![Some(ColorTargetState {
+                        format: JUMP_FLOOD_TEXTURE_FORMAT,
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: default(),
+                depth_stencil: None,
+                multisample: default(),
+                push_constant_ranges: vec![],
+                zero_initialize_workgroup_memory: false,
+            })
+        };
+
+        self.pipelines = Some([
+            pipeline(
+                "edge_detection: jump_flood_seed_pipeline",
+                &self.seed_layout,
+                true,
+            ),
+            pipeline("edge_detection: jump_flood_pipeline", &self.layout, false),
+        ]);
+    }
+
+    /// Returns the compiled pipelines and the steps of the jump flood, or `None` if they aren't queued
+    /// or ready to run yet.
+    pub fn get_passes<'w>(
+        &'w self,
+        pipeline_cache: &'w PipelineCache,
+    ) -> Option<JumpFloodPasses<'w>> {
+        let [seed_pipeline, jump_pipeline] = self.pipelines?;
+        Some(JumpFloodPasses {
+            pipeline: self,
+            seed_pipeline: pipeline_cache.get_render_pipeline(seed_pipeline)?,
+            jump_pipeline: pipeline_cache.get_render_pipeline(jump_pipeline)?,
+            steps: self.steps.binding()?,
+        })
+    }
+}
+
+/// The jump flood passes of a [`JumpFloodPipeline`] once they're ready to run, see [`JumpFloodPipeline::get_passes`].
+pub struct JumpFloodPasses<'w> {
+    pipeline: &'w JumpFloodPipeline,
+    seed_pipeline: &'w RenderPipeline,
+    jump_pipeline: &'w RenderPipeline,
+    steps: BindingResource<'w>,
+}
+
+impl JumpFloodPasses<'_> {
+    /// Jump floods the edge mask and returns the texture holding the nearest edge pixels.
+    pub fn run<'a>(
+        &self,
+        render_context: &mut RenderContext,
+        mask_view: &TextureView,
+        textures: &'a EdgeDetectionJumpFloodTextures,
+        thick_outline: &ThickOutline,
+    ) -> &'a TextureView {
+        let diagnostics = render_context.diagnostic_recorder();
+        let time_span = diagnostics.time_span(render_context.command_encoder(), "jump_flood");
+
+        let mut pass = |label,
+                        pipeline,
+                        layout: &BindGroupLayout,
+                        input: &TextureView,
+                        output: &TextureView,
+                        offset| {
+            let bind_group = render_context.render_device().create_bind_group(
+                "edge_detection_jump_flood_bind_group",
+                layout,
+                &BindGroupEntries::sequential((input, self.steps.clone())),
+            );
+
+            let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+                label: Some(label),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: output,
+                    resolve_target: None,
+                    ops: Operations::default(),
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_render_pipeline(pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[offset]);
+            render_pass.draw(0..3, 0..1);
+        };
+
+        let [a, b] = &textures.textures;
+        let (mut input, mut output) = (&b.default_view, &a.default_view);
+
+        pass(
+            "edge_detection_jump_flood_seed_pass",
+            self.seed_pipeline,
+            &self.pipeline.seed_layout,
+            mask_view,
+            output,
+            self.pipeline.step_offsets[0],
+        );
+
+        for step in thick_outline.jump_flood_steps() {
+            (input, output) = (output, input);
+            pass(
+                "edge_detection_jump_flood_pass",
+                self.jump_pipeline,
+                &self.pipeline.layout,
+                input,
+                output,
+                self.pipeline.step_offsets[step.trailing_zeros() as usize],
+            );
+        }
+        time_span.end(render_context.command_encoder());
+
+        output
+    }
+}
+
+/// Queues the [`JumpFloodPipeline`] pipelines with the first view drawing a [`ThickOutline`],
+/// so the apps without one never compile them.
+pub fn queue_jump_flood_pipelines(
+    mut jump_flood_pipeline: ResMut<JumpFloodPipeline>,
+    pipeline_cache: Res<PipelineCache>,
+    views: Query<&EdgeDetectionPipelineId>,
+) {
+    if jump_flood_pipeline.pipelines.is_none()
+        && views
+            .iter()
+            .any(|pipeline_id| pipeline_id.key.thick_outline)
+    {
+        jump_flood_pipeline.queue_pipelines(&pipeline_cache);
+    }
+}
@@ -0,0 +1,64 @@
+//! Jump flooding of the edge mask.
+//!
+//! Each texel of the jump flood texture holds the pixel coordinates of the nearest edge pixel found so far,
+//! or `NO_SEED` if none has been found yet. The `seed` pass initializes it from the edge mask, then each `jump` pass
+//! looks for nearer edge pixels at a halving step, so `log2(radius)` passes find the nearest one within `radius`.
+
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct JumpFloodStep {
+    step: u32,
+}
+
+// Matches `JUMP_FLOOD_NO_SEED`, the largest coordinate of the Rg16Uint texture.
+const NO_SEED: u32 = 0xffffu;
+
+#ifdef SEED
+// The edge mask.
+@group(0) @binding(0) var input_texture: texture_2d<f32>;
+#else
+// The previous jump flood texture.
+@group(0) @binding(0) var input_texture: texture_2d<u32>;
+#endif
+@group(0) @binding(1) var<uniform> jump_flood: JumpFloodStep;
+
+#ifdef SEED
+@fragment
+fn seed(in: FullscreenVertexOutput) -> @location(0) vec4u {
+    let pixel_coord = vec2i(in.position.xy);
+    let edges = textureLoad(input_texture, pixel_coord, 0);
+    return select(vec4u(NO_SEED), vec4u(vec2u(pixel_coord), 0u, 0u), edges.a > 0.5);
+}
+#else
+@fragment
+fn jump(in: FullscreenVertexOutput) -> @location(0) vec4u {
+    let pixel_coord = vec2i(in.position.xy);
+    let size = vec2i(textureDimensions(input_texture));
+    let step = i32(jump_flood.step);
+
+    var nearest = vec2u(NO_SEED);
+    var nearest_distance = 3.4e38;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            let coord = pixel_coord + vec2i(x, y) * step;
+            if any(coord < vec2i(0)) || any(coord >= size) {
+                continue;
+            }
+
+            let seed = textureLoad(input_texture, coord, 0).xy;
+            if seed.x == NO_SEED {
+                continue;
+            }
+
+            let offset = vec2f(seed) - vec2f(pixel_coord);
+            let distance = dot(offset, offset);
+            if distance < nearest_distance {
+                nearest = seed;
+                nearest_distance = distance;
+            }
+        }
+    }
+
+    return vec4u(nearest, 0u, 0u);
+}
+#endif
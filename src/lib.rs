@@ -1,70 +1,387 @@
+use std::f32::consts::FRAC_PI_4;
+
 use bevy::{
-    asset::{embedded_asset, load_internal_asset},
+    asset::{embedded_asset, load_internal_asset, weak_handle, AssetPath},
     core_pipeline::{
+        core_2d::graph::Node2d,
         core_3d::{
             graph::{Core3d, Node3d},
-            DEPTH_TEXTURE_SAMPLING_SUPPORTED,
+            prepare_core_3d_depth_textures, prepare_prepass_textures, Camera3dDepthTextureUsage,
         },
         fullscreen_vertex_shader::fullscreen_shader_vertex_state,
-        prepass::{DepthPrepass, NormalPrepass, ViewPrepassTextures},
+        prepass::{DeferredPrepass, DepthPrepass, NormalPrepass, ViewPrepassTextures},
     },
-    ecs::query::QueryItem,
+    diagnostic::FrameCount,
+    ecs::{entity::EntityHashSet, query::QueryItem, system::SystemParam},
+    image::ImageLoaderSettings,
+    math::bounding::{Aabb3d, BoundingVolume},
+    pbr::{ScreenSpaceAmbientOcclusion, ScreenSpaceAmbientOcclusionResources},
+    platform::collections::{HashMap, HashSet},
     prelude::*,
+    reflect::reflect_remote,
     render::{
-        extract_component::{
-            ComponentUniforms, DynamicUniformIndex, ExtractComponent, UniformComponentPlugin,
-        },
+        camera::{ExtractedCamera, ManualTextureViews, NormalizedRenderTarget, SubCameraView},
+        extract_component::{DynamicUniformIndex, ExtractComponent, UniformComponentPlugin},
         render_asset::RenderAssets,
         render_graph::{
-            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+            InternedRenderLabel, InternedRenderSubGraph, NodeRunError, RenderGraph,
+            RenderGraphContext, RenderLabel, RenderSubGraph, ViewNode, ViewNodeRunner,
         },
         render_resource::{
             binding_types::{texture_2d, uniform_buffer},
             *,
         },
-        renderer::{RenderContext, RenderDevice},
+        renderer::{RenderAdapter, RenderContext, RenderDevice},
+        storage::GpuShaderStorageBuffer,
         sync_component::SyncComponentPlugin,
         sync_world::RenderEntity,
-        texture::GpuImage,
-        view::{ExtractedView, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms},
+        texture::{FallbackImage, GpuImage},
+        view::{ExtractedView, ViewDepthTexture, ViewTarget, ViewUniform, ViewUniformOffset},
         Extract, Render, RenderApp, RenderSet,
     },
+    window::PrimaryWindow,
 };
 use binding_types::{
-    sampler, texture_2d_multisampled, texture_depth_2d, texture_depth_2d_multisampled,
+    sampler, storage_buffer_sized, texture_2d_multisampled, texture_depth_2d,
+    texture_depth_2d_multisampled, texture_storage_2d,
 };
 
+mod adaptive;
+mod calibrate;
+mod checkerboard;
+mod compute;
+mod core_2d;
+mod coverage;
+mod custom_shader;
+mod dilation;
+mod edge_aa;
+mod edge_source;
+mod flash;
+mod glitch;
+mod half_rate;
+mod jump_flood;
+mod mask;
+mod mask_output;
+mod mesh_tag;
+mod overlay;
+mod pass;
+#[cfg(feature = "serde")]
+mod preset;
+mod readback;
+mod support;
+mod taa;
+mod temporal;
+mod transition;
+
+pub use adaptive::*;
+pub use calibrate::*;
+pub use checkerboard::*;
+pub use compute::*;
+pub use core_2d::*;
+pub use coverage::*;
+pub use custom_shader::*;
+pub use dilation::*;
+pub use edge_aa::*;
+pub use edge_source::*;
+pub use flash::*;
+pub use glitch::*;
+pub use half_rate::*;
+pub use jump_flood::*;
+pub use mask::*;
+pub use mask_output::*;
+pub use mesh_tag::*;
+pub use overlay::*;
+pub use pass::*;
+#[cfg(feature = "serde")]
+pub use preset::*;
+pub use readback::*;
+pub use support::*;
+pub use taa::*;
+pub use temporal::*;
+pub use transition::*;
+
 pub const EDGE_DETECTION_SHADER_HANDLE: Handle<Shader> =
-    Handle::weak_from_u128(98765432109876543210987654321098765);
+    weak_handle!("835ab0db-eaeb-444f-b7b1-e1f1e5396639");
+pub const EDGE_DETECTION_COMMON_SHADER_HANDLE: Handle<Shader> =
+    weak_handle!("d8621658-63a9-49f6-95eb-1b275004e2c4");
 
-/// An edge detection post-processing plugin based on the sobel filter.
+/// An edge detection post-processing plugin based on the sobel filter, or another [`EdgeDetectionOperator`].
+///
+/// The plugin can be added once per render graph, e.g. once for [`Core3d`] and once for a custom port of it
+/// with extra passes, each adding its nodes to its [`EdgeDetectionPlugin::graph`]. The systems, resources and
+/// 2D node are set up by the first one, whose [`EdgeDetectionPlugin::placement`] is the default placement of
+/// every camera: the placements of the others are added as [`EdgeDetectionPlugin::additional_placements`].
 pub struct EdgeDetectionPlugin {
-    pub before: Node3d,
+    /// The render graph the nodes are added to. It must be added before the plugin, and be a port of [`Core3d`]
+    /// at least for the nodes of the placements: the fixed placements run between its nodes, and the edges of
+    /// the meshes with an [`EdgeColorOverride`] are drawn between [`Node3d::EndPrepasses`] and
+    /// [`Node3d::StartMainPass`].
+    pub graph: InternedRenderSubGraph,
+    /// The nodes the edge detection runs after, with [`EdgeDetectionPlacement::PostProcess`].
+    pub after: Vec<InternedRenderLabel>,
+    /// The nodes the edge detection runs before, with [`EdgeDetectionPlacement::PostProcess`].
+    pub before: Vec<InternedRenderLabel>,
+    /// Where the edge detection runs in the render graph, for the cameras without [`EdgeDetection::placement`].
+    pub placement: EdgeDetectionPlacement,
+    /// The other placements the cameras can pick with [`EdgeDetection::placement`].
+    ///
+    /// The render graph can't change at runtime, so the edge detection node is added at each placement up front,
+    /// and only the node at the placement of a camera processes it each frame. Switching the placement of a camera
+    /// takes effect on the next frame, without skipping the edges nor drawing them twice.
+    pub additional_placements: Vec<EdgeDetectionPlacement>,
+    /// The node the edge detection runs before on the 2D cameras, after [`Node2d::Tonemapping`].
+    ///
+    /// The 2D views have no prepass, and their depth texture can't be sampled, so only the color, shadow
+    /// and custom [`EdgeSource`]s detect edges there, e.g. around the meshes and sprites of a 2.5D game
+    /// with [`EdgeDetection::enable_color`]. The depth and normal sources are ignored, and so is
+    /// [`EdgeDetection::placement`].
+    ///
+    /// `None` doesn't add the edge detection node to the [`Core2d`](bevy::core_pipeline::core_2d::graph::Core2d)
+    /// render graph.
+    pub before_2d: Option<Node2d>,
+    /// Whether the edge detection runs as a compute pass instead of a fullscreen fragment pass, which saves bandwidth
+    /// on the platforms where the fragment passes are bound by it: the depth, normals and color around each tile
+    /// of pixels are fetched once into workgroup memory, instead of once per kernel reading them.
+    ///
+    /// The output is identical. Only the views writing the edges straight to an HDR main texture use it, see
+    /// [`EdgeDetectionKey::compute_supported`], as the LDR formats can't be written as storage textures.
+    /// The others, and all the views on the adapters without [`EdgeDetectionSupport::compute_output`],
+    /// keep the fragment pass.
+    pub use_compute: bool,
+    /// A shader replacing the built-in edge detection shader, e.g. a custom edge operator or stylization.
+    /// The cameras can pick their own with an [`EdgeDetectionShader`], see it for the requirements of the shader.
+    ///
+    /// `None` uses the built-in shader, like the cameras whose custom shader fails to compile.
+    pub shader: Option<Handle<Shader>>,
 }
 
 impl Default for EdgeDetectionPlugin {
     fn default() -> Self {
         Self {
-            before: Node3d::Fxaa,
+            graph: Core3d.intern(),
+            after: vec![Node3d::PostProcessing.intern()],
+            before: vec![Node3d::Fxaa.intern()],
+            placement: EdgeDetectionPlacement::PostProcess,
+            additional_placements: Vec::new(),
+            before_2d: Some(Node2d::Fxaa),
+            use_compute: false,
+            shader: None,
         }
     }
 }
 
-impl Plugin for EdgeDetectionPlugin {
-    fn build(&self, app: &mut App) {
+/// Where the edge detection runs in the [`Core3d`] render graph, see [`EdgeDetectionPlugin::placement`].
+///
+/// It's reflected as an opaque value, the render labels of [`EdgeDetectionPlacement::Between`] can't be reflected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(opaque)]
+#[reflect(Debug, Default, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeDetectionPlacement {
+    /// After [`EdgeDetectionPlugin::after`], by default the tonemapping and post-processing,
+    /// and before [`EdgeDetectionPlugin::before`].
+    /// The edges are drawn over everything, including the transparent geometry.
+    #[default]
+    PostProcess,
+    /// After the opaque and transmissive passes, before the transparent pass, so the alpha blended geometry
+    /// is drawn over the edges. The edges are drawn before the tonemapping, and the color source only sees
+    /// the opaque geometry.
+    ///
+    /// With MSAA, the last pass renders into the multisampled main texture and resolves it,
+    /// so the edges are kept when the transparent pass resolves it again.
+    BeforeTransparent,
+    /// After the main passes and the motion blur, before the TAA resolve, so the edges are anti-aliased
+    /// along with the scene and kept in the history of the TAA, instead of being smeared by it.
+    /// Like [`EdgeDetectionPlacement::BeforeTransparent`], the edges are drawn before the tonemapping.
+    ///
+    /// The other placements run after the resolve, and detect the edges at the unjittered positions of the pixels,
+    /// so they don't wobble as the TAA jitters the projection. Without a
+    /// [`TemporalAntiAliasing`](bevy::core_pipeline::experimental::taa::TemporalAntiAliasing) camera,
+    /// it runs where the TAA would.
+    BeforeTaa,
+    /// After the TAA resolve and the motion blur, before the bloom and the tonemapping, so the edges brighter
+    /// than 1.0 feed the bloom of HDR cameras, e.g. with an [`EdgeDetection::edge_emissive_strength`] above 1.0.
+    /// Like [`EdgeDetectionPlacement::BeforeTransparent`], the edges are drawn before the tonemapping.
+    BeforeBloom,
+    /// After the main passes and the bloom, right before [`Node3d::Tonemapping`], so the edges are tonemapped
+    /// and exposed along with the scene, instead of being drawn over the tonemapped output. The edge colors are
+    /// linear values of the HDR main texture on HDR cameras, so a dark `edge_color` isn't washed out by the
+    /// tonemapping curve, and isn't brightened by the bloom.
+    BeforeTonemapping,
+    /// Between two nodes of the render graph, e.g. after a film grain node of another plugin
+    /// sitting between [`Node3d::PostProcessing`] and [`Node3d::Fxaa`], so the grain isn't applied to the edges.
+    /// Like [`EdgeDetectionPlacement::PostProcess`], the nodes must run after the main passes.
+    ///
+    /// The render labels can't be serialized, so it's skipped by the `serde` feature.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Between {
+        after: InternedRenderLabel,
+        before: InternedRenderLabel,
+    },
+}
+
+impl EdgeDetectionPlacement {
+    /// Returns the nodes the edge detection runs after and before in the graph of `plugin`.
+    fn nodes(
+        self,
+        plugin: &EdgeDetectionPlugin,
+    ) -> (Vec<InternedRenderLabel>, Vec<InternedRenderLabel>) {
+        match self {
+            Self::PostProcess => (plugin.after.clone(), plugin.before.clone()),
+            Self::BeforeTransparent => (
+                vec![Node3d::MainTransmissivePass.intern()],
+                vec![Node3d::MainTransparentPass.intern()],
+            ),
+            // The TAA node sits between them when its plugin is added, see `EdgeDetectionPlugin::finish`.
+            Self::BeforeTaa => (
+                vec![Node3d::MotionBlur.intern()],
+                vec![Node3d::Bloom.intern()],
+            ),
+            // The TAA node sits before it when its plugin is added, see `EdgeDetectionPlugin::finish`.
+            Self::BeforeBloom => (
+                vec![Node3d::MotionBlur.intern()],
+                vec![Node3d::Bloom.intern()],
+            ),
+            Self::BeforeTonemapping => (
+                vec![Node3d::DepthOfField.intern()],
+                vec![Node3d::Tonemapping.intern()],
+            ),
+            Self::Between { after, before } => (vec![after], vec![before]),
+        }
+    }
+}
+
+/// The placements the edge detection node was added at, in the render world.
+#[derive(Resource, Clone, Debug)]
+pub struct EdgeDetectionPlacements {
+    /// [`EdgeDetectionPlugin::placement`].
+    pub default: EdgeDetectionPlacement,
+    /// [`EdgeDetectionPlugin::additional_placements`].
+    pub additional: Vec<EdgeDetectionPlacement>,
+}
+
+impl EdgeDetectionPlacements {
+    /// Returns the placement of a camera with the `placement` of its [`EdgeDetection`],
+    /// falling back to the default placement if the node wasn't added at it.
+    pub fn resolve(&self, placement: Option<EdgeDetectionPlacement>) -> EdgeDetectionPlacement {
+        match placement {
+            Some(placement)
+                if placement == self.default || self.additional.contains(&placement) =>
+            {
+                placement
+            }
+            Some(placement) => {
+                warn_once!(
+                    "Edge detection placement {placement:?} isn't in `EdgeDetectionPlugin::additional_placements`, \
+                    using the default placement"
+                );
+                self.default
+            }
+            None => self.default,
+        }
+    }
+
+    /// Whether the edge detection may run before the transparent pass on some cameras, and after it on others.
+    fn mixed(&self) -> bool {
+        let before_transparent = |placement: &EdgeDetectionPlacement| {
+            *placement == EdgeDetectionPlacement::BeforeTransparent
+        };
+        let mut placements = self.additional.iter().chain([&self.default]);
+        placements.clone().any(before_transparent) && !placements.all(before_transparent)
+    }
+}
+
+impl EdgeDetectionPlugin {
+    /// Sets up everything but the nodes of the 3D render graph, once for all the instances of the plugin.
+    fn build_shared(&self, app: &mut App) {
+        #[cfg(not(feature = "shader_hot_reload"))]
         load_internal_asset!(
             app,
             EDGE_DETECTION_SHADER_HANDLE,
             "edge_detection.wgsl",
             Shader::from_wgsl
         );
+        // Loaded by path by the pipeline instead, so the asset watcher reloads it when it's edited.
+        #[cfg(feature = "shader_hot_reload")]
+        embedded_asset!(app, "edge_detection.wgsl");
+        load_internal_asset!(
+            app,
+            EDGE_DETECTION_COMMON_SHADER_HANDLE,
+            "edge_detection_common.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            EDGE_COMPOSITE_SHADER_HANDLE,
+            "edge_composite.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            JUMP_FLOOD_SHADER_HANDLE,
+            "jump_flood.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            EDGE_AA_SHADER_HANDLE,
+            "edge_aa.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            EDGE_DILATION_SHADER_HANDLE,
+            "edge_dilation.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            EDGE_TEMPORAL_SHADER_HANDLE,
+            "edge_temporal.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            EDGE_OVERLAY_SHADER_HANDLE,
+            "edge_overlay.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            EDGE_MESH_TAG_SHADER_HANDLE,
+            "edge_mesh_tag.wgsl",
+            Shader::from_wgsl
+        );
 
         embedded_asset!(app, "perlin_noise.png");
 
-        app.register_type::<EdgeDetection>();
+        app.register_type::<EdgeDetection>()
+            .init_resource::<EdgeSources>();
 
         app.add_plugins(SyncComponentPlugin::<EdgeDetection>::default())
-            .add_plugins(UniformComponentPlugin::<EdgeDetectionUniform>::default());
+            .add_plugins(UniformComponentPlugin::<EdgeDetectionUniform>::default())
+            .add_systems(
+                PostUpdate,
+                (configure_depth_source, configure_normal_source)
+                    .in_set(EdgeDetectionSet::Configure),
+            )
+            .add_systems(Update, generate_noise_texture_mips);
+
+        coverage::build(app);
+        mask_output::build(app);
+        readback::build(app);
+        mesh_tag::build(app);
+        adaptive::build(app);
+        calibrate::build(app);
+        glitch::build(app);
+        flash::build(app);
+        transition::build(app);
+        support::build(app);
+        overlay::build(app);
+        compute::build(app, self.use_compute);
+        custom_shader::build(app, self.shader.clone());
+        #[cfg(feature = "serde")]
+        preset::build(app);
 
         // We need to get the render app from the main app
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
@@ -72,150 +389,430 @@ impl Plugin for EdgeDetectionPlugin {
         };
 
         render_app
+            .insert_resource(EdgeDetectionPlacements {
+                default: self.placement,
+                additional: self.additional_placements.clone(),
+            })
             .init_resource::<SpecializedRenderPipelines<EdgeDetectionPipeline>>()
+            .init_resource::<SpecializedRenderPipelines<EdgeCompositePipeline>>()
+            .configure_sets(
+                Render,
+                (
+                    EdgeDetectionSet::Prepare
+                        .in_set(RenderSet::Prepare)
+                        .after(prepare_prepass_textures)
+                        .after(prepare_core_3d_depth_textures),
+                    EdgeDetectionSet::PrepareResources.in_set(RenderSet::PrepareResources),
+                ),
+            )
             .add_systems(
                 ExtractSchedule,
-                EdgeDetectionUniform::extract_edge_detection_settings,
+                (
+                    EdgeDetectionUniform::extract_edge_detection_settings,
+                    clear_edge_detection_history_resets,
+                )
+                    .chain()
+                    .in_set(EdgeDetectionSet::Extract),
             )
             .add_systems(
                 Render,
-                prepare_edge_detection_pipelines.in_set(RenderSet::Prepare),
+                (
+                    prepare_edge_detection_pipelines,
+                    (
+                        prepare_edge_detection_checkerboard,
+                        prepare_edge_detection_half_rate,
+                        prepare_edge_detection_temporal_history,
+                        prepare_edge_detection_jitter,
+                        prepare_edge_calibration,
+                    )
+                        .after(prepare_edge_detection_pipelines),
+                )
+                    .in_set(EdgeDetectionSet::Prepare),
             )
-            .add_render_graph_node::<ViewNodeRunner<EdgeDetectionNode>>(Core3d, EdgeDetectionLabel)
-            .add_render_graph_edges(
-                Core3d,
+            .add_systems(
+                Render,
                 (
-                    Node3d::PostProcessing,
-                    EdgeDetectionLabel,
-                    self.before.clone(),
-                ),
+                    prepare_edge_detection_mask_textures,
+                    prepare_view_edge_mask_textures,
+                    prepare_edge_detection_jump_flood_textures,
+                    queue_jump_flood_pipelines,
+                    prepare_edge_detection_aa_textures,
+                    prepare_edge_detection_dilation_textures,
+                )
+                    .in_set(EdgeDetectionSet::PrepareResources),
             );
+
+        if let Some(before_2d) = &self.before_2d {
+            core_2d::build(render_app, before_2d.intern());
+        }
     }
 
-    fn finish(&self, app: &mut App) {
+    /// Creates the resources needing the render device, once for all the instances of the plugin.
+    fn finish_shared(&self, app: &mut App) {
+        edge_source::finish(app);
+        support::finish(app);
+        overlay::finish(app);
+
+        if self.use_compute
+            && !app
+                .world()
+                .get_resource::<EdgeDetectionSupport>()
+                .is_some_and(|support| support.compute_output)
+        {
+            info!(
+                "Edge detection runs as a fragment pass because the adapter can't write the HDR main textures from a compute shader"
+            );
+        }
+        mesh_tag::finish(app);
+
         app.sub_app_mut(RenderApp)
-            .init_resource::<EdgeDetectionPipeline>();
+            .init_resource::<EdgeDetectionPipeline>()
+            .init_resource::<EdgeCompositePipeline>()
+            .init_resource::<JumpFloodPipeline>()
+            .init_resource::<EdgeAaPipeline>()
+            .init_resource::<EdgeDilationPipeline>()
+            .init_resource::<EdgeTemporalPipeline>();
+    }
+
+    /// Adds the nodes to [`EdgeDetectionPlugin::graph`].
+    fn add_nodes(&self, render_app: &mut SubApp) {
+        let additional_placements = self
+            .additional_placements
+            .iter()
+            .filter(|&&placement| placement != self.placement)
+            .map(|&placement| (EdgeDetectionPlacementLabel(placement).intern(), placement));
+
+        for (label, placement) in [(EdgeDetectionLabel.intern(), self.placement)]
+            .into_iter()
+            .chain(additional_placements)
+        {
+            let node = ViewNodeRunner::new(EdgeDetectionNode { placement }, render_app.world_mut());
+            let mut render_graph = render_app.world_mut().resource_mut::<RenderGraph>();
+            let graph = render_graph.sub_graph_mut(self.graph);
+            graph.add_node(label, node);
+
+            let (after, before) = placement.nodes(self);
+            for after in after {
+                graph.add_node_edge(after, label);
+            }
+            for before in before {
+                graph.add_node_edge(label, before);
+            }
+        }
+
+        // The overlay is drawn over the edges of the camera, unless they're drawn between arbitrary nodes,
+        // which may come after it.
+        let overlay_after = match self.placement {
+            EdgeDetectionPlacement::PostProcess
+            | EdgeDetectionPlacement::BeforeTransparent
+            | EdgeDetectionPlacement::BeforeTaa
+            | EdgeDetectionPlacement::BeforeBloom
+            | EdgeDetectionPlacement::BeforeTonemapping => Some(EdgeDetectionLabel.intern()),
+            EdgeDetectionPlacement::Between { .. } => None,
+        };
+        overlay::add_node(render_app, self, overlay_after);
+        mesh_tag::add_node(render_app, self.graph);
+    }
+}
+
+impl Plugin for EdgeDetectionPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<SyncComponentPlugin<EdgeDetection>>() {
+            self.build_shared(app);
+        } else if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            // The cameras of the graph of this instance may pick its placements too.
+            let mut placements = render_app
+                .world_mut()
+                .resource_mut::<EdgeDetectionPlacements>();
+            for &placement in [&self.placement]
+                .into_iter()
+                .chain(&self.additional_placements)
+            {
+                if placement != placements.default && !placements.additional.contains(&placement) {
+                    placements.additional.push(placement);
+                }
+            }
+        }
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            self.add_nodes(render_app);
+        }
+    }
+
+    // Added once per render graph.
+    fn is_unique(&self) -> bool {
+        false
+    }
+
+    fn finish(&self, app: &mut App) {
+        // Nothing is rendered without a render world, e.g. in headless apps, see `EdgeDetectionStatus::NoRenderer`.
+        let Some(render_app) = app.get_sub_app(RenderApp) else {
+            return;
+        };
+
+        // The instances of the other graphs only connect their own nodes.
+        if !render_app
+            .world()
+            .contains_resource::<EdgeDetectionPipeline>()
+        {
+            self.finish_shared(app);
+        }
+
+        // The TAA plugin may be added after this one, its node is only known once all the plugins are built.
+        let mut render_graph = app
+            .sub_app_mut(RenderApp)
+            .world_mut()
+            .resource_mut::<RenderGraph>();
+        let graph = render_graph.sub_graph_mut(self.graph);
+        if graph.get_node_state(Node3d::Taa).is_ok() {
+            let additional_placements = self
+                .additional_placements
+                .iter()
+                .filter(|&&placement| placement != self.placement)
+                .map(|&placement| (EdgeDetectionPlacementLabel(placement).intern(), placement));
+            for (label, placement) in [(EdgeDetectionLabel.intern(), self.placement)]
+                .into_iter()
+                .chain(additional_placements)
+            {
+                match placement {
+                    EdgeDetectionPlacement::BeforeTaa => graph.add_node_edge(label, Node3d::Taa),
+                    EdgeDetectionPlacement::BeforeBloom => graph.add_node_edge(Node3d::Taa, label),
+                    _ => {}
+                }
+            }
+        }
     }
 }
 
+/// The system sets of the edge detection, to order your own systems against the crate's ones.
+///
+/// The main world systems always run before the settings of the cameras are extracted,
+/// so changes made to [`EdgeDetection`] in `Update` or `PostUpdate` are picked up the same frame.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EdgeDetectionSet {
+    /// The main world systems changing the settings of the cameras, in `Update` and `PostUpdate`,
+    /// e.g. [`EdgeDetectionAdaptiveQuality`] or [`configure_depth_source`].
+    /// Run your own changes to [`EdgeDetection`] after it for them to take precedence.
+    Configure,
+    /// Extracts [`EdgeDetection`] and its [`EdgeDetectionUniform`] to the render world, in `ExtractSchedule`.
+    Extract,
+    /// Specializes the pipelines of the views and fills in the per-frame fields of their [`EdgeDetectionUniform`],
+    /// in [`RenderSet::Prepare`]. The uniforms can still be changed after it, until they're uploaded
+    /// in [`RenderSet::PrepareResources`].
+    Prepare,
+    /// Creates the per-view textures of the edge detection, in [`RenderSet::PrepareResources`].
+    PrepareResources,
+}
+
 // This contains global data used by the render pipeline. This will be created once on startup.
 #[derive(Resource)]
 pub struct EdgeDetectionPipeline {
+    /// The built-in edge detection shader, [`EDGE_DETECTION_SHADER_HANDLE`], or the one loaded from its path
+    /// with the `shader_hot_reload` feature.
+    pub shader: Handle<Shader>,
     pub noise_texture: Handle<Image>,
     pub linear_sampler: Sampler,
+    pub nearest_sampler: Sampler,
     pub noise_sampler: Sampler,
-    pub layout_with_msaa: BindGroupLayout,
-    pub layout_without_msaa: BindGroupLayout,
+    /// Whether the color attachment and the prepass textures are bound as filterable, with a filtering sampler.
+    /// If `false`, they're bound as non-filterable with the nearest sampler, and the shader loads their nearest texels.
+    /// See [`NON_FILTERABLE_ENV_VAR`].
+    pub filterable: bool,
+    /// The bind group layouts, created on demand for each layout key in use.
+    pub layouts: HashMap<EdgeDetectionLayoutKey, BindGroupLayout>,
 }
 
+/// Set this environment variable to bind the float textures as non-filterable even if the adapter can filter them,
+/// e.g. to test the fallback of the platforms that can't.
+pub const NON_FILTERABLE_ENV_VAR: &str = "BEVY_EDGE_DETECTION_NON_FILTERABLE";
+
 impl EdgeDetectionPipeline {
-    pub fn bind_group_layout(&self, multisampled: bool) -> &BindGroupLayout {
-        if multisampled {
-            &self.layout_with_msaa
-        } else {
-            &self.layout_without_msaa
-        }
+    /// Returns the bind group layout of `key`.
+    ///
+    /// The layout must have been created by [`EdgeDetectionPipeline::prepare_bind_group_layout`].
+    pub fn bind_group_layout(&self, key: EdgeDetectionLayoutKey) -> &BindGroupLayout {
+        &self.layouts[&key]
     }
-}
 
-impl FromWorld for EdgeDetectionPipeline {
-    fn from_world(world: &mut World) -> Self {
-        let render_device = world.resource::<RenderDevice>();
+    /// Creates the bind group layout of `key` if it doesn't exist yet.
+    pub fn prepare_bind_group_layout(
+        &mut self,
+        render_device: &RenderDevice,
+        key: EdgeDetectionLayoutKey,
+    ) -> &BindGroupLayout {
+        let filterable = self.filterable;
+        self.layouts
+            .entry(key)
+            .or_insert_with(|| Self::create_bind_group_layout(render_device, key, filterable))
+    }
 
-        let noise_texture = world.load_asset("embedded://bevy_edge_detection/perlin_noise.png");
+    fn create_bind_group_layout(
+        render_device: &RenderDevice,
+        key: EdgeDetectionLayoutKey,
+        filterable: bool,
+    ) -> BindGroupLayout {
+        let (depth_prepass, normal_prepass) = if key.multisampled {
+            // Without float filtering, the depth is loaded as a float too, the GL backend can't load depth textures.
+            let depth_prepass = match filterable {
+                true => texture_depth_2d_multisampled(),
+                false => texture_2d_multisampled(TextureSampleType::Float { filterable: false }),
+            };
+            (
+                depth_prepass,
+                texture_2d_multisampled(TextureSampleType::Float { filterable: false }),
+            )
+        } else if filterable {
+            (
+                texture_depth_2d(),
+                texture_2d(TextureSampleType::Float { filterable }),
+            )
+        } else {
+            // The depth is loaded as a float, some backends can't read depth textures without a comparison sampler.
+            (
+                texture_2d(TextureSampleType::Float { filterable: false }),
+                texture_2d(TextureSampleType::Float { filterable }),
+            )
+        };
+        let sampler_binding = match filterable {
+            true => SamplerBindingType::Filtering,
+            false => SamplerBindingType::NonFiltering,
+        };
 
-        let layout_with_msaa = render_device.create_bind_group_layout(
-            "edge_detection: bind_group_layout with msaa",
-            &BindGroupLayoutEntries::sequential(
-                // The layout entries will only be visible in the fragment stage
-                ShaderStages::FRAGMENT,
-                (
-                    // color attachment
-                    texture_2d(TextureSampleType::Float { filterable: true }),
-                    // depth prepass
-                    texture_depth_2d_multisampled(),
-                    // normal prepass
-                    texture_2d_multisampled(TextureSampleType::Float { filterable: false }),
-                    // texture sampler
-                    sampler(SamplerBindingType::Filtering),
-                    // perlin-noise texture
-                    texture_2d(TextureSampleType::Float { filterable: true }),
-                    // perlin-noise sampler
-                    sampler(SamplerBindingType::Filtering),
-                    // view
-                    uniform_buffer::<ViewUniform>(true),
-                    // The uniform that will control the effect
-                    uniform_buffer::<EdgeDetectionUniform>(true),
-                ),
+        let mut entries = BindGroupLayoutEntries::sequential(
+            // The layout entries will only be visible in the fragment stage
+            ShaderStages::FRAGMENT,
+            (
+                // view, first like in the mesh view bindings
+                uniform_buffer::<ViewUniform>(true),
+                // depth prepass
+                depth_prepass,
+                // normal prepass
+                normal_prepass,
+                // texture sampler
+                sampler(sampler_binding),
+                // perlin-noise texture
+                texture_2d(TextureSampleType::Float { filterable: true }),
+                // perlin-noise sampler
+                sampler(SamplerBindingType::Filtering),
+                // color attachment
+                texture_2d(TextureSampleType::Float { filterable }),
+                // The uniform that will control the effect
+                uniform_buffer::<EdgeDetectionUniform>(true),
             ),
-        );
+        )
+        .to_vec();
 
-        let layout_without_msaa = render_device.create_bind_group_layout(
-            "edge_detection: bind_group_layout without msaa",
-            &BindGroupLayoutEntries::sequential(
-                // The layout entries will only be visible in the fragment stage
-                ShaderStages::FRAGMENT,
-                (
-                    // color attachment
-                    texture_2d(TextureSampleType::Float { filterable: true }),
-                    // depth prepass
-                    texture_depth_2d(),
-                    // normal prepass
-                    texture_2d(TextureSampleType::Float { filterable: true }),
-                    // texture sampler
-                    sampler(SamplerBindingType::Filtering),
-                    // perlin-noise texture
-                    texture_2d(TextureSampleType::Float { filterable: true }),
-                    // perlin-noise sampler
-                    sampler(SamplerBindingType::Filtering),
-                    // view
-                    uniform_buffer::<ViewUniform>(true),
-                    // The uniform that will control the effect
-                    uniform_buffer::<EdgeDetectionUniform>(true),
-                ),
-            ),
-        );
+        if !key.depth {
+            // The depth textures can't be sampled on the platform.
+            entries.retain(|entry| entry.binding != 1);
+        }
 
-        let linear_sampler = render_device.create_sampler(&SamplerDescriptor {
-            label: Some("edge detection linear sampler"),
-            mag_filter: FilterMode::Linear,
-            min_filter: FilterMode::Linear,
-            ..default()
-        });
+        if !key.normal {
+            // The normals are reconstructed from the depth.
+            entries.retain(|entry| entry.binding != 2);
+        }
 
-        let noise_sampler = render_device.create_sampler(&SamplerDescriptor {
-            label: Some("edge detection noise sampler"),
-            mag_filter: FilterMode::Linear,
-            min_filter: FilterMode::Linear,
-            address_mode_u: AddressMode::Repeat,
-            address_mode_v: AddressMode::Repeat,
-            ..default()
-        });
+        if key.stencil {
+            // stencil aspect of the depth prepass
+            let stencil_prepass = if key.multisampled {
+                texture_2d_multisampled(TextureSampleType::Uint)
+            } else {
+                texture_2d(TextureSampleType::Uint)
+            };
+            entries.push(stencil_prepass.build(8, ShaderStages::FRAGMENT));
+        }
 
-        Self {
-            noise_texture,
-            linear_sampler,
-            noise_sampler,
-            layout_with_msaa,
-            layout_without_msaa,
+        if key.motion_vectors {
+            // motion vector prepass
+            let motion_vector_prepass = if key.multisampled {
+                texture_2d_multisampled(TextureSampleType::Float { filterable: false })
+            } else {
+                texture_2d(TextureSampleType::Float { filterable })
+            };
+            entries.push(motion_vector_prepass.build(9, ShaderStages::FRAGMENT));
         }
-    }
-}
 
-impl SpecializedRenderPipeline for EdgeDetectionPipeline {
-    type Key = EdgeDetectionKey;
+        if key.edge_coverage {
+            // edge pixel counter
+            entries.push(storage_buffer_sized(false, None).build(10, ShaderStages::FRAGMENT));
+        }
 
-    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
-        let targets = vec![Some(ColorTargetState {
-            format: if key.hdr {
-                ViewTarget::TEXTURE_FORMAT_HDR
-            } else {
-                TextureFormat::bevy_default()
-            },
-            blend: None,
-            write_mask: ColorWrites::ALL,
-        })];
+        // color attachment sampler
+        entries.push(sampler(sampler_binding).build(11, ShaderStages::FRAGMENT));
+
+        if key.ambient_occlusion {
+            // screen space ambient occlusion
+            entries.push(
+                texture_2d(TextureSampleType::Float { filterable: false })
+                    .build(12, ShaderStages::FRAGMENT),
+            );
+        }
+
+        if key.auto_calibrate {
+            // gradient histograms
+            entries.push(storage_buffer_sized(false, None).build(13, ShaderStages::FRAGMENT));
+        }
+
+        for index in 0..MAX_EDGE_SOURCES {
+            if key.edge_sources & (1 << index) != 0 {
+                // custom edge source
+                entries.push(texture_2d(TextureSampleType::Float { filterable }).build(
+                    EDGE_SOURCE_FIRST_BINDING + index as u32,
+                    ShaderStages::FRAGMENT,
+                ));
+            }
+        }
+
+        if key.material {
+            // deferred G-buffer, after the edge sources
+            entries.push(texture_2d(TextureSampleType::Uint).build(18, ShaderStages::FRAGMENT));
+        }
+
+        if key.shadow {
+            // screen space shadow mask
+            entries.push(
+                texture_2d(TextureSampleType::Float { filterable })
+                    .build(19, ShaderStages::FRAGMENT),
+            );
+        }
+
+        if key.screen_mask {
+            // screen space mask of the edges
+            entries.push(
+                texture_2d(TextureSampleType::Float { filterable })
+                    .build(23, ShaderStages::FRAGMENT),
+            );
+        }
+
+        if key.mesh_tags {
+            // depth of the excluded and color overridden meshes, read without a comparison
+            entries.push(
+                texture_2d(TextureSampleType::Float { filterable: false })
+                    .build(20, ShaderStages::FRAGMENT),
+            );
+            // their tags
+            entries.push(texture_2d(TextureSampleType::Uint).build(21, ShaderStages::FRAGMENT));
+        }
+
+        if key.compute {
+            for entry in &mut entries {
+                entry.visibility = ShaderStages::COMPUTE;
+            }
+            // output, the main texture of the view
+            entries.push(
+                texture_storage_2d(
+                    ViewTarget::TEXTURE_FORMAT_HDR,
+                    StorageTextureAccess::WriteOnly,
+                )
+                .build(EDGE_DETECTION_OUTPUT_BINDING, ShaderStages::COMPUTE),
+            );
+        }
+
+        render_device.create_bind_group_layout("edge_detection: bind_group_layout", &entries)
+    }
 
+    /// Returns the shader defs of `key`, shared by the render and compute variants of the pipeline.
+    fn shader_defs(&self, key: EdgeDetectionKey) -> Vec<ShaderDefVal> {
         let mut shader_defs = vec![];
 
         if key.enable_depth {
@@ -226,416 +823,8566 @@ impl SpecializedRenderPipeline for EdgeDetectionPipeline {
             shader_defs.push("ENABLE_NORMAL".into());
         }
 
+        if key.depth_texture {
+            shader_defs.push("DEPTH_TEXTURE".into());
+        }
+
+        if key.normals {
+            shader_defs.push("NORMALS".into());
+        }
+
+        if key.geometric_normals {
+            shader_defs.push("GEOMETRIC_NORMALS".into());
+        }
+
+        if key.deferred_normals {
+            shader_defs.push("DEFERRED_NORMALS".into());
+        }
+
+        if key.layout_key().material {
+            shader_defs.push("DEFERRED_PREPASS".into());
+        }
+
         if key.enable_color {
             shader_defs.push("ENABLE_COLOR".into());
         }
 
+        if key.enable_material {
+            shader_defs.push("ENABLE_MATERIAL".into());
+        }
+
+        if key.enable_shadow {
+            shader_defs.push("ENABLE_SHADOW".into());
+        }
+
+        if key.screen_mask {
+            shader_defs.push("SCREEN_MASK".into());
+        }
+
+        shader_defs.push(key.operator.shader_def().into());
+        shader_defs.push(ShaderDefVal::UInt(
+            "KERNEL_RADIUS".into(),
+            key.kernel_radius,
+        ));
+        shader_defs.push(key.color_space.shader_def().into());
+
+        if !self.filterable {
+            shader_defs.push("NON_FILTERABLE".into());
+        }
+
         if key.multisampled {
             shader_defs.push("MULTISAMPLED".into());
+            shader_defs.push(ShaderDefVal::UInt("SAMPLE_COUNT".into(), key.samples));
         }
 
-        match key.projection {
-            ProjectionType::Perspective => shader_defs.push("VIEW_PROJECTION_PERSPECTIVE".into()),
-            ProjectionType::Orthographic => shader_defs.push("VIEW_PROJECTION_ORTHOGRAPHIC".into()),
-            _ => (),
-        };
+        if key.all_samples {
+            shader_defs.push("ALL_SAMPLES".into());
+        }
 
-        RenderPipelineDescriptor {
-            label: Some("edge_detection: pipeline".into()),
-            layout: vec![self.bind_group_layout(key.multisampled).clone()],
-            vertex: fullscreen_shader_vertex_state(),
-            fragment: Some(FragmentState {
-                shader: EDGE_DETECTION_SHADER_HANDLE,
-                shader_defs,
-                entry_point: "fragment".into(),
-                targets,
-            }),
-            primitive: default(),
-            depth_stencil: None,
-            multisample: default(),
-            push_constant_ranges: vec![],
-            zero_initialize_workgroup_memory: false,
+        if key.enable_stencil {
+            shader_defs.push("ENABLE_STENCIL".into());
         }
-    }
-}
 
-#[derive(Component, Clone, Copy)]
-pub struct EdgeDetectionPipelineId(CachedRenderPipelineId);
+        if key.enable_motion_emphasis || key.enable_motion_smear {
+            shader_defs.push("MOTION_VECTOR_PREPASS".into());
+        }
 
-pub fn prepare_edge_detection_pipelines(
-    mut commands: Commands,
-    pipeline_cache: Res<PipelineCache>,
-    mut pipelines: ResMut<SpecializedRenderPipelines<EdgeDetectionPipeline>>,
-    edge_detection_pipeline: Res<EdgeDetectionPipeline>,
-    view_targets: Query<(
-        Entity,
-        &ExtractedView,
-        &EdgeDetection,
-        &Msaa,
-        Option<&Projection>,
-    )>,
-) {
-    for (entity, view, edge_detection, msaa, projection) in view_targets.iter() {
-        let (hdr, multisampled) = (view.hdr, *msaa != Msaa::Off);
-
-        commands
-            .entity(entity)
-            .insert(EdgeDetectionPipelineId(pipelines.specialize(
-                &pipeline_cache,
-                &edge_detection_pipeline,
-                EdgeDetectionKey::new(edge_detection, hdr, multisampled, projection),
-            )));
-    }
-}
+        if key.enable_motion_emphasis {
+            shader_defs.push("ENABLE_MOTION_EMPHASIS".into());
+        }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-pub enum ProjectionType {
-    None,
-    Perspective,
-    Orthographic,
+        if key.enable_motion_smear {
+            shader_defs.push("ENABLE_MOTION_SMEAR".into());
+        }
+
+        if key.edge_color_from_object {
+            shader_defs.push("EDGE_COLOR_FROM_OBJECT".into());
+        }
+
+        if key.channel_edge_colors {
+            shader_defs.push("CHANNEL_EDGE_COLORS".into());
+        }
+
+        if let Some(debug_view) = key.debug_view.shader_def() {
+            shader_defs.push("DEBUG_VIEW".into());
+            shader_defs.push(debug_view.into());
+        }
+
+        // The strengths of the depth, normal and color sources are kept apart.
+        if key.channel_edge_colors || key.debug_view.reads_channel_edges() {
+            shader_defs.push("CHANNEL_EDGES".into());
+        }
+
+        if key.gamma_encoded {
+            shader_defs.push("GAMMA_ENCODED_TARGET".into());
+        }
+
+        if key.write_alpha_mask {
+            shader_defs.push("WRITE_ALPHA_MASK".into());
+        } else if key.preserve_scene_alpha {
+            shader_defs.push("PRESERVE_SCENE_ALPHA".into());
+        }
+
+        if key.overlay {
+            shader_defs.push("OVERLAY_CAMERA".into());
+        }
+
+        if key.hdr {
+            shader_defs.push("HDR_TARGET".into());
+        }
+
+        if key.ambient_occlusion {
+            shader_defs.push("AMBIENT_OCCLUSION".into());
+        }
+
+        if key.one_sided_depth {
+            shader_defs.push("ONE_SIDED_DEPTH".into());
+        }
+
+        if key.no_background_edges {
+            shader_defs.push("NO_BACKGROUND_EDGES".into());
+        }
+
+        if key.slope_thickness {
+            shader_defs.push("SLOPE_THICKNESS".into());
+        }
+
+        if key.corner_emphasis {
+            shader_defs.push("CORNER_EMPHASIS".into());
+        }
+
+        if key.aspect_corrected {
+            shader_defs.push("ASPECT_CORRECTED".into());
+        }
+
+        if key.boil {
+            shader_defs.push("BOIL".into());
+        }
+
+        if key.multi_stroke {
+            shader_defs.push("MULTI_STROKE".into());
+        }
+
+        if key.hatching {
+            shader_defs.push("HATCHING".into());
+        }
+
+        if key.exclusion_rects {
+            shader_defs.push("EXCLUSION_RECTS".into());
+        }
+
+        if key.excluded_meshes || key.edge_color_overrides {
+            shader_defs.push("MESH_TAGS".into());
+        }
+
+        if key.excluded_meshes {
+            shader_defs.push("EXCLUDED_MESHES".into());
+        }
+
+        if key.edge_color_overrides {
+            shader_defs.push("EDGE_COLOR_OVERRIDES".into());
+        }
+
+        if key.region_overrides {
+            shader_defs.push("REGION_OVERRIDES".into());
+        }
+
+        if key.scan {
+            shader_defs.push("SCAN".into());
+        }
+
+        if key.reveal {
+            shader_defs.push("REVEAL".into());
+        }
+
+        if key.mask_volume {
+            shader_defs.push("MASK_VOLUME".into());
+        }
+
+        if key.posterize {
+            shader_defs.push("POSTERIZE".into());
+        }
+
+        if key.sharpen {
+            shader_defs.push("SHARPEN".into());
+        }
+
+        if key.edges_only {
+            shader_defs.push("EDGES_ONLY".into());
+        }
+
+        if key.blueprint {
+            shader_defs.push("BLUEPRINT".into());
+        }
+
+        if key.inverted {
+            shader_defs.push("INVERTED".into());
+        }
+
+        if key.checkerboard {
+            shader_defs.push("CHECKERBOARD".into());
+        }
+
+        if key.scaled {
+            shader_defs.push("SCALED".into());
+        }
+
+        if key.edge_coverage {
+            shader_defs.push("EDGE_COVERAGE".into());
+        }
+
+        if key.auto_calibrate {
+            shader_defs.push("AUTO_CALIBRATE".into());
+        }
+
+        if key.edge_sources != 0 {
+            shader_defs.push("EDGE_SOURCES".into());
+        }
+
+        for index in 0..MAX_EDGE_SOURCES {
+            if key.edge_sources & (1 << index) != 0 {
+                shader_defs.push(format!("EDGE_SOURCE_{index}").into());
+            }
+        }
+
+        if key.edge_mask {
+            shader_defs.push("EDGE_MASK".into());
+        }
+
+        if key.mask_output {
+            shader_defs.push("MASK_OUTPUT".into());
+        }
+
+        if key.compute {
+            shader_defs.push("COMPUTE".into());
+        }
+
+        match key.projection {
+            ProjectionType::Perspective => shader_defs.push("VIEW_PROJECTION_PERSPECTIVE".into()),
+            ProjectionType::Orthographic => shader_defs.push("VIEW_PROJECTION_ORTHOGRAPHIC".into()),
+            _ => (),
+        };
+
+        shader_defs
+    }
 }
 
-impl From<Option<&Projection>> for ProjectionType {
-    fn from(proj: Option<&Projection>) -> Self {
-        if let Some(projection) = proj {
-            return match projection {
-                Projection::Perspective(_) => Self::Perspective,
-                Projection::Orthographic(_) => Self::Orthographic,
-                Projection::Custom(_) => panic!(),
-            };
+/// The parts of [`EdgeDetectionKey`] that change the bind group layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EdgeDetectionLayoutKey {
+    /// Whether the prepass textures are multisampled.
+    pub multisampled: bool,
+    /// Whether the stencil aspect of the depth prepass is bound.
+    pub stencil: bool,
+    /// Whether the motion vector prepass is bound.
+    pub motion_vectors: bool,
+    /// Whether the edge coverage counter is bound.
+    pub edge_coverage: bool,
+    /// Whether the ambient occlusion texture is bound.
+    pub ambient_occlusion: bool,
+    /// Whether the deferred G-buffer is bound, for the material edges or the normals.
+    pub material: bool,
+    /// Whether the shadow mask is bound.
+    pub shadow: bool,
+    /// Whether the screen mask is bound.
+    pub screen_mask: bool,
+    /// Whether the gradient histograms are bound.
+    pub auto_calibrate: bool,
+    /// Whether the tags of the excluded and color overridden meshes are bound.
+    pub mesh_tags: bool,
+    /// Whether the depth texture is bound.
+    pub depth: bool,
+    /// Whether the normal prepass texture is bound.
+    pub normal: bool,
+    /// The bits of the [`EdgeSource`]s whose texture is bound.
+    pub edge_sources: u8,
+    /// Whether the bindings are visible to the compute variant, which also binds its output.
+    pub compute: bool,
+}
+
+const NOISE_TEXTURE_PATH: &str = "embedded://bevy_edge_detection/perlin_noise.png";
+
+/// The path of the edge detection shader, loaded in place of [`EDGE_DETECTION_SHADER_HANDLE`]
+/// with the `shader_hot_reload` feature.
+#[cfg(feature = "shader_hot_reload")]
+const EDGE_DETECTION_SHADER_PATH: &str = "embedded://bevy_edge_detection/edge_detection.wgsl";
+
+/// The noise texture holds distortion offsets, not colors, so it's loaded as linear data.
+/// Decoding it as sRGB would skew the offsets toward the dark end.
+fn noise_texture_settings(settings: &mut ImageLoaderSettings) {
+    settings.is_srgb = false;
+}
+
+/// Generates the mip chain of the noise texture once it's loaded, so high distortion frequencies
+/// sample a filtered version of the noise instead of aliasing.
+pub fn generate_noise_texture_mips(
+    mut events: EventReader<AssetEvent<Image>>,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let noise_texture_path = AssetPath::parse(NOISE_TEXTURE_PATH);
+
+    for event in events.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else {
+            continue;
         };
 
-        Self::None
+        if asset_server.get_path(*id).as_ref() != Some(&noise_texture_path) {
+            continue;
+        }
+
+        if let Some(image) = images.get_mut(*id) {
+            generate_mips(image);
+        }
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-pub struct EdgeDetectionKey {
-    /// Whether to enable depth-based edge detection.
-    /// If `true`, edges will be detected based on depth variations.
-    pub enable_depth: bool,
-    /// Whether to enable normal-based edge detection.
-    /// If `true`, edges will be detected based on normal direction variations.
-    pub enable_normal: bool,
-    /// Whether to enable color-based edge detection.
-    /// If `true`, edges will be detected based on color variations.
-    pub enable_color: bool,
+/// Appends the mip chain of an `Rgba8Unorm` image to its data, each level being a 2x2 box filter of the previous one.
+/// The texture repeats, so the filter wraps around the borders.
+fn generate_mips(image: &mut Image) {
+    let descriptor = &image.texture_descriptor;
+    if descriptor.format != TextureFormat::Rgba8Unorm || descriptor.mip_level_count > 1 {
+        return;
+    }
 
-    /// Whether we're using HDR.
-    pub hdr: bool,
-    /// Whether the render target is multisampled.
-    pub multisampled: bool,
-    /// The projection type of view
-    pub projection: ProjectionType,
+    let Some(data) = &mut image.data else {
+        return;
+    };
+
+    let mut size = UVec2::new(descriptor.size.width, descriptor.size.height);
+    let mut level_start = 0;
+    let mut mip_level_count = 1;
+
+    while size.max_element() > 1 {
+        let next_size = (size / 2).max(UVec2::ONE);
+
+        for y in 0..next_size.y {
+            for x in 0..next_size.x {
+                let mut sum = [0u32; 4];
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let source_x = (x * 2 + dx) % size.x;
+                    let source_y = (y * 2 + dy) % size.y;
+                    let texel = level_start + ((source_y * size.x + source_x) * 4) as usize;
+                    for (channel, sum) in sum.iter_mut().enumerate() {
+                        *sum += data[texel + channel] as u32;
+                    }
+                }
+                data.extend(sum.map(|sum| ((sum + 2) / 4) as u8));
+            }
+        }
+
+        level_start += (size.x * size.y * 4) as usize;
+        size = next_size;
+        mip_level_count += 1;
+    }
+
+    image.texture_descriptor.mip_level_count = mip_level_count;
+}
+
+impl FromWorld for EdgeDetectionPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let noise_texture = world
+            .resource::<AssetServer>()
+            .load_with_settings(NOISE_TEXTURE_PATH, noise_texture_settings);
+
+        #[cfg(not(feature = "shader_hot_reload"))]
+        let shader = EDGE_DETECTION_SHADER_HANDLE;
+        #[cfg(feature = "shader_hot_reload")]
+        let shader = world
+            .resource::<AssetServer>()
+            .load(EDGE_DETECTION_SHADER_PATH);
+
+        let filterable = world.resource::<EdgeDetectionSupport>().float_filtering;
+        let render_device = world.resource::<RenderDevice>();
+
+        let linear_sampler = render_device.create_sampler(&SamplerDescriptor {
+            label: Some("edge detection linear sampler"),
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..default()
+        });
+
+        let nearest_sampler = render_device.create_sampler(&SamplerDescriptor {
+            label: Some("edge detection nearest sampler"),
+            mag_filter: FilterMode::Nearest,
+            min_filter: FilterMode::Nearest,
+            ..default()
+        });
+
+        let noise_sampler = render_device.create_sampler(&SamplerDescriptor {
+            label: Some("edge detection noise sampler"),
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_filter: FilterMode::Linear,
+            address_mode_u: AddressMode::Repeat,
+            address_mode_v: AddressMode::Repeat,
+            ..default()
+        });
+
+        Self {
+            shader,
+            noise_texture,
+            linear_sampler,
+            nearest_sampler,
+            noise_sampler,
+            filterable,
+            layouts: HashMap::default(),
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for EdgeDetectionPipeline {
+    type Key = EdgeDetectionKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let mut targets = vec![Some(ColorTargetState {
+            format: key.output_format(),
+            blend: None,
+            write_mask: ColorWrites::ALL,
+        })];
+
+        if key.mask_output {
+            targets.push(Some(ColorTargetState {
+                format: EDGE_MASK_OUTPUT_FORMAT,
+                blend: None,
+                write_mask: ColorWrites::ALL,
+            }));
+        }
+
+        let shader_defs = self.shader_defs(key);
+
+        RenderPipelineDescriptor {
+            label: Some("edge_detection: pipeline".into()),
+            layout: vec![self.bind_group_layout(key.layout_key()).clone()],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: key.shader.map_or_else(|| self.shader.clone(), Handle::Weak),
+                shader_defs,
+                entry_point: "fragment".into(),
+                targets,
+            }),
+            primitive: default(),
+            depth_stencil: None,
+            multisample: MultisampleState {
+                // The intermediate mask is never multisampled, the composite pass writes the output.
+                count: if key.edge_mask {
+                    1
+                } else {
+                    key.output_samples()
+                },
+                ..default()
+            },
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: false,
+        }
+    }
 }
 
-impl EdgeDetectionKey {
-    pub fn new(
-        edge_detection: &EdgeDetection,
-        hdr: bool,
-        multisampled: bool,
-        projection: Option<&Projection>,
-    ) -> Self {
-        Self {
-            enable_depth: edge_detection.enable_depth,
-            enable_normal: edge_detection.enable_normal,
-            enable_color: edge_detection.enable_color,
+#[derive(Component, Clone, Copy, PartialEq)]
+pub struct EdgeDetectionPipelineId {
+    pub id: CachedRenderPipelineId,
+    pub key: EdgeDetectionKey,
+    /// The key the pipelines were specialized for, which differs from `key` once a failing custom shader
+    /// fell back to the built-in one. The pipelines are only specialized again when it changes.
+    pub specialized_key: EdgeDetectionKey,
+    /// The pipeline compositing the edge mask onto the screen, if the edges are drawn into a mask.
+    pub composite_id: Option<CachedRenderPipelineId>,
+    /// The compute variant of the pipeline, with [`EdgeDetectionKey::compute`]. Until it's compiled,
+    /// the edges are drawn by the render pipeline of `id`, specialized without it.
+    pub compute_id: Option<CachedComputePipelineId>,
+    /// The placement of the node processing the view, see [`EdgeDetection::placement`].
+    pub placement: EdgeDetectionPlacement,
+    /// The scale of the resolution of the edge mask, see [`EdgeDetection::detection_scale`].
+    pub mask_scale: f32,
+    /// The size of the main texture of the view, which the compute variant is dispatched over.
+    pub target_size: UVec2,
+}
+
+impl EdgeDetectionPipelineId {
+    /// Returns the size of the edge mask and of the textures post-filtering it, for the main texture of `view_target`.
+    pub fn mask_size(&self, view_target: &ViewTarget) -> Extent3d {
+        let size = view_target.main_texture().size();
+        let scale = |extent: u32| ((extent as f32 * self.mask_scale).ceil() as u32).max(1);
+
+        Extent3d {
+            width: scale(size.width),
+            height: scale(size.height),
+            ..size
+        }
+    }
+}
+
+/// The components prepared for each view, removed when the view isn't rendered.
+type PreparedEdgeDetection = (
+    EdgeDetectionPipelineId,
+    EdgeDetectionMaskTexture,
+    EdgeDetectionJumpFloodTextures,
+    EdgeDetectionAaTexture,
+    EdgeDetectionDilationTexture,
+    EdgeDetectionCheckerboardHistory,
+    EdgeDetectionHalfRateHistory,
+    EdgeDetectionTemporalHistory,
+    ViewEdgeMaskTexture,
+    ViewEdgeMeshTagTextures,
+);
+
+/// The rest of the parameters of [`prepare_edge_detection_pipelines`], bundled so it stays within the 16 parameters
+/// of bevy's systems.
+#[derive(SystemParam)]
+pub struct PrepareEdgeDetectionParams<'w, 's> {
+    default_shader: Res<'w, DefaultEdgeDetectionShader>,
+    custom_shaders: Query<'w, 's, &'static EdgeDetectionShader>,
+    render_adapter: Res<'w, RenderAdapter>,
+    unsupported_formats: Local<'s, HashSet<TextureFormat>>,
+    prepared_pipelines: Query<'w, 's, &'static EdgeDetectionPipelineId>,
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn prepare_edge_detection_pipelines(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<EdgeDetectionPipeline>>,
+    mut composite_pipelines: ResMut<SpecializedRenderPipelines<EdgeCompositePipeline>>,
+    mut compute_pipelines: Option<ResMut<SpecializedComputePipelines<EdgeDetectionPipeline>>>,
+    mut edge_detection_pipeline: ResMut<EdgeDetectionPipeline>,
+    mut composite_pipeline: ResMut<EdgeCompositePipeline>,
+    render_device: Res<RenderDevice>,
+    gpu_storage_buffers: Res<RenderAssets<GpuShaderStorageBuffer>>,
+    placements: Res<EdgeDetectionPlacements>,
+    support: Res<EdgeDetectionSupport>,
+    mut views_without_projection: Local<EntityHashSet>,
+    excluded_meshes: Query<(), With<NoEdgeDetection>>,
+    edge_color_palette: Res<EdgeColorPalette>,
+    mut params: PrepareEdgeDetectionParams,
+    view_targets: Query<(
+        Entity,
+        &ExtractedView,
+        &EdgeDetection,
+        &Msaa,
+        Option<&Projection>,
+        Option<&ViewPrepassTextures>,
+        Option<&ViewDepthTexture>,
+        Option<&ViewTarget>,
+        Option<&EdgeCoverageBuffer>,
+        Option<&EdgeCalibrationBuffer>,
+        Option<&ExtractedEdgeSources>,
+        Has<ScreenSpaceAmbientOcclusion>,
+        Option<&ExtractedCamera>,
+        Has<Camera2d>,
+        Has<EdgeDetectionMaskOutput>,
+    )>,
+) {
+    views_without_projection.retain(|entity| view_targets.contains(*entity));
+
+    for (
+        entity,
+        view,
+        edge_detection,
+        msaa,
+        projection,
+        prepass_textures,
+        view_depth_texture,
+        view_target,
+        edge_coverage_buffer,
+        calibration_buffer,
+        edge_sources,
+        ssao_available,
+        camera,
+        core_2d,
+        mask_output,
+    ) in view_targets.iter()
+    {
+        // A zero-sized target can't be bound, e.g. an image target resized to 0x0 during a layout pass.
+        let empty_target = view.viewport.z == 0
+            || view.viewport.w == 0
+            || view_target.is_some_and(|view_target| {
+                let size = view_target.main_texture().size();
+                size.width == 0 || size.height == 0
+            });
+        if empty_target {
+            commands.entity(entity).remove::<PreparedEdgeDetection>();
+            continue;
+        }
+
+        // The 2D views are processed by the node of the `Core2d` graph, at the default placement.
+        let placement = match core_2d {
+            true => EdgeDetectionPlacement::PostProcess,
+            false => placements.resolve(edge_detection.placement),
+        };
+
+        // The format is the one of the view's own target, not the one of the primary window.
+        let target_format = match view_target {
+            Some(view_target) => view_target.main_texture_format(),
+            None if view.hdr => ViewTarget::TEXTURE_FORMAT_HDR,
+            None => TextureFormat::bevy_default(),
+        };
+        if !support.target_format_supported(&params.render_adapter, target_format) {
+            if params.unsupported_formats.insert(target_format) {
+                warn!(
+                    "Edge detection is skipped on the views rendering into {target_format:?} textures, \
+                    which it can't render to or sample"
+                );
+            }
+            commands.entity(entity).remove::<PreparedEdgeDetection>();
+            continue;
+        }
+
+        if ProjectionType::from(projection) == ProjectionType::None
+            && views_without_projection.insert(entity)
+        {
+            info!(
+                "Edge detection view {entity} has no known projection, \
+                inferring it from the projection matrix in the shader"
+            );
+        }
+
+        let motion_vectors_available = prepass_textures
+            .is_some_and(|prepass_textures| prepass_textures.motion_vectors.is_some());
+
+        let stencil_supported = edge_detection
+            .depth_source
+            .texture(prepass_textures, view_depth_texture)
+            .is_some_and(|(depth_texture, _)| depth_texture.format().has_stencil_aspect());
+
+        if edge_detection.enable_stencil && !stencil_supported {
+            warn_once!(
+                "Stencil edge detection is disabled because the depth texture has no stencil aspect"
+            );
+        }
+
+        let deferred_available =
+            prepass_textures.is_some_and(|prepass_textures| prepass_textures.deferred.is_some());
+
+        let normals_available =
+            prepass_textures.is_some_and(|prepass_textures| prepass_textures.normal.is_some());
+
+        if edge_detection.enable_normal
+            && edge_detection.normal_source == NormalSource::Prepass
+            && !normals_available
+            && !deferred_available
+            && !core_2d
+        {
+            warn_once!(
+                "The normals are reconstructed from the depth because the camera has no normal prepass"
+            );
+        }
+
+        if edge_detection.enable_material && !deferred_available {
+            warn_once!(
+                "Material edge detection is disabled because the camera has no deferred prepass"
+            );
+        }
+
+        // A camera stacked over another one on the same target, without clearing it,
+        // must only outline the pixels of its own geometry.
+        let overlay = !core_2d
+            && camera.is_some_and(|camera| {
+                camera.sorted_camera_index_for_target > 0
+                    && matches!(camera.clear_color, ClearColorConfig::None)
+            });
+
+        if (edge_coverage_buffer.is_some() || calibration_buffer.is_some())
+            && !support.fragment_storage
+        {
+            warn_once!(
+                "Edge coverage and auto calibration are disabled because the fragment shaders can't write to storage buffers"
+            );
+        }
+
+        // The counter buffer is only bound once it has been uploaded to the GPU.
+        let edge_coverage = support.fragment_storage
+            && edge_coverage_buffer
+                .is_some_and(|buffer| gpu_storage_buffers.get(&buffer.0).is_some());
+        let auto_calibrate = support.fragment_storage
+            && calibration_buffer
+                .is_some_and(|buffer| gpu_storage_buffers.get(&buffer.buffer).is_some());
+
+        let key = EdgeDetectionKey::new(
+            edge_detection,
+            target_format,
+            msaa.samples(),
+            projection,
+            stencil_supported,
+            motion_vectors_available,
+            ssao_available,
+            deferred_available,
+            overlay,
+            edge_coverage,
+            auto_calibrate,
+            edge_sources.map_or(0, ExtractedEdgeSources::mask),
+            placement == EdgeDetectionPlacement::BeforeTransparent,
+            mask_output,
+            !excluded_meshes.is_empty(),
+            !edge_color_palette.colors.is_empty(),
+            normals_available,
+        );
+
+        // The 2D views have no prepass to detect the depth and normal edges in.
+        if core_2d && !edge_detection.enable_color {
+            warn_once!(
+                "Edge detection on 2D cameras has no depth nor normal edges, enable `EdgeDetection::enable_color`"
+            );
+        }
+        let key = match core_2d {
+            true => EdgeDetectionKey {
+                enable_depth: false,
+                enable_normal: false,
+                normals: false,
+                geometric_normals: false,
+                deferred_normals: false,
+                one_sided_depth: false,
+                no_background_edges: false,
+                excluded_meshes: false,
+                edge_color_overrides: false,
+                ..key
+            },
+            false => key,
+        };
+
+        if key.thick_outline && !support.thick_outline {
+            warn_once!(
+                "The thick outlines are disabled because the adapter can't render to the jump flood textures"
+            );
+        }
+        let key = EdgeDetectionKey {
+            thick_outline: key.thick_outline && support.thick_outline,
+            ..key
+        };
+
+        // Without depth texture sampling, e.g. on WebGL2, the color-based sources still draw their edges.
+        if !support.depth_sampling && !edge_detection.enable_color {
+            warn_once!(
+                "Edge detection without depth texture sampling has no depth nor normal edges, enable `EdgeDetection::enable_color`"
+            );
+        }
+        let key = match support.depth_sampling {
+            true => key,
+            false => EdgeDetectionKey {
+                enable_depth: false,
+                enable_normal: false,
+                enable_stencil: false,
+                depth_texture: false,
+                normals: false,
+                geometric_normals: false,
+                deferred_normals: false,
+                one_sided_depth: false,
+                no_background_edges: false,
+                slope_thickness: false,
+                temporal: false,
+                excluded_meshes: false,
+                edge_color_overrides: false,
+                overlay: false,
+                ..key
+            },
+        };
+
+        let key = EdgeDetectionKey {
+            shader: params
+                .default_shader
+                .resolve(params.custom_shaders.get(entity).ok()),
+            ..key
+        };
+
+        // The compute variant writes to the main texture of the view, which needs the storage usage.
+        // It's only compiled from the built-in shader.
+        let key = EdgeDetectionKey {
+            compute: compute_pipelines.is_some()
+                && key.shader.is_none()
+                && support.compute_output
+                && view_target.is_some_and(|view_target| {
+                    compute_output(&key, view_target.main_texture().usage())
+                }),
+            ..key
+        };
+
+        let mask_scale = edge_detection.detection_scale(mask_output);
+        let target_size = view_target.map_or(UVec2::ZERO, |view_target| {
+            let size = view_target.main_texture().size();
+            UVec2::new(size.width, size.height)
+        });
+
+        // The pipelines are specialized again when the key changes, or when the custom shader they were specialized
+        // with fails to compile, rather than hashing the key into the specialized pipelines every frame.
+        if let Ok(prepared) = params.prepared_pipelines.get(entity) {
+            if prepared.specialized_key == key
+                && !(prepared.key.shader.is_some() && pipeline_failed(&pipeline_cache, prepared.id))
+            {
+                let pipeline_id = EdgeDetectionPipelineId {
+                    placement,
+                    mask_scale,
+                    target_size,
+                    ..*prepared
+                };
+                if pipeline_id != *prepared {
+                    commands.entity(entity).insert(pipeline_id);
+                }
+                continue;
+            }
+        }
+
+        let mut specialize = |key: EdgeDetectionKey| {
+            // The render pipeline is used until the compute one is compiled.
+            let compute_id = compute_pipelines
+                .as_mut()
+                .filter(|_| key.compute)
+                .map(|pipelines| {
+                    edge_detection_pipeline
+                        .prepare_bind_group_layout(&render_device, key.layout_key());
+                    pipelines.specialize(&pipeline_cache, &edge_detection_pipeline, key)
+                });
+            let key = EdgeDetectionKey {
+                compute: false,
+                ..key
+            };
+
+            edge_detection_pipeline.prepare_bind_group_layout(&render_device, key.layout_key());
+
+            let composite_id = key.edge_mask.then(|| {
+                let composite_key = key.composite_key();
+                composite_pipeline
+                    .prepare_bind_group_layout(&render_device, composite_key.layout_key());
+                composite_pipelines.specialize(&pipeline_cache, &composite_pipeline, composite_key)
+            });
+
+            (
+                pipelines.specialize(&pipeline_cache, &edge_detection_pipeline, key),
+                composite_id,
+                compute_id,
+            )
+        };
+
+        // The pipelines of the other side of the transparent pass are compiled ahead,
+        // so switching the placement doesn't skip the edges while they compile.
+        if placements.mixed() {
+            specialize(EdgeDetectionKey {
+                before_transparent: !key.before_transparent,
+                ..key
+            });
+        }
+
+        let (mut id, mut composite_id, mut compute_id) = specialize(key);
+        let specialized_key = key;
+        let mut key = key;
+
+        // A custom shader failing to compile falls back to the built-in one, rather than drawing no edges.
+        if key.shader.is_some() && pipeline_failed(&pipeline_cache, id) {
+            warn_once!(
+                "The custom edge detection shader of view {entity} failed to compile, using the built-in shader"
+            );
+            key.shader = None;
+            (id, composite_id, compute_id) = specialize(key);
+        }
+
+        commands.entity(entity).insert(EdgeDetectionPipelineId {
+            id,
+            key,
+            specialized_key,
+            composite_id,
+            compute_id,
+            placement,
+            mask_scale,
+            target_size,
+        });
+    }
+}
+
+/// The projection type of a view, used to specialize the depth handling of the shader.
+///
+/// It's passed as the `VIEW_PROJECTION_*` shader defs of bevy's mesh pipelines, which pick the fast path
+/// of the depth linearization in the view transformations of `bevy_pbr`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProjectionType {
+    /// The projection is unknown, e.g. the view has no [`Projection`] or uses a custom one.
+    ///
+    /// The shader then unprojects depth with the inverse projection matrix of the view
+    /// and infers whether the view is orthographic from its projection matrix.
+    None,
+    Perspective,
+    Orthographic,
+}
+
+impl From<Option<&Projection>> for ProjectionType {
+    fn from(proj: Option<&Projection>) -> Self {
+        if let Some(projection) = proj {
+            return match projection {
+                Projection::Perspective(_) => Self::Perspective,
+                Projection::Orthographic(_) => Self::Orthographic,
+                Projection::Custom(_) => Self::None,
+            };
+        };
+
+        Self::None
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EdgeDetectionKey {
+    /// Whether to enable depth-based edge detection.
+    /// If `true`, edges will be detected based on depth variations.
+    pub enable_depth: bool,
+    /// Whether to enable normal-based edge detection.
+    /// If `true`, edges will be detected based on normal direction variations.
+    pub enable_normal: bool,
+    /// Whether the depth texture is bound. Without depth texture sampling, e.g. on WebGL2, it isn't,
+    /// and only the color-based sources draw edges, see [`EdgeDetectionSupport::depth_sampling`].
+    pub depth_texture: bool,
+    /// Whether the normals are read, by the normal source or the steep angle adjustment of the depth source.
+    /// The normal prepass isn't bound otherwise.
+    pub normals: bool,
+    /// Whether the normals are reconstructed from the depth instead of sampled from the normal prepass,
+    /// see [`NormalSource`] and [`EdgeDetection::geometric_normals_only`]. The normal prepass isn't bound then.
+    /// Only `true` if the normals are read.
+    pub geometric_normals: bool,
+    /// Whether to enable color-based edge detection.
+    /// If `true`, edges will be detected based on color variations.
+    pub enable_color: bool,
+    /// Whether to enable stencil-based edge detection.
+    /// Only `true` if the depth prepass texture has a stencil aspect.
+    pub enable_stencil: bool,
+    /// Whether to enable material-based edge detection.
+    /// Only `true` if the view has a deferred prepass.
+    pub enable_material: bool,
+    /// Whether the normals are decoded from the deferred G-buffer, on the deferred views without a normal prepass.
+    /// Only `true` if the normals are read, and not reconstructed from the depth.
+    pub deferred_normals: bool,
+    /// Whether to enable shadow-based edge detection, see [`EdgeDetection::shadow_mask`].
+    pub enable_shadow: bool,
+    /// The kernel the gradients of the depth, normal, color, material and shadow sources are measured with.
+    pub operator: EdgeDetectionOperator,
+    /// The number of rings of the kernel, see [`EdgeDetection::kernel_radius`].
+    pub kernel_radius: u32,
+    /// The space the color gradients are measured in, see [`EdgeDetection::color_space`].
+    pub color_space: ColorEdgeSpace,
+    /// Whether to emphasize the edges of moving objects along their motion.
+    /// Only `true` if the motion emphasis is non-zero and the view has a motion vector prepass.
+    pub enable_motion_emphasis: bool,
+    /// Whether to smear the uv distortion along the motion of each pixel.
+    /// Only `true` if the motion smear strength is non-zero and the view has a motion vector prepass.
+    pub enable_motion_smear: bool,
+
+    /// Whether the depth edges are only drawn on the nearer surface, see [`EdgeDetection::one_sided_depth_edges`].
+    pub one_sided_depth: bool,
+    /// Whether no edges are drawn against the background, see [`BackgroundEdgeMode::None`].
+    pub no_background_edges: bool,
+    /// Whether the thicknesses are boosted where the depth slope is steep.
+    pub slope_thickness: bool,
+    /// Whether the corners are emphasized, see [`EdgeDetection::corner_emphasis`].
+    pub corner_emphasis: bool,
+    /// Whether the edge strength is scaled by the ambient occlusion.
+    /// Only `true` if the ambient occlusion influence is non-zero and the view has SSAO.
+    pub ambient_occlusion: bool,
+    /// Whether the uv distortion is corrected by the viewport aspect ratio.
+    pub aspect_corrected: bool,
+    /// Whether the edges are cut out of the exclusion rectangles.
+    pub exclusion_rects: bool,
+    /// Whether the edges are multiplied by the screen mask, see [`EdgeDetection::screen_mask`].
+    pub screen_mask: bool,
+    /// Whether the edges are cut out around the [`NoEdgeDetection`] meshes, i.e. the render world has any.
+    pub excluded_meshes: bool,
+    /// Whether the edges around the [`EdgeColorOverride`] meshes take their color, i.e. the render world has any.
+    pub edge_color_overrides: bool,
+    /// Whether the thresholds and edge color are overridden in screen regions.
+    pub region_overrides: bool,
+    /// Whether the edges are limited to the scan sphere.
+    pub scan: bool,
+    /// Whether the edges are drawn in progressively, see [`EdgeDetection::reveal`].
+    pub reveal: bool,
+    /// Whether the edges are limited to the mask volume.
+    pub mask_volume: bool,
+    /// Whether the scene color is posterized under the edges.
+    pub posterize: bool,
+    /// Whether the scene color is graded beside the edges, see [`EdgeDetection::edge_adjacent_grading`].
+    pub adjacent_grading: bool,
+    /// Whether the scene is sharpened along the edges instead of drawing them, see [`OutputMode::Sharpen`].
+    pub sharpen: bool,
+    /// Whether only the edges are written, without the scene, see [`OutputMode::EdgesOnly`].
+    pub edges_only: bool,
+    /// Whether the edges are drawn over a flat background, see [`OutputMode::Blueprint`].
+    pub blueprint: bool,
+    /// Whether the edge color is drawn everywhere but on the edges, see [`OutputMode::Inverted`].
+    pub inverted: bool,
+    /// Whether only half of the pixels are rendered each frame, see [`EdgeDetection::checkerboard`].
+    pub checkerboard: bool,
+    /// Whether the edges are blended with their reprojected history, see [`EdgeDetection::temporal_blend`].
+    /// Only `true` if the view has a motion vector prepass.
+    pub temporal: bool,
+    /// Whether the edges are detected every other frame, and reprojected on the others, see [`EdgeDetection::half_rate`].
+    /// Only `true` if the view has a motion vector prepass.
+    pub half_rate: bool,
+    /// Whether the edges are detected at a lower resolution than the view's, see [`EdgeDetection::resolution_scale`].
+    pub scaled: bool,
+    /// Whether each contour is drawn with several offset strokes.
+    pub multi_stroke: bool,
+    /// Whether the edges are broken into strokes, see [`EdgeDetection::hatching`].
+    pub hatching: bool,
+    /// Whether the noise lookup jumps to a new offset `boil_fps` times per second.
+    pub boil: bool,
+    /// Whether the edge color is derived from the color of the object, see [`EdgeColorMode::FromObject`].
+    pub edge_color_from_object: bool,
+    /// Whether any of the depth, normal and color edges have their own color, see [`EdgeDetection::depth_edge_color`].
+    pub channel_edge_colors: bool,
+    /// What replaces the output of the view, see [`EdgeDetection::debug_view`].
+    pub debug_view: EdgeDebugView,
+    /// Whether the edge pixels are counted for the [`EdgeCoverage`] of the view.
+    pub edge_coverage: bool,
+    /// Whether the gradient histograms are built for the [`EdgeDetectionAutoCalibrate`] of the view.
+    pub auto_calibrate: bool,
+    /// The bits of the [`EdgeSource`]s of the view, in registration order.
+    pub edge_sources: u8,
+    /// Whether the corners of the edges are rounded, see [`EdgeDetection::corner_rounding`].
+    pub corner_rounding: bool,
+    /// Whether the edge mask is anti-aliased, see [`EdgeDetection::edge_aa`].
+    pub edge_aa: bool,
+    /// Whether the edge mask is dilated, see [`EdgeDetection::dilation`].
+    pub dilation: bool,
+    /// Whether a thick outline is drawn around the edges, see [`EdgeDetection::thick_outline`].
+    pub thick_outline: bool,
+    /// Whether the edges are drawn into an intermediate mask, which is post-filtered
+    /// and composited onto the screen by a second pass.
+    pub edge_mask: bool,
+    /// Whether the strength of the edges is also written to the [`ViewEdgeMaskTexture`] of the view,
+    /// see [`EdgeDetectionMaskOutput`].
+    pub mask_output: bool,
+    /// Whether the edge detection runs before the transparent pass, see [`EdgeDetectionPlacement::BeforeTransparent`].
+    pub before_transparent: bool,
+    /// Whether the output is written by the compute variant of the pipeline, see [`EdgeDetectionPlugin::use_compute`].
+    pub compute: bool,
+    /// The custom shader of the view, see [`EdgeDetectionShader`], `None` for the built-in one.
+    /// Set when the pipelines are prepared.
+    pub shader: Option<AssetId<Shader>>,
+
+    /// The format of the main texture of the view, which depends on its own target:
+    /// the cameras of different windows may have different formats.
+    pub target_format: TextureFormat,
+    /// Whether we're using HDR, i.e. the main texture of the view has a float format, e.g. an `Rgba16Float` one.
+    pub hdr: bool,
+    /// Whether the render target holds gamma encoded values, i.e. it's an LDR target without an sRGB format.
+    /// If `true`, the blending of the edges is done in linear space by decoding and re-encoding the colors.
+    pub gamma_encoded: bool,
+    /// Whether the edge opacity is written into the alpha channel, see [`EdgeDetection::write_alpha_mask`].
+    pub write_alpha_mask: bool,
+    /// Whether the alpha of the scene is kept, see [`EdgeDetection::preserve_scene_alpha`].
+    pub preserve_scene_alpha: bool,
+    /// Whether the view is an overlay camera stacked over another camera on the same target,
+    /// i.e. it doesn't clear the target. Its edges are then limited to the pixels of its own geometry.
+    pub overlay: bool,
+    /// Whether the render target is multisampled.
+    pub multisampled: bool,
+    /// The number of samples of the render target.
+    pub samples: u32,
+    /// Whether all the samples of the multisampled depth and normal textures are used.
+    pub all_samples: bool,
+    /// The projection type of view
+    pub projection: ProjectionType,
+}
+
+impl EdgeDetectionKey {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        edge_detection: &EdgeDetection,
+        target_format: TextureFormat,
+        samples: u32,
+        projection: Option<&Projection>,
+        stencil_supported: bool,
+        motion_vectors_available: bool,
+        ssao_available: bool,
+        deferred_available: bool,
+        overlay: bool,
+        edge_coverage: bool,
+        auto_calibrate: bool,
+        edge_sources: u8,
+        before_transparent: bool,
+        mask_output: bool,
+        excluded_meshes: bool,
+        edge_color_overrides: bool,
+        normals_available: bool,
+    ) -> Self {
+        let corner_rounding = edge_detection.corner_rounding > 0.0;
+        let thick_outline = edge_detection.thick_outline.is_some();
+        let dilation = edge_detection.dilation > 0;
+        let adjacent_grading = edge_detection.edge_adjacent_grading.is_some();
+        let half_rate = edge_detection.half_rate && motion_vectors_available;
+        let checkerboard = edge_detection.checkerboard && !half_rate;
+        let temporal =
+            edge_detection.temporal_blend > 0.0 && motion_vectors_available && !half_rate;
+        let scaled = edge_detection.detection_scale(mask_output) < 1.0;
+        let hdr = matches!(
+            target_format,
+            TextureFormat::Rgba16Float | TextureFormat::Rgba32Float | TextureFormat::Rg11b10Ufloat
+        );
+        let normals = edge_detection.enable_normal
+            || (edge_detection.enable_depth && edge_detection.steep_angle_multiplier != 0.0);
+        // The deferred views hold the normals in their G-buffer, without a normal prepass.
+        let geometric_normals = normals
+            && (edge_detection.normal_source == NormalSource::Depth
+                || !(normals_available || deferred_available)
+                || (edge_detection.enable_normal && edge_detection.geometric_normals_only));
+
+        Self {
+            enable_depth: edge_detection.enable_depth,
+            enable_normal: edge_detection.enable_normal,
+            depth_texture: true,
+            normals,
+            geometric_normals,
+            deferred_normals: normals && !geometric_normals && !normals_available,
+            enable_color: edge_detection.enable_color,
+            enable_stencil: edge_detection.enable_stencil && stencil_supported,
+            enable_material: edge_detection.enable_material && deferred_available,
+            enable_shadow: edge_detection.shadow_mask.is_some(),
+            screen_mask: edge_detection.screen_mask.is_some(),
+            operator: edge_detection.operator,
+            kernel_radius: edge_detection.kernel_radius.clamp(1, MAX_KERNEL_RADIUS),
+            color_space: edge_detection.color_space,
+            enable_motion_emphasis: edge_detection.motion_emphasis != 0.0
+                && motion_vectors_available,
+            enable_motion_smear: edge_detection.motion_smear_strength != 0.0
+                && motion_vectors_available,
+
+            one_sided_depth: edge_detection.enable_depth && edge_detection.one_sided_depth_edges,
+            no_background_edges: edge_detection.background_edges == BackgroundEdgeMode::None,
+            slope_thickness: edge_detection.slope_thickness_boost > 0.0,
+            corner_emphasis: edge_detection.corner_emphasis > 0.0,
+            ambient_occlusion: edge_detection.ao_influence != 0.0 && ssao_available,
+            aspect_corrected: edge_detection.aspect_corrected,
+            exclusion_rects: !edge_detection.exclusion_rects.is_empty(),
+            excluded_meshes,
+            edge_color_overrides,
+            region_overrides: !edge_detection.region_overrides.is_empty(),
+            scan: edge_detection.scan.is_some(),
+            reveal: edge_detection.reveal.is_some(),
+            mask_volume: edge_detection.mask_volume.is_some(),
+            posterize: edge_detection.posterize.is_some(),
+            adjacent_grading,
+            sharpen: matches!(edge_detection.output_mode, OutputMode::Sharpen { .. }),
+            edges_only: edge_detection.output_mode == OutputMode::EdgesOnly,
+            blueprint: matches!(edge_detection.output_mode, OutputMode::Blueprint { .. }),
+            inverted: edge_detection.output_mode == OutputMode::Inverted,
+            checkerboard,
+            temporal,
+            half_rate,
+            scaled,
+            multi_stroke: edge_detection.strokes > 1,
+            hatching: edge_detection.hatching.is_some(),
+            boil: edge_detection.boil_fps > 0.0,
+            edge_color_from_object: matches!(
+                edge_detection.edge_color_mode,
+                EdgeColorMode::FromObject { .. }
+            ),
+            debug_view: edge_detection.debug_view,
+            channel_edge_colors: edge_detection.depth_edge_color.is_some()
+                || edge_detection.normal_edge_color.is_some()
+                || edge_detection.color_edge_color.is_some(),
+            edge_coverage,
+            auto_calibrate,
+            edge_sources,
+            corner_rounding,
+            edge_aa: edge_detection.edge_aa,
+            dilation,
+            thick_outline,
+            // The mask output isn't multisampled, so the edges of a multisampled output are drawn
+            // into the edge mask with it, and composited by the second pass.
+            edge_mask: corner_rounding
+                || edge_detection.edge_aa
+                || dilation
+                || thick_outline
+                || adjacent_grading
+                || checkerboard
+                || temporal
+                || half_rate
+                || scaled
+                || (mask_output && before_transparent && samples > 1),
+            mask_output,
+            before_transparent,
+            // Set when the pipelines are prepared, with the usages of the main texture of the view.
+            compute: false,
+            shader: None,
+
+            target_format,
+            hdr,
+            // LDR targets with an sRGB format are encoded by the GPU on write and decoded on read,
+            // so only non-sRGB LDR targets hold gamma encoded values.
+            gamma_encoded: !hdr && !target_format.is_srgb(),
+            write_alpha_mask: edge_detection.write_alpha_mask,
+            preserve_scene_alpha: edge_detection.preserve_scene_alpha,
+            overlay,
+            multisampled: samples > 1,
+            samples,
+            all_samples: edge_detection.all_samples && samples > 1,
+            projection: projection.into(),
+        }
+    }
+
+    pub fn layout_key(&self) -> EdgeDetectionLayoutKey {
+        EdgeDetectionLayoutKey {
+            multisampled: self.multisampled,
+            stencil: self.enable_stencil,
+            motion_vectors: self.enable_motion_emphasis || self.enable_motion_smear,
+            edge_coverage: self.edge_coverage,
+            ambient_occlusion: self.ambient_occlusion,
+            material: self.enable_material || self.deferred_normals,
+            shadow: self.enable_shadow,
+            screen_mask: self.screen_mask,
+            auto_calibrate: self.auto_calibrate,
+            mesh_tags: self.excluded_meshes || self.edge_color_overrides,
+            depth: self.depth_texture,
+            normal: self.normals && !self.geometric_normals && !self.deferred_normals,
+            edge_sources: self.edge_sources,
+            compute: self.compute,
+        }
+    }
+
+    /// Whether the compute variant of the pipeline can write the output of the key, see [`EdgeDetectionPlugin::use_compute`].
+    ///
+    /// It writes the edges straight to a single sampled output of [`ViewTarget::TEXTURE_FORMAT_HDR`]. The edge mask, the mask output and
+    /// the custom [`EdgeSource`]s, whose gradient functions may sample with implicit derivatives, need the fragment pass.
+    pub fn compute_supported(&self) -> bool {
+        self.target_format == ViewTarget::TEXTURE_FORMAT_HDR
+            && self.samples == 1
+            && !self.edge_mask
+            && !self.mask_output
+            && self.edge_sources == 0
+    }
+
+    /// The number of samples of the texture the output is rendered into.
+    ///
+    /// Before the transparent pass, the output of a multisampled view is rendered into its multisampled
+    /// main texture and resolved, otherwise it's written to the resolved main texture.
+    pub fn output_samples(&self) -> u32 {
+        if self.before_transparent && self.multisampled {
+            self.samples
+        } else {
+            1
+        }
+    }
+
+    /// The format of the texture the edge detection pass renders into.
+    pub fn output_format(&self) -> TextureFormat {
+        if self.edge_mask {
+            EDGE_MASK_TEXTURE_FORMAT
+        } else {
+            self.target_format
+        }
+    }
+
+    pub fn composite_key(&self) -> EdgeCompositeKey {
+        EdgeCompositeKey {
+            target_format: self.target_format,
+            hdr: self.hdr,
+            gamma_encoded: self.gamma_encoded,
+            write_alpha_mask: self.write_alpha_mask,
+            preserve_scene_alpha: self.preserve_scene_alpha,
+            posterize: self.posterize,
+            adjacent_grading: self.adjacent_grading,
+            sharpen: self.sharpen,
+            edges_only: self.edges_only,
+            debug_view: self.debug_view != EdgeDebugView::None,
+            blueprint: self.blueprint,
+            inverted: self.inverted,
+            corner_rounding: self.corner_rounding,
+            thick_outline: self.thick_outline,
+            scaled: self.scaled,
+            reproject: self.half_rate,
+            multisampled: self.half_rate && self.multisampled,
+            samples: self.output_samples(),
+        }
+    }
+}
+
+/// The depth texture sampled by the depth-based edge detection.
+///
+/// The other sources read the depth too, e.g. to reconstruct the world positions of the pixels,
+/// so it's bound even without [`EdgeDetection::enable_depth`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DepthSource {
+    /// Sample the depth prepass texture.
+    /// A [`DepthPrepass`] is added to the camera.
+    #[default]
+    Prepass,
+    /// Sample the depth texture of the main pass, which avoids the cost of a depth prepass.
+    /// `TEXTURE_BINDING` is added to the [`Camera3d::depth_texture_usages`] of the camera.
+    ///
+    /// Note that the main pass depth texture is sampled after the transparent pass, so it contains
+    /// the transparent geometry that writes depth, whereas the depth prepass never contains transparent geometry.
+    MainPass,
+}
+
+impl DepthSource {
+    /// Returns the depth texture and its view for this source, if the view has one.
+    pub fn texture<'a>(
+        &self,
+        prepass_textures: Option<&'a ViewPrepassTextures>,
+        view_depth_texture: Option<&'a ViewDepthTexture>,
+    ) -> Option<(&'a Texture, &'a TextureView)> {
+        match self {
+            DepthSource::Prepass => prepass_textures
+                .and_then(|prepass_textures| prepass_textures.depth.as_ref())
+                .map(|depth| (&depth.texture.texture, &depth.texture.default_view)),
+            DepthSource::MainPass => view_depth_texture
+                .map(|view_depth_texture| (&view_depth_texture.texture, view_depth_texture.view())),
+        }
+    }
+}
+
+/// Adds the [`DepthPrepass`] or makes the main pass depth texture sampleable,
+/// depending on the [`DepthSource`] of each [`EdgeDetection`].
+/// Nothing is changed on the platforms without [`EdgeDetectionSupport::depth_sampling`].
+#[allow(clippy::type_complexity)]
+pub fn configure_depth_source(
+    mut commands: Commands,
+    support: Option<Res<EdgeDetectionSupport>>,
+    mut query: Query<
+        (
+            Entity,
+            &EdgeDetection,
+            Option<&mut Camera3d>,
+            Has<DepthPrepass>,
+        ),
+        (Changed<EdgeDetection>, Without<Camera2d>),
+    >,
+) {
+    if support.is_some_and(|support| !support.depth_sampling) {
+        return;
+    }
+
+    for (entity, edge_detection, camera_3d, has_depth_prepass) in query.iter_mut() {
+        match edge_detection.depth_source {
+            DepthSource::Prepass => {
+                if !has_depth_prepass {
+                    commands.entity(entity).insert(DepthPrepass);
+                }
+            }
+            DepthSource::MainPass => {
+                let Some(mut camera_3d) = camera_3d else {
+                    continue;
+                };
+
+                let usages = TextureUsages::from(camera_3d.depth_texture_usages);
+                if !usages.contains(TextureUsages::TEXTURE_BINDING) {
+                    camera_3d.depth_texture_usages =
+                        Camera3dDepthTextureUsage::from(usages | TextureUsages::TEXTURE_BINDING);
+                }
+            }
+        }
+    }
+}
+
+/// The normals compared by the normal-based edge detection, see [`EdgeDetection::normal_source`].
+///
+/// Without [`EdgeDetection::enable_normal`], the normals are only read by the steep angle adjustment
+/// of the depth-based edge detection, see [`EdgeDetection::steep_angle_multiplier`]. They're then
+/// reconstructed from the depth unless the camera has a normal prepass anyway.
+///
+/// The normals reconstructed from the depth are flat shaded and ignore normal maps, like
+/// [`EdgeDetection::geometric_normals_only`], and they're less accurate along the silhouettes,
+/// where the neighbors of a pixel may all belong to the background.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NormalSource {
+    /// Sample the normal prepass texture.
+    /// A [`NormalPrepass`] is added to the camera while [`EdgeDetection::enable_normal`] is `true`, and removed after.
+    ///
+    /// The cameras with a [`DeferredPrepass`] decode the normals of their G-buffer instead, like the deferred
+    /// lighting pass. The normals are still reconstructed from the depth if the camera has neither.
+    #[default]
+    Prepass,
+    /// Sample the normal prepass texture, or the G-buffer, if the camera has one, and reconstruct the normals
+    /// from the depth otherwise. Nothing is added to the camera, e.g. for the prepasses managed by another plugin.
+    Auto,
+    /// Reconstruct the normals from the depth, which avoids the cost of a normal prepass.
+    Depth,
+}
+
+/// Marks the [`NormalPrepass`] added by [`configure_normal_source`], which is removed once it's unused.
+/// A normal prepass added by the app, e.g. for SSAO, is left in place.
+#[derive(Component)]
+pub struct EdgeDetectionNormalPrepass;
+
+/// Adds the [`NormalPrepass`] to the cameras whose [`EdgeDetection`] samples it, see [`NormalSource::Prepass`],
+/// and removes it once the normal-based edge detection is disabled.
+///
+/// The cameras with a [`DeferredPrepass`] read the normals from their G-buffer, and don't need one.
+#[allow(clippy::type_complexity)]
+pub fn configure_normal_source(
+    mut commands: Commands,
+    support: Option<Res<EdgeDetectionSupport>>,
+    query: Query<
+        (
+            Entity,
+            &EdgeDetection,
+            Has<NormalPrepass>,
+            Has<DeferredPrepass>,
+            Has<EdgeDetectionNormalPrepass>,
+        ),
+        (
+            Or<(Changed<EdgeDetection>, Added<DeferredPrepass>)>,
+            Without<Camera2d>,
+        ),
+    >,
+) {
+    let depth_sampling = support.is_none_or(|support| support.depth_sampling);
+
+    for (entity, edge_detection, has_normal_prepass, deferred, added) in query.iter() {
+        let needed = edge_detection.normal_source == NormalSource::Prepass
+            && edge_detection.enable_normal
+            && depth_sampling
+            && !deferred;
+
+        if needed && !has_normal_prepass {
+            commands
+                .entity(entity)
+                .insert((NormalPrepass, EdgeDetectionNormalPrepass));
+        } else if !needed && added {
+            commands
+                .entity(entity)
+                .remove::<(NormalPrepass, EdgeDetectionNormalPrepass)>();
+        }
+    }
+}
+
+/// The kernel the gradients are measured with, see [`EdgeDetection::operator`].
+///
+/// The kernels are normalized to the response of the Sobel kernel across a sharp step,
+/// so the thresholds keep their meaning when the operator is changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeDetectionOperator {
+    /// The 3x3 Sobel kernel, weighing the center row or column twice as much as the sides.
+    #[default]
+    Sobel,
+    /// The 3x3 Prewitt kernel, weighing the rows or columns evenly. It's more sensitive to noise than Sobel.
+    Prewitt,
+    /// The 3x3 Scharr kernel, weighing the center row or column 10/3 as much as the sides.
+    /// Its gradients are the most rotationally symmetric, which keeps the edges of thin geometry
+    /// and high-frequency normal maps even whatever their direction.
+    Scharr,
+    /// The 2x2 Roberts cross, differences along the diagonals. It samples a third of the texels of
+    /// the 3x3 kernels, for cheaper, crisper and more aliased edges with a retro look.
+    RobertsCross,
+}
+
+impl EdgeDetectionOperator {
+    /// The shader def selecting the kernel in the shader.
+    fn shader_def(self) -> &'static str {
+        match self {
+            Self::Sobel => "OPERATOR_SOBEL",
+            Self::Prewitt => "OPERATOR_PREWITT",
+            Self::Scharr => "OPERATOR_SCHARR",
+            Self::RobertsCross => "OPERATOR_ROBERTS_CROSS",
+        }
+    }
+}
+
+/// The space the color gradients are measured in, see [`EdgeDetection::color_space`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorEdgeSpace {
+    /// The channels of the scene color as rendered. Smooth gradients of saturated colors are outlined,
+    /// while colors at a similar RGB distance but of very different brightness aren't told apart.
+    #[default]
+    LinearRgb,
+    /// The relative luminance only, filtered as a single channel, which is the cheapest.
+    /// Edges between colors of the same brightness are lost.
+    Luminance,
+    /// The perceptual OkLab space, where the distances follow the perceived differences
+    /// of both the lightness and the hue.
+    OkLab,
+}
+
+impl ColorEdgeSpace {
+    /// The shader def selecting the space in the shader.
+    fn shader_def(self) -> &'static str {
+        match self {
+            Self::LinearRgb => "COLOR_SPACE_LINEAR_RGB",
+            Self::Luminance => "COLOR_SPACE_LUMINANCE",
+            Self::OkLab => "COLOR_SPACE_OKLAB",
+        }
+    }
+}
+
+/// How the color of the detected edges is chosen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeColorMode {
+    /// Draw every edge with [`EdgeDetection::edge_color`].
+    #[default]
+    Uniform,
+    /// Tint the edges of each object by a darkened version of the object's own color.
+    ///
+    /// The scene color is sampled a couple of texels toward the nearer side of the depth discontinuity,
+    /// so silhouettes take the color of the object in front. The alpha of [`EdgeDetection::edge_color`]
+    /// is still used as the opacity of the edges.
+    FromObject {
+        /// How much the object color is darkened.
+        ///
+        /// Range: [0.0, 1.0], where 1.0 is black.
+        darken: f32,
+        /// How much the saturation of the object color is increased.
+        ///
+        /// Range: [0.0, inf), where 0.0 keeps the object saturation.
+        saturate: f32,
+    },
+}
+
+/// What the detected edges are used for, see [`EdgeDetection::output_mode`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutputMode {
+    /// Draw the edges over the scene.
+    #[default]
+    Edges,
+    /// Sharpen the scene proportionally to the strength of the edges, instead of drawing them.
+    ///
+    /// The scene color is pushed away from the average of its four neighbors, an unsharp mask,
+    /// and clamped to the range of the neighborhood so it doesn't ring, which also keeps HDR colors positive.
+    /// With only the depth and normal sources enabled, flat surfaces aren't sharpened, so noisy textures stay as they are.
+    /// The edge colors are ignored, and the edge opacity is still written with [`EdgeDetection::write_alpha_mask`].
+    Sharpen {
+        /// Strength of the sharpening on the edges.
+        ///
+        /// Range: [0.0, inf), 1.0 doubles the local contrast before clamping.
+        amount: f32,
+    },
+    /// Write only the edges, without the scene: the edge color in rgb and the edge opacity in alpha.
+    ///
+    /// Render the camera into an image and composite it over another camera with an [`EdgeOverlay`],
+    /// to draw the outlines of one view over a different one. The alpha isn't premultiplied, and the pixels
+    /// without edges are fully transparent whatever the clear color of the camera, so the image can also be
+    /// composited like any other, e.g. the outlines of an item preview drawn over a UI texture with an [`ImageNode`](bevy::ui::widget::ImageNode).
+    EdgesOnly,
+    /// Draw the edges over a flat background instead of the scene, e.g. for a "blueprint" debug view.
+    /// The scene color can still be one of the sources of the edges, see [`EdgeDetection::enable_color`].
+    Blueprint {
+        /// The color replacing the scene. Like the edge colors, it isn't clamped on HDR cameras.
+        background: Color,
+    },
+    /// Draw the edge color everywhere but on the edges, where the scene shows through, for an inverted sketch.
+    Inverted,
+}
+
+/// The edges drawn against the background, see [`EdgeDetection::background_edges`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BackgroundEdgeMode {
+    /// The silhouettes against the background are drawn on the side of the surfaces only,
+    /// with the thickness of the depth edges.
+    #[default]
+    Silhouette,
+    /// No edges are drawn against the background, neither on it nor on the surfaces within the reach
+    /// of the kernel from it, e.g. to only outline the shapes overlapping each other.
+    None,
+}
+
+/// A debug view replacing the output of the edge detection, see [`EdgeDetection::debug_view`].
+///
+/// The strengths are drawn in grayscale, from black without an edge to white, before they're modulated,
+/// e.g. by the ambient occlusion or the distance fade. The sources that are disabled, or unsupported by the view,
+/// are black.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeDebugView {
+    /// The edges are drawn as configured.
+    #[default]
+    None,
+    /// The strength of the depth edges.
+    DepthEdges,
+    /// The strength of the normal edges.
+    NormalEdges,
+    /// The strength of the color edges.
+    ColorEdges,
+    /// The strength of all the edges combined, once modulated, like the [`ViewEdgeMaskTexture`].
+    CombinedMask,
+    /// How much the steep angle adjustment raises the depth threshold, as a heatmap from blue, where the threshold
+    /// is kept, to red, where it's doubled or more, see [`EdgeDetection::steep_angle_threshold`] and
+    /// [`EdgeDetection::steep_angle_multiplier`]. Black on the background, and without the depth source.
+    SteepAngleFactor,
+}
+
+impl EdgeDebugView {
+    /// The shader def selecting the view in the shader, `None` without a debug view.
+    fn shader_def(self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::DepthEdges => Some("DEBUG_VIEW_DEPTH_EDGES"),
+            Self::NormalEdges => Some("DEBUG_VIEW_NORMAL_EDGES"),
+            Self::ColorEdges => Some("DEBUG_VIEW_COLOR_EDGES"),
+            Self::CombinedMask => Some("DEBUG_VIEW_COMBINED_MASK"),
+            Self::SteepAngleFactor => Some("DEBUG_VIEW_STEEP_ANGLE_FACTOR"),
+        }
+    }
+
+    /// Whether the view shows the strength of a single source.
+    fn reads_channel_edges(self) -> bool {
+        matches!(
+            self,
+            Self::DepthEdges | Self::NormalEdges | Self::ColorEdges
+        )
+    }
+}
+
+/// How the edge color is combined with the scene, see [`EdgeDetection::blend_mode`].
+///
+/// The edges are blended by their opacity, so translucent edge colors and anti-aliased edges fade
+/// into the scene in every mode but `Replace`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeBlendMode {
+    /// The edge color is drawn over the scene.
+    #[default]
+    AlphaOver,
+    /// The edge color replaces the pixels more than half covered by the edges, without blending,
+    /// for crisp lines e.g. in pixel art.
+    Replace,
+    /// The scene is multiplied by the edge color, so the edges darken it and keep its texture.
+    Multiply,
+    /// The edge color is added to the scene, so the edges glow, e.g. through a bloom pass on HDR cameras.
+    Additive,
+}
+
+impl EdgeBlendMode {
+    /// The value of the blend mode in the shader, matching the `EDGE_BLEND_*` constants.
+    fn index(self) -> u32 {
+        match self {
+            Self::AlphaOver => 0,
+            Self::Replace => 1,
+            Self::Multiply => 2,
+            Self::Additive => 3,
+        }
+    }
+}
+
+/// The unit of the thicknesses of the edges, see [`EdgeDetection::units`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeUnits {
+    /// The thicknesses are in pixels of the view, so the lines get relatively thinner at higher resolutions.
+    #[default]
+    Pixels,
+    /// The thicknesses are fractions of the height of the view, e.g. `0.002` for about 2 pixels at 1080p,
+    /// so the lines keep their relative weight at any resolution. The frame of a
+    /// [`SubCameraView`] is used instead, so the tiles of a frame draw the same lines.
+    ///
+    /// The depth threshold of perspective cameras is scaled by `tan(fov / 2) / tan(reference_fov / 2)`,
+    /// as the depth differences across a fraction of the screen grow with the FOV, so animating the FOV doesn't
+    /// make the edges pop in and out. With the [`EdgeDetection::fov_compensation`], the thicknesses follow the zoom
+    /// instead, as in pixels.
+    ScreenHeightFraction,
+}
+
+/// A progressive draw-in of the edges, see [`EdgeDetection::reveal`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct RevealSettings {
+    /// How much of the edges is drawn, animated from 0.0 (none) to 1.0 (all of them, as without the reveal).
+    pub progress: f32,
+    /// The order in which the edges appear.
+    pub mode: RevealMode,
+    /// Width of the fade between the drawn and hidden edges, in units of progress.
+    /// Zero reveals each pixel at once.
+    pub softness: f32,
+}
+
+impl Default for RevealSettings {
+    fn default() -> Self {
+        Self {
+            progress: 1.0,
+            mode: RevealMode::Noise,
+            softness: 0.1,
+        }
+    }
+}
+
+/// The order in which the edges appear with [`RevealSettings`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RevealMode {
+    /// In blotches following the noise texture, like lines being inked here and there.
+    #[default]
+    Noise,
+    /// Outward from a point of the screen, in normalized viewport coordinates from (0.0, 0.0) at the top left
+    /// to (1.0, 1.0) at the bottom right. The edges of the farthest corner appear last.
+    Radial { origin: Vec2 },
+}
+
+/// A world-space sphere outside of which no edges are drawn, see [`EdgeDetection::scan`].
+///
+/// Animate the `radius` from a system for the classic expanding "scan" pulse.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ScanSettings {
+    /// Center of the sphere, in world space.
+    pub center: Vec3,
+    /// Radius of the sphere.
+    pub radius: f32,
+    /// Width of the fade of the edges inside the surface of the sphere, in world units.
+    pub edge_falloff: f32,
+}
+
+/// Strokes the edges are broken into, see [`EdgeDetection::hatching`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct HatchingSettings {
+    /// Length of a stroke and the gap after it, in pixels along the edge.
+    pub scale: f32,
+    /// Fraction of the `scale` covered by the stroke, the noise lengthening or shortening each stroke around it.
+    ///
+    /// Range: [0.0, 1.0]
+    pub coverage: f32,
+}
+
+impl Default for HatchingSettings {
+    fn default() -> Self {
+        Self {
+            scale: 8.0,
+            coverage: 0.6,
+        }
+    }
+}
+
+/// Toon banding of the scene color under the edges, see [`EdgeDetection::posterize`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct PosterizeSettings {
+    /// Number of bands the scene color is quantized into.
+    ///
+    /// Range: [2, 256]
+    pub levels: u32,
+    /// Whether only the luminance is quantized, which keeps the hue and saturation of the scene.
+    /// If `false`, each channel is quantized on its own.
+    pub preserve_hue: bool,
+}
+
+impl Default for PosterizeSettings {
+    fn default() -> Self {
+        Self {
+            levels: 4,
+            preserve_hue: true,
+        }
+    }
+}
+
+/// Grading of the scene color in a halo next to the edges, see [`EdgeDetection::edge_adjacent_grading`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct AdjacentGrading {
+    /// Width of the halo beside the edges, in pixels. The grading fades out linearly across it.
+    ///
+    /// Range: [0.0, 8.0]
+    pub width_px: f32,
+    /// How much the scene color is desaturated next to the edges, 1.0 being grayscale.
+    ///
+    /// Range: [0.0, 1.0]
+    pub desaturate: f32,
+    /// How much the scene color is lightened towards white next to the edges, 1.0 being white.
+    ///
+    /// Range: [0.0, 1.0]
+    pub lighten: f32,
+}
+
+impl Default for AdjacentGrading {
+    fn default() -> Self {
+        Self {
+            width_px: 3.0,
+            desaturate: 0.5,
+            lighten: 0.15,
+        }
+    }
+}
+
+/// Settings of the edges in a screen region, see [`EdgeDetection::region_overrides`].
+#[derive(Clone, Debug, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct EdgeRegionOverride {
+    /// The region, in normalized coordinates of the camera viewport like the [`EdgeDetection::exclusion_rects`].
+    pub rect: Rect,
+    /// The settings used instead of the ones of the camera in the region.
+    pub settings: EdgeRegionSettings,
+}
+
+impl Default for EdgeRegionOverride {
+    fn default() -> Self {
+        Self {
+            rect: Rect::new(0.0, 0.0, 1.0, 1.0),
+            settings: EdgeRegionSettings::default(),
+        }
+    }
+}
+
+/// The settings of the [`EdgeDetection`] an [`EdgeRegionOverride`] replaces in its region.
+/// The depth threshold is scaled like the one of the camera, by the `fov_compensation` and the
+/// `normalized_depth_threshold`.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct EdgeRegionSettings {
+    /// See [`EdgeDetection::depth_threshold`].
+    pub depth_threshold: f32,
+    /// See [`EdgeDetection::normal_threshold`]. It's used for both the concave and convex creases in the region.
+    pub normal_threshold: f32,
+    /// See [`EdgeDetection::color_threshold`].
+    pub color_threshold: f32,
+    /// See [`EdgeDetection::edge_color`]. Ignored with [`EdgeColorMode::FromObject`], except for its alpha.
+    pub edge_color: Color,
+}
+
+impl Default for EdgeRegionSettings {
+    fn default() -> Self {
+        Self::from(&EdgeDetection::default())
+    }
+}
+
+impl From<&EdgeDetection> for EdgeRegionSettings {
+    /// The settings of the camera, to start an override from.
+    fn from(edge_detection: &EdgeDetection) -> Self {
+        Self {
+            depth_threshold: edge_detection.depth_threshold,
+            normal_threshold: edge_detection.normal_threshold,
+            color_threshold: edge_detection.color_threshold,
+            edge_color: edge_detection.edge_color,
+        }
+    }
+}
+
+/// The material attributes of the deferred G-buffer compared by [`EdgeDetection::enable_material`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct MaterialEdgeAttributes {
+    /// Whether the base color is compared. Note that unlit materials store their color as emissive,
+    /// so their base color is black.
+    pub base_color: bool,
+    /// Whether the perceptual roughness is compared.
+    pub roughness: bool,
+    /// Whether the metallic is compared, e.g. to outline metal trims.
+    pub metallic: bool,
+    /// Whether the reflectance is compared.
+    pub reflectance: bool,
+}
+
+impl Default for MaterialEdgeAttributes {
+    fn default() -> Self {
+        Self {
+            base_color: true,
+            roughness: true,
+            metallic: true,
+            reflectance: false,
+        }
+    }
+}
+
+impl MaterialEdgeAttributes {
+    fn weights(&self) -> Vec4 {
+        Vec4::new(
+            self.base_color.into(),
+            self.roughness.into(),
+            self.metallic.into(),
+            self.reflectance.into(),
+        )
+    }
+}
+
+/// Draws the edges of the scene rendered by the camera.
+///
+/// # Camera stacking
+///
+/// An overlay camera stacked over another one on the same target, i.e. with a higher [`Camera::order`] and
+/// [`ClearColorConfig::None`], only outlines its own geometry: the pixels where its depth was left cleared
+/// belong to the cameras below it, and they've already been outlined by their own `EdgeDetection`.
+/// The overlay camera must keep clearing its depth, which is the default of [`Camera3d::depth_load_op`].
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct EdgeDetection {
+    /// Depth threshold, used to detect edges with significant depth changes.
+    /// Areas where the depth variation exceeds this threshold will be marked as edges.
+    ///
+    /// The depth is linearized before it's compared, so the threshold is in world units, the Sobel gradient
+    /// of the view depth: a step of `depth_threshold / 4.0` across the thickness is an edge, whatever the near
+    /// and far planes are, under perspective and orthographic projections alike. See [`EdgeDetection::normalized_depth_threshold`] to give it as a fraction instead.
+    pub depth_threshold: f32,
+    /// Whether [`EdgeDetection::depth_threshold`] is a fraction of the depth range of the projection,
+    /// between its near and far planes, instead of world units.
+    ///
+    /// The world units suit presets shared between scenes of the same scale, the fraction between scenes whose
+    /// scale follows their depth range, e.g. a preset tuned on a tabletop scene used on a landscape.
+    /// Custom projections have no known range, their threshold is used in world units.
+    pub normalized_depth_threshold: bool,
+    /// Normal threshold, used to detect edges with significant normal direction changes.
+    /// Areas where the normal direction variation exceeds this threshold will be marked as edges.
+    ///
+    /// It's used for both the concave and convex creases, unless they have their own threshold.
+    pub normal_threshold: f32,
+    /// Normal threshold of the concave creases, where the surface folds inward like the inner corners of a room.
+    ///
+    /// `None` uses `normal_threshold`.
+    pub normal_threshold_concave: Option<f32>,
+    /// Normal threshold of the convex creases, where the surface folds outward like the outer edges of a cube,
+    /// e.g. higher than the concave one for toon shading with strong lines in the creases and subtle ones on the bevels.
+    ///
+    /// The normal turns toward the direction of travel across a convex crease and away from it across a concave one,
+    /// which is measured with the world positions of the neighbors. It costs four more depth samples on the
+    /// normal edges when the concave and convex thresholds differ.
+    ///
+    /// `None` uses `normal_threshold`.
+    pub normal_threshold_convex: Option<f32>,
+    /// Color threshold, used to detect edges with significant color changes.
+    /// Areas where the color variation exceeds this threshold will be marked as edges.
+    ///
+    /// The gradient is measured in [`EdgeDetection::color_space`], and a sharp step between black and white
+    /// reads as 4.0 in [`ColorEdgeSpace::Luminance`] and [`ColorEdgeSpace::OkLab`], about 6.9 in
+    /// [`ColorEdgeSpace::LinearRgb`], where the channels add up. The perceptual spaces weigh the steps
+    /// by their perceived contrast, so dark and bright areas are outlined more evenly.
+    ///
+    /// Range: [0.0, 4.0] in the perceptual spaces, [0.0, 7.0] in linear RGB, more on HDR targets.
+    pub color_threshold: f32,
+    /// Material threshold, used to detect edges with significant material changes on the deferred path,
+    /// see [`EdgeDetection::enable_material`].
+    pub material_threshold: f32,
+    /// Shadow threshold, used to detect edges along the boundaries of the shadows of the `shadow_mask`.
+    /// Areas where the visibility variation of the mask exceeds this threshold will be marked as edges.
+    pub shadow_threshold: f32,
+    /// Half the width of the band around the `depth_threshold` over which the depth edges fade in, in the units
+    /// of the threshold. The gradients within `depth_threshold ± depth_threshold_softness` draw partial edges
+    /// along a smoothstep, which anti-aliases the lines and keeps the edges whose gradient hovers around
+    /// the threshold from popping. The partial edges are blended with the scene by their opacity.
+    ///
+    /// Where several sources draw partial edges on the same pixel, the strongest one is kept, like the full edges.
+    /// 0.0 draws hard edges.
+    pub depth_threshold_softness: f32,
+    /// Like the [`EdgeDetection::depth_threshold_softness`], around the concave and convex normal thresholds.
+    pub normal_threshold_softness: f32,
+    /// Like the [`EdgeDetection::depth_threshold_softness`], around the `color_threshold`.
+    pub color_threshold_softness: f32,
+
+    /// Thickness of the edges detected based on depth variations, in [`EdgeDetection::units`].
+    /// This value controls the width of the edges drawn when depth-based edge detection is enabled.
+    /// Higher values result in thicker edges.
+    pub depth_thickness: f32,
+    /// Thickness of the edges detected based on normal direction variations, in [`EdgeDetection::units`].
+    /// This value controls the width of the edges drawn when normal-based edge detection is enabled.
+    /// Higher values result in thicker edges.
+    pub normal_thickness: f32,
+    /// Thickness of the edges detected based on color variations, in [`EdgeDetection::units`].
+    /// This value controls the width of the edges drawn when color-based edge detection is enabled.
+    /// Higher values result in thicker edges.
+    pub color_thickness: f32,
+    /// Thickness of the edges detected based on stencil value changes, in [`EdgeDetection::units`].
+    /// This value controls the width of the edges drawn when stencil-based edge detection is enabled.
+    /// Higher values result in thicker edges.
+    pub stencil_thickness: f32,
+    /// Thickness of the edges detected based on material changes, in [`EdgeDetection::units`].
+    /// This value controls the width of the edges drawn when material-based edge detection is enabled.
+    /// Higher values result in thicker edges.
+    pub material_thickness: f32,
+    /// Thickness of the edges detected along the boundaries of the shadows, in [`EdgeDetection::units`].
+    /// This value controls the width of the edges drawn when the `shadow_mask` is set.
+    /// Higher values result in thicker edges.
+    pub shadow_thickness: f32,
+    /// The number of rings of samples the edges are detected with, up to [`MAX_KERNEL_RADIUS`].
+    ///
+    /// The kernels sample the neighbors at the thickness, so beyond about two pixels they skip the pixels
+    /// in between and the lines break into dots. Each ring measures the kernel at another fraction of the
+    /// thickness, so a radius of about the largest thickness keeps the lines solid. Every ring adds a full
+    /// kernel of samples, so small radii stay cheap. A radius of 1 is the single 3x3 kernel.
+    pub kernel_radius: u32,
+    /// The unit of the thicknesses of the depth, normal, color, stencil, material and shadow edges.
+    /// The other sizes, e.g. the [`EdgeDetection::dilation`], are in pixels.
+    pub units: EdgeUnits,
+
+    /// Whether to compensate the zoom of a perspective camera, so the relative line weight is kept when its FOV animates.
+    /// The thicknesses are scaled by `tan(reference_fov / 2) / tan(fov / 2)`, the factor the objects grow by on screen,
+    /// which also keeps the depth gradients of their surfaces measured across the same distance,
+    /// so the edges don't pop in and out during the zoom.
+    ///
+    /// The scale of the thicknesses is bounded to [`MAX_FOV_THICKNESS_SCALE`] and its inverse,
+    /// beyond it the depth threshold is scaled instead. Orthographic cameras are unaffected.
+    pub fov_compensation: bool,
+    /// The vertical FOV, in radians, at which the thicknesses and the depth threshold are used as is by `fov_compensation`.
+    pub reference_fov: f32,
+
+    /// Steep angle threshold, used to adjust the depth threshold when viewing surfaces at steep angles.
+    /// When the angle between the view direction and the surface normal is very steep, the depth gradient
+    /// can appear artificially large, causing non-edge regions to be mistakenly detected as edges.
+    /// This threshold defines the angle at which the depth threshold adjustment begins to take effect.
+    ///
+    /// Range: [0.0, 1.0]
+    pub steep_angle_threshold: f32,
+    /// Multiplier applied to the depth threshold when the view angle is steep.
+    /// When the angle between the view direction and the surface normal exceeds the `steep_angle_threshold`,
+    /// the depth threshold is scaled by this multiplier to reduce the likelihood of false edge detection.
+    ///
+    /// A value of 1.0 means no adjustment, while values greater than 1.0 increase the depth threshold,
+    /// making edge detection less sensitive in steep angles.
+    ///
+    /// The adjustment grows with the height of the view at the depth of the surface, in world units, like the depth
+    /// differences between the pixels of a slanted surface do. It's the same for a perspective and an orthographic
+    /// camera seeing the surface over the same height, and doesn't change with the distance of an orthographic camera.
+    ///
+    /// It used to grow with the distance of the surface instead, whatever the projection. The previous values
+    /// are divided by `2.0 * (fov / 2.0).tan()` to keep the same edges under perspective, e.g. 0.30 became 0.36
+    /// with the default 45° fov.
+    ///
+    /// Range: [0.0, inf)
+    pub steep_angle_multiplier: f32,
+    /// Whether the depth edges are only drawn on the nearer surface of the discontinuities.
+    /// The kernel marks the pixels on both sides, so a character in front of a wall gets a line on the character
+    /// and a mirrored one on the wall, which doubles the line weight and leaves halos on the wall when the camera moves.
+    ///
+    /// The normal and color edges are still drawn on both sides.
+    pub one_sided_depth_edges: bool,
+    /// The edges drawn against the background, the pixels at the far plane of the depth, e.g. the sky of a
+    /// [`Skybox`](bevy::core_pipeline::Skybox) or the clear color, under perspective and orthographic projections.
+    /// The background has no depth gradient nor normal of its own, so neither the depth nor the normal edges
+    /// are ever drawn on it.
+    ///
+    /// It needs the depth texture, see [`EdgeDetection::depth_source`]. Without it, e.g. on 2D cameras,
+    /// nothing is known to be background.
+    pub background_edges: BackgroundEdgeMode,
+    /// Boost of the thicknesses where the depth slope is steep.
+    /// Surfaces viewed nearly edge-on get thin, broken edges because the gradient is spread over few pixels,
+    /// so the thicknesses are scaled up to `1.0 + slope_thickness_boost` there to keep an even line weight
+    /// around curved silhouettes.
+    ///
+    /// Zero keeps the thicknesses unchanged and compiles the boost out of the shader.
+    ///
+    /// Range: [0.0, 4.0]
+    pub slope_thickness_boost: f32,
+    /// How much the thicknesses of the depth, normal, color and material edges shrink with the view distance,
+    /// like ink lines under perspective. Beyond `thickness_reference_distance`, they're scaled by
+    /// `(thickness_reference_distance / distance) ^ thickness_attenuation`, down to one texel so distant objects
+    /// keep their outline. The distance is the one of the nearest surface around each pixel, so both sides
+    /// of a silhouette are drawn alike.
+    ///
+    /// Zero keeps the constant pixel thicknesses, 1.0 is fully perspective-correct.
+    /// Orthographic views don't shrink the objects with their distance, so it's ignored there.
+    ///
+    /// Range: [0.0, 1.0]
+    pub thickness_attenuation: f32,
+    /// View distance, in world units, up to which the edges keep their full thickness with `thickness_attenuation`.
+    pub thickness_reference_distance: f32,
+    /// Emphasis of the corners, where the direction of the normal or color gradient changes within the kernel.
+    /// The corners are found with a Harris-like measure of the Sobel gradients, which is zero along straight edges,
+    /// and the normal and color thresholds are divided by up to `1.0 + corner_emphasis` there, so the corners
+    /// are drawn heavier than the lines meeting at them. The depth edges are unaffected.
+    ///
+    /// Zero compiles the emphasis out of the shader.
+    ///
+    /// Range: [0.0, 8.0]
+    pub corner_emphasis: f32,
+
+    /// Influence of the screen space ambient occlusion on the edge strength, on cameras with
+    /// [`ScreenSpaceAmbientOcclusion`]. Positive values keep the edges in occluded creases and corners
+    /// and lighten the open ones, like an ink illustration, negative values do the inverse.
+    /// The edges are scaled by `mix(1.0, 1.0 - ao, influence)`, `ao` being the ambient visibility,
+    /// or by `mix(1.0, ao, -influence)` for negative values.
+    ///
+    /// Zero compiles the ambient occlusion out of the shader, as does a camera without SSAO.
+    ///
+    /// Range: [-1.0, 1.0]
+    pub ao_influence: f32,
+
+    /// The kernel the gradients of the depth, normal, color, material and shadow sources are measured with.
+    /// The stencil source compares the neighbors directly, and the custom [`EdgeSource`]s measure their own gradients.
+    pub operator: EdgeDetectionOperator,
+
+    /// The depth texture sampled by the depth-based edge detection.
+    pub depth_source: DepthSource,
+    /// The normals compared by the normal-based edge detection.
+    pub normal_source: NormalSource,
+
+    /// Filtering of the scene color sampled by the color-based edge detection and the edge color modes.
+    /// [`FilterMode::Nearest`] keeps the hard pixel edges of scenes rendered at a low resolution.
+    #[reflect(remote = FilterModeReflect)]
+    #[cfg_attr(feature = "serde", serde(with = "FilterModeSerde"))]
+    pub color_filter: FilterMode,
+    /// The space the color gradients of the color-based edge detection are measured in.
+    /// The color of the edges themselves is unaffected.
+    pub color_space: ColorEdgeSpace,
+
+    /// Whether to use all the samples of the multisampled depth and normal textures when MSAA is enabled.
+    /// If `true`, the nearest depth and the most divergent normal across the samples of each texel are used,
+    /// so thin sub-pixel features covering only some samples still get stable edges.
+    /// If `false`, only the sample being shaded is used, which is cheaper.
+    pub all_samples: bool,
+
+    /// Strength of the speed-line emphasis of fast-moving objects.
+    /// When non-zero and the camera has a [`MotionVectorPrepass`](bevy::core_pipeline::prepass::MotionVectorPrepass),
+    /// edges are streaked along the screen-space motion of each pixel and boosted proportionally to its speed.
+    /// Static pixels are unaffected.
+    ///
+    /// A value of 1.0 streaks the edges over the distance moved in one frame.
+    /// Zero disables the emphasis and compiles it out of the shader.
+    pub motion_emphasis: f32,
+    /// Strength of the smearing of the uv distortion along the motion of each pixel.
+    /// When non-zero and the camera has a [`MotionVectorPrepass`](bevy::core_pipeline::prepass::MotionVectorPrepass),
+    /// each pixel is offset back by a noisy fraction of its screen-space motion, so fast motion smears the lines.
+    /// Static pixels are unaffected.
+    ///
+    /// Zero disables the smear and compiles it out of the shader.
+    pub motion_smear_strength: f32,
+
+    /// Radius in pixels of the rounding of the edge caps and joins.
+    /// When non-zero, the edges are drawn into an intermediate mask, blurred by a disc of this radius
+    /// and re-thresholded, which replaces the blocky staircase corners of thick edges by rounded caps and smooth
+    /// joins while keeping their width. The radius is limited to half the width of the edges,
+    /// so the edges of a pixel or two are left as is.
+    ///
+    /// Zero skips the extra pass. The radius can be animated without recompiling the shader.
+    ///
+    /// Range: [0.0, 8.0]
+    pub corner_rounding: f32,
+    /// Number of pixels the edges are grown by, for bold outlines on large targets. When non-zero,
+    /// the edges are drawn into an intermediate mask and dilated by a disc of this radius in a separate pass,
+    /// before they're anti-aliased and composited. Each pixel takes the color of the strongest edge around it,
+    /// so the edge color stays crisp, and the scene under the edges isn't smeared.
+    ///
+    /// Zero skips the extra pass. The radius can be animated without recompiling the shaders.
+    ///
+    /// Range: [0, 8]
+    pub dilation: u32,
+
+    /// Whether to anti-alias the edges, smoothing the stair-stepping of thin diagonal lines.
+    /// The edges are drawn into an intermediate mask which is filtered by an FXAA-style pass before
+    /// being composited, so unlike a full-screen FXAA the scene itself stays sharp.
+    pub edge_aa: bool,
+
+    /// Whether to render the edges of only half of the pixels each frame, in a checkerboard alternating
+    /// every frame, which about halves the cost of the edge detection pass on low-end targets.
+    /// The other half keeps the edges of the previous frame from a per-view history, so static scenes
+    /// converge to the same edges as rendering every pixel after two frames, but moving edges are a frame late on
+    /// every other pixel. The [`EdgeDetectionAdaptiveQuality::GPU_TIME`] diagnostic measures the difference.
+    ///
+    /// The history is discarded when the render target is resized, set `reset_history` when the camera teleports.
+    /// Note that the [`EdgeCoverage`] only counts the pixels rendered in the frame.
+    pub checkerboard: bool,
+    /// Whether to detect the edges every other frame only, e.g. at 30Hz for a game rendering at 60Hz
+    /// on battery-constrained devices. On the other frames the edge detection pass is skipped, and the edges
+    /// of the previous frame are reprojected from a per-view history along the motion vectors of the camera,
+    /// which needs a [`MotionVectorPrepass`](bevy::core_pipeline::prepass::MotionVectorPrepass).
+    /// The [`EdgeDetectionAdaptiveQuality::GPU_TIME`] diagnostic measures the difference.
+    ///
+    /// The ghosting is bounded: the reprojected edges are at most a frame old, and the ones reprojected
+    /// from outside the screen or moving too far in a frame are dropped. The edges are detected on the first frame
+    /// and when the render target is resized, set `reset_history` when the camera teleports.
+    /// It takes precedence over `checkerboard`, and is ignored on cameras without motion vectors.
+    pub half_rate: bool,
+    /// Weight of the history in the edges, to stabilize the edges shimmering and crawling under slow camera motion,
+    /// as single frames flip the pixels near the thresholds on and off. The edges of the previous frames are kept
+    /// in a per-view history, reprojected along the motion vectors of the camera, which needs a
+    /// [`MotionVectorPrepass`](bevy::core_pipeline::prepass::MotionVectorPrepass), and blended with the edges
+    /// of the frame before they're post-filtered and composited. Higher values are steadier, but the edges
+    /// take longer to appear and fade.
+    ///
+    /// The history is rejected where it can't be trusted, so fast-moving objects don't leave trails: outside
+    /// the screen, under fast motion, and where the depth changed since the previous frame. It's discarded when
+    /// the render target is resized, set `reset_history` when the camera teleports.
+    ///
+    /// Zero skips the extra pass, as do the cameras without motion vectors and `half_rate`,
+    /// which reprojects its own history.
+    ///
+    /// Range: [0.0, 0.95]
+    pub temporal_blend: f32,
+    /// Set to `true` to render all the pixels in the next frame with `checkerboard`, or to detect the edges
+    /// in the next frame with `half_rate`, e.g. when the camera teleports or the scene is cut.
+    /// It also discards the history of `temporal_blend`.
+    /// It's set back to `false` once extracted.
+    pub reset_history: bool,
+    /// The scale of the resolution the edges are detected at, in `(0.0, 1.0]`, e.g. `0.5` for half of the width
+    /// and height of the view. Below `1.0`, the edges are detected into a smaller intermediate mask, and upsampled
+    /// bilinearly as they're composited onto the view, which saves most of the cost of the edge detection pass
+    /// on large targets, at the expense of softer lines.
+    ///
+    /// The thicknesses and radii stay in pixels of the view, so the line weight doesn't change with the scale.
+    /// It's ignored with `checkerboard`, `half_rate` and [`EdgeDetectionMaskOutput`], whose textures are at
+    /// the resolution of the view, see [`EdgeDetection::detection_scale`].
+    pub resolution_scale: f32,
+
+    /// A thick outline drawn around the edges, e.g. to highlight a selected object.
+    /// The edges are drawn into an intermediate mask and jump flooded to find the distance of each pixel
+    /// to the nearest edge, so the cost only grows with the log2 of the radius.
+    ///
+    /// `None` skips the extra passes. The radius and color can be animated without recompiling the shaders.
+    pub thick_outline: Option<ThickOutline>,
+
+    /// Frequency of UV distortion applied to the edge detection process.
+    /// This controls how often the distortion effect repeats across the UV coordinates.
+    /// Higher values result in more frequent distortion patterns.
+    pub uv_distortion_frequency: Vec2,
+
+    /// Strength of UV distortion applied to the edge detection process.
+    /// This controls the intensity of the distortion effect.
+    /// Higher values result in more pronounced distortion.
+    pub uv_distortion_strength: Vec2,
+
+    /// Speed the distortion pattern scrolls at, in repetitions of the noise texture per second,
+    /// so the lines wobble over time instead of holding still.
+    pub uv_distortion_speed: Vec2,
+    /// Number of times per second the scrolling distortion is updated, so the lines "boil" like traditional
+    /// animation drawn on twos or threes, e.g. 8 fps. Unlike `boil_fps`, the pattern moves by steps
+    /// instead of jumping to a random offset.
+    ///
+    /// `None` scrolls continuously.
+    pub uv_distortion_fps: Option<f32>,
+
+    /// The noise texture distorting the edges, in place of the embedded perlin noise.
+    /// Its red and green channels hold the distortion offsets, so it should be loaded as linear data,
+    /// e.g. with `is_srgb: false` in its [`ImageLoaderSettings`]. It's sampled with a repeating sampler,
+    /// so it should tile, and it may have any size.
+    ///
+    /// `None` uses the embedded noise, which is also used until the texture is loaded.
+    /// It isn't serialized, the presets keep the texture of the camera they're applied to.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub noise_texture: Option<Handle<Image>>,
+    /// Scale applied to the noise lookup UV, before the `uv_distortion_frequency` multiply.
+    /// Higher values result in a finer distortion pattern.
+    pub noise_uv_scale: Vec2,
+    /// Rotation in radians applied to the noise lookup UV, before the `uv_distortion_frequency` multiply.
+    /// Rotating the pattern breaks up axis-aligned wobble. The noise texture repeats,
+    /// so any rotation is seamless.
+    pub noise_rotation: f32,
+    /// Number of times per second the noise lookup jumps to a new random offset,
+    /// giving the "line boil" of hand-drawn animation where lines are redrawn a few times per second.
+    /// The jumps only depend on the elapsed time, so they're independent of the frame rate.
+    ///
+    /// Zero disables it and keeps the static pattern.
+    pub boil_fps: f32,
+
+    /// Number of noise octaves layered for the uv distortion, for a more organic hand-drawn wobble.
+    /// One octave is the plain noise lookup, octaves beyond this count aren't sampled.
+    ///
+    /// Range: [1, 4]
+    pub distortion_octaves: u32,
+    /// Frequency multiplier between successive noise octaves.
+    pub distortion_lacunarity: f32,
+    /// Amplitude multiplier between successive noise octaves.
+    pub distortion_gain: f32,
+    /// Bias added to the mip level of the noise lookup, which is chosen from the distortion frequency and the
+    /// viewport size. Positive values blur the noise further, negative values sharpen it at the cost of aliasing.
+    pub noise_lod_bias: f32,
+
+    /// Number of times each contour is drawn, like the overlapping strokes of a sketch.
+    /// The extra strokes are offset by the noise texture, so they're stable unless `boil_fps` is set.
+    ///
+    /// One stroke compiles the extra strokes out of the shader.
+    ///
+    /// Range: [1, 3]
+    pub strokes: u32,
+    /// Maximum offset of the extra `strokes` from the contour, in pixels.
+    pub stroke_scatter: f32,
+    /// Breaks the edges into short strokes along them, like pen-and-ink hatching. The strokes follow the direction
+    /// of the gradients of the depth, normal and color sources, and the noise texture jitters them, so they look
+    /// hand-drawn, following the `uv_distortion_frequency` and `boil_fps`.
+    ///
+    /// `None` compiles the hatching out of the shader, and draws continuous lines.
+    pub hatching: Option<HatchingSettings>,
+
+    /// Whether to correct the uv distortion by the viewport aspect ratio.
+    /// If `true`, the noise pattern and the distortion offsets are measured in physical pixels, relative to
+    /// [`NOISE_REFERENCE_HEIGHT`], so the wobble keeps its shape on any aspect ratio, e.g. 16:9 or 21:9,
+    /// and its wavelength in pixels when the window is resized.
+    /// If `false`, the distortion is applied in raw UV space, which preserves the legacy output.
+    pub aspect_corrected: bool,
+
+    /// Screen-space rectangles where no edges are drawn, e.g. under the HUD.
+    /// They're in normalized coordinates of the camera viewport, from (0.0, 0.0) at the top left
+    /// to (1.0, 1.0) at the bottom right, so they follow the viewport in split-screen.
+    /// With a [`SubCameraView`], they're relative to the whole image instead, so they span the tiles of a capture.
+    ///
+    /// At most [`MAX_EXCLUSION_RECTS`] rectangles are used. An empty list compiles the exclusion out of the shader.
+    pub exclusion_rects: Vec<Rect>,
+    /// Width in pixels of the fade of the edges inside the borders of the `exclusion_rects`.
+    /// Zero cuts the edges sharply at the borders.
+    pub exclusion_feather: f32,
+    /// Screen-space rectangles with their own thresholds and edge color, e.g. a scanner viewport drawn in green
+    /// within the view of the camera. Where they overlap, the last one containing the pixel is used.
+    ///
+    /// At most [`MAX_REGION_OVERRIDES`] regions are used. An empty list compiles the overrides out of the shader.
+    pub region_overrides: Vec<EdgeRegionOverride>,
+
+    /// A world-space sphere outside of which no edges are drawn.
+    /// The world position of each pixel is reconstructed from its depth, which works for any projection.
+    ///
+    /// `None` compiles the scan out of the shader. The sphere can be animated without recompiling it.
+    pub scan: Option<ScanSettings>,
+
+    /// Draws the edges in progressively, e.g. for an intro where the linework draws itself,
+    /// rather than popping in all at once. Animate [`RevealSettings::progress`] from a system.
+    ///
+    /// `None` compiles the reveal out of the shader.
+    pub reveal: Option<RevealSettings>,
+
+    /// A world-space box outside of which no edges are drawn, e.g. a "glitch zone" placed in the level.
+    /// The world position of each pixel is reconstructed from its depth, which works for any projection.
+    ///
+    /// `None` compiles the volume out of the shader.
+    pub mask_volume: Option<Aabb3d>,
+    /// Width of the fade of the edges outside of the `mask_volume`, in world units.
+    /// The fade follows the euclidean distance to the box, so it doesn't slice geometry with hard planes.
+    pub mask_volume_margin: f32,
+
+    /// View distance, in world units, from which the edges fade out, reaching zero at `fade_end`,
+    /// so the tiny depth and normal variations of distant geometry don't turn into noise.
+    pub fade_start: f32,
+    /// View distance, in world units, beyond which no edges are drawn. The edges of a pixel fade by the
+    /// nearest surface around it, so the silhouettes of near objects against a far background are kept whole.
+    ///
+    /// Zero or infinity disables the fade. It's also disabled on 2D cameras, which have no depth.
+    pub fade_end: f32,
+
+    /// View distance, in world units, below which no edges are drawn, e.g. to keep the first-person viewmodel clean.
+    /// Like the `fade_end`, the edges of a pixel are limited by the nearest surface around it, on perspective
+    /// and orthographic cameras alike.
+    ///
+    /// Zero, with an infinite `depth_range_max`, disables the depth window. It's also disabled on 2D cameras.
+    pub depth_range_min: f32,
+    /// View distance, in world units, beyond which no edges are drawn, e.g. to keep the distant skyline clean.
+    /// Infinity leaves the far end of the depth window open.
+    pub depth_range_max: f32,
+    /// Width of the fade of the edges outside of the depth window, in world units, so its ends don't slice
+    /// through the objects with a hard line.
+    pub depth_range_feather: f32,
+
+    /// Quantizes the scene color into bands before the edges are drawn over it, for a cel-shaded look
+    /// without a second fullscreen pass. On HDR cameras, the color is quantized after a local tonemap
+    /// and mapped back, so the bands are spread over the visible range.
+    ///
+    /// `None` compiles the posterization out of the shader.
+    pub posterize: Option<PosterizeSettings>,
+    /// Desaturates and lightens the scene color in a thin halo beside the edges, but not under them,
+    /// like ink bleeding into paper. The halo is the edge mask dilated by `width_px`, minus the edges themselves,
+    /// and where the halos of close edges overlap the strongest one is kept, so the grading never applies twice.
+    ///
+    /// The edges are drawn into an intermediate mask to be dilated. `None` skips the extra pass.
+    pub edge_adjacent_grading: Option<AdjacentGrading>,
+
+    /// Whether to write the edge opacity into the alpha channel of the render target, e.g. for a custom
+    /// composite downstream to know where the edges are without an extra texture.
+    ///
+    /// Note that the alpha channel is the transparency of the window on windows with a transparent
+    /// [`CompositeAlphaMode`](bevy::window::CompositeAlphaMode), so the scene would be see-through
+    /// everywhere but on the edges.
+    pub write_alpha_mask: bool,
+    /// Whether to keep the alpha of the scene, instead of writing 1.0.
+    /// Ignored when `write_alpha_mask` is set.
+    pub preserve_scene_alpha: bool,
+
+    /// Where the edge detection runs in the render graph, e.g. before or after a film grain pass.
+    /// It must be [`EdgeDetectionPlugin::placement`] or one of the [`EdgeDetectionPlugin::additional_placements`],
+    /// the node isn't added at the others.
+    ///
+    /// `None` runs at [`EdgeDetectionPlugin::placement`]. It can be changed at runtime.
+    pub placement: Option<EdgeDetectionPlacement>,
+
+    /// Edge color, used to draw the detected edges.
+    /// Typically a high-contrast color (e.g., red or black) to visually highlight the edges.
+    ///
+    /// Translucent edges are blended with the scene in linear space on both HDR and LDR cameras,
+    /// so the same color looks the same on both.
+    pub edge_color: Color,
+    /// Color of the depth edges, e.g. the bold silhouettes of a toon look, drawn over the thinner interior lines
+    /// of the normal and color edges in their own colors.
+    ///
+    /// Where several sources detect an edge, the depth edges are drawn over the normal edges, which are drawn over
+    /// the color edges, each blended by its strength and the alpha of its color. The line weights follow the
+    /// thickness of each source, e.g. [`EdgeDetection::depth_thickness`].
+    ///
+    /// `None` draws them with [`EdgeDetection::edge_color`], along with its distance gradient, region overrides
+    /// and [`EdgeColorMode`], which don't apply to the sources with their own color.
+    pub depth_edge_color: Option<Color>,
+    /// Color of the normal edges, the creases. See [`EdgeDetection::depth_edge_color`].
+    pub normal_edge_color: Option<Color>,
+    /// Color of the color edges, e.g. the boundaries of the textures and materials.
+    /// See [`EdgeDetection::depth_edge_color`].
+    pub color_edge_color: Option<Color>,
+    /// Color the edges shade into with the view distance, from [`EdgeDetection::edge_color`] up to
+    /// `color_gradient_start` to this color at `color_gradient_end`, e.g. a bluish gray for an atmospheric
+    /// perspective. The edges of a pixel are shaded by the nearest surface around it, like the distance fade.
+    ///
+    /// `None` draws every edge with [`EdgeDetection::edge_color`]. The gradient is ignored on 2D cameras,
+    /// which have no depth, and with [`EdgeColorMode::FromObject`], except for its alpha.
+    pub edge_color_far: Option<Color>,
+    /// View distance, in world units, from which the edges shade into [`EdgeDetection::edge_color_far`].
+    pub color_gradient_start: f32,
+    /// View distance, in world units, from which the edges are drawn with [`EdgeDetection::edge_color_far`].
+    pub color_gradient_end: f32,
+    /// Multiplier of the linear color of the edges, of every source, e.g. above 1.0 for the edges to glow through
+    /// the bloom of an HDR camera, with [`EdgeDetectionPlacement::BeforeBloom`]. The edge colors are kept
+    /// unclamped in the HDR targets.
+    ///
+    /// On the LDR cameras, the scaled colors are clamped to 1.0.
+    pub edge_emissive_strength: f32,
+    /// How the color of the detected edges is chosen.
+    pub edge_color_mode: EdgeColorMode,
+    /// What the detected edges are used for: drawn over the scene, sharpening it, or written alone.
+    pub output_mode: OutputMode,
+    /// Replaces the output with the raw strength of a source, or the steep angle adjustment, e.g. to tell
+    /// which source draws a stray line while tuning the thresholds. It can be changed at runtime, e.g. from an
+    /// inspector, which respecializes the pipelines.
+    pub debug_view: EdgeDebugView,
+    /// How the edges are combined with the scene. It can be changed at runtime without recompiling the shaders.
+    pub blend_mode: EdgeBlendMode,
+    /// How much of the effect is applied, fading the output from the untouched scene at 0.0
+    /// to the scene with its edges at 1.0, e.g. when entering a "scan mode". The posterization, grading
+    /// and sharpening fade with the edges, and with [`OutputMode::EdgesOnly`] the opacity of the edges does.
+    ///
+    /// At 0.0 the fullscreen pass is skipped, unless the [`EdgeCoverage`] or the [`EdgeDetectionAutoCalibrate`]
+    /// of the view still need the edges, or the edges are written alone with [`OutputMode::EdgesOnly`].
+    ///
+    /// Range: [0.0, 1.0]
+    pub intensity: f32,
+    /// Edge color, used to draw the edges detected based on stencil value changes.
+    /// These edges are drawn on top of the edges of the other sources.
+    pub stencil_edge_color: Color,
+    /// Edge color, used to draw the edges detected along the boundaries of the shadows.
+    /// These edges are drawn on top of the edges of the depth, normal, color and material sources.
+    pub shadow_edge_color: Color,
+
+    /// Whether to enable depth-based edge detection.
+    /// If `true`, edges will be detected based on depth variations.
+    pub enable_depth: bool,
+    /// Whether to enable normal-based edge detection.
+    /// If `true`, edges will be detected based on normal direction variations.
+    pub enable_normal: bool,
+    /// Whether the normal source ignores the detail of normal maps, e.g. the grooves of a brick wall.
+    /// If `true`, the normals compared are the geometric normals reconstructed from the depth of each pixel's
+    /// neighbors, instead of the normals of the normal prepass.
+    ///
+    /// The reconstruction is preferred to blurring the prepass normals: a blur wide enough to hide
+    /// the normal maps would also round off the creases of the geometry, while the depth only ever holds
+    /// the geometry. Each normal sample becomes a handful of depth samples, and the reconstructed normals
+    /// are flat shaded, so smooth meshes with a coarse tessellation may show their facets at low `normal_threshold`s.
+    ///
+    /// The depth and color sources are unaffected.
+    pub geometric_normals_only: bool,
+    /// Whether to enable color-based edge detection.
+    /// If `true`, edges will be detected based on color variations.
+    pub enable_color: bool,
+    /// Whether to enable stencil-based edge detection.
+    /// If `true`, edges will be detected wherever the stencil value changes between neighbors.
+    ///
+    /// This requires the depth texture of the [`DepthSource`] to have a stencil aspect, otherwise the stencil
+    /// source is compiled out and a warning is logged once. Note that both the depth prepass and the main pass
+    /// depth textures of bevy's core 3d pipeline are [`TextureFormat::Depth32Float`] textures without a stencil
+    /// aspect, so this needs a render graph drawing into a custom depth texture with a stencil aspect,
+    /// e.g. a [`TextureFormat::Depth24PlusStencil8`] one, with mesh pipelines specialized for it.
+    pub enable_stencil: bool,
+    /// Whether to enable material-based edge detection.
+    /// If `true`, edges will be detected where the `material_attributes` of the deferred G-buffer change sharply,
+    /// e.g. along metal trims on a flat wall where the depth and normals are continuous.
+    ///
+    /// This requires a [`DeferredPrepass`](bevy::core_pipeline::prepass::DeferredPrepass) on the camera,
+    /// otherwise the material source is compiled out and a warning is logged once.
+    pub enable_material: bool,
+    /// The attributes of the G-buffer compared by `enable_material`.
+    pub material_attributes: MaterialEdgeAttributes,
+    /// A screen-space shadow mask, to draw lines along the boundaries of the cast shadows.
+    /// Its red channel holds the visibility of the light at each pixel of the view, from 0.0 in shadow to 1.0 lit,
+    /// and it's sampled at the uv of the view, so it's usually rendered at the size of the view by a second camera
+    /// with the same transform and projection, see the `shadow_edges` example.
+    ///
+    /// The shadow maps of bevy's lights are in the space of the lights and split into cascades, so they aren't
+    /// bound directly: the mask also lets the shadows of any other technique be outlined.
+    ///
+    /// `None` compiles the shadow source out of the shader. The mask reads as fully lit until it's loaded.
+    /// It isn't serialized, the presets keep the texture of the camera they're applied to.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub shadow_mask: Option<Handle<Image>>,
+    /// A screen-space mask multiplying the edges, e.g. for a scanner sweep: they're drawn where its red channel
+    /// is 1.0, and not where it's 0.0. It spans the viewport of the camera, or the whole frame of a sub view,
+    /// whatever its resolution, and the changes to the image are picked up every frame.
+    ///
+    /// `None` compiles the mask out of the shader. The mask reads as white until it's loaded.
+    /// It isn't serialized, the presets keep the texture of the camera they're applied to.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub screen_mask: Option<Handle<Image>>,
+}
+
+/// Reflects the [`FilterMode`] of [`EdgeDetection::color_filter`], which doesn't implement `Reflect` itself.
+#[reflect_remote(FilterMode)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[reflect(Debug, PartialEq)]
+enum FilterModeReflect {
+    Nearest,
+    Linear,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(remote = "FilterMode")]
+enum FilterModeSerde {
+    Nearest,
+    Linear,
+}
+
+impl EdgeDetection {
+    /// The scale of the resolution the edges are actually detected at, see [`EdgeDetection::resolution_scale`].
+    /// `mask_output` is whether the camera has an [`EdgeDetectionMaskOutput`].
+    pub fn detection_scale(&self, mask_output: bool) -> f32 {
+        if self.checkerboard || self.half_rate || mask_output {
+            return 1.0;
+        }
+
+        self.resolution_scale.clamp(MIN_RESOLUTION_SCALE, 1.0)
+    }
+}
+
+impl EdgeDetection {
+    /// The default settings, as a constant the presets start from.
+    const DEFAULT: Self = Self {
+        depth_threshold: 1.0,
+        normalized_depth_threshold: false,
+        normal_threshold: 0.8,
+        normal_threshold_concave: None,
+        normal_threshold_convex: None,
+        color_threshold: 0.1,
+        material_threshold: 0.2,
+        shadow_threshold: 0.5,
+        depth_threshold_softness: 0.0,
+        normal_threshold_softness: 0.0,
+        color_threshold_softness: 0.0,
+
+        depth_thickness: 1.0,
+        normal_thickness: 1.0,
+        color_thickness: 1.0,
+        stencil_thickness: 1.0,
+        material_thickness: 1.0,
+        shadow_thickness: 1.0,
+        kernel_radius: 1,
+        units: EdgeUnits::Pixels,
+
+        fov_compensation: false,
+        // The fov of the default `PerspectiveProjection`.
+        reference_fov: FRAC_PI_4,
+
+        steep_angle_threshold: 0.00,
+        steep_angle_multiplier: 0.36,
+        one_sided_depth_edges: false,
+        background_edges: BackgroundEdgeMode::Silhouette,
+        slope_thickness_boost: 0.0,
+        thickness_attenuation: 0.0,
+        thickness_reference_distance: 10.0,
+        corner_emphasis: 0.0,
+        ao_influence: 0.0,
+
+        operator: EdgeDetectionOperator::Sobel,
+
+        depth_source: DepthSource::Prepass,
+        normal_source: NormalSource::Prepass,
+
+        color_filter: FilterMode::Linear,
+        color_space: ColorEdgeSpace::LinearRgb,
+
+        all_samples: true,
+
+        motion_emphasis: 0.0,
+        motion_smear_strength: 0.0,
+
+        corner_rounding: 0.0,
+        dilation: 0,
+        edge_aa: false,
+
+        checkerboard: false,
+        half_rate: false,
+        temporal_blend: 0.0,
+        reset_history: false,
+        resolution_scale: 1.0,
+
+        thick_outline: None,
+
+        uv_distortion_frequency: Vec2::splat(1.0),
+        uv_distortion_strength: Vec2::splat(0.004),
+        uv_distortion_speed: Vec2::ZERO,
+        uv_distortion_fps: None,
+
+        noise_texture: None,
+        noise_uv_scale: Vec2::ONE,
+        noise_rotation: 0.0,
+        boil_fps: 0.0,
+
+        distortion_octaves: 1,
+        distortion_lacunarity: 2.0,
+        distortion_gain: 0.5,
+        noise_lod_bias: 0.0,
+
+        strokes: 1,
+        stroke_scatter: 2.0,
+        hatching: None,
+
+        aspect_corrected: true,
+
+        exclusion_rects: Vec::new(),
+        exclusion_feather: 0.0,
+        region_overrides: Vec::new(),
+
+        scan: None,
+
+        reveal: None,
+
+        mask_volume: None,
+        mask_volume_margin: 0.5,
+
+        fade_start: 0.0,
+        fade_end: 0.0,
+
+        depth_range_min: 0.0,
+        depth_range_max: f32::INFINITY,
+        depth_range_feather: 0.5,
+
+        posterize: None,
+        edge_adjacent_grading: None,
+
+        write_alpha_mask: false,
+        preserve_scene_alpha: false,
+
+        placement: None,
+
+        edge_color: Color::BLACK,
+        depth_edge_color: None,
+        normal_edge_color: None,
+        color_edge_color: None,
+        edge_color_far: None,
+        color_gradient_start: 0.0,
+        color_gradient_end: 100.0,
+        edge_emissive_strength: 1.0,
+        edge_color_mode: EdgeColorMode::Uniform,
+        output_mode: OutputMode::Edges,
+        debug_view: EdgeDebugView::None,
+        blend_mode: EdgeBlendMode::AlphaOver,
+        intensity: 1.0,
+        stencil_edge_color: Color::BLACK,
+        shadow_edge_color: Color::BLACK,
+
+        enable_depth: true,
+        enable_normal: true,
+        geometric_normals_only: false,
+        enable_color: false,
+        enable_stencil: false,
+        enable_material: false,
+        material_attributes: MaterialEdgeAttributes {
+            base_color: true,
+            roughness: true,
+            metallic: true,
+            reflectance: false,
+        },
+        shadow_mask: None,
+        screen_mask: None,
+    };
+
+    /// Bold black outlines over a posterized scene, for a cel-shaded look.
+    pub const TOON: Self = {
+        let mut toon = Self::DEFAULT;
+        toon.depth_thickness = 2.0;
+        toon.normal_thickness = 2.0;
+        toon.edge_aa = true;
+        toon.posterize = Some(PosterizeSettings {
+            levels: 4,
+            preserve_hue: true,
+        });
+        toon
+    };
+
+    /// Thin graphite lines, drawn in a few wobbling strokes broken into hatches, for a pencil sketch.
+    pub const SKETCH: Self = {
+        let mut sketch = Self::DEFAULT;
+        sketch.enable_color = true;
+        sketch.edge_color = Color::srgba(0.15, 0.15, 0.2, 0.85);
+        sketch.uv_distortion_strength = Vec2::splat(0.006);
+        sketch.boil_fps = 8.0;
+        sketch.strokes = 3;
+        sketch.hatching = Some(HatchingSettings {
+            scale: 8.0,
+            coverage: 0.6,
+        });
+        sketch
+    };
+
+    /// White lines over a flat blue background, see [`OutputMode::Blueprint`].
+    pub const BLUEPRINT: Self = {
+        let mut blueprint = Self::DEFAULT;
+        blueprint.enable_color = true;
+        blueprint.edge_color = Color::WHITE;
+        blueprint.output_mode = OutputMode::Blueprint {
+            background: Color::srgb(0.05, 0.2, 0.45),
+        };
+        blueprint
+    };
+}
+
+impl Default for EdgeDetection {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The maximum number of [`EdgeDetection::exclusion_rects`].
+pub const MAX_EXCLUSION_RECTS: usize = 8;
+
+/// The maximum number of [`EdgeDetection::region_overrides`].
+pub const MAX_REGION_OVERRIDES: usize = 4;
+
+/// The largest [`EdgeDetection::kernel_radius`].
+pub const MAX_KERNEL_RADIUS: u32 = 4;
+
+/// The smallest [`EdgeDetection::resolution_scale`], below it the edges are too coarse to be upsampled.
+pub const MIN_RESOLUTION_SCALE: f32 = 0.25;
+
+/// The viewport height the [`EdgeDetection::aspect_corrected`] uv distortion is measured against, in pixels.
+/// The noise lookup and the distortion offsets are in physical pixels, so the wobble at this height matches
+/// a frame-relative one, and keeps its wavelength in pixels at any other height.
+pub const NOISE_REFERENCE_HEIGHT: f32 = 1080.0;
+
+/// Bounds the scale of the thicknesses by the [`EdgeDetection::fov_compensation`], so a narrow zoom doesn't draw huge lines.
+pub const MAX_FOV_THICKNESS_SCALE: f32 = 4.0;
+
+/// Returns the scales of the thicknesses and of the depth threshold compensating the zoom from `reference_fov` to `fov`.
+///
+/// The depth gradient is measured across the thickness, so scaling the thickness with the zoom keeps the gradient
+/// of the surfaces. What the bound of the thickness leaves out is compensated by the threshold, as the gradient
+/// measured across a pixel shrinks with the zoom.
+fn fov_compensation(fov: f32, reference_fov: f32) -> (f32, f32) {
+    let zoom = (reference_fov * 0.5).tan() / (fov * 0.5).tan().max(f32::EPSILON);
+    let thickness_scale = zoom.clamp(MAX_FOV_THICKNESS_SCALE.recip(), MAX_FOV_THICKNESS_SCALE);
+    (thickness_scale, thickness_scale / zoom)
+}
+
+/// Returns the scales of the thicknesses and of the depth threshold converting [`EdgeUnits::ScreenHeightFraction`]
+/// to the pixels of a frame `frame_height` pixels high, seen with the vertical `fov` of a perspective camera.
+///
+/// The depth differences across a fraction of the screen grow with the height of the frustum it spans,
+/// so the depth threshold grows with it from the `reference_fov`.
+fn screen_fraction_scale(frame_height: f32, fov: Option<f32>, reference_fov: f32) -> (f32, f32) {
+    let depth_threshold_scale = fov.map_or(1.0, |fov| {
+        (fov * 0.5).tan() / (reference_fov * 0.5).tan().max(f32::EPSILON)
+    });
+    (frame_height, depth_threshold_scale)
+}
+
+/// Returns the distance between the near and far planes of `projection`,
+/// which scales the [`EdgeDetection::normalized_depth_threshold`].
+pub(crate) fn depth_range(projection: &Projection) -> Option<f32> {
+    match projection {
+        Projection::Perspective(perspective) => Some(perspective.far - perspective.near),
+        Projection::Orthographic(orthographic) => Some(orthographic.far - orthographic.near),
+        Projection::Custom(_) => None,
+    }
+}
+
+#[derive(Component, Clone, Copy, ShaderType, ExtractComponent)]
+pub struct EdgeDetectionUniform {
+    pub depth_threshold: f32,
+    // of the concave creases
+    pub normal_threshold: f32,
+    pub color_threshold: f32,
+    pub material_threshold: f32,
+    pub shadow_threshold: f32,
+    pub normal_threshold_convex: f32,
+    // 0.0 for the hard edges
+    pub depth_threshold_softness: f32,
+    pub normal_threshold_softness: f32,
+    pub color_threshold_softness: f32,
+
+    pub depth_thickness: f32,
+    pub normal_thickness: f32,
+    pub color_thickness: f32,
+    pub stencil_thickness: f32,
+    pub material_thickness: f32,
+    pub shadow_thickness: f32,
+
+    pub steep_angle_threshold: f32,
+    pub steep_angle_multiplier: f32,
+    pub slope_thickness_boost: f32,
+    pub thickness_attenuation: f32,
+    pub thickness_reference_distance: f32,
+    pub corner_emphasis: f32,
+    pub ao_influence: f32,
+
+    pub motion_emphasis: f32,
+    pub motion_smear_strength: f32,
+    pub corner_rounding: f32,
+    pub dilation: f32,
+    pub temporal_blend: f32,
+    pub thick_outline_radius: f32,
+    pub boil_fps: f32,
+    pub time: f32,
+    pub distortion_octaves: u32,
+    pub distortion_lacunarity: f32,
+    pub distortion_gain: f32,
+    pub strokes: u32,
+    pub stroke_scatter: f32,
+    pub noise_lod_bias: f32,
+    /// The [`NOISE_REFERENCE_HEIGHT`] the pixels of the aspect corrected uv distortion are divided by.
+    pub noise_reference_height: f32,
+    // x: scale, in pixels; y: coverage
+    pub hatching: Vec2,
+
+    pub uv_distortion: Vec4,
+    /// The offset of the noise lookup uv, scrolling at [`EdgeDetection::uv_distortion_speed`].
+    pub noise_scroll: Vec2,
+    /// The TAA jitter of the prepass textures in pixels, undone when sampling them, see [`prepare_edge_detection_jitter`].
+    pub jitter: Vec2,
+    /// The columns of the noise uv transform, a `mat2x2` is laid out differently in uniforms on WebGL2.
+    pub noise_uv_transform: Vec4,
+
+    pub edge_color: LinearRgba,
+    pub depth_edge_color: LinearRgba,
+    pub normal_edge_color: LinearRgba,
+    pub color_edge_color: LinearRgba,
+    // bits 0, 1 and 2: whether the depth, normal and color edges are drawn with their own color
+    pub channel_edge_colors: u32,
+    pub edge_color_far: LinearRgba,
+    // x: start; y: end, in view distances; z: 1.0 if the edge color shades into `edge_color_far`
+    pub edge_color_gradient: Vec4,
+    pub stencil_edge_color: LinearRgba,
+    pub shadow_edge_color: LinearRgba,
+    pub thick_outline_color: LinearRgba,
+    pub blueprint_background: LinearRgba,
+    // x: darken; y: saturate
+    pub object_color_adjustment: Vec2,
+
+    // xy: min; zw: max
+    pub exclusion_rects: [Vec4; MAX_EXCLUSION_RECTS],
+    pub exclusion_rect_count: u32,
+    pub exclusion_feather: f32,
+
+    // xy: min; zw: max
+    pub region_rects: [Vec4; MAX_REGION_OVERRIDES],
+    // x: depth; y: normal; z: color
+    pub region_thresholds: [Vec4; MAX_REGION_OVERRIDES],
+    pub region_edge_colors: [LinearRgba; MAX_REGION_OVERRIDES],
+    pub region_count: u32,
+
+    // xyz: center; w: radius
+    pub scan: Vec4,
+    pub scan_falloff: f32,
+
+    // x: progress; y: softness; zw: origin
+    pub reveal: Vec4,
+    // 0: noise; 1: radial
+    pub reveal_mode: u32,
+
+    pub mask_volume_center: Vec4,
+    pub mask_volume_half_size: Vec4,
+    pub mask_volume_margin: f32,
+
+    pub fade_start: f32,
+    // 0.0 if the distance fade is disabled
+    pub fade_end: f32,
+
+    pub depth_range_min: f32,
+    // 0.0 if the depth window has no far end
+    pub depth_range_max: f32,
+    pub depth_range_feather: f32,
+
+    pub posterize_levels: f32,
+    pub posterize_preserve_hue: u32,
+    // x: width in pixels; y: desaturate; z: lighten
+    pub adjacent_grading: Vec4,
+
+    pub sharpen_amount: f32,
+    pub intensity: f32,
+    pub edge_emissive_strength: f32,
+    pub blend_mode: u32,
+    pub checkerboard_phase: u32,
+    pub calibrate: u32,
+    pub reproject: u32,
+
+    // x: base color; y: roughness; z: metallic; w: reflectance, 1.0 if compared
+    pub material_attributes: Vec4,
+
+    // x: threshold; y: thickness
+    pub edge_sources: [Vec4; MAX_EDGE_SOURCES],
+    pub edge_source_colors: [LinearRgba; MAX_EDGE_SOURCES],
+
+    /// The colors of the [`EdgeColorPalette`], indexed by the tags of the [`EdgeColorOverride`] meshes.
+    pub edge_color_overrides: [LinearRgba; MAX_EDGE_COLOR_OVERRIDES],
+
+    // xy: offset of the viewport in the whole frame of a sub view; zw: size of the frame, zero without a sub view
+    pub sub_view: Vec4,
+    // xy: origin of the viewport of the camera in the target; zw: its size, in pixels, zero without a camera
+    pub viewport: Vec4,
+}
+
+impl EdgeDetectionUniform {
+    #[allow(clippy::type_complexity, clippy::too_many_arguments)]
+    pub fn extract_edge_detection_settings(
+        mut commands: Commands,
+        mut query: Extract<
+            Query<(
+                RenderEntity,
+                EntityRef,
+                Ref<EdgeDetection>,
+                Option<Ref<Camera>>,
+                Option<Ref<Projection>>,
+            )>,
+        >,
+        edge_sources: Extract<Res<EdgeSources>>,
+        time: Extract<Res<Time>>,
+        frame_count: Extract<Res<FrameCount>>,
+        primary_window: Extract<Option<Single<Entity, With<PrimaryWindow>>>>,
+        windows: Extract<Query<(), With<Window>>>,
+        images: Extract<Res<Assets<Image>>>,
+        manual_texture_views: Extract<Res<ManualTextureViews>>,
+        edge_color_palette: Res<EdgeColorPalette>,
+        mut extracted: Query<&mut EdgeDetectionUniform, Without<AnimatedEdgeDetection>>,
+    ) {
+        for (entity, main_entity, edge_detection, camera, projection) in query.iter_mut() {
+            // Adding the mask output changes the detection scale, removing it clears the render entity.
+            let changed = edge_detection.is_changed()
+                || camera.as_ref().is_some_and(DetectChanges::is_changed)
+                || projection.as_ref().is_some_and(DetectChanges::is_changed)
+                || edge_color_palette.is_changed()
+                || main_entity
+                    .get_ref::<EdgeDetectionMaskOutput>()
+                    .is_some_and(|mask_output| mask_output.is_added());
+            let edge_detection = edge_detection.into_inner();
+            let camera = camera.as_deref();
+            let projection = projection.as_deref();
+
+            let mut entity_commands = commands
+                .get_entity(entity)
+                .expect("Edge Detection entity wasn't synced.");
+
+            // Inactive cameras and empty targets aren't rendered, so their per-view state is dropped
+            // instead of being prepared, and their textures are returned to the texture cache.
+            // The size of a target that went away, e.g. a closed window, isn't updated, so its existence is checked.
+            let rendered = camera.is_none_or(|camera| {
+                let target_exists = match camera
+                    .target
+                    .normalize(primary_window.as_deref().copied())
+                {
+                    Some(NormalizedRenderTarget::Window(window)) => {
+                        windows.contains(window.entity())
+                    }
+                    Some(NormalizedRenderTarget::Image(image)) => images.contains(&image.handle),
+                    // E.g. the view of a layer of a cubemap or 2D array texture, which goes away with the texture.
+                    Some(NormalizedRenderTarget::TextureView(handle)) => {
+                        manual_texture_views.contains_key(&handle)
+                    }
+                    None => false,
+                };
+
+                camera.is_active
+                    && target_exists
+                    && camera
+                        .physical_viewport_size()
+                        .is_some_and(|size| size.x > 0 && size.y > 0)
+            });
+            if !rendered {
+                entity_commands.remove::<(
+                    EdgeDetection,
+                    EdgeDetectionUniform,
+                    ExtractedEdgeSources,
+                    PreparedEdgeDetection,
+                )>();
+                continue;
+            }
+
+            // The settings are only extracted again when they changed, or when the flashes, the glitch or
+            // the edge sources may change them every frame. Removing `EdgeDetection` clears the render entity,
+            // so it's extracted anew when it's added back.
+            let animated =
+                main_entity.contains::<EdgeFlashes>() || main_entity.contains::<EdgeGlitch>();
+            if !changed && !animated && edge_sources.is_empty() {
+                if let Ok(mut uniform) = extracted.get_mut(entity) {
+                    uniform.time = time.elapsed_secs_wrapped();
+                    uniform.noise_scroll = noise_scroll(edge_detection, time.elapsed_secs_f64());
+                    // Zeroed on the frames the temporal history is discarded.
+                    uniform.temporal_blend =
+                        edge_detection.temporal_blend.clamp(0.0, MAX_TEMPORAL_BLEND);
+                    continue;
+                }
+            }
+
+            // The flashes and the glitch change the extracted copy, the settings of the camera are left as is.
+            let flashed;
+            let edge_detection = match main_entity.get::<EdgeFlashes>() {
+                Some(flashes) => {
+                    flashed = flashes.apply(edge_detection);
+                    &flashed
+                }
+                None => edge_detection,
+            };
+            let glitched;
+            let edge_detection = match main_entity.get::<EdgeGlitch>() {
+                Some(glitch) => {
+                    glitched = glitch.apply(edge_detection, frame_count.0);
+                    &glitched
+                }
+                None => edge_detection,
+            };
+
+            let mut uniform = EdgeDetectionUniform {
+                time: time.elapsed_secs_wrapped(),
+                noise_scroll: noise_scroll(edge_detection, time.elapsed_secs_f64()),
+                sub_view: camera
+                    .and_then(|camera| camera.sub_camera_view.zip(camera.physical_viewport_size()))
+                    .map_or(Vec4::ZERO, |(sub_view, viewport_size)| {
+                        sub_view_frame(&sub_view, viewport_size)
+                    }),
+                // The composite pass doesn't bind the view, it leaves the pixels of the other cameras drawing to
+                // the target, e.g. in split-screen, as is with this rect.
+                viewport: camera.and_then(Camera::physical_viewport_rect).map_or(
+                    Vec4::ZERO,
+                    |rect| {
+                        rect.min
+                            .extend(rect.width())
+                            .extend(rect.height())
+                            .as_vec4()
+                    },
+                ),
+                edge_color_overrides: edge_color_palette.uniform(),
+                ..EdgeDetectionUniform::from(edge_detection)
+            };
+            let mut depth_threshold_scale = 1.0;
+            if let (true, Some(Projection::Perspective(perspective))) =
+                (edge_detection.fov_compensation, projection)
+            {
+                let (thickness_scale, fov_depth_threshold_scale) =
+                    fov_compensation(perspective.fov, edge_detection.reference_fov);
+                uniform.depth_thickness *= thickness_scale;
+                uniform.normal_thickness *= thickness_scale;
+                uniform.color_thickness *= thickness_scale;
+                uniform.stencil_thickness *= thickness_scale;
+                uniform.material_thickness *= thickness_scale;
+                uniform.shadow_thickness *= thickness_scale;
+                depth_threshold_scale *= fov_depth_threshold_scale;
+            }
+            // The frame of a sub view, or else the viewport, of the camera.
+            let frame_height = match uniform.sub_view.w > 0.0 {
+                true => uniform.sub_view.w,
+                false => uniform.viewport.w,
+            };
+            if edge_detection.units == EdgeUnits::ScreenHeightFraction && frame_height > 0.0 {
+                // The fov compensation already scaled the thicknesses with the zoom.
+                let fov = match projection {
+                    Some(Projection::Perspective(perspective))
+                        if !edge_detection.fov_compensation =>
+                    {
+                        Some(perspective.fov)
+                    }
+                    _ => None,
+                };
+                let (thickness_scale, fraction_depth_threshold_scale) =
+                    screen_fraction_scale(frame_height, fov, edge_detection.reference_fov);
+                uniform.depth_thickness *= thickness_scale;
+                uniform.normal_thickness *= thickness_scale;
+                uniform.color_thickness *= thickness_scale;
+                uniform.stencil_thickness *= thickness_scale;
+                uniform.material_thickness *= thickness_scale;
+                uniform.shadow_thickness *= thickness_scale;
+                depth_threshold_scale *= fraction_depth_threshold_scale;
+            }
+            if edge_detection.normalized_depth_threshold {
+                depth_threshold_scale *= projection.and_then(depth_range).unwrap_or(1.0);
+            }
+            uniform.depth_threshold *= depth_threshold_scale;
+            uniform.depth_threshold_softness *= depth_threshold_scale;
+            for thresholds in &mut uniform.region_thresholds {
+                thresholds.x *= depth_threshold_scale;
+            }
+            // The edge mask is dilated in its own pixels, fewer than the view's at a lower resolution scale.
+            uniform.dilation *=
+                edge_detection.detection_scale(main_entity.contains::<EdgeDetectionMaskOutput>());
+            // The 2D views have no depth to fade the edges by.
+            if main_entity.contains::<Camera2d>() {
+                uniform.fade_end = 0.0;
+                uniform.depth_range_min = 0.0;
+                uniform.depth_range_max = 0.0;
+                uniform.edge_color_gradient.z = 0.0;
+            }
+
+            let sources = edge_sources.extract(main_entity, &mut uniform);
+
+            entity_commands.insert((edge_detection.clone(), uniform, sources));
+            // The copy is extracted again on the frame the flashes or the glitch are removed.
+            if animated {
+                entity_commands.insert(AnimatedEdgeDetection);
+            } else {
+                entity_commands.remove::<AnimatedEdgeDetection>();
+            }
+        }
+    }
+}
+
+/// Marks the views of the render world whose extracted [`EdgeDetection`] was changed by their [`EdgeFlashes`]
+/// or [`EdgeGlitch`], so their settings are extracted again every frame.
+#[derive(Component)]
+pub struct AnimatedEdgeDetection;
+
+/// Returns the offset of the noise lookup uv after `elapsed` seconds, see [`EdgeDetection::uv_distortion_speed`].
+///
+/// The noise texture repeats, so the offset is wrapped, keeping its precision however long the app runs.
+fn noise_scroll(edge_detection: &EdgeDetection, elapsed: f64) -> Vec2 {
+    let elapsed = match edge_detection.uv_distortion_fps {
+        Some(fps) if fps > 0.0 => (elapsed * fps as f64).floor() / fps as f64,
+        _ => elapsed,
+    };
+    (edge_detection.uv_distortion_speed.as_dvec2() * elapsed)
+        .fract_gl()
+        .as_vec2()
+}
+
+/// Returns the offset of the viewport in the whole frame of a sub view, and the size of the frame, in pixels.
+///
+/// The viewport shows the `size` section of the frame, so the frame is scaled to the pixels of the viewport.
+/// The screen space effects are then computed in the frame, so they line up across the sub views tiling it.
+fn sub_view_frame(sub_view: &SubCameraView, viewport_size: UVec2) -> Vec4 {
+    let scale = viewport_size.as_vec2() / sub_view.size.as_vec2().max(Vec2::ONE);
+    let offset = sub_view.offset * scale;
+    let full_size = sub_view.full_size.as_vec2() * scale;
+    Vec4::new(offset.x, offset.y, full_size.x, full_size.y)
+}
+
+impl From<&EdgeDetection> for EdgeDetectionUniform {
+    fn from(ed: &EdgeDetection) -> Self {
+        let mut exclusion_rects = [Vec4::ZERO; MAX_EXCLUSION_RECTS];
+        for (rect, exclusion_rect) in ed.exclusion_rects.iter().zip(&mut exclusion_rects) {
+            *exclusion_rect = rect.min.extend(rect.max.x).extend(rect.max.y);
+        }
+
+        let mut region_rects = [Vec4::ZERO; MAX_REGION_OVERRIDES];
+        let mut region_thresholds = [Vec4::ZERO; MAX_REGION_OVERRIDES];
+        let mut region_edge_colors = [LinearRgba::NONE; MAX_REGION_OVERRIDES];
+        for (index, region) in ed
+            .region_overrides
+            .iter()
+            .take(MAX_REGION_OVERRIDES)
+            .enumerate()
+        {
+            let settings = &region.settings;
+            region_rects[index] = region
+                .rect
+                .min
+                .extend(region.rect.max.x)
+                .extend(region.rect.max.y);
+            region_thresholds[index] = Vec4::new(
+                settings.depth_threshold,
+                settings.normal_threshold,
+                settings.color_threshold,
+                0.0,
+            );
+            region_edge_colors[index] = settings.edge_color.into();
+        }
+
+        Self {
+            depth_threshold: ed.depth_threshold,
+            normal_threshold: ed.normal_threshold_concave.unwrap_or(ed.normal_threshold),
+            color_threshold: ed.color_threshold,
+            material_threshold: ed.material_threshold,
+            shadow_threshold: ed.shadow_threshold,
+            normal_threshold_convex: ed.normal_threshold_convex.unwrap_or(ed.normal_threshold),
+            depth_threshold_softness: ed.depth_threshold_softness.max(0.0),
+            normal_threshold_softness: ed.normal_threshold_softness.max(0.0),
+            color_threshold_softness: ed.color_threshold_softness.max(0.0),
+
+            depth_thickness: ed.depth_thickness,
+            normal_thickness: ed.normal_thickness,
+            color_thickness: ed.color_thickness,
+            stencil_thickness: ed.stencil_thickness,
+            material_thickness: ed.material_thickness,
+            shadow_thickness: ed.shadow_thickness,
+
+            steep_angle_threshold: ed.steep_angle_threshold,
+            steep_angle_multiplier: ed.steep_angle_multiplier,
+            slope_thickness_boost: ed.slope_thickness_boost,
+            thickness_attenuation: ed.thickness_attenuation.clamp(0.0, 1.0),
+            thickness_reference_distance: ed.thickness_reference_distance.max(0.0),
+            corner_emphasis: ed.corner_emphasis,
+            ao_influence: ed.ao_influence,
+
+            motion_emphasis: ed.motion_emphasis,
+            motion_smear_strength: ed.motion_smear_strength,
+            corner_rounding: ed.corner_rounding,
+            dilation: ed.dilation.min(MAX_DILATION) as f32,
+            temporal_blend: ed.temporal_blend.clamp(0.0, MAX_TEMPORAL_BLEND),
+            thick_outline_radius: ed
+                .thick_outline
+                .map_or(0.0, |thick_outline| thick_outline.radius_px),
+            boil_fps: ed.boil_fps,
+            // Set on extraction.
+            time: 0.0,
+            distortion_octaves: ed.distortion_octaves,
+            distortion_lacunarity: ed.distortion_lacunarity,
+            distortion_gain: ed.distortion_gain,
+            strokes: ed.strokes,
+            stroke_scatter: ed.stroke_scatter,
+            noise_lod_bias: ed.noise_lod_bias,
+            noise_reference_height: NOISE_REFERENCE_HEIGHT,
+            hatching: ed.hatching.map_or(Vec2::ZERO, |hatching| {
+                Vec2::new(hatching.scale.max(1.0), hatching.coverage.clamp(0.0, 1.0))
+            }),
+
+            uv_distortion: Vec4::new(
+                ed.uv_distortion_frequency.x,
+                ed.uv_distortion_frequency.y,
+                ed.uv_distortion_strength.x,
+                ed.uv_distortion_strength.y,
+            ),
+            // Set on extraction.
+            noise_scroll: Vec2::ZERO,
+            jitter: Vec2::ZERO,
+            noise_uv_transform: Vec4::from_array(
+                (Mat2::from_angle(ed.noise_rotation) * Mat2::from_diagonal(ed.noise_uv_scale))
+                    .to_cols_array(),
+            ),
+
+            edge_color: ed.edge_color.into(),
+            depth_edge_color: ed.depth_edge_color.map_or(LinearRgba::NONE, Into::into),
+            normal_edge_color: ed.normal_edge_color.map_or(LinearRgba::NONE, Into::into),
+            color_edge_color: ed.color_edge_color.map_or(LinearRgba::NONE, Into::into),
+            channel_edge_colors: [
+                ed.depth_edge_color,
+                ed.normal_edge_color,
+                ed.color_edge_color,
+            ]
+            .iter()
+            .enumerate()
+            .filter(|(_, color)| color.is_some())
+            .fold(0, |bits, (index, _)| bits | 1 << index),
+            edge_color_far: ed.edge_color_far.map_or(LinearRgba::NONE, Into::into),
+            edge_color_gradient: Vec4::new(
+                ed.color_gradient_start.max(0.0),
+                ed.color_gradient_end.max(ed.color_gradient_start.max(0.0)),
+                if ed.edge_color_far.is_some() {
+                    1.0
+                } else {
+                    0.0
+                },
+                0.0,
+            ),
+            stencil_edge_color: ed.stencil_edge_color.into(),
+            shadow_edge_color: ed.shadow_edge_color.into(),
+            thick_outline_color: ed
+                .thick_outline
+                .map_or(LinearRgba::NONE, |thick_outline| thick_outline.color.into()),
+            blueprint_background: match ed.output_mode {
+                OutputMode::Blueprint { background } => background.into(),
+                _ => LinearRgba::NONE,
+            },
+            object_color_adjustment: match ed.edge_color_mode {
+                EdgeColorMode::Uniform => Vec2::ZERO,
+                EdgeColorMode::FromObject { darken, saturate } => Vec2::new(darken, saturate),
+            },
+
+            exclusion_rects,
+            exclusion_rect_count: ed.exclusion_rects.len().min(MAX_EXCLUSION_RECTS) as u32,
+            exclusion_feather: ed.exclusion_feather,
+
+            region_rects,
+            region_thresholds,
+            region_edge_colors,
+            region_count: ed.region_overrides.len().min(MAX_REGION_OVERRIDES) as u32,
+
+            scan: ed
+                .scan
+                .map_or(Vec4::ZERO, |scan| scan.center.extend(scan.radius)),
+            scan_falloff: ed.scan.map_or(0.0, |scan| scan.edge_falloff),
+
+            reveal: ed.reveal.map_or(Vec4::ZERO, |reveal| {
+                let origin = match reveal.mode {
+                    RevealMode::Noise => Vec2::ZERO,
+                    RevealMode::Radial { origin } => origin,
+                };
+                Vec4::new(
+                    reveal.progress,
+                    reveal.softness.max(0.0),
+                    origin.x,
+                    origin.y,
+                )
+            }),
+            reveal_mode: ed.reveal.map_or(0, |reveal| {
+                matches!(reveal.mode, RevealMode::Radial { .. }).into()
+            }),
+
+            mask_volume_center: ed
+                .mask_volume
+                .map_or(Vec4::ZERO, |volume| volume.center().extend(0.0)),
+            mask_volume_half_size: ed
+                .mask_volume
+                .map_or(Vec4::ZERO, |volume| volume.half_size().extend(0.0)),
+            mask_volume_margin: ed.mask_volume_margin,
+
+            fade_start: ed.fade_start.clamp(0.0, ed.fade_end.max(0.0)),
+            fade_end: match ed.fade_end.is_finite() {
+                true => ed.fade_end.max(0.0),
+                false => 0.0,
+            },
+
+            depth_range_min: ed.depth_range_min.max(0.0),
+            depth_range_max: match ed.depth_range_max.is_finite() {
+                true => ed
+                    .depth_range_max
+                    .max(ed.depth_range_min)
+                    .max(f32::MIN_POSITIVE),
+                false => 0.0,
+            },
+            depth_range_feather: ed.depth_range_feather.max(0.0),
+
+            posterize_levels: ed
+                .posterize
+                .map_or(0.0, |posterize| posterize.levels.clamp(2, 256) as f32),
+            posterize_preserve_hue: ed
+                .posterize
+                .is_some_and(|posterize| posterize.preserve_hue)
+                .into(),
+            adjacent_grading: ed.edge_adjacent_grading.map_or(Vec4::ZERO, |grading| {
+                Vec4::new(
+                    grading.width_px.clamp(0.0, 8.0),
+                    grading.desaturate.clamp(0.0, 1.0),
+                    grading.lighten.clamp(0.0, 1.0),
+                    0.0,
+                )
+            }),
+
+            sharpen_amount: match ed.output_mode {
+                OutputMode::Sharpen { amount } => amount.max(0.0),
+                _ => 0.0,
+            },
+            intensity: ed.intensity.clamp(0.0, 1.0),
+            edge_emissive_strength: ed.edge_emissive_strength.max(0.0),
+            blend_mode: ed.blend_mode.index(),
+            checkerboard_phase: CHECKERBOARD_ALL_PIXELS,
+            calibrate: 0,
+            reproject: 0,
+
+            material_attributes: ed.material_attributes.weights(),
+
+            edge_sources: [Vec4::ZERO; MAX_EDGE_SOURCES],
+            edge_source_colors: [LinearRgba::NONE; MAX_EDGE_SOURCES],
+
+            // Set on extraction.
+            edge_color_overrides: [LinearRgba::NONE; MAX_EDGE_COLOR_OVERRIDES],
+
+            sub_view: Vec4::ZERO,
+            viewport: Vec4::ZERO,
+        }
+    }
+}
+
+/// The edge detection node at [`EdgeDetectionPlugin::placement`].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+pub struct EdgeDetectionLabel;
+
+/// The edge detection node at one of the [`EdgeDetectionPlugin::additional_placements`].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+pub struct EdgeDetectionPlacementLabel(pub EdgeDetectionPlacement);
+
+// The post process node used for the render graph
+//
+// The passes draw over the whole view target rather than being scissored to the camera viewport: they swap the
+// source and destination textures of the post processing, so the pixels of the other cameras drawing to the target
+// must be written too. The shaders copy them as is, and clamp their samples to the viewport.
+#[derive(Default)]
+pub struct EdgeDetectionNode {
+    /// The placement of the node, it only processes the views at this placement.
+    pub placement: EdgeDetectionPlacement,
+}
+
+impl ViewNode for EdgeDetectionNode {
+    type ViewQuery = (
+        &'static EdgeDetection,
+        &'static ViewTarget,
+        (
+            Option<&'static ViewPrepassTextures>,
+            Option<&'static EdgeDetection2dTextures>,
+            Option<&'static ViewEdgeMeshTagTextures>,
+        ),
+        Option<&'static ViewDepthTexture>,
+        &'static ViewUniformOffset,
+        &'static DynamicUniformIndex<EdgeDetectionUniform>,
+        &'static EdgeDetectionPipelineId,
+        Option<&'static EdgeCoverageBuffer>,
+        Option<&'static EdgeCalibrationBuffer>,
+        Option<&'static ExtractedEdgeSources>,
+        (
+            Option<&'static EdgeDetectionMaskTexture>,
+            Option<&'static ViewEdgeMaskTexture>,
+        ),
+        (
+            Option<&'static EdgeDetectionHalfRateHistory>,
+            Option<&'static EdgeDetectionTemporalHistory>,
+        ),
+        Option<&'static EdgeDetectionJumpFloodTextures>,
+        (
+            Option<&'static EdgeDetectionAaTexture>,
+            Option<&'static EdgeDetectionDilationTexture>,
+        ),
+        Option<&'static ScreenSpaceAmbientOcclusionResources>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (
+            edge_detection,
+            view_target,
+            (prepass_textures, textures_2d, mesh_tag_textures),
+            view_depth_texture,
+            view_uniform_index,
+            ed_uniform_index,
+            edge_detection_pipeline_id,
+            edge_coverage_buffer,
+            calibration_buffer,
+            edge_sources,
+            (mask_texture, mask_output_texture),
+            (half_rate_history, temporal_history),
+            jump_flood_textures,
+            (aa_texture, dilation_texture),
+            ssao_resources,
+        ): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        // The view is processed by the node at its placement.
+        if edge_detection_pipeline_id.placement != self.placement {
+            return Ok(());
+        }
+
+        let target_size = view_target.main_texture().size();
+        if target_size.width == 0 || target_size.height == 0 {
+            return Ok(());
+        }
+
+        // The effect is faded out, the scene is left as is unless the edges are still counted, measured, output
+        // or debugged.
+        // Faded out edges alone are still written, transparent, instead of leaving the scene.
+        let key = edge_detection_pipeline_id.key;
+        if edge_detection.intensity <= 0.0
+            && !key.edges_only
+            && key.debug_view == EdgeDebugView::None
+            && !key.edge_coverage
+            && !key.auto_calibrate
+            && !key.mask_output
+        {
+            return Ok(());
+        }
+
+        let Some(pass) = EdgeDetectionPass::get(world, edge_detection_pipeline_id) else {
+            return Ok(());
+        };
+
+        // The 2D views have no prepass, the textures standing in for it are bound instead.
+        let (depth, normal_view) = match textures_2d {
+            Some(textures_2d) => (
+                Some((&textures_2d.depth_texture, &textures_2d.depth)),
+                Some(&textures_2d.normal),
+            ),
+            None => {
+                let depth = edge_detection
+                    .depth_source
+                    .texture(prepass_textures, view_depth_texture);
+
+                // Without a normal prepass, the normals are reconstructed from the depth.
+                let normal_view = prepass_textures
+                    .and_then(|prepass_textures| prepass_textures.normal.as_ref())
+                    .map(|normal_texture| &normal_texture.texture.default_view);
+
+                (depth, normal_view)
+            }
+        };
+
+        // Without depth texture sampling, the depth isn't bound.
+        if key.depth_texture && depth.is_none() {
+            return Ok(());
+        }
+
+        // The stencil aspect needs its own view, the default view only covers the depth aspect.
+        let stencil_view = depth
+            .filter(|_| key.enable_stencil)
+            .map(|(depth_texture, _)| {
+                depth_texture.create_view(&TextureViewDescriptor {
+                    label: Some("edge_detection_stencil_view"),
+                    aspect: TextureAspect::StencilOnly,
+                    ..default()
+                })
+            });
+
+        // The SSAO textures may not be prepared yet, the white fallback leaves the edges unchanged.
+        let ambient_occlusion_view = match ssao_resources {
+            Some(ssao_resources) => {
+                &ssao_resources
+                    .screen_space_ambient_occlusion_texture
+                    .default_view
+            }
+            None => &world.resource::<FallbackImage>().d2.texture_view,
+        };
+
+        let edge_coverage_buffer = edge_coverage_buffer.and_then(|buffer| {
+            world
+                .resource::<RenderAssets<GpuShaderStorageBuffer>>()
+                .get(&buffer.0)
+        });
+        let calibration_buffer = calibration_buffer.and_then(|buffer| {
+            world
+                .resource::<RenderAssets<GpuShaderStorageBuffer>>()
+                .get(&buffer.buffer)
+        });
+
+        // The textures of the edge sources may not be loaded yet, the white fallback has no gradient.
+        let edge_source_views = edge_sources.map_or([None; MAX_EDGE_SOURCES], |edge_sources| {
+            edge_sources.0.map(|image| {
+                image.map(
+                    |image| match world.resource::<RenderAssets<GpuImage>>().get(image) {
+                        Some(gpu_image) => &gpu_image.texture_view,
+                        None => &world.resource::<FallbackImage>().d2.texture_view,
+                    },
+                )
+            })
+        });
+
+        // The shadow mask may not be loaded yet, the white fallback is fully lit.
+        let shadow_mask_view = edge_detection.shadow_mask.as_ref().map(|image| {
+            match world.resource::<RenderAssets<GpuImage>>().get(image) {
+                Some(gpu_image) => &gpu_image.texture_view,
+                None => &world.resource::<FallbackImage>().d2.texture_view,
+            }
+        });
+
+        // The screen mask may not be loaded yet, the white fallback keeps all the edges.
+        let screen_mask_view = edge_detection.screen_mask.as_ref().map(|image| {
+            match world.resource::<RenderAssets<GpuImage>>().get(image) {
+                Some(gpu_image) => &gpu_image.texture_view,
+                None => &world.resource::<FallbackImage>().d2.texture_view,
+            }
+        });
+
+        // The noise texture may not be loaded yet, the embedded noise is used until it is.
+        let noise_view = edge_detection.noise_texture.as_ref().and_then(|image| {
+            world
+                .resource::<RenderAssets<GpuImage>>()
+                .get(image)
+                .map(|gpu_image| &gpu_image.texture_view)
+        });
+
+        // The bind_group gets created each frame.
+        //
+        // Normally, you would create a bind_group in the Queue set,
+        // but this doesn't work with the post_process_write().
+        // The reason it doesn't work is because each post_process_write will alternate the source/destination.
+        // The only way to have the correct source/destination for the bind_group
+        // is to make sure you get it during the node execution.
+        //
+        // The main texture is only flipped by the pass once the inputs are checked, the scene is kept as is otherwise.
+        pass.record(
+            render_context,
+            view_target,
+            &EdgeDetectionInputs {
+                depth: depth.map(|(_, depth_view)| depth_view),
+                stencil: stencil_view.as_ref(),
+                normal: normal_view,
+                motion_vectors: prepass_textures.and_then(ViewPrepassTextures::motion_vectors_view),
+                deferred: prepass_textures.and_then(ViewPrepassTextures::deferred_view),
+                noise: noise_view,
+                shadow_mask: shadow_mask_view,
+                screen_mask: screen_mask_view,
+                mesh_tags: mesh_tag_textures,
+                ambient_occlusion: Some(ambient_occlusion_view),
+                edge_coverage: edge_coverage_buffer.map(|buffer| &buffer.buffer),
+                calibration: calibration_buffer.map(|buffer| &buffer.buffer),
+                edge_sources: edge_source_views,
+                mask: mask_texture.map(|mask_texture| &mask_texture.0.default_view),
+                mask_output: mask_output_texture
+                    .map(|mask_output_texture| &mask_output_texture.0.default_view),
+                aa: aa_texture,
+                dilation: dilation_texture,
+                jump_flood: jump_flood_textures,
+                thick_outline: edge_detection.thick_outline,
+                reproject: half_rate_history.is_some_and(|history| history.reproject),
+                temporal: temporal_history,
+                color_filter: edge_detection.color_filter,
+                view_offset: view_uniform_index.offset,
+                uniform_offset: ed_uniform_index.index(),
+            },
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        any::TypeId,
+        ops::Range,
+        slice,
+        sync::{Arc, Mutex, MutexGuard, PoisonError},
+        time::Duration,
+    };
+
+    use bevy::{
+        app::{Plugins, PluginsState, ScheduleRunnerPlugin},
+        asset::{LoadState, RenderAssetUsages},
+        color::EuclideanDistance,
+        core_pipeline::{
+            core_3d::CORE_3D_DEPTH_FORMAT,
+            experimental::taa::{TemporalAntiAliasPlugin, TemporalAntiAliasing},
+            tonemapping::{DebandDither, Tonemapping},
+        },
+        diagnostic::{DiagnosticPath, DiagnosticsStore},
+        image::TextureFormatPixelInfo,
+        pbr::{DefaultOpaqueRendererMethod, PbrPlugin},
+        reflect::serde::TypedReflectDeserializer,
+        render::{
+            batching::gpu_preprocessing::{GpuPreprocessingMode, GpuPreprocessingSupport},
+            camera::{
+                ManualTextureView, ManualTextureViewHandle, RenderTarget, ScalingMode,
+                TemporalJitter, Viewport,
+            },
+            diagnostic::RenderDiagnosticsPlugin,
+            gpu_readback::{Readback, ReadbackComplete},
+            pipelined_rendering::PipelinedRenderingPlugin,
+            render_graph::EmptyNode,
+            render_resource::{CachedPipelineState, PipelineCacheError},
+            renderer::{RenderAdapterInfo, RenderQueue},
+            settings::{Backends, WgpuFeatures, WgpuLimits, WgpuSettings},
+            RenderPlugin,
+        },
+        tasks::tick_global_task_pools_on_main_thread,
+        time::TimeUpdateStrategy,
+        window::{ExitCondition, WindowRef},
+        winit::WinitPlugin,
+    };
+    use serde::de::DeserializeSeed;
+
+    use super::*;
+
+    /// Loads the noise texture like the plugin does, and returns it once it's loaded.
+    fn load_noise_texture() -> Image {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            ImagePlugin::default(),
+        ))
+        .add_systems(Update, generate_noise_texture_mips);
+        embedded_asset!(app, "perlin_noise.png");
+        app.finish();
+
+        let noise_texture: Handle<Image> = app
+            .world()
+            .resource::<AssetServer>()
+            .load_with_settings(NOISE_TEXTURE_PATH, noise_texture_settings);
+
+        for _ in 0..1000 {
+            app.update();
+            match app
+                .world()
+                .resource::<AssetServer>()
+                .load_state(&noise_texture)
+            {
+                LoadState::Loaded => break,
+                LoadState::Failed(error) => panic!("failed to load the noise texture: {error}"),
+                _ => std::thread::sleep(Duration::from_millis(1)),
+            }
+        }
+
+        // Let the mips be generated from the load event.
+        app.update();
+
+        app.world()
+            .resource::<Assets<Image>>()
+            .get(&noise_texture)
+            .expect("noise texture not loaded")
+            .clone()
+    }
+
+    #[test]
+    fn noise_texture_is_linear() {
+        let format = load_noise_texture().texture_descriptor.format;
+        assert!(!format.is_srgb(), "noise texture loaded as {format:?}");
+        assert_eq!(format, TextureFormat::Rgba8Unorm);
+    }
+
+    #[test]
+    fn noise_texture_has_mips() {
+        let image = load_noise_texture();
+        let descriptor = &image.texture_descriptor;
+        let max_size = descriptor.size.width.max(descriptor.size.height);
+        assert_eq!(descriptor.mip_level_count, max_size.ilog2() + 1);
+
+        let data_size: u32 = (0..descriptor.mip_level_count)
+            .map(|level| {
+                let size = descriptor.size.mip_level_size(level, TextureDimension::D2);
+                size.width * size.height * 4
+            })
+            .sum();
+        assert_eq!(image.data.map(|data| data.len()), Some(data_size as usize));
+    }
+
+    /// The distortion scrolls continuously, or by steps at `uv_distortion_fps`, wrapped in the repeating noise.
+    #[test]
+    fn noise_scrolls_at_distortion_fps() {
+        let mut edge_detection = EdgeDetection {
+            uv_distortion_speed: Vec2::new(0.25, -0.5),
+            ..default()
+        };
+        assert_eq!(noise_scroll(&edge_detection, 0.0), Vec2::ZERO);
+        assert!(noise_scroll(&edge_detection, 1.1).abs_diff_eq(Vec2::new(0.275, 0.45), 1e-5));
+        // Long running apps keep the offset in the texture.
+        assert!(noise_scroll(&edge_detection, 1e6 + 1.0).abs_diff_eq(Vec2::new(0.25, 0.5), 1e-5));
+
+        edge_detection.uv_distortion_fps = Some(8.0);
+        let drawing = noise_scroll(&edge_detection, 1.0);
+        assert_eq!(noise_scroll(&edge_detection, 1.1), drawing);
+        assert_ne!(noise_scroll(&edge_detection, 1.13), drawing);
+    }
+
+    #[test]
+    fn fov_compensation_keeps_line_weight() {
+        let reference_fov = 60f32.to_radians();
+        assert_eq!(fov_compensation(reference_fov, reference_fov), (1.0, 1.0));
+
+        // Zooming in grows the objects and the thicknesses alike, the depth threshold is kept.
+        let fov = 2.0 * ((reference_fov * 0.5).tan() / 2.0).atan();
+        let (thickness_scale, depth_threshold_scale) = fov_compensation(fov, reference_fov);
+        assert!((thickness_scale - 2.0).abs() < 1e-5);
+        assert!((depth_threshold_scale - 1.0).abs() < 1e-5);
+
+        // Past the bound of the thicknesses, the depth threshold takes over.
+        let fov = 2.0 * ((reference_fov * 0.5).tan() / 8.0).atan();
+        let (thickness_scale, depth_threshold_scale) = fov_compensation(fov, reference_fov);
+        assert_eq!(thickness_scale, MAX_FOV_THICKNESS_SCALE);
+        assert!((depth_threshold_scale - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn screen_fraction_keeps_line_weight() {
+        let reference_fov = 60f32.to_radians();
+
+        // Three times the pixels draw three times thicker lines, with the same depth threshold.
+        let (thickness_720p, _) = screen_fraction_scale(720.0, None, reference_fov);
+        let (thickness_2160p, depth_threshold_scale) =
+            screen_fraction_scale(2160.0, None, reference_fov);
+        assert_eq!(thickness_2160p, thickness_720p * 3.0);
+        assert_eq!(depth_threshold_scale, 1.0);
+
+        // Doubling the height of the frustum doubles the depth differences across the same fraction of the screen.
+        let fov = 2.0 * ((reference_fov * 0.5).tan() * 2.0).atan();
+        let (_, depth_threshold_scale) = screen_fraction_scale(720.0, Some(fov), reference_fov);
+        assert!((depth_threshold_scale - 2.0).abs() < 1e-5);
+        let (_, depth_threshold_scale) =
+            screen_fraction_scale(720.0, Some(reference_fov), reference_fov);
+        assert!((depth_threshold_scale - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn edge_flashes_stack_and_restore() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )));
+        flash::build(&mut app);
+
+        let edge_detection = EdgeDetection::default();
+        let camera = app.world_mut().spawn(edge_detection.clone()).id();
+        let edge_color = |app: &App| {
+            let flashes = app.world().get::<EdgeFlashes>(camera);
+            flashes.map(|flashes| LinearRgba::from(flashes.apply(&edge_detection).edge_color))
+        };
+
+        let red = EdgeFlash {
+            curve: EaseFunction::Linear,
+            ..EdgeFlash::new(Color::linear_rgb(1.0, 0.0, 0.0), Duration::from_millis(400))
+        };
+        let blue = EdgeFlash {
+            color: Color::linear_rgb(0.0, 0.0, 1.0),
+            ..red
+        };
+
+        // The first update has no delta.
+        app.update();
+        app.world_mut()
+            .send_event(EdgeFlashEvent { camera, flash: red });
+        app.update();
+        assert_eq!(edge_color(&app), Some(LinearRgba::RED));
+
+        app.update();
+        app.world_mut().send_event(EdgeFlashEvent {
+            camera,
+            flash: blue,
+        });
+        app.update();
+        // The red flash keeps fading under the blue one.
+        assert_eq!(edge_color(&app), Some(LinearRgba::BLUE));
+        app.update();
+        let color = edge_color(&app).unwrap();
+        assert!(color.red > 0.0 && color.blue > color.red && color.green == 0.0);
+
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(edge_color(&app), None);
+        assert_eq!(
+            app.world().get::<EdgeDetection>(camera).unwrap().edge_color,
+            edge_detection.edge_color
+        );
+    }
+
+    /// The frames of the tiles of a 2x2 grid are the frame of a single view of the whole image,
+    /// so the screen space effects stitch across the tiles.
+    #[test]
+    fn sub_view_tiles_stitch() {
+        let full_size = UVec2::new(256, 192);
+        let tile_size = full_size / 2;
+
+        for tile in [UVec2::ZERO, UVec2::X, UVec2::Y, UVec2::ONE] {
+            let sub_view = SubCameraView {
+                full_size,
+                offset: (tile * tile_size).as_vec2(),
+                size: tile_size,
+            };
+
+            // Rendered at the resolution of the whole image, a pixel of the tile is the pixel of the image
+            // at its position plus the offset of the tile.
+            let frame = sub_view_frame(&sub_view, tile_size);
+            assert_eq!(frame.xy(), (tile * tile_size).as_vec2());
+            assert_eq!(frame.zw(), full_size.as_vec2());
+
+            // Rendered at twice the resolution, the frame is scaled with the tile.
+            let frame = sub_view_frame(&sub_view, tile_size * 2);
+            assert_eq!(frame.xy(), (tile * tile_size * 2).as_vec2());
+            assert_eq!(frame.zw(), (full_size * 2).as_vec2());
+        }
+    }
+
+    /// Only the 32-bit float formats, the depth included, decide whether the float textures are filtered,
+    /// the other float formats are always filterable.
+    #[test]
+    fn float_filtering_selection() {
+        for format in [
+            ViewTarget::TEXTURE_FORMAT_HDR,
+            TextureFormat::Rgb10a2Unorm,
+            TextureFormat::Rg16Float,
+            EDGE_MASK_TEXTURE_FORMAT,
+            TextureFormat::Rgba8UnormSrgb,
+        ] {
+            assert!(!support::needs_float32_filtering(format), "{format:?}");
+        }
+        for format in [
+            TextureFormat::Depth32Float,
+            TextureFormat::Rgba32Float,
+            TextureFormat::R32Float,
+        ] {
+            assert!(support::needs_float32_filtering(format), "{format:?}");
+        }
+
+        let formats = [TextureFormat::Rgba16Float, TextureFormat::Rg16Float];
+        assert!(support::formats_filterable(formats, WgpuFeatures::empty()));
+        let formats = [TextureFormat::Rgba16Float, TextureFormat::Depth32Float];
+        assert!(!support::formats_filterable(formats, WgpuFeatures::empty()));
+        assert!(support::formats_filterable(
+            formats,
+            WgpuFeatures::FLOAT32_FILTERABLE
+        ));
+    }
+
+    /// The edge detection of the test cameras. The depth prepass reads as cleared on the GL backend of the software
+    /// adapters running the tests, so the depth is read from the main pass, except by `depth_prepass_edges`.
+    fn test_edge_detection() -> EdgeDetection {
+        EdgeDetection {
+            depth_source: DepthSource::MainPass,
+            ..default()
+        }
+    }
+
+    /// Serializes the tests rendering with their own renderer.
+    static RENDER_TESTS: Mutex<()> = Mutex::new(());
+
+    /// A headless app rendering the edge detection of cameras into images, for the tests creating the pipelines.
+    struct RenderTestApp {
+        app: App,
+        /// The validation errors of the GPU, collected instead of panicking.
+        errors: Arc<Mutex<Vec<String>>>,
+        _guard: MutexGuard<'static, ()>,
+    }
+
+    impl RenderTestApp {
+        fn new() -> Self {
+            Self::with_plugin(EdgeDetectionPlugin::default())
+        }
+
+        /// Creates the app with `plugin`, the [`EdgeDetectionPlugin`] and the plugins it should be tested with.
+        fn with_plugin<M>(plugin: impl Plugins<M>) -> Self {
+            Self::build(plugin, false)
+        }
+
+        /// Creates the app with the float texture filtering disabled through [`NON_FILTERABLE_ENV_VAR`].
+        fn non_filterable() -> Self {
+            Self::build(EdgeDetectionPlugin::default(), true)
+        }
+
+        fn build<M>(plugin: impl Plugins<M>, non_filterable: bool) -> Self {
+            let guard = RENDER_TESTS.lock().unwrap_or_else(PoisonError::into_inner);
+
+            // The variable is read when the renderer is created, the other tests keep the filtering of the adapter.
+            if non_filterable {
+                std::env::set_var(NON_FILTERABLE_ENV_VAR, "1");
+            }
+
+            let mut app = App::new();
+            app.add_plugins((
+                DefaultPlugins
+                    .set(WindowPlugin {
+                        primary_window: None,
+                        exit_condition: ExitCondition::DontExit,
+                        ..default()
+                    })
+                    // Without GPU culling, which the software adapters running the tests can't compile.
+                    .set(RenderPlugin {
+                        render_creation: WgpuSettings {
+                            disabled_features: Some(WgpuFeatures::MULTI_DRAW_INDIRECT),
+                            ..default()
+                        }
+                        .into(),
+                        ..default()
+                    })
+                    .set(PbrPlugin {
+                        use_gpu_instance_buffer_builder: false,
+                        ..default()
+                    })
+                    .disable::<WinitPlugin>()
+                    // Keeps the render world in the render sub app.
+                    .disable::<PipelinedRenderingPlugin>(),
+                ScheduleRunnerPlugin::default(),
+                plugin,
+            ));
+
+            // The renderer is created asynchronously.
+            while app.plugins_state() == PluginsState::Adding {
+                tick_global_task_pools_on_main_thread();
+            }
+            app.finish();
+            app.cleanup();
+            std::env::remove_var(NON_FILTERABLE_ENV_VAR);
+            // Batches the meshes on the CPU, like the disabled GPU culling.
+            app.sub_app_mut(RenderApp)
+                .insert_resource(GpuPreprocessingSupport {
+                    max_supported_mode: GpuPreprocessingMode::None,
+                });
+
+            let errors = Arc::new(Mutex::new(Vec::new()));
+            let errors_handler = errors.clone();
+            app.world()
+                .resource::<RenderDevice>()
+                .wgpu_device()
+                .on_uncaptured_error(Box::new(move |error| {
+                    errors_handler.lock().unwrap().push(error.to_string());
+                }));
+
+            let mesh = app
+                .world_mut()
+                .resource_mut::<Assets<Mesh>>()
+                .add(Cuboid::default());
+            app.world_mut()
+                .spawn((Mesh3d(mesh), MeshMaterial3d::<StandardMaterial>::default()));
+
+            Self {
+                app,
+                errors,
+                _guard: guard,
+            }
+        }
+
+        /// Adds an image the cameras can render into, and which can be read back.
+        fn image_target(&mut self) -> RenderTarget {
+            self.image_target_with_format(TextureFormat::bevy_default())
+        }
+
+        fn image_target_with_format(&mut self, format: TextureFormat) -> RenderTarget {
+            self.image_target_with_size(UVec2::splat(64), format)
+        }
+
+        fn image_target_with_size(&mut self, size: UVec2, format: TextureFormat) -> RenderTarget {
+            let mut image = Image::new_fill(
+                Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                &vec![0; format.pixel_size()],
+                format,
+                RenderAssetUsages::default(),
+            );
+            image.texture_descriptor.usage |=
+                TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC;
+            let image = self
+                .app
+                .world_mut()
+                .resource_mut::<Assets<Image>>()
+                .add(image);
+            RenderTarget::Image(image.into())
+        }
+
+        /// Adds a 2D array image of `layers` layers, and returns it along with a target for each layer, like the
+        /// faces of a cubemap are rendered into. The whole image can be read back.
+        fn array_layer_targets(&mut self, layers: u32) -> (RenderTarget, Vec<RenderTarget>) {
+            let size = UVec2::splat(64);
+            let format = TextureFormat::bevy_default();
+            let mut image = Image::new_fill(
+                Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: layers,
+                },
+                TextureDimension::D2,
+                &vec![0; format.pixel_size()],
+                format,
+                RenderAssetUsages::default(),
+            );
+            image.texture_descriptor.usage |=
+                TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC;
+            image.texture_view_descriptor = Some(TextureViewDescriptor {
+                dimension: Some(TextureViewDimension::D2Array),
+                ..default()
+            });
+            let image = self
+                .app
+                .world_mut()
+                .resource_mut::<Assets<Image>>()
+                .add(image);
+
+            // The views of the layers are created from the texture of the render world.
+            let mut texture = None;
+            for _ in 0..100 {
+                self.update();
+                texture = self
+                    .app
+                    .sub_app(RenderApp)
+                    .world()
+                    .resource::<RenderAssets<GpuImage>>()
+                    .get(&image)
+                    .map(|gpu_image| gpu_image.texture.clone());
+                if texture.is_some() {
+                    break;
+                }
+            }
+            let texture = texture.expect("the array image wasn't prepared");
+
+            let mut manual_texture_views =
+                self.app.world_mut().resource_mut::<ManualTextureViews>();
+            let targets = (0..layers)
+                .map(|layer| {
+                    let handle = ManualTextureViewHandle(layer);
+                    let texture_view = texture.create_view(&TextureViewDescriptor {
+                        dimension: Some(TextureViewDimension::D2),
+                        base_array_layer: layer,
+                        array_layer_count: Some(1),
+                        ..default()
+                    });
+                    manual_texture_views.insert(
+                        handle,
+                        ManualTextureView {
+                            texture_view,
+                            size,
+                            format,
+                        },
+                    );
+                    RenderTarget::TextureView(handle)
+                })
+                .collect();
+
+            (RenderTarget::Image(image.into()), targets)
+        }
+
+        fn spawn_camera(&mut self, camera: Camera) -> Entity {
+            self.app
+                .world_mut()
+                .spawn((
+                    Camera3d::default(),
+                    camera,
+                    Transform::from_xyz(0.0, 1.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+                    Msaa::Off,
+                    test_edge_detection(),
+                ))
+                .id()
+        }
+
+        /// Runs a frame, and panics on the validation errors.
+        fn update(&mut self) {
+            self.app.update();
+
+            if let Some(error) = self.errors.lock().unwrap().first() {
+                panic!("{error}");
+            }
+        }
+
+        /// Runs frames until the `targets` are read back, and returns their pixels in order.
+        fn read_back(&mut self, targets: &[RenderTarget]) -> Vec<Vec<u8>> {
+            let pixels = Arc::new(Mutex::new(vec![None; targets.len()]));
+            let readbacks: Vec<Entity> = targets
+                .iter()
+                .enumerate()
+                .map(|(index, target)| {
+                    let RenderTarget::Image(target) = target else {
+                        panic!("only images can be read back");
+                    };
+                    let pixels = pixels.clone();
+                    self.app
+                        .world_mut()
+                        .spawn(Readback::texture(target.handle.clone()))
+                        .observe(move |trigger: Trigger<ReadbackComplete>| {
+                            pixels.lock().unwrap()[index] = Some(trigger.event().0.clone());
+                        })
+                        .id()
+                })
+                .collect();
+
+            for _ in 0..100 {
+                self.update();
+                if pixels.lock().unwrap().iter().all(Option::is_some) {
+                    break;
+                }
+            }
+            for readback in readbacks {
+                self.app.world_mut().despawn(readback);
+            }
+
+            let pixels = pixels.lock().unwrap().drain(..).collect::<Option<Vec<_>>>();
+            pixels.expect("the targets weren't read back")
+        }
+
+        /// Whether the adapter reads back what was rendered into the multisampled textures. They read as cleared
+        /// on the GL backend of the software adapters, so no depth nor normal edge is drawn with MSAA there.
+        fn multisampled_textures_readable(&self) -> bool {
+            let adapter_info = self.app.world().resource::<RenderAdapterInfo>();
+            !Backends::from(adapter_info.backend).contains(Backends::GL)
+        }
+
+        /// Returns the pipeline of the view of `camera` in the render world, if it's prepared.
+        fn pipeline_id(&mut self, camera: Entity) -> Option<EdgeDetectionPipelineId> {
+            let render_entity = self.app.world().get::<RenderEntity>(camera)?.id();
+            self.app
+                .sub_app(RenderApp)
+                .world()
+                .get::<EdgeDetectionPipelineId>(render_entity)
+                .copied()
+        }
+
+        /// Runs frames until the pipeline of `camera` is created, then draws a few frames with it,
+        /// validating the bind groups.
+        fn wait_for_pipeline(&mut self, camera: Entity) -> EdgeDetectionPipelineId {
+            for _ in 0..1000 {
+                self.update();
+
+                let Some(pipeline_id) = self.pipeline_id(camera) else {
+                    continue;
+                };
+                match self
+                    .app
+                    .sub_app(RenderApp)
+                    .world()
+                    .resource::<PipelineCache>()
+                    .get_render_pipeline_state(pipeline_id.id)
+                {
+                    CachedPipelineState::Ok(_) => {
+                        for _ in 0..5 {
+                            self.update();
+                        }
+                        return pipeline_id;
+                    }
+                    // The shaders are loaded asynchronously too.
+                    CachedPipelineState::Err(
+                        PipelineCacheError::ShaderNotLoaded(_)
+                        | PipelineCacheError::ShaderImportNotYetAvailable,
+                    ) => {}
+                    CachedPipelineState::Err(error) => {
+                        panic!("failed to create the pipeline: {error}")
+                    }
+                    _ => {}
+                }
+            }
+            panic!("the pipeline wasn't created");
+        }
+
+        /// Runs frames until the [`EdgeCoverage`] of `camera` is read back, and returns its percent.
+        fn coverage(&mut self, camera: Entity) -> f32 {
+            // The coverage is read back a few frames late.
+            for _ in 0..10 {
+                self.update();
+            }
+            self.app
+                .world()
+                .get::<EdgeCoverage>(camera)
+                .unwrap()
+                .percent
+        }
+    }
+
+    /// Forced through [`NON_FILTERABLE_ENV_VAR`], the non-filterable layout and shader path create a working pipeline.
+    #[test]
+    fn non_filterable_pipeline() {
+        let mut app = RenderTestApp::non_filterable();
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+
+        app.wait_for_pipeline(camera);
+        assert!(
+            !app.app
+                .sub_app(RenderApp)
+                .world()
+                .resource::<EdgeDetectionPipeline>()
+                .filterable
+        );
+    }
+
+    /// The depth edges are detected in the depth prepass, the default depth source, like in the main pass depth.
+    #[test]
+    fn depth_prepass_edges() {
+        let mut app = RenderTestApp::new();
+        if !app
+            .app
+            .world()
+            .resource::<EdgeDetectionSupport>()
+            .depth_sampling
+        {
+            return;
+        }
+
+        let settings = [DepthSource::Prepass, DepthSource::MainPass];
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        for (depth_source, target) in settings.into_iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    depth_source,
+                    // Only the depth edges are drawn.
+                    enable_normal: false,
+                    ..default()
+                });
+            assert!(app.wait_for_pipeline(camera).key.layout_key().depth);
+            assert_eq!(
+                app.app.world().entity(camera).contains::<DepthPrepass>(),
+                depth_source == DepthSource::Prepass
+            );
+        }
+
+        let [prepass, main_pass] = app.read_back(&targets).try_into().unwrap();
+        let edge_pixels = |pixels: &[u8]| {
+            pixels
+                .chunks_exact(4)
+                .filter(|pixel| pixel[..3] == [0; 3])
+                .count()
+        };
+        let (prepass_edges, main_pass_edges) = (edge_pixels(&prepass), edge_pixels(&main_pass));
+        assert!(main_pass_edges > 0, "no depth edge in the main pass depth");
+        assert!(
+            prepass_edges.abs_diff(main_pass_edges) <= main_pass_edges / 10,
+            "the depth prepass has {prepass_edges} edge pixels, the main pass depth {main_pass_edges}"
+        );
+    }
+
+    /// The support report is copied to the main world, and matches the pipelines the views get.
+    #[test]
+    fn support_matches_pipelines() {
+        let mut app = RenderTestApp::new();
+        assert_eq!(
+            app.app.world().resource::<EdgeDetectionStatus>(),
+            &EdgeDetectionStatus::Pending
+        );
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+        app.app
+            .world_mut()
+            .entity_mut(camera)
+            .insert(EdgeCoverage::default());
+        app.wait_for_pipeline(camera);
+
+        let support = app.app.world().resource::<EdgeDetectionSupport>().clone();
+        assert_eq!(
+            app.app.world().resource::<EdgeDetectionStatus>(),
+            &EdgeDetectionStatus::Active {
+                depth_sampling: support.depth_sampling
+            }
+        );
+        let render_world = app.app.sub_app(RenderApp).world();
+        assert_eq!(render_world.resource::<EdgeDetectionSupport>(), &support);
+        assert!(support.max_samples >= 1);
+
+        assert_eq!(
+            support
+                .fallbacks()
+                .any(|fallback| fallback == EdgeDetectionFallback::NearestSampling),
+            !support.float_filtering
+        );
+        assert_eq!(
+            render_world.resource::<EdgeDetectionPipeline>().filterable,
+            support.float_filtering
+        );
+
+        let key = app.pipeline_id(camera).unwrap().key;
+        assert_eq!(key.edge_coverage, support.fragment_storage);
+        assert_eq!(
+            support
+                .fallbacks()
+                .any(|fallback| fallback == EdgeDetectionFallback::NoFragmentStorage),
+            !support.fragment_storage
+        );
+    }
+
+    /// Without a renderer, the plugin builds and runs, and reports that nothing is drawn.
+    #[test]
+    fn headless_app_reports_no_renderer() {
+        let mut app = App::new();
+        app.add_plugins((
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    ..default()
+                })
+                .set(RenderPlugin {
+                    render_creation: WgpuSettings {
+                        backends: None,
+                        ..default()
+                    }
+                    .into(),
+                    ..default()
+                })
+                .disable::<WinitPlugin>(),
+            ScheduleRunnerPlugin::default(),
+            EdgeDetectionPlugin::default(),
+        ));
+        assert!(app.get_sub_app(RenderApp).is_none());
+
+        app.finish();
+        app.cleanup();
+        app.world_mut()
+            .spawn((Camera3d::default(), EdgeDetection::default()));
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let status = app.world().resource::<EdgeDetectionStatus>();
+        assert_eq!(status, &EdgeDetectionStatus::NoRenderer);
+        assert!(!status.is_active());
+    }
+
+    /// Without depth texture sampling, like on WebGL2, no prepass is added nor bound,
+    /// and the color-based edges are still drawn.
+    #[test]
+    fn color_edges_without_depth_sampling() {
+        let mut app = RenderTestApp::new();
+        app.app
+            .world_mut()
+            .resource_mut::<EdgeDetectionSupport>()
+            .depth_sampling = false;
+        app.app
+            .sub_app_mut(RenderApp)
+            .world_mut()
+            .resource_mut::<EdgeDetectionSupport>()
+            .depth_sampling = false;
+
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+        app.app.world_mut().entity_mut(camera).insert((
+            EdgeDetection {
+                enable_color: true,
+                ..default()
+            },
+            EdgeCoverage::default(),
+        ));
+
+        let key = app.wait_for_pipeline(camera).key;
+        assert!(!key.enable_depth && !key.enable_normal && key.enable_color);
+        assert!(!key.layout_key().depth && !key.layout_key().normal);
+
+        assert!(app.coverage(camera) > 0.0);
+        let camera = app.app.world().entity(camera);
+        assert!(!camera.contains::<DepthPrepass>() && !camera.contains::<NormalPrepass>());
+    }
+
+    /// The pipelines of a view are only specialized again, and its pipeline id only updated, when its key changes.
+    #[test]
+    fn pipelines_respecialized_when_the_key_changes() {
+        let mut app = RenderTestApp::new();
+        let msaa_supported = app
+            .app
+            .world()
+            .resource::<EdgeDetectionSupport>()
+            .max_samples
+            >= 4;
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+        let initial = app.wait_for_pipeline(camera);
+
+        // The tick the pipeline id of the view was last inserted at.
+        let changed = |app: &RenderTestApp| {
+            let render_entity = app.app.world().get::<RenderEntity>(camera).unwrap().id();
+            app.app
+                .sub_app(RenderApp)
+                .world()
+                .entity(render_entity)
+                .get_change_ticks::<EdgeDetectionPipelineId>()
+                .unwrap()
+                .changed
+        };
+
+        let mut last_changed = changed(&app);
+        let mut last_id = initial.id;
+        let mut frame = |app: &mut RenderTestApp,
+                         edge_detection: EdgeDetection,
+                         msaa: Msaa,
+                         respecialized: bool| {
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert((edge_detection, msaa));
+            app.update();
+            let pipeline_id = app.pipeline_id(camera).unwrap();
+            assert_eq!(pipeline_id.key.samples, msaa.samples());
+            assert_eq!(changed(app) != last_changed, respecialized);
+            assert_eq!(pipeline_id.id != last_id, respecialized);
+            last_changed = changed(app);
+            last_id = pipeline_id.id;
+        };
+
+        let with_color = EdgeDetection {
+            enable_color: true,
+            ..default()
+        };
+        let without_normal = EdgeDetection {
+            enable_normal: false,
+            ..default()
+        };
+        for _ in 0..3 {
+            frame(&mut app, EdgeDetection::default(), Msaa::Off, false);
+        }
+        frame(&mut app, with_color.clone(), Msaa::Off, true);
+        frame(&mut app, with_color, Msaa::Off, false);
+        frame(&mut app, without_normal.clone(), Msaa::Off, true);
+        frame(&mut app, without_normal, Msaa::Off, false);
+        if msaa_supported {
+            frame(&mut app, EdgeDetection::default(), Msaa::Sample4, true);
+            frame(&mut app, EdgeDetection::default(), Msaa::Sample4, false);
+        }
+        frame(&mut app, EdgeDetection::default(), Msaa::Off, true);
+        assert_eq!(last_id, initial.id);
+    }
+
+    /// Changing the MSAA of a camera at runtime specializes its pipelines on the new sample count,
+    /// and the depth and normal edges are still drawn from the multisampled prepass textures.
+    #[test]
+    fn msaa_changes_respecialize_the_pipelines() {
+        let mut app = RenderTestApp::new();
+        if app
+            .app
+            .world()
+            .resource::<EdgeDetectionSupport>()
+            .max_samples
+            < 4
+        {
+            return;
+        }
+
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+        app.app
+            .world_mut()
+            .entity_mut(camera)
+            .insert(EdgeCoverage::default());
+
+        let mut coverages = vec![];
+        for msaa in [Msaa::Off, Msaa::Sample4, Msaa::Off] {
+            app.app.world_mut().entity_mut(camera).insert(msaa);
+            app.update();
+            let key = app.wait_for_pipeline(camera).key;
+            assert_eq!(key.samples, msaa.samples());
+            assert_eq!(key.layout_key().multisampled, msaa != Msaa::Off);
+
+            coverages.push(app.coverage(camera));
+        }
+
+        assert_eq!(coverages[2], coverages[0], "coverages: {coverages:?}");
+        if app.multisampled_textures_readable() {
+            // The resolved silhouette only moves by a fraction of a pixel.
+            assert!(coverages[0] > 0.0, "coverages: {coverages:?}");
+            assert!(
+                (coverages[1] - coverages[0]).abs() < coverages[0] * 0.25,
+                "coverages: {coverages:?}"
+            );
+        }
+    }
+
+    /// The pipelines of cameras on targets with different formats are specialized for their own target,
+    /// and a target going away, like a closed window, drops the pipeline of its camera until it's retargeted.
+    #[test]
+    fn per_target_specialization() {
+        let mut app = RenderTestApp::new();
+        let target = app.image_target();
+        let ldr_camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+        let target = app.image_target();
+        let hdr_camera = app.spawn_camera(Camera {
+            target,
+            hdr: true,
+            ..default()
+        });
+
+        let ldr_key = app.wait_for_pipeline(ldr_camera).key;
+        assert_eq!(ldr_key.target_format, TextureFormat::bevy_default());
+        assert!(!ldr_key.hdr);
+        let hdr_key = app.wait_for_pipeline(hdr_camera).key;
+        assert_eq!(hdr_key.target_format, ViewTarget::TEXTURE_FORMAT_HDR);
+        assert!(hdr_key.hdr);
+        assert_eq!(
+            hdr_key.composite_key().target_format,
+            ViewTarget::TEXTURE_FORMAT_HDR
+        );
+
+        // Targets a window which was closed.
+        let closed_window = app.app.world_mut().spawn_empty().id();
+        app.app.world_mut().despawn(closed_window);
+        app.app
+            .world_mut()
+            .get_mut::<Camera>(hdr_camera)
+            .unwrap()
+            .target = RenderTarget::Window(WindowRef::Entity(closed_window));
+        app.update();
+        app.update();
+        assert!(app.pipeline_id(hdr_camera).is_none());
+
+        // Reopens it.
+        let target = app.image_target();
+        app.app
+            .world_mut()
+            .get_mut::<Camera>(hdr_camera)
+            .unwrap()
+            .target = target;
+        let hdr_key = app.wait_for_pipeline(hdr_camera).key;
+        assert_eq!(hdr_key.target_format, ViewTarget::TEXTURE_FORMAT_HDR);
+        assert_eq!(
+            app.pipeline_id(ldr_camera).unwrap().key.target_format,
+            TextureFormat::bevy_default()
+        );
+    }
+
+    /// The cameras rendering into images of other formats, e.g. portals, get their own pipelines in the same frame.
+    #[test]
+    fn image_targets_of_other_formats() {
+        let mut app = RenderTestApp::new();
+        let unorm_target = app.image_target_with_format(TextureFormat::Rgba8Unorm);
+        let float_target = app.image_target_with_format(TextureFormat::Rgba16Float);
+        let mut cameras = Vec::new();
+        for (target, hdr) in [(&unorm_target, false), (&float_target, true)] {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                hdr,
+                ..default()
+            });
+            cameras.push(camera);
+        }
+
+        let unorm_key = app.wait_for_pipeline(cameras[0]).key;
+        let float_key = app.wait_for_pipeline(cameras[1]).key;
+        assert_ne!(unorm_key.target_format, float_key.target_format);
+        assert!(float_key.hdr && !unorm_key.hdr);
+
+        let [unorm, float] = app
+            .read_back(&[unorm_target, float_target])
+            .try_into()
+            .unwrap();
+        assert!(unorm.chunks_exact(4).any(|pixel| pixel[..3] == [0; 3]));
+        assert!(float.chunks_exact(8).any(|pixel| pixel[..6] == [0; 6]));
+    }
+
+    /// The cameras rendering into the layers of an array texture, like the faces of a cubemap, each detect
+    /// the edges of their own view.
+    #[test]
+    fn array_layer_targets() {
+        let mut app = RenderTestApp::new();
+        let (image, targets) = app.array_layer_targets(2);
+        let cameras: Vec<Entity> = targets
+            .into_iter()
+            .map(|target| {
+                app.spawn_camera(Camera {
+                    target,
+                    ..default()
+                })
+            })
+            .collect();
+        // The second layer looks away from the cube.
+        *app.app
+            .world_mut()
+            .get_mut::<Transform>(cameras[1])
+            .unwrap() = Transform::from_xyz(0.0, 1.0, 3.0).looking_to(Vec3::Z, Vec3::Y);
+
+        for &camera in &cameras {
+            app.wait_for_pipeline(camera);
+        }
+
+        let [pixels] = app.read_back(&[image]).try_into().unwrap();
+        let [facing, away] = [0, 1].map(|layer| {
+            let layer_size = 64 * 64 * TextureFormat::bevy_default().pixel_size();
+            &pixels[layer * layer_size..(layer + 1) * layer_size]
+        });
+        assert!(facing.chunks_exact(4).any(|pixel| pixel[..3] == [0; 3]));
+        assert!(!away.chunks_exact(4).any(|pixel| pixel[..3] == [0; 3]));
+    }
+
+    /// The views rendering into the formats the edge detection can't render to or sample skip it.
+    #[test]
+    fn unsupported_target_formats() {
+        let app = RenderTestApp::new();
+        let render_world = app.app.get_sub_app(RenderApp).unwrap().world();
+        let support = render_world.resource::<EdgeDetectionSupport>();
+        let adapter = render_world.resource::<RenderAdapter>();
+
+        for format in [
+            TextureFormat::Rgba8UnormSrgb,
+            ViewTarget::TEXTURE_FORMAT_HDR,
+            TextureFormat::Rgba8Unorm,
+        ] {
+            assert!(
+                support.target_format_supported(adapter, format),
+                "{format:?}"
+            );
+        }
+        for format in [
+            TextureFormat::R32Uint,
+            TextureFormat::Depth32Float,
+            TextureFormat::Bc1RgbaUnorm,
+        ] {
+            assert!(
+                !support.target_format_supported(adapter, format),
+                "{format:?}"
+            );
+        }
+    }
+
+    /// The edges drawn before the tonemapping are written into the HDR main texture, and tonemapped with the scene.
+    #[test]
+    fn edges_before_tonemapping() {
+        let placement = EdgeDetectionPlacement::BeforeTonemapping;
+        let mut app = RenderTestApp::with_plugin(EdgeDetectionPlugin {
+            additional_placements: vec![placement],
+            ..default()
+        });
+
+        let render_graph = app.app.sub_app(RenderApp).world().resource::<RenderGraph>();
+        let edges = &render_graph
+            .get_sub_graph(Core3d)
+            .unwrap()
+            .get_node_state(EdgeDetectionPlacementLabel(placement))
+            .unwrap()
+            .edges;
+        assert!(edges
+            .output_edges()
+            .iter()
+            .any(|edge| edge.get_input_node() == Node3d::Tonemapping.intern()));
+
+        let targets = [app.image_target(), app.image_target()];
+        for (hdr, target) in [false, true].into_iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                hdr,
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    placement: Some(placement),
+                    ..test_edge_detection()
+                });
+            let pipeline_id = app.wait_for_pipeline(camera);
+            assert_eq!(pipeline_id.placement, placement);
+            assert_eq!(pipeline_id.key.hdr, hdr);
+        }
+
+        for pixels in app.read_back(&targets) {
+            assert!(pixels.chunks_exact(4).any(|pixel| pixel[..3] == [0; 3]));
+        }
+    }
+
+    /// The edges drawn before the transparent pass are covered by the alpha blended geometry in front of them,
+    /// like the opaque geometry they outline, while the default placement draws them over it.
+    #[test]
+    fn transparent_geometry_covers_the_edges_before_it() {
+        let placement = EdgeDetectionPlacement::BeforeTransparent;
+        let mut app = RenderTestApp::with_plugin(EdgeDetectionPlugin {
+            additional_placements: vec![placement],
+            ..default()
+        });
+
+        // A pane of glass between the cameras and the cube, covering their whole view.
+        let world = app.app.world_mut();
+        let pane = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Rectangle::new(20.0, 20.0));
+        let glass = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                base_color: Color::srgba(1.0, 1.0, 1.0, 0.5),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                double_sided: true,
+                cull_mode: None,
+                ..default()
+            });
+        let camera_position = Vec3::new(0.0, 1.0, 3.0);
+        let pane_position = camera_position * 0.5;
+        world.spawn((
+            Mesh3d(pane),
+            MeshMaterial3d(glass),
+            Transform::from_translation(pane_position)
+                .looking_at(pane_position * 2.0 - camera_position, Vec3::Y),
+        ));
+
+        let targets = [app.image_target(), app.image_target()];
+        for (placement, target) in [None, Some(placement)].into_iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    placement,
+                    ..test_edge_detection()
+                });
+            let pipeline_id = app.wait_for_pipeline(camera);
+            assert_eq!(pipeline_id.key.before_transparent, placement.is_some());
+        }
+
+        let [over, under] = app.read_back(&targets).try_into().unwrap();
+        let is_black = |pixel: &[u8]| pixel[..3] == [0; 3];
+        assert!(
+            over.chunks_exact(4).any(is_black),
+            "the edges weren't drawn over the glass"
+        );
+        assert!(
+            !under.chunks_exact(4).any(is_black),
+            "the edges weren't covered by the glass"
+        );
+        assert_ne!(over, under);
+    }
+
+    /// Toggling the settings every other frame leaves nothing behind in the render world, and the settings
+    /// are only extracted again when they change.
+    #[test]
+    fn toggled_edge_detection_is_cleaned_up() {
+        let mut app = RenderTestApp::new();
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target: target.clone(),
+            ..default()
+        });
+        let edge_detection = EdgeDetection {
+            edge_color: Color::srgb(1.0, 0.0, 0.0),
+            ..test_edge_detection()
+        };
+        app.app
+            .world_mut()
+            .entity_mut(camera)
+            .insert(edge_detection.clone());
+        app.wait_for_pipeline(camera);
+
+        let uniform = |app: &RenderTestApp| {
+            let render_entity = app.app.world().get::<RenderEntity>(camera).unwrap().id();
+            app.app
+                .sub_app(RenderApp)
+                .world()
+                .get::<EdgeDetectionUniform>(render_entity)
+                .copied()
+        };
+
+        for frame in 0..10 {
+            let mut entity = app.app.world_mut().entity_mut(camera);
+            let enabled = frame % 2 == 1;
+            if enabled {
+                entity.insert(edge_detection.clone());
+            } else {
+                entity.remove::<EdgeDetection>();
+            }
+            app.update();
+
+            if enabled {
+                assert_eq!(uniform(&app).unwrap().edge_color, LinearRgba::RED);
+            } else {
+                assert!(uniform(&app).is_none());
+                assert!(app.pipeline_id(camera).is_none());
+                let render_entity = app.app.world().get::<RenderEntity>(camera).unwrap().id();
+                assert!(app
+                    .app
+                    .sub_app(RenderApp)
+                    .world()
+                    .get::<EdgeDetection>(render_entity)
+                    .is_none());
+            }
+        }
+
+        // Unchanged settings keep their uniform, with the time of each frame.
+        let time = uniform(&app).unwrap().time;
+        app.update();
+        assert!(uniform(&app).unwrap().time > time);
+
+        app.app
+            .world_mut()
+            .get_mut::<EdgeDetection>(camera)
+            .unwrap()
+            .edge_color = Color::srgb(0.0, 0.0, 1.0);
+        app.update();
+        assert_eq!(uniform(&app).unwrap().edge_color, LinearRgba::BLUE);
+
+        app.app
+            .world_mut()
+            .entity_mut(camera)
+            .remove::<EdgeDetection>();
+        let [pixels] = app.read_back(&[target]).try_into().unwrap();
+        assert!(!pixels
+            .chunks_exact(4)
+            .any(|pixel| pixel[2] > 250 && pixel[0] < 5 && pixel[1] < 5));
+    }
+
+    /// A tiling normal map of bricks, beveled into the mortar, in tangent space.
+    fn brick_normal_map() -> Image {
+        const SIZE: u32 = 128;
+        const BRICK: UVec2 = UVec2::new(16, 8);
+        const BEVEL: u32 = 2;
+
+        let mut data = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                // Every other row is offset by half a brick.
+                let x = x + (y / BRICK.y % 2) * BRICK.x / 2;
+                let (u, v) = (x % BRICK.x, y % BRICK.y);
+
+                let mut normal = Vec3::Z;
+                if u < BEVEL {
+                    normal.x = -1.0;
+                } else if u >= BRICK.x - BEVEL {
+                    normal.x = 1.0;
+                }
+                if v < BEVEL {
+                    normal.y = 1.0;
+                } else if v >= BRICK.y - BEVEL {
+                    normal.y = -1.0;
+                }
+
+                let packed = normal.normalize() * 0.5 + 0.5;
+                data.extend(
+                    (packed * 255.0)
+                        .round()
+                        .as_uvec3()
+                        .to_array()
+                        .map(|c| c as u8),
+                );
+                data.push(255);
+            }
+        }
+
+        Image::new(
+            Extent3d {
+                width: SIZE,
+                height: SIZE,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8Unorm,
+            RenderAssetUsages::default(),
+        )
+    }
+
+    /// Returns the edge coverage of the normal source alone, for a camera looking at the cube
+    /// in front of a wall, with or without the normal map of the bricks.
+    fn brick_wall_coverage(normal_map: bool, geometric_normals_only: bool) -> f32 {
+        let mut app = RenderTestApp::new();
+
+        let world = app.app.world_mut();
+        let mesh = world.resource_mut::<Assets<Mesh>>().add(
+            Mesh::from(Rectangle::new(6.0, 6.0))
+                .with_generated_tangents()
+                .unwrap(),
+        );
+        let normal_map_texture = normal_map.then(|| {
+            world
+                .resource_mut::<Assets<Image>>()
+                .add(brick_normal_map())
+        });
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                normal_map_texture,
+                ..default()
+            });
+        world.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(material),
+            Transform::from_xyz(0.0, 1.0, -1.0),
+        ));
+
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+        app.app.world_mut().entity_mut(camera).insert((
+            EdgeDetection {
+                enable_depth: false,
+                geometric_normals_only,
+                ..test_edge_detection()
+            },
+            EdgeCoverage::default(),
+        ));
+
+        app.wait_for_pipeline(camera);
+        app.coverage(camera)
+    }
+
+    /// With the geometric normals, the normal map of a brick wall draws no edges,
+    /// while the silhouette and corners of the cube in front of it keep theirs.
+    #[test]
+    fn geometric_normals_ignore_normal_maps() {
+        let silhouette = brick_wall_coverage(false, false);
+        let bricks = brick_wall_coverage(true, false);
+        let geometric = brick_wall_coverage(true, true);
+
+        assert!(silhouette > 0.0);
+        assert!(
+            bricks > silhouette * 2.0,
+            "{bricks}% of edges with the normal map, {silhouette}% without it"
+        );
+        assert!(
+            (geometric - silhouette).abs() < silhouette * 0.5,
+            "{geometric}% of edges with the geometric normals, {silhouette}% without the normal map"
+        );
+    }
+
+    /// Returns the percentage of edge pixels of the normal-based edge detection of the cube,
+    /// and whether the camera got a normal prepass. The `deferred` camera shades the cube in its G-buffer.
+    fn normal_source_coverage(normal_source: NormalSource, deferred: bool) -> (f32, bool) {
+        let mut app = RenderTestApp::new();
+        if deferred {
+            app.app
+                .insert_resource(DefaultOpaqueRendererMethod::deferred());
+        }
+
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+        app.app.world_mut().entity_mut(camera).insert((
+            EdgeDetection {
+                enable_depth: false,
+                normal_source,
+                ..test_edge_detection()
+            },
+            EdgeCoverage::default(),
+        ));
+        if deferred {
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert((DepthPrepass, DeferredPrepass));
+        }
+
+        let key = app.wait_for_pipeline(camera).key;
+        assert_eq!(key.deferred_normals, deferred);
+        let coverage = app.coverage(camera);
+
+        let camera = app.app.world().entity(camera);
+        (coverage, camera.contains::<NormalPrepass>())
+    }
+
+    /// Without a normal prepass, the normals reconstructed from the depth draw about the same edges.
+    #[test]
+    fn normals_are_reconstructed_without_a_normal_prepass() {
+        let (prepass, prepass_added) = normal_source_coverage(NormalSource::Prepass, false);
+        let (auto, auto_added) = normal_source_coverage(NormalSource::Auto, false);
+        let (depth, depth_added) = normal_source_coverage(NormalSource::Depth, false);
+
+        assert!(prepass_added);
+        assert!(!auto_added && !depth_added);
+        assert!(prepass > 0.0, "{prepass}% of edges with the normal prepass");
+        for reconstructed in [auto, depth] {
+            assert!(
+                (reconstructed - prepass).abs() < prepass * 0.5,
+                "{reconstructed}% of edges with the reconstructed normals, {prepass}% with the normal prepass"
+            );
+        }
+    }
+
+    /// The deferred cameras decode the normals of their G-buffer instead of getting a normal prepass,
+    /// and draw the same edges as the forward path.
+    #[test]
+    fn deferred_normals_match_the_forward_path() {
+        let (forward, _) = normal_source_coverage(NormalSource::Prepass, false);
+        let (deferred, deferred_added) = normal_source_coverage(NormalSource::Prepass, true);
+
+        assert!(!deferred_added);
+        assert!(forward > 0.0, "{forward}% of edges with the normal prepass");
+        assert!(
+            (deferred - forward).abs() < forward * 0.05,
+            "{deferred}% of edges with the deferred normals, {forward}% with the normal prepass"
+        );
+    }
+
+    /// Renders the cube with the edges detected at `resolution_scale`, and returns the edge coverage,
+    /// the pipeline of the view and the size of its edge mask.
+    fn resolution_scale_coverage(
+        resolution_scale: f32,
+    ) -> (f32, EdgeDetectionPipelineId, (u32, u32)) {
+        let mut app = RenderTestApp::new();
+
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+        app.app.world_mut().entity_mut(camera).insert((
+            EdgeDetection {
+                resolution_scale,
+                ..test_edge_detection()
+            },
+            EdgeCoverage::default(),
+        ));
+
+        let pipeline_id = app.wait_for_pipeline(camera);
+        let coverage = app.coverage(camera);
+
+        let render_entity = app.app.world().get::<RenderEntity>(camera).unwrap().id();
+        let mask_size = app
+            .app
+            .sub_app(RenderApp)
+            .world()
+            .get::<EdgeDetectionMaskTexture>(render_entity)
+            .map_or((0, 0), |mask| {
+                (mask.0.texture.width(), mask.0.texture.height())
+            });
+        (coverage, pipeline_id, mask_size)
+    }
+
+    /// At half resolution, the edges are detected into a mask of a quarter of the pixels,
+    /// and cover about the same part of the view once upsampled.
+    #[test]
+    fn edges_are_detected_at_the_resolution_scale() {
+        let (full, full_pipeline, _) = resolution_scale_coverage(1.0);
+        let (half, half_pipeline, half_mask) = resolution_scale_coverage(0.5);
+
+        assert!(!full_pipeline.key.scaled);
+        assert!(half_pipeline.key.scaled && half_pipeline.key.edge_mask);
+        assert!(half_pipeline.key.composite_key().scaled);
+        assert_eq!(half_mask, (32, 32));
+        assert!(full > 0.0, "{full}% of edges at full resolution");
+        assert!(
+            (half - full).abs() < full * 0.5,
+            "{half}% of edges at half resolution, {full}% at full resolution"
+        );
+    }
+
+    /// Renders the cube on an HDR camera, with the edge detection running as a compute pass or not,
+    /// and returns the pipeline of the view, its edge coverage and the pixels of the target.
+    /// `None` if the adapter can't run the compute variant.
+    fn render_compute_variant(
+        use_compute: bool,
+    ) -> Option<(EdgeDetectionPipelineId, f32, Vec<u8>)> {
+        let mut app = RenderTestApp::with_plugin(EdgeDetectionPlugin {
+            use_compute,
+            ..default()
+        });
+        if use_compute
+            && !app
+                .app
+                .world()
+                .resource::<EdgeDetectionSupport>()
+                .compute_output
+        {
+            return None;
+        }
+
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target: target.clone(),
+            hdr: true,
+            ..default()
+        });
+        app.app.world_mut().entity_mut(camera).insert((
+            EdgeDetection {
+                enable_color: true,
+                ..test_edge_detection()
+            },
+            EdgeCoverage::default(),
+        ));
+
+        let mut pipeline_id = app.wait_for_pipeline(camera);
+        // The render pipeline draws the edges until the compute one is compiled.
+        while let Some(compute_id) = pipeline_id.compute_id {
+            match app
+                .app
+                .sub_app(RenderApp)
+                .world()
+                .resource::<PipelineCache>()
+                .get_compute_pipeline_state(compute_id)
+            {
+                CachedPipelineState::Ok(_) => break,
+                CachedPipelineState::Err(error) => {
+                    panic!("failed to create the compute pipeline: {error}")
+                }
+                _ => {
+                    app.update();
+                    pipeline_id = app.pipeline_id(camera).unwrap();
+                }
+            }
+        }
+
+        let coverage = app.coverage(camera);
+        let pixels = app.read_back(&[target]).remove(0);
+        Some((pipeline_id, coverage, pixels))
+    }
+
+    /// The compute variant draws the same edges as the fragment pass.
+    #[test]
+    fn compute_pass_matches_the_fragment_pass() {
+        let Some((compute_pipeline, compute_coverage, compute_pixels)) =
+            render_compute_variant(true)
+        else {
+            // The adapter can't write the HDR main textures from a compute shader.
+            return;
+        };
+        let (fragment_pipeline, fragment_coverage, fragment_pixels) =
+            render_compute_variant(false).unwrap();
+
+        assert!(compute_pipeline.key.compute && compute_pipeline.compute_id.is_some());
+        assert!(!fragment_pipeline.key.compute && fragment_pipeline.compute_id.is_none());
+        assert!(
+            fragment_coverage > 0.0,
+            "{fragment_coverage}% of edges with the fragment pass"
+        );
+        assert_eq!(
+            compute_coverage, fragment_coverage,
+            "{compute_coverage}% of edges with the compute pass, {fragment_coverage}% with the fragment pass"
+        );
+
+        let max_difference = compute_pixels
+            .iter()
+            .zip(&fragment_pixels)
+            .map(|(compute, fragment)| compute.abs_diff(*fragment))
+            .max()
+            .unwrap();
+        assert!(
+            max_difference <= 1,
+            "the compute pass differs from the fragment pass by up to {max_difference}"
+        );
+    }
+
+    /// A custom shader replaces the built-in one, through the bindings it imports,
+    /// and the views whose custom shader fails to compile fall back to the built-in one.
+    #[test]
+    fn custom_edge_detection_shader() {
+        let mut app = RenderTestApp::new();
+
+        let mut shaders = app.app.world_mut().resource_mut::<Assets<Shader>>();
+        let custom = shaders.add(Shader::from_wgsl(
+            "#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+            #import bevy_edge_detection::bindings::ed_uniform
+
+            @fragment
+            fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4f {
+                return vec4f(ed_uniform.edge_color.rgb, 1.0);
+            }",
+            "custom_edge_detection.wgsl",
+        ));
+        let broken = shaders.add(Shader::from_wgsl(
+            "@fragment fn fragment(",
+            "broken_edge_detection.wgsl",
+        ));
+
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target: target.clone(),
+            ..default()
+        });
+        app.app.world_mut().entity_mut(camera).insert((
+            EdgeDetection {
+                edge_color: Color::srgb(1.0, 0.0, 1.0),
+                ..default()
+            },
+            EdgeDetectionShader(custom.clone()),
+        ));
+
+        let pipeline_id = app.wait_for_pipeline(camera);
+        assert_eq!(pipeline_id.key.shader, Some(custom.id()));
+        let magenta = |pixel: &[u8]| pixel[0] > 250 && pixel[1] < 5 && pixel[2] > 250;
+        let [pixels] = app.read_back(&[target.clone()]).try_into().unwrap();
+        assert!(pixels.chunks_exact(4).all(magenta));
+
+        app.app
+            .world_mut()
+            .entity_mut(camera)
+            .insert(EdgeDetectionShader(broken));
+        // The pipeline of the broken shader fails once the shader is processed.
+        for _ in 0..1000 {
+            app.update();
+            if app
+                .pipeline_id(camera)
+                .is_some_and(|pipeline_id| pipeline_id.key.shader.is_none())
+            {
+                break;
+            }
+        }
+
+        let pipeline_id = app.wait_for_pipeline(camera);
+        assert_eq!(pipeline_id.key.shader, None);
+        let [pixels] = app.read_back(&[target]).try_into().unwrap();
+        assert!(!pixels.chunks_exact(4).all(magenta));
+    }
+
+    /// The normal prepass is only added while the normal-based edge detection is enabled,
+    /// and the normal texture only bound while the normals are read.
+    #[test]
+    fn normal_prepass_follows_enable_normal() {
+        let mut app = RenderTestApp::new();
+
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+
+        let set_edge_detection = |app: &mut RenderTestApp, edit: fn(&mut EdgeDetection)| {
+            let mut entity = app.app.world_mut().entity_mut(camera);
+            edit(&mut entity.get_mut::<EdgeDetection>().unwrap());
+            // The prepasses change the frame after, and the pipeline is specialized again.
+            app.update();
+            let key = app.wait_for_pipeline(camera).key;
+            (
+                app.app.world().entity(camera).contains::<NormalPrepass>(),
+                key,
+            )
+        };
+
+        let (normal_prepass, key) = set_edge_detection(&mut app, |_| {});
+        assert!(normal_prepass);
+        assert!(key.layout_key().normal);
+
+        // The steep angle adjustment of the depth edges still reads the normals.
+        let (normal_prepass, key) = set_edge_detection(&mut app, |edge_detection| {
+            edge_detection.enable_normal = false
+        });
+        assert!(!normal_prepass);
+        assert!(key.normals && key.geometric_normals);
+        assert!(!key.layout_key().normal);
+
+        let (_, key) = set_edge_detection(&mut app, |edge_detection| {
+            edge_detection.steep_angle_multiplier = 0.0;
+        });
+        assert!(!key.normals && !key.geometric_normals);
+
+        let (normal_prepass, key) = set_edge_detection(&mut app, |edge_detection| {
+            edge_detection.enable_normal = true
+        });
+        assert!(normal_prepass);
+        assert!(key.layout_key().normal);
+    }
+
+    /// Sanitizes the inputs in the `x` of the even elements, depth then packed normal,
+    /// into the next element.
+    const SANITIZE_TEST_SHADER: &str = "
+#import bevy_edge_detection::common::{sanitize_depth, sanitize_normal}
+
+@group(0) @binding(0) var<storage, read_write> values: array<vec4f>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3u) {
+    let input = values[id.x * 2u];
+    values[id.x * 2u + 1u] = vec4f(sanitize_depth(input.x, 0.25), sanitize_normal(input.yzw, vec3f(0.5)));
+}
+";
+
+    /// The infinite and NaN depths and normals are replaced by the fallback of the shader,
+    /// and the depths are clamped to the range of the depth buffer.
+    #[test]
+    fn sanitize_non_finite_samples() {
+        let cases = [
+            (Vec4::new(0.7, 0.1, 0.2, 0.3), Vec4::new(0.7, 0.1, 0.2, 0.3)),
+            (
+                Vec4::new(f32::NAN, f32::NAN, 0.2, 0.3),
+                Vec4::new(0.25, 0.5, 0.5, 0.5),
+            ),
+            (
+                Vec4::new(f32::INFINITY, 0.1, f32::INFINITY, 0.3),
+                Vec4::new(0.25, 0.5, 0.5, 0.5),
+            ),
+            (
+                Vec4::new(f32::NEG_INFINITY, 0.1, 0.2, f32::NEG_INFINITY),
+                Vec4::new(0.25, 0.5, 0.5, 0.5),
+            ),
+            (Vec4::new(1.5, 0.0, 0.0, 1.0), Vec4::new(1.0, 0.0, 0.0, 1.0)),
+            (
+                Vec4::new(-0.5, 1.0, 1.0, 1.0),
+                Vec4::new(0.0, 1.0, 1.0, 1.0),
+            ),
+        ];
+
+        let mut app = RenderTestApp::new();
+        let shader = app
+            .app
+            .world_mut()
+            .resource_mut::<Assets<Shader>>()
+            .add(Shader::from_wgsl(SANITIZE_TEST_SHADER, file!()));
+
+        let render_world = app.app.sub_app_mut(RenderApp).world_mut();
+        let render_device = render_world.resource::<RenderDevice>().clone();
+        let layout = render_device.create_bind_group_layout(
+            "sanitize_test_layout",
+            &BindGroupLayoutEntries::single(
+                ShaderStages::COMPUTE,
+                storage_buffer_sized(false, None),
+            ),
+        );
+        let pipeline_id = render_world
+            .resource::<PipelineCache>()
+            .queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some("sanitize_test_pipeline".into()),
+                layout: vec![layout.clone()],
+                push_constant_ranges: vec![],
+                shader,
+                shader_defs: vec![],
+                entry_point: "main".into(),
+                zero_initialize_workgroup_memory: false,
+            });
+
+        let pipeline = (0..1000)
+            .find_map(|_| {
+                app.update();
+                let pipeline_cache = app
+                    .app
+                    .sub_app(RenderApp)
+                    .world()
+                    .resource::<PipelineCache>();
+                match pipeline_cache.get_compute_pipeline_state(pipeline_id) {
+                    CachedPipelineState::Err(
+                        PipelineCacheError::ShaderNotLoaded(_)
+                        | PipelineCacheError::ShaderImportNotYetAvailable,
+                    ) => None,
+                    CachedPipelineState::Err(error) => {
+                        panic!("failed to create the pipeline: {error}")
+                    }
+                    _ => pipeline_cache.get_compute_pipeline(pipeline_id).cloned(),
+                }
+            })
+            .expect("the pipeline wasn't created");
+
+        let values: Vec<u8> = cases
+            .iter()
+            .flat_map(|&(input, _)| [input, Vec4::ZERO])
+            .flat_map(|value| value.to_array())
+            .flat_map(f32::to_ne_bytes)
+            .collect();
+        let size = values.len() as u64;
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("sanitize_test_values"),
+            contents: &values,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+        });
+        let readback = render_device.create_buffer(&BufferDescriptor {
+            label: Some("sanitize_test_readback"),
+            size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = render_device.create_bind_group(
+            "sanitize_test_bind_group",
+            &layout,
+            &BindGroupEntries::single(buffer.as_entire_binding()),
+        );
+
+        let mut encoder =
+            render_device.create_command_encoder(&CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor::default());
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(cases.len() as u32, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&buffer, 0, &readback, 0, size);
+        let render_world = app.app.sub_app(RenderApp).world();
+        render_world
+            .resource::<RenderQueue>()
+            .submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        render_device.map_buffer(&slice, MapMode::Read, |result| result.unwrap());
+        render_device.poll(Maintain::Wait);
+        let values: Vec<f32> = slice
+            .get_mapped_range()
+            .chunks_exact(4)
+            .map(|bytes| f32::from_ne_bytes(bytes.try_into().unwrap()))
+            .collect();
+
+        for (&(input, expected), values) in cases.iter().zip(values.chunks_exact(8)) {
+            assert_eq!(
+                Vec4::from_slice(&values[4..]),
+                expected,
+                "sanitized {input}"
+            );
+        }
+    }
+
+    /// The uniform of a view fits in the smallest uniform binding range of the downlevel devices. Only the uniform
+    /// of the view is bound, at an offset aligned to the limits of the device by the `ComponentUniforms`,
+    /// so the number of views is bounded by the size of the buffer, not by the binding range.
+    #[test]
+    fn uniform_fits_downlevel_binding_range() {
+        let size = EdgeDetectionUniform::min_size().get();
+        let max_size = WgpuLimits::downlevel_webgl2_defaults().max_uniform_buffer_binding_size;
+        assert!(
+            size <= max_size as u64,
+            "{size} bytes, more than {max_size}"
+        );
+    }
+
+    /// Each of many cameras draws its edges with its own settings, bound at its own offset of the uniform buffer.
+    #[test]
+    fn many_views_get_their_own_settings() {
+        const VIEWS: usize = 64;
+        let color = |index: usize| [index as u8 * 4, 255 - index as u8 * 4, 128];
+
+        let mut app = RenderTestApp::new();
+        let targets: Vec<RenderTarget> = (0..VIEWS).map(|_| app.image_target()).collect();
+        let cameras: Vec<Entity> = targets
+            .iter()
+            .enumerate()
+            .map(|(index, target)| {
+                let camera = app.spawn_camera(Camera {
+                    target: target.clone(),
+                    ..default()
+                });
+                let [red, green, blue] = color(index);
+                app.app
+                    .world_mut()
+                    .entity_mut(camera)
+                    .insert(EdgeDetection {
+                        edge_color: Color::srgb_u8(red, green, blue),
+                        ..test_edge_detection()
+                    });
+                camera
+            })
+            .collect();
+        for camera in cameras {
+            app.wait_for_pipeline(camera);
+        }
+
+        for (index, pixels) in app.read_back(&targets).iter().enumerate() {
+            let mut edge_pixels = 0;
+            for pixel in pixels.chunks_exact(4) {
+                let Some(drawn_by) = (0..VIEWS).find(|&other| {
+                    color(other)
+                        .iter()
+                        .zip(pixel)
+                        .all(|(color, pixel)| color.abs_diff(*pixel) <= 1)
+                }) else {
+                    continue;
+                };
+                assert_eq!(
+                    drawn_by, index,
+                    "view {index} drew the edges of view {drawn_by}"
+                );
+                edge_pixels += 1;
+            }
+            assert!(edge_pixels > 0, "view {index} drew no edges");
+        }
+    }
+
+    /// The depth threshold is in world units, or a fraction of the depth range when normalized,
+    /// so the same outlines are drawn whatever the near and far planes are.
+    #[test]
+    fn depth_threshold_is_portable_across_depth_ranges() {
+        const TOLERANCE: u8 = 8;
+
+        let mut app = RenderTestApp::new();
+        let views = [
+            (0.01, 500.0, false, 1.0),
+            (0.5, 100.0, false, 1.0),
+            (0.5, 100.0, true, 1.0 / 99.5),
+        ];
+        let targets: Vec<RenderTarget> = views.iter().map(|_| app.image_target()).collect();
+        for (&(near, far, normalized_depth_threshold, depth_threshold), target) in
+            views.iter().zip(&targets)
+        {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app.world_mut().entity_mut(camera).insert((
+                Projection::from(PerspectiveProjection {
+                    near,
+                    far,
+                    ..default()
+                }),
+                EdgeDetection {
+                    depth_threshold,
+                    normalized_depth_threshold,
+                    enable_normal: false,
+                    enable_color: false,
+                    ..test_edge_detection()
+                },
+            ));
+            app.wait_for_pipeline(camera);
+        }
+
+        let rendered = app.read_back(&targets);
+        assert!(
+            rendered[0]
+                .chunks_exact(4)
+                .any(|pixel| pixel[..3] == [0; 3]),
+            "no edges were drawn"
+        );
+        for (index, pixels) in rendered.iter().enumerate().skip(1) {
+            let different_pixels = rendered[0]
+                .iter()
+                .zip(pixels)
+                .filter(|(expected, pixel)| expected.abs_diff(**pixel) > TOLERANCE)
+                .count();
+            assert_eq!(different_pixels, 0, "view {index} drew other edges");
+        }
+    }
+
+    /// The region overrides replace the thresholds and edge color of the pixels they contain,
+    /// the last one containing a pixel winning over the others.
+    #[test]
+    fn region_overrides_replace_settings() {
+        const GREEN: [u8; 3] = [0, 255, 0];
+
+        let mut app = RenderTestApp::new();
+        let targets = [app.image_target(), app.image_target()];
+        let region_overrides = [
+            Vec::new(),
+            vec![
+                EdgeRegionOverride {
+                    settings: EdgeRegionSettings {
+                        edge_color: Color::srgb(0.0, 1.0, 0.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                // Over the first one on the left half, with no edges.
+                EdgeRegionOverride {
+                    rect: Rect::new(0.0, 0.0, 0.5, 1.0),
+                    settings: EdgeRegionSettings {
+                        depth_threshold: f32::MAX,
+                        normal_threshold: f32::MAX,
+                        color_threshold: f32::MAX,
+                        ..default()
+                    },
+                },
+            ],
+        ];
+        for (region_overrides, target) in region_overrides.into_iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    region_overrides,
+                    ..test_edge_detection()
+                });
+            app.wait_for_pipeline(camera);
+        }
+
+        let rendered = app.read_back(&targets);
+        let mut overridden_edges = 0;
+        for (index, (expected, pixel)) in rendered[0]
+            .chunks_exact(4)
+            .zip(rendered[1].chunks_exact(4))
+            .enumerate()
+        {
+            let edge = expected[..3] == [0; 3];
+            let left = index % 64 < 32;
+            assert_ne!(
+                pixel[..3],
+                [0; 3],
+                "pixel {index} drew the edge color of the camera"
+            );
+            assert_eq!(
+                pixel[..3] == GREEN,
+                edge && !left,
+                "pixel {index} wasn't overridden"
+            );
+            overridden_edges += usize::from(pixel[..3] == GREEN);
+        }
+        assert!(overridden_edges > 0, "no edges were drawn");
+    }
+
+    /// Changing the operator respecializes the pipeline, and every operator outlines the scene
+    /// about as much as the Sobel kernel, their gradients being normalized alike.
+    #[test]
+    fn operators_respecialize_and_outline() {
+        let mut app = RenderTestApp::new();
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target: target.clone(),
+            ..default()
+        });
+
+        let mut pipelines = Vec::new();
+        let mut edge_counts = Vec::new();
+        for operator in [
+            EdgeDetectionOperator::Sobel,
+            EdgeDetectionOperator::Prewitt,
+            EdgeDetectionOperator::Scharr,
+            EdgeDetectionOperator::RobertsCross,
+        ] {
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    operator,
+                    ..test_edge_detection()
+                });
+            pipelines.push(app.wait_for_pipeline(camera).id);
+
+            let pixels = app.read_back(slice::from_ref(&target)).remove(0);
+            edge_counts.push(
+                pixels
+                    .chunks_exact(4)
+                    .filter(|pixel| pixel[..3] == [0; 3])
+                    .count(),
+            );
+        }
+
+        for (index, pipeline) in pipelines.iter().enumerate() {
+            assert!(
+                !pipelines[..index].contains(pipeline),
+                "operator {index} wasn't respecialized"
+            );
+        }
+        let sobel = edge_counts[0];
+        assert!(sobel > 0, "no edges were drawn");
+        for (index, &count) in edge_counts.iter().enumerate() {
+            assert!(
+                count >= sobel / 2 && count <= sobel * 2,
+                "operator {index} drew {count} edge pixels, Sobel {sobel}"
+            );
+        }
+    }
+
+    /// A thick outline of a thin pole skips the pixels between the pole and the samples of the kernel
+    /// with a single ring, and the larger kernels fill them in, also on multisampled views.
+    #[test]
+    fn kernel_radius_fills_thick_lines() {
+        let mut app = RenderTestApp::new();
+        let pole = app
+            .app
+            .world_mut()
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::new(0.05, 3.0, 0.05));
+        app.app.world_mut().spawn((
+            Mesh3d(pole),
+            MeshMaterial3d::<StandardMaterial>::default(),
+            Transform::from_xyz(-1.0, 0.0, 0.0),
+        ));
+
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target: target.clone(),
+            ..default()
+        });
+
+        let mut edge_counts = Vec::new();
+        for kernel_radius in [1, MAX_KERNEL_RADIUS] {
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    depth_thickness: 4.0,
+                    normal_thickness: 4.0,
+                    kernel_radius,
+                    ..test_edge_detection()
+                });
+            assert_eq!(
+                app.wait_for_pipeline(camera).key.kernel_radius,
+                kernel_radius
+            );
+
+            let pixels = app.read_back(slice::from_ref(&target)).remove(0);
+            edge_counts.push(
+                pixels
+                    .chunks_exact(4)
+                    .filter(|pixel| pixel[..3] == [0; 3])
+                    .count(),
+            );
+        }
+        assert!(
+            edge_counts[1] > edge_counts[0],
+            "the larger kernel drew {} edge pixels, the single ring {}",
+            edge_counts[1],
+            edge_counts[0]
+        );
+
+        // The rings sample the same textures, so the largest kernel validates with all the samples too.
+        if app
+            .app
+            .world()
+            .resource::<EdgeDetectionSupport>()
+            .max_samples
+            < 4
+        {
+            return;
+        }
+        app.app.world_mut().entity_mut(camera).insert((
+            Msaa::Sample4,
+            EdgeDetection {
+                depth_thickness: 4.0,
+                kernel_radius: MAX_KERNEL_RADIUS + 1,
+                all_samples: true,
+                ..test_edge_detection()
+            },
+        ));
+        let key = app.wait_for_pipeline(camera).key;
+        assert!(key.all_samples && key.samples == 4);
+        assert_eq!(key.kernel_radius, MAX_KERNEL_RADIUS);
+    }
+
+    /// A 2D camera outlines its meshes with the color source, without any prepass.
+    #[test]
+    fn core_2d_outlines_colors() {
+        let mut app = RenderTestApp::new();
+        let target = app.image_target();
+        let camera = app
+            .app
+            .world_mut()
+            .spawn((
+                Camera2d,
+                Camera {
+                    target: target.clone(),
+                    ..default()
+                },
+                Msaa::Off,
+                EdgeDetection {
+                    enable_color: true,
+                    ..default()
+                },
+            ))
+            .id();
+
+        let world = app.app.world_mut();
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Rectangle::new(20.0, 20.0));
+        let material = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(Color::WHITE);
+        world.spawn((Mesh2d(mesh), MeshMaterial2d(material)));
+
+        app.wait_for_pipeline(camera);
+        let pixels = app.read_back(slice::from_ref(&target)).remove(0);
+
+        // The rectangle spans the pixels 22 to 41 of the 64x64 target.
+        let is_edge = |x: usize, y: usize| pixels[(y * 64 + x) * 4..][..3] == [0; 3];
+        for y in 0..64 {
+            for x in 0..64 {
+                let near_border = [x, y].iter().all(|&coord| (20..44).contains(&coord))
+                    && ![x, y].iter().all(|&coord| (24..40).contains(&coord));
+                if is_edge(x, y) {
+                    assert!(near_border, "edge at {x}, {y}, away from the rectangle");
+                }
+            }
+        }
+        assert!(
+            (0..64).any(|x| is_edge(x, 32)),
+            "the rectangle wasn't outlined"
+        );
+    }
+
+    /// The dilation grows the edges in the mask without changing their color, and covers the edges
+    /// it started from, also when they're detected at a lower resolution.
+    #[test]
+    fn dilation_grows_the_edges() {
+        let mut app = RenderTestApp::new();
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target: target.clone(),
+            ..default()
+        });
+
+        let mut edge_pixels = Vec::new();
+        for (dilation, resolution_scale) in [(0, 1.0), (2, 1.0), (0, 0.5), (2, 0.5)] {
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    dilation,
+                    resolution_scale,
+                    ..test_edge_detection()
+                });
+            let key = app.wait_for_pipeline(camera).key;
+            assert_eq!(key.dilation, dilation > 0);
+            assert_eq!(key.edge_mask, dilation > 0 || resolution_scale < 1.0);
+
+            let pixels = app.read_back(slice::from_ref(&target)).remove(0);
+            // The edges upsampled from a lower resolution are blurred a little.
+            let is_edge = |pixel: &[u8]| pixel[..3].iter().all(|&channel| channel < 16);
+            edge_pixels.push(pixels.chunks_exact(4).map(is_edge).collect::<Vec<_>>());
+        }
+
+        let count = |edges: &[bool]| edges.iter().filter(|&&edge| edge).count();
+        let [thin, dilated, scaled_thin, scaled_dilated] = &edge_pixels[..] else {
+            unreachable!();
+        };
+        assert!(count(thin) > 0, "no edges were drawn");
+        assert!(
+            thin.iter()
+                .zip(dilated)
+                .all(|(&thin, &dilated)| !thin || dilated),
+            "the dilation dropped edge pixels"
+        );
+        assert!(
+            count(dilated) > count(thin) * 3 / 2,
+            "the dilation drew {} edge pixels, {} without",
+            count(dilated),
+            count(thin)
+        );
+        assert!(
+            count(scaled_dilated) > count(scaled_thin),
+            "the dilation drew {} edge pixels at half resolution, {} without",
+            count(scaled_dilated),
+            count(scaled_thin)
+        );
+    }
+
+    /// The temporal blend falls back to the edges of the frame on the cameras without motion vectors,
+    /// and the history of a still camera converges to the same edges.
+    #[test]
+    fn temporal_blend_needs_motion_vectors() {
+        let mut app = RenderTestApp::new();
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target: target.clone(),
+            ..default()
+        });
+        app.app
+            .world_mut()
+            .entity_mut(camera)
+            .insert(EdgeDetection {
+                temporal_blend: 0.5,
+                ..test_edge_detection()
+            });
+
+        let key = app.wait_for_pipeline(camera).key;
+        assert!(!key.temporal && !key.edge_mask);
+        let single_frame = app.read_back(slice::from_ref(&target)).remove(0);
+
+        app.app
+            .world_mut()
+            .entity_mut(camera)
+            .insert(bevy::core_pipeline::prepass::MotionVectorPrepass);
+        let key = app.wait_for_pipeline(camera).key;
+        assert!(key.temporal && key.edge_mask);
+        for _ in 0..10 {
+            app.update();
+        }
+        let stabilized = app.read_back(slice::from_ref(&target)).remove(0);
+
+        let is_edge = |pixel: &[u8]| pixel[..3].iter().all(|&channel| channel < 16);
+        let count = |pixels: &[u8]| {
+            pixels
+                .chunks_exact(4)
+                .filter(|pixel| is_edge(pixel))
+                .count()
+        };
+        assert!(count(&single_frame) > 0, "no edges were drawn");
+        assert!(
+            count(&stabilized).abs_diff(count(&single_frame)) <= count(&single_frame) / 20,
+            "the stabilized edges cover {} pixels, {} in a single frame",
+            count(&stabilized),
+            count(&single_frame)
+        );
+    }
+
+    #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderSubGraph)]
+    struct CustomGraph;
+
+    /// A port of the nodes of `Core3d` the edge detection runs between.
+    fn add_custom_graph(app: &mut App) {
+        let mut render_graph = app
+            .sub_app_mut(RenderApp)
+            .world_mut()
+            .resource_mut::<RenderGraph>();
+        let mut graph = RenderGraph::default();
+        for node in [
+            Node3d::EndPrepasses,
+            Node3d::StartMainPass,
+            Node3d::EndMainPass,
+            Node3d::Fxaa,
+        ] {
+            graph.add_node(node, EmptyNode);
+        }
+        render_graph.add_sub_graph(CustomGraph, graph);
+    }
+
+    /// The plugin is added once for `Core3d` and once for a custom graph, between custom nodes,
+    /// sharing everything but its nodes.
+    #[test]
+    fn plugin_adds_nodes_to_each_graph() {
+        let mut app = RenderTestApp::with_plugin((
+            EdgeDetectionPlugin::default(),
+            add_custom_graph,
+            EdgeDetectionPlugin {
+                graph: CustomGraph.intern(),
+                after: vec![Node3d::EndMainPass.intern()],
+                before_2d: None,
+                ..default()
+            },
+        ));
+
+        let render_graph = app.app.sub_app(RenderApp).world().resource::<RenderGraph>();
+        for (graph, after) in [
+            (Core3d.intern(), Node3d::PostProcessing.intern()),
+            (CustomGraph.intern(), Node3d::EndMainPass.intern()),
+        ] {
+            let graph = render_graph.get_sub_graph(graph).unwrap();
+            let edges = &graph.get_node_state(EdgeDetectionLabel).unwrap().edges;
+            assert!(edges
+                .input_edges()
+                .iter()
+                .any(|edge| edge.get_output_node() == after));
+            assert!(edges
+                .output_edges()
+                .iter()
+                .any(|edge| edge.get_input_node() == Node3d::Fxaa.intern()));
+            assert!(graph.get_node_state(EdgeOverlayLabel).is_ok());
+            assert!(graph.get_node_state(EdgeMeshTagLabel).is_ok());
+        }
+
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+        app.wait_for_pipeline(camera);
+    }
+
+    /// The edges detected after the TAA resolve undo the jitter of the prepass textures, so they don't wobble
+    /// over a still scene, and the ones detected before the resolve run ahead of the TAA node and keep it.
+    /// The ones detected before the bloom run right after the TAA node.
+    #[test]
+    fn taa_jitter_follows_the_placement() {
+        for placement in [
+            EdgeDetectionPlacement::PostProcess,
+            EdgeDetectionPlacement::BeforeTaa,
+            EdgeDetectionPlacement::BeforeBloom,
+        ] {
+            let mut app = RenderTestApp::with_plugin((
+                EdgeDetectionPlugin {
+                    placement,
+                    ..default()
+                },
+                TemporalAntiAliasPlugin,
+            ));
+
+            let taa_edges = &app
+                .app
+                .sub_app(RenderApp)
+                .world()
+                .resource::<RenderGraph>()
+                .get_sub_graph(Core3d)
+                .unwrap()
+                .get_node_state(Node3d::Taa)
+                .unwrap()
+                .edges;
+            let before_taa = taa_edges
+                .input_edges()
+                .iter()
+                .any(|edge| edge.get_output_node() == EdgeDetectionLabel.intern());
+            let after_taa = taa_edges
+                .output_edges()
+                .iter()
+                .any(|edge| edge.get_input_node() == EdgeDetectionLabel.intern());
+            assert_eq!(before_taa, placement == EdgeDetectionPlacement::BeforeTaa);
+            assert_eq!(after_taa, placement == EdgeDetectionPlacement::BeforeBloom);
+
+            let target = app.image_target();
+            let camera = app.spawn_camera(Camera {
+                target,
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(TemporalAntiAliasing::default());
+            app.wait_for_pipeline(camera);
+
+            for _ in 0..4 {
+                app.update();
+                let render_entity = app.app.world().get::<RenderEntity>(camera).unwrap().id();
+                let render_world = app.app.sub_app(RenderApp).world();
+                let jitter = render_world
+                    .get::<EdgeDetectionUniform>(render_entity)
+                    .unwrap()
+                    .jitter;
+                let offset = render_world
+                    .get::<TemporalJitter>(render_entity)
+                    .unwrap()
+                    .offset;
+                match placement {
+                    EdgeDetectionPlacement::BeforeTaa => assert_eq!(jitter, Vec2::ZERO),
+                    _ => assert_eq!(jitter, offset),
+                }
+            }
+        }
+    }
+
+    /// The blueprint mode draws the edges over a flat background, and the inverted mode draws the edge color
+    /// everywhere but on the edges, where the scene shows through.
+    #[test]
+    fn blueprint_and_inverted_output_modes() {
+        let output_modes = [
+            None,
+            Some(OutputMode::Edges),
+            Some(OutputMode::Blueprint {
+                background: Color::linear_rgb(0.0, 0.0, 1.0),
+            }),
+            Some(OutputMode::Inverted),
+        ];
+
+        let mut app = RenderTestApp::new();
+        let targets: Vec<_> = output_modes.iter().map(|_| app.image_target()).collect();
+        for (&output_mode, target) in output_modes.iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            let mut entity = app.app.world_mut().entity_mut(camera);
+            match output_mode {
+                Some(output_mode) => entity.insert(EdgeDetection {
+                    edge_color: Color::srgb(1.0, 0.0, 0.0),
+                    output_mode,
+                    ..test_edge_detection()
+                }),
+                None => entity.remove::<EdgeDetection>(),
+            };
+            if output_mode.is_some() {
+                app.wait_for_pipeline(camera);
+            }
+        }
+
+        let [scene, edges, blueprint, inverted] = app.read_back(&targets).try_into().unwrap();
+        let is_red = |pixel: &[u8]| pixel[0] > 250 && pixel[1] < 5 && pixel[2] < 5;
+
+        // Only the red edges blended over the blue background, none of the scene.
+        assert!(blueprint.chunks_exact(4).any(is_red));
+        assert!(blueprint
+            .chunks_exact(4)
+            .any(|pixel| pixel[..3] == [0, 0, 255]));
+        assert!(blueprint.chunks_exact(4).all(|pixel| pixel[1] == 0));
+
+        let mut edge_pixels = 0;
+        for ((scene, edges), inverted) in scene
+            .chunks_exact(4)
+            .zip(edges.chunks_exact(4))
+            .zip(inverted.chunks_exact(4))
+        {
+            if edges[..3] == [255, 0, 0] {
+                edge_pixels += 1;
+                assert_eq!(
+                    inverted[..3],
+                    scene[..3],
+                    "the scene wasn't drawn on the edges"
+                );
+            }
+        }
+        assert!(edge_pixels > 0, "no edges were drawn");
+        assert!(
+            inverted
+                .chunks_exact(4)
+                .filter(|pixel| is_red(pixel))
+                .count()
+                > inverted.len() / 4 / 8,
+            "the edge color wasn't drawn around the edges"
+        );
+    }
+
+    /// The edge colors scaled above 1.0 are clamped on the LDR targets, a linear gray scaled by 4.0 being drawn white.
+    #[test]
+    fn emissive_edges_clamp_on_ldr_targets() {
+        let settings = [(Color::WHITE, 1.0), (Color::linear_rgb(0.5, 0.5, 0.5), 4.0)];
+
+        let mut app = RenderTestApp::new();
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        for (&(edge_color, edge_emissive_strength), target) in settings.iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    edge_color,
+                    edge_emissive_strength,
+                    ..test_edge_detection()
+                });
+            app.wait_for_pipeline(camera);
+        }
+
+        let [white, emissive] = app.read_back(&targets).try_into().unwrap();
+        assert!(white.chunks_exact(4).any(|pixel| pixel[..3] == [255; 3]));
+        assert_eq!(emissive, white, "the emissive edges weren't clamped");
+    }
+
+    /// A custom noise texture replaces the embedded noise: a black one doesn't distort the edges at all,
+    /// whatever its size, and the embedded noise is used until it's loaded.
+    #[test]
+    fn custom_noise_texture_replaces_embedded_noise() {
+        const UNLOADED_NOISE: Handle<Image> = weak_handle!("3f0c8b71-26d4-4e9a-b5f2-7a1d9c04e8b3");
+
+        let mut app = RenderTestApp::new();
+        let black_noise = app
+            .app
+            .world_mut()
+            .resource_mut::<Assets<Image>>()
+            .add(Image::new_fill(
+                Extent3d {
+                    width: 4,
+                    height: 2,
+                    ..default()
+                },
+                TextureDimension::D2,
+                &[0; 4],
+                TextureFormat::Rgba8Unorm,
+                RenderAssetUsages::default(),
+            ));
+
+        let settings = [
+            (Vec2::ZERO, None),
+            (Vec2::splat(0.05), Some(black_noise)),
+            (Vec2::splat(0.05), None),
+            (Vec2::splat(0.05), Some(UNLOADED_NOISE)),
+        ];
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        for ((uv_distortion_strength, noise_texture), target) in settings.into_iter().zip(&targets)
+        {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    uv_distortion_strength,
+                    noise_texture,
+                    ..test_edge_detection()
+                });
+            app.wait_for_pipeline(camera);
+        }
+
+        let [undistorted, black_noise, embedded_noise, unloaded_noise] =
+            app.read_back(&targets).try_into().unwrap();
+        assert!(undistorted
+            .chunks_exact(4)
+            .any(|pixel| pixel[..3] == [0; 3]));
+        assert_eq!(
+            black_noise, undistorted,
+            "the black noise distorted the edges"
+        );
+        assert_ne!(
+            embedded_noise, undistorted,
+            "the embedded noise didn't distort the edges"
+        );
+        assert_eq!(
+            unloaded_noise, embedded_noise,
+            "the embedded noise wasn't used in place of the unloaded one"
+        );
+    }
+
+    /// The blend modes combine the edge color with the scene, and the intensity fades the effect out,
+    /// down to the untouched scene at zero where the pass is skipped.
+    #[test]
+    fn blend_modes_and_intensity() {
+        let no_edges = EdgeDetection {
+            depth_threshold: f32::MAX,
+            normal_threshold: f32::MAX,
+            ..test_edge_detection()
+        };
+        let settings = [
+            no_edges,
+            test_edge_detection(),
+            EdgeDetection {
+                intensity: 0.0,
+                ..test_edge_detection()
+            },
+            EdgeDetection {
+                intensity: 0.5,
+                ..test_edge_detection()
+            },
+            EdgeDetection {
+                blend_mode: EdgeBlendMode::Multiply,
+                edge_color: Color::WHITE,
+                ..test_edge_detection()
+            },
+            EdgeDetection {
+                blend_mode: EdgeBlendMode::Additive,
+                edge_color: Color::srgb(0.5, 0.5, 0.5),
+                ..test_edge_detection()
+            },
+            EdgeDetection {
+                blend_mode: EdgeBlendMode::Replace,
+                ..test_edge_detection()
+            },
+        ];
+
+        let mut app = RenderTestApp::new();
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        for (edge_detection, target) in settings.into_iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(edge_detection);
+            app.wait_for_pipeline(camera);
+        }
+
+        let [scene, edges, faded, half, multiply, additive, replace] =
+            app.read_back(&targets).try_into().unwrap();
+        let brightness = |pixel: &[u8]| {
+            pixel[..3]
+                .iter()
+                .map(|&channel| channel as u32)
+                .sum::<u32>()
+        };
+
+        assert_eq!(faded, scene, "the faded out edges changed the scene");
+        assert_eq!(multiply, scene, "multiplying by white changed the scene");
+
+        let mut edge_pixels = 0;
+        for (index, pixel) in scene.chunks_exact(4).enumerate() {
+            let texel = index * 4..index * 4 + 4;
+            assert!(
+                additive[texel.clone()][..3]
+                    .iter()
+                    .zip(&pixel[..3])
+                    .all(|(added, scene)| added >= scene),
+                "pixel {index} was darkened by the additive edges"
+            );
+            assert!(
+                replace[texel.clone()] == *pixel || replace[texel.clone()][..3] == [0; 3],
+                "pixel {index} was blended by the replacing edges"
+            );
+
+            if edges[texel.clone()][..3] == [0; 3] && brightness(pixel) > 60 {
+                edge_pixels += 1;
+                let half = brightness(&half[texel.clone()]);
+                assert!(
+                    0 < half && half < brightness(pixel),
+                    "pixel {index} wasn't faded halfway"
+                );
+                assert!(brightness(&additive[texel]) > brightness(pixel));
+            }
+        }
+        assert!(edge_pixels > 0, "no edges were drawn");
+    }
+
+    /// The edges fade out with the view distance, the cube being about 3 units away from the camera.
+    #[test]
+    fn edges_fade_with_distance() {
+        let fades = [
+            // No edges at all.
+            (0.0, f32::MIN_POSITIVE),
+            (0.0, 0.0),
+            (10.0, 20.0),
+            (0.5, 1.0),
+            (2.0, 8.0),
+        ];
+
+        let mut app = RenderTestApp::new();
+        let targets: Vec<_> = fades.iter().map(|_| app.image_target()).collect();
+        for (&(fade_start, fade_end), target) in fades.iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    fade_start,
+                    fade_end,
+                    ..test_edge_detection()
+                });
+            app.wait_for_pipeline(camera);
+        }
+
+        let [scene, edges, before_start, beyond_end, partial] =
+            app.read_back(&targets).try_into().unwrap();
+        assert!(edges.chunks_exact(4).any(|pixel| pixel[..3] == [0; 3]));
+        assert_ne!(edges, scene);
+        assert_eq!(before_start, edges, "the edges faded before the fade start");
+        assert_eq!(
+            beyond_end, scene,
+            "the edges were drawn beyond the fade end"
+        );
+        assert!(
+            partial != edges && partial != scene,
+            "the edges didn't fade partially"
+        );
+    }
+
+    /// The edges are limited to the depth window, the cube being about 3 units away from the camera,
+    /// on both perspective and orthographic views.
+    #[test]
+    fn edges_limited_to_depth_window() {
+        let orthographic = Projection::from(OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical {
+                viewport_height: 3.0,
+            },
+            ..OrthographicProjection::default_3d()
+        });
+        let settings = [
+            // No edges at all.
+            (Projection::default(), 0.0, f32::MIN_POSITIVE),
+            (Projection::default(), 0.0, f32::INFINITY),
+            (Projection::default(), 1.0, 10.0),
+            (Projection::default(), 5.0, f32::INFINITY),
+            (Projection::default(), 0.0, 1.0),
+            (orthographic.clone(), 0.0, f32::MIN_POSITIVE),
+            (orthographic.clone(), 0.0, f32::INFINITY),
+            (orthographic, 5.0, f32::INFINITY),
+        ];
+
+        let mut app = RenderTestApp::new();
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        for ((projection, depth_range_min, depth_range_max), target) in
+            settings.into_iter().zip(&targets)
+        {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app.world_mut().entity_mut(camera).insert((
+                projection,
+                EdgeDetection {
+                    depth_range_min,
+                    depth_range_max,
+                    ..test_edge_detection()
+                },
+            ));
+            app.wait_for_pipeline(camera);
+        }
+
+        let [scene, edges, within, beyond_min, beyond_max, orthographic_scene, orthographic_edges, orthographic_beyond_min] =
+            app.read_back(&targets).try_into().unwrap();
+        assert!(edges.chunks_exact(4).any(|pixel| pixel[..3] == [0; 3]));
+        assert_ne!(edges, scene);
+        assert_eq!(within, edges, "the edges were cut inside of the window");
+        assert_eq!(beyond_min, scene, "the edges were drawn before the window");
+        assert_eq!(beyond_max, scene, "the edges were drawn beyond the window");
+
+        assert_ne!(orthographic_edges, orthographic_scene);
+        assert_eq!(
+            orthographic_beyond_min, orthographic_scene,
+            "the edges were drawn before the window of the orthographic view"
+        );
+    }
+
+    /// The edges are multiplied by the screen mask, which spans the viewport whatever its resolution.
+    /// The halved mask has a texel per pixel, so it's read as is with or without float filtering.
+    #[test]
+    fn edges_multiplied_by_screen_mask() {
+        let mut app = RenderTestApp::new();
+        let mut mask = |pixels: &[[u8; 4]]| {
+            let image = Image::new(
+                Extent3d {
+                    width: pixels.len() as u32,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                pixels.concat(),
+                TextureFormat::Rgba8Unorm,
+                RenderAssetUsages::default(),
+            );
+            app.app
+                .world_mut()
+                .resource_mut::<Assets<Image>>()
+                .add(image)
+        };
+        let screen_masks = [
+            None,
+            Some(mask(
+                &[[[0, 0, 0, 255]; 32], [[255, 255, 255, 255]; 32]].concat(),
+            )),
+            Some(mask(&[[0, 0, 0, 255]])),
+        ];
+
+        let targets: Vec<_> = screen_masks.iter().map(|_| app.image_target()).collect();
+        for (screen_mask, target) in screen_masks.into_iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    screen_mask,
+                    ..test_edge_detection()
+                });
+            app.wait_for_pipeline(camera);
+        }
+
+        let [edges, half, scene] = app.read_back(&targets).try_into().unwrap();
+        // The pixels of the left and right halves of the 64x64 targets.
+        let half_pixels = |pixels: &[u8], right: bool| -> Vec<u8> {
+            pixels
+                .chunks_exact(4)
+                .enumerate()
+                .filter(|(index, _)| (index % 64 >= 32) == right)
+                .flat_map(|(_, pixel)| pixel.to_vec())
+                .collect()
+        };
+        for right in [false, true] {
+            assert_ne!(half_pixels(&edges, right), half_pixels(&scene, right));
+        }
+        assert_eq!(
+            half_pixels(&half, false),
+            half_pixels(&scene, false),
+            "the edges were drawn under the black half of the mask"
+        );
+        assert_eq!(
+            half_pixels(&half, true),
+            half_pixels(&edges, true),
+            "the edges were cut under the white half of the mask"
+        );
+    }
+
+    /// Without softness, every pixel is either the scene or the opaque edge color, while a soft threshold
+    /// blends partial edges into the scene.
+    #[test]
+    fn soft_thresholds_draw_partial_edges() {
+        let settings = [(0.0, 0.0), (1.0, 0.0), (1.0, 0.4)];
+
+        let mut app = RenderTestApp::new();
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        for (&(intensity, color_threshold_softness), target) in settings.iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    enable_depth: false,
+                    enable_normal: false,
+                    enable_color: true,
+                    color_threshold: 0.5,
+                    color_threshold_softness,
+                    intensity,
+                    ..default()
+                });
+            app.wait_for_pipeline(camera);
+        }
+
+        let [scene, hard, soft] = app.read_back(&targets).try_into().unwrap();
+        // Whether the pixels are neither the scene nor the black edges.
+        let partial = |pixels: &[u8]| {
+            pixels
+                .chunks_exact(4)
+                .zip(scene.chunks_exact(4))
+                .filter(|(pixel, scene)| pixel != scene && pixel[..3] != [0; 3])
+                .count()
+        };
+        assert!(hard.chunks_exact(4).any(|pixel| pixel[..3] == [0; 3]));
+        assert_eq!(partial(&hard), 0, "the hard edges were blended");
+        assert!(partial(&soft) > 0, "the soft edges weren't blended");
+    }
+
+    /// The edges shade into the far color with the view distance, the cube being about 3 units away from
+    /// the camera, on both perspective and orthographic views.
+    #[test]
+    fn edge_color_shades_with_distance() {
+        let orthographic = Projection::from(OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical {
+                viewport_height: 3.0,
+            },
+            ..OrthographicProjection::default_3d()
+        });
+        let red = Color::srgb(1.0, 0.0, 0.0);
+        let settings = [
+            (Projection::default(), None, 0.5, 1.0),
+            (Projection::default(), Some(red), 10.0, 20.0),
+            (Projection::default(), Some(red), 0.5, 1.0),
+            (Projection::default(), Some(red), 2.0, 8.0),
+            (orthographic, Some(red), 0.5, 1.0),
+        ];
+
+        let mut app = RenderTestApp::new();
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        for ((projection, edge_color_far, color_gradient_start, color_gradient_end), target) in
+            settings.into_iter().zip(&targets)
+        {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app.world_mut().entity_mut(camera).insert((
+                projection,
+                EdgeDetection {
+                    edge_color_far,
+                    color_gradient_start,
+                    color_gradient_end,
+                    ..test_edge_detection()
+                },
+            ));
+            app.wait_for_pipeline(camera);
+        }
+
+        let [uniform, before_start, beyond_end, partial, orthographic] =
+            app.read_back(&targets).try_into().unwrap();
+        let is_black = |pixel: &[u8]| pixel[..3] == [0; 3];
+        let is_red = |pixel: &[u8]| pixel[0] > 250 && pixel[1] < 5 && pixel[2] < 5;
+
+        assert!(uniform.chunks_exact(4).any(is_black));
+        assert_eq!(
+            before_start, uniform,
+            "the edges shaded before the gradient start"
+        );
+        for pixels in [&beyond_end, &orthographic] {
+            assert!(pixels.chunks_exact(4).any(is_red));
+            assert!(
+                !pixels.chunks_exact(4).any(is_black),
+                "the edges weren't shaded beyond the gradient end"
+            );
+        }
+        assert!(
+            partial != uniform && partial != beyond_end,
+            "the edges didn't shade partially"
+        );
+    }
+
+    /// The hatching breaks the edges into strokes, drawing only some of their pixels.
+    #[test]
+    fn hatching_breaks_edges_into_strokes() {
+        let hatchings = [None, Some(HatchingSettings::default())];
+
+        let mut app = RenderTestApp::new();
+        let targets: Vec<_> = hatchings.iter().map(|_| app.image_target()).collect();
+        for (&hatching, target) in hatchings.iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    hatching,
+                    ..test_edge_detection()
+                });
+            let pipeline_id = app.wait_for_pipeline(camera);
+            assert_eq!(pipeline_id.key.hatching, hatching.is_some());
+        }
+
+        let [lines, strokes] = app.read_back(&targets).try_into().unwrap();
+        let is_edge = |pixel: &[u8]| pixel[..3] == [0; 3];
+        let edge_pixels = |pixels: &[u8]| {
+            pixels
+                .chunks_exact(4)
+                .filter(|pixel| is_edge(pixel))
+                .count()
+        };
+        assert!(
+            0 < edge_pixels(&strokes) && edge_pixels(&strokes) < edge_pixels(&lines),
+            "the edges weren't broken into strokes"
+        );
+        assert!(lines
+            .chunks_exact(4)
+            .zip(strokes.chunks_exact(4))
+            .all(|(line, stroke)| !is_edge(stroke) || is_edge(line)));
+    }
+
+    /// The edges thin out with the view distance, beyond the reference distance and down to one texel,
+    /// except on orthographic views. The cube is about 3 units away from the camera.
+    #[test]
+    fn thickness_attenuates_with_distance() {
+        let orthographic = Projection::from(OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical {
+                viewport_height: 3.0,
+            },
+            ..OrthographicProjection::default_3d()
+        });
+        let settings = [
+            (Projection::default(), 0.0, 1.0),
+            (Projection::default(), 1.0, 1.0),
+            (Projection::default(), 1.0, 100.0),
+            (orthographic.clone(), 0.0, 1.0),
+            (orthographic, 1.0, 1.0),
+        ];
+
+        let mut app = RenderTestApp::new();
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        for ((projection, thickness_attenuation, thickness_reference_distance), target) in
+            settings.into_iter().zip(&targets)
+        {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app.world_mut().entity_mut(camera).insert((
+                projection,
+                EdgeDetection {
+                    depth_thickness: 3.0,
+                    normal_thickness: 3.0,
+                    thickness_attenuation,
+                    thickness_reference_distance,
+                    ..test_edge_detection()
+                },
+            ));
+            app.wait_for_pipeline(camera);
+        }
+
+        let [constant, attenuated, before_reference, orthographic, orthographic_attenuated] =
+            app.read_back(&targets).try_into().unwrap();
+        let edge_pixels = |pixels: &[u8]| {
+            pixels
+                .chunks_exact(4)
+                .filter(|pixel| pixel[..3] == [0; 3])
+                .count()
+        };
+        assert!(
+            0 < edge_pixels(&attenuated) && edge_pixels(&attenuated) < edge_pixels(&constant),
+            "the edges didn't thin out"
+        );
+        assert_eq!(
+            before_reference, constant,
+            "the edges thinned out before the reference distance"
+        );
+        assert_eq!(
+            orthographic_attenuated, orthographic,
+            "the orthographic edges thinned out"
+        );
+    }
+
+    /// The creases between the faces of the cube are convex, so they're drawn with the convex normal threshold.
+    #[test]
+    fn convex_and_concave_normal_thresholds() {
+        let no_edges = EdgeDetection {
+            enable_normal: false,
+            ..test_edge_detection()
+        };
+        let settings = [
+            no_edges,
+            test_edge_detection(),
+            EdgeDetection {
+                normal_threshold_convex: Some(f32::MAX),
+                ..test_edge_detection()
+            },
+            EdgeDetection {
+                normal_threshold_concave: Some(f32::MAX),
+                ..test_edge_detection()
+            },
+        ];
+
+        let mut app = RenderTestApp::new();
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        for (edge_detection, target) in settings.into_iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    // Only the normal edges are drawn.
+                    enable_depth: false,
+                    ..edge_detection
+                });
+            app.wait_for_pipeline(camera);
+        }
+
+        let [scene, edges, no_convex, no_concave] = app.read_back(&targets).try_into().unwrap();
+        let background = &scene[..4];
+        let pixel = |pixels: &[u8], x: i32, y: i32| -> [u8; 4] {
+            let index = (y.clamp(0, 63) * 64 + x.clamp(0, 63)) as usize * 4;
+            pixels[index..index + 4].try_into().unwrap()
+        };
+        // The creases inside the silhouette of the cube, away from the background.
+        let creases: Vec<_> = (0..64)
+            .flat_map(|y| (0..64).map(move |x| (x, y)))
+            .filter(|&(x, y)| {
+                pixel(&edges, x, y)[..3] == [0; 3]
+                    && (-2..=2)
+                        .all(|dy| (-2..=2).all(|dx| pixel(&scene, x + dx, y + dy) != background))
+            })
+            .collect();
+        assert!(!creases.is_empty(), "no creases were drawn");
+        for (x, y) in creases {
+            assert_eq!(
+                pixel(&no_convex, x, y),
+                pixel(&scene, x, y),
+                "the convex crease at {x}, {y} was drawn"
+            );
+            assert_eq!(
+                pixel(&no_concave, x, y),
+                [0, 0, 0, 255],
+                "the convex crease at {x}, {y} wasn't drawn"
+            );
+        }
+    }
+
+    /// With one-sided depth edges, the cube in front of a wall is outlined on its side only.
+    #[test]
+    fn one_sided_depth_edges_skip_the_surface_behind() {
+        let mut app = RenderTestApp::new();
+        let world = app.app.world_mut();
+        let wall = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::new(20.0, 20.0, 0.1));
+        let red = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                base_color: Color::srgb(1.0, 0.0, 0.0),
+                unlit: true,
+                ..default()
+            });
+        world.spawn((
+            Mesh3d(wall),
+            MeshMaterial3d(red),
+            Transform::from_xyz(0.0, 0.0, -2.0),
+        ));
+
+        let settings = [
+            EdgeDetection {
+                enable_depth: false,
+                ..test_edge_detection()
+            },
+            test_edge_detection(),
+            EdgeDetection {
+                one_sided_depth_edges: true,
+                ..test_edge_detection()
+            },
+        ];
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        for (edge_detection, target) in settings.into_iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    // Only the depth edges are drawn.
+                    enable_normal: false,
+                    ..edge_detection
+                });
+            app.wait_for_pipeline(camera);
+        }
+
+        let [scene, two_sided, one_sided] = app.read_back(&targets).try_into().unwrap();
+        let mut wall_edges = 0;
+        let mut cube_edges = 0;
+        for ((scene, two_sided), one_sided) in scene
+            .chunks_exact(4)
+            .zip(two_sided.chunks_exact(4))
+            .zip(one_sided.chunks_exact(4))
+        {
+            let on_wall = scene[0] > 200 && scene[1] < 50;
+            let edge = one_sided[..3] == [0; 3];
+            if on_wall {
+                wall_edges += usize::from(two_sided[..3] == [0; 3]);
+                assert!(!edge, "the wall behind the cube was outlined");
+            } else {
+                cube_edges += usize::from(edge);
+                assert_eq!(edge, two_sided[..3] == [0; 3], "the cube lost its outline");
+            }
+        }
+        assert!(
+            wall_edges > 0,
+            "the two-sided edges weren't drawn on the wall"
+        );
+        assert!(cube_edges > 0, "the cube wasn't outlined");
+    }
+
+    /// The luminance ignores the edges between colors of the same brightness, which the other spaces outline,
+    /// and every space outlines a change of brightness.
+    #[test]
+    fn color_spaces_measure_the_gradients() {
+        let mut app = RenderTestApp::new();
+        let world = app.app.world_mut();
+        let square = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Rectangle::new(16.0, 16.0));
+        // As bright as the red background.
+        let green = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(Color::linear_rgb(0.0, 0.2126 / 0.7152, 0.0));
+        let white = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(Color::WHITE);
+        world.spawn((
+            Mesh2d(square.clone()),
+            MeshMaterial2d(green),
+            Transform::from_xyz(-14.0, 0.0, 0.0),
+        ));
+        world.spawn((
+            Mesh2d(square),
+            MeshMaterial2d(white),
+            Transform::from_xyz(14.0, 0.0, 0.0),
+        ));
+
+        let spaces = [
+            ColorEdgeSpace::LinearRgb,
+            ColorEdgeSpace::Luminance,
+            ColorEdgeSpace::OkLab,
+        ];
+        let targets: Vec<_> = spaces.iter().map(|_| app.image_target()).collect();
+        let mut cameras = Vec::new();
+        for (color_space, target) in spaces.into_iter().zip(&targets) {
+            cameras.push(
+                app.app
+                    .world_mut()
+                    .spawn((
+                        Camera2d,
+                        Camera {
+                            target: target.clone(),
+                            clear_color: ClearColorConfig::Custom(Color::linear_rgb(1.0, 0.0, 0.0)),
+                            ..default()
+                        },
+                        Msaa::Off,
+                        DebandDither::Disabled,
+                        EdgeDetection {
+                            enable_color: true,
+                            color_space,
+                            ..default()
+                        },
+                    ))
+                    .id(),
+            );
+        }
+        for camera in cameras {
+            app.wait_for_pipeline(camera);
+        }
+        let [linear_rgb, luminance, oklab] = app.read_back(&targets).try_into().unwrap();
+
+        // The green square spans the pixels 18 to 33 of the 64x64 target, the white one 46 to 61.
+        let edges = |pixels: &[u8], columns: Range<usize>| {
+            columns
+                .filter(|&x| pixels[(32 * 64 + x) * 4..][..3] == [0; 3])
+                .count()
+        };
+        for (name, pixels) in [("linear RGB", &linear_rgb), ("OkLab", &oklab)] {
+            assert!(
+                edges(pixels, 0..32) > 0,
+                "{name} didn't outline the green square"
+            );
+        }
+        assert_eq!(
+            edges(&luminance, 0..32),
+            0,
+            "the luminance outlined the green square"
+        );
+        for (name, pixels) in [
+            ("linear RGB", &linear_rgb),
+            ("luminance", &luminance),
+            ("OkLab", &oklab),
+        ] {
+            assert!(
+                edges(pixels, 32..64) > 0,
+                "{name} didn't outline the white square"
+            );
+        }
+    }
+
+    /// The mask output holds the edges of the view even when they aren't drawn,
+    /// and is only created for the cameras with an `EdgeDetectionMaskOutput`.
+    #[test]
+    fn mask_output_holds_the_edges() {
+        let mut app = RenderTestApp::new();
+        let settings = [
+            (
+                EdgeDetection {
+                    intensity: 0.0,
+                    ..test_edge_detection()
+                },
+                true,
+            ),
+            (test_edge_detection(), false),
+        ];
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        let mut cameras = Vec::new();
+        for ((edge_detection, mask_output), target) in settings.into_iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            let mut entity = app.app.world_mut().entity_mut(camera);
+            entity.insert(edge_detection);
+            if mask_output {
+                entity.insert(EdgeDetectionMaskOutput);
+            }
+            cameras.push(camera);
+        }
+        for &camera in &cameras {
+            app.wait_for_pipeline(camera);
+        }
+        let [undrawn, drawn] = app.read_back(&targets).try_into().unwrap();
+
+        let render_entity =
+            |app: &RenderTestApp, camera| app.app.world().get::<RenderEntity>(camera).unwrap().id();
+        let render_world = app.app.sub_app(RenderApp).world();
+        assert!(render_world
+            .get::<ViewEdgeMaskTexture>(render_entity(&app, cameras[1]))
+            .is_none());
+        let texture = &render_world
+            .get::<ViewEdgeMaskTexture>(render_entity(&app, cameras[0]))
+            .expect("the mask output wasn't created")
+            .0
+            .texture;
+        assert_eq!(texture.format(), EDGE_MASK_OUTPUT_FORMAT);
+        assert_eq!((texture.width(), texture.height()), (64, 64));
+
+        // The rows of the copy are aligned to 256 bytes.
+        let render_device = app.app.world().resource::<RenderDevice>();
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: None,
+            size: 256 * 64,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = render_device.create_command_encoder(&default());
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(256),
+                    rows_per_image: None,
+                },
+            },
+            texture.size(),
+        );
+        app.app
+            .world()
+            .resource::<RenderQueue>()
+            .submit([encoder.finish()]);
+        let slice = buffer.slice(..);
+        render_device.map_buffer(&slice, MapMode::Read, |result| result.unwrap());
+        render_device.poll(Maintain::Wait);
+        let mask: Vec<u8> = slice
+            .get_mapped_range()
+            .chunks(256)
+            .flat_map(|row| row[..64].to_vec())
+            .collect();
+
+        let mut edges = 0;
+        for (pixel, &mask) in mask.iter().enumerate() {
+            let edge = drawn[pixel * 4..][..3] == [0; 3];
+            edges += usize::from(edge);
+            assert_eq!(
+                mask == 255,
+                edge,
+                "the mask differs from the edges at {pixel}"
+            );
+            assert_ne!(
+                undrawn[pixel * 4..][..3],
+                [0; 3],
+                "an edge was drawn at {pixel}"
+            );
+        }
+        assert!(edges > 0, "the cube wasn't outlined");
+
+        // Removing the component drops the mask.
+        app.app
+            .world_mut()
+            .entity_mut(cameras[0])
+            .remove::<EdgeDetectionMaskOutput>();
+        app.wait_for_pipeline(cameras[0]);
+        assert!(app
+            .app
+            .sub_app(RenderApp)
+            .world()
+            .get::<ViewEdgeMaskTexture>(render_entity(&app, cameras[0]))
+            .is_none());
+    }
+
+    /// The edge mask of a camera with an `EdgeDetectionReadback` is sent to the main world, with the cube outlined.
+    #[test]
+    fn edge_mask_read_back() {
+        let mut app = RenderTestApp::new();
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+        app.app
+            .world_mut()
+            .entity_mut(camera)
+            .insert(EdgeDetectionReadback);
+        assert!(app
+            .app
+            .world()
+            .entity(camera)
+            .contains::<EdgeDetectionMaskOutput>());
+        app.wait_for_pipeline(camera);
+
+        let readbacks = Arc::new(Mutex::new(Vec::new()));
+        let sent = readbacks.clone();
+        app.app
+            .add_systems(Update, move |mut events: EventReader<EdgeMaskReadback>| {
+                sent.lock().unwrap().extend(events.read().cloned());
+            });
+        for _ in 0..100 {
+            app.update();
+            if !readbacks.lock().unwrap().is_empty() {
+                break;
+            }
+        }
+
+        let readbacks = readbacks.lock().unwrap();
+        let readback = readbacks.first().expect("the edge mask wasn't read back");
+        assert_eq!(readback.entity, camera);
+        assert_eq!((readback.width, readback.height), (64, 64));
+        assert_eq!(readback.data.len(), 64 * 64);
+        assert!(
+            readback.data.iter().any(|&strength| strength > 0),
+            "the cube wasn't outlined"
+        );
+        assert_eq!(readback.get(UVec2::new(64, 0)), None);
+    }
+
+    /// With the render diagnostics, the edge detection and its passes are measured in their own spans.
+    #[test]
+    fn edge_detection_diagnostic_spans() {
+        let mut app =
+            RenderTestApp::with_plugin((EdgeDetectionPlugin::default(), RenderDiagnosticsPlugin));
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+        app.wait_for_pipeline(camera);
+
+        // The software adapters running the tests have no timestamp queries, only the CPU time is measured.
+        let diagnostics = app.app.world().resource::<DiagnosticsStore>();
+        for path in [
+            "render/edge_detection/elapsed_cpu",
+            "render/edge_detection/detection/elapsed_cpu",
+        ] {
+            assert!(
+                diagnostics
+                    .get(&DiagnosticPath::new(path))
+                    .is_some_and(|diagnostic| diagnostic.measurement().is_some()),
+                "{path} wasn't measured"
+            );
+        }
+    }
+
+    /// The numbers and colors are interpolated, the switches flip halfway, and the transition removes itself
+    /// once the camera has the target settings.
+    #[test]
+    fn edge_detection_transitions() {
+        let subtle = EdgeDetection::default();
+        let dramatic = EdgeDetection {
+            depth_threshold: 3.0,
+            uv_distortion_strength: Vec2::splat(0.01),
+            edge_color: Color::WHITE,
+            enable_color: true,
+            thick_outline: Some(ThickOutline::default()),
+            ..default()
+        };
+
+        let start = subtle.lerp(&dramatic, 0.0);
+        assert_eq!(start.depth_threshold, subtle.depth_threshold);
+        assert!(!start.enable_color);
+
+        let before_halfway = subtle.lerp(&dramatic, 0.25);
+        assert_eq!(before_halfway.depth_threshold, 1.5);
+        assert!(before_halfway
+            .uv_distortion_strength
+            .abs_diff_eq(Vec2::splat(0.0055), 1e-6));
+        assert!(!before_halfway.enable_color);
+        assert_eq!(before_halfway.thick_outline, None);
+        let gray = Srgba::from(before_halfway.edge_color);
+        assert!(gray.red > 0.0 && gray.red < 1.0);
+
+        let after_halfway = subtle.lerp(&dramatic, 0.5);
+        assert!(after_halfway.enable_color);
+        assert!(after_halfway.thick_outline.is_some());
+
+        let end = subtle.lerp(&dramatic, 1.0);
+        assert_eq!(end.depth_threshold, dramatic.depth_threshold);
+        assert!(Srgba::from(end.edge_color).distance(&Srgba::WHITE) < 1e-3);
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )));
+        transition::build(&mut app);
+        let camera = app
+            .world_mut()
+            .spawn((
+                subtle.clone(),
+                EdgeDetectionTransition::new(
+                    subtle.clone(),
+                    dramatic.clone(),
+                    Duration::from_millis(500),
+                ),
+            ))
+            .id();
+
+        let mut tweened = false;
+        for _ in 0..10 {
+            app.update();
+            let depth_threshold = app
+                .world()
+                .get::<EdgeDetection>(camera)
+                .unwrap()
+                .depth_threshold;
+            tweened |= depth_threshold > subtle.depth_threshold
+                && depth_threshold < dramatic.depth_threshold;
+        }
+        assert!(tweened);
+        assert!(app.world().get::<EdgeDetectionTransition>(camera).is_none());
+        let edge_detection = app.world().get::<EdgeDetection>(camera).unwrap();
+        assert_eq!(edge_detection.depth_threshold, dramatic.depth_threshold);
+        assert_eq!(edge_detection.edge_color, Color::WHITE);
+    }
+
+    /// The presets only change their own settings, from the defaults.
+    #[test]
+    fn presets_start_from_the_defaults() {
+        let default = EdgeDetection::default();
+        assert_eq!(default.reference_fov, PerspectiveProjection::default().fov);
+        assert_eq!(
+            default.material_attributes,
+            MaterialEdgeAttributes::default()
+        );
+
+        assert!(EdgeDetection::TOON.posterize.is_some());
+        assert_eq!(EdgeDetection::TOON.edge_color, default.edge_color);
+        assert!(EdgeDetection::SKETCH.hatching.is_some());
+        assert_eq!(EdgeDetection::SKETCH.posterize, None);
+        assert!(matches!(
+            EdgeDetection::BLUEPRINT.output_mode,
+            OutputMode::Blueprint { .. }
+        ));
+        assert_eq!(
+            EdgeDetection::BLUEPRINT.depth_threshold,
+            default.depth_threshold
+        );
+    }
+
+    /// A preset file with missing and unknown fields still loads, the missing fields taking their default values,
+    /// and the preset is applied to the cameras again when it changes.
+    #[cfg(feature = "serde")]
+    #[test]
+    fn edge_detection_presets() {
+        use bevy::asset::ron;
+
+        let edge_detection: EdgeDetection = ron::de::from_str(
+            r#"(
+                depth_threshold: 2.5,
+                edge_color: LinearRgba((red: 1.0, green: 0.0, blue: 0.0, alpha: 1.0)),
+                thick_outline: Some((radius_px: 4.0)),
+                color_filter: Nearest,
+                removed_in_a_later_version: 1.0,
+            )"#,
+        )
+        .unwrap();
+        assert_eq!(edge_detection.depth_threshold, 2.5);
+        assert_eq!(
+            edge_detection.edge_color,
+            Color::LinearRgba(LinearRgba::RED)
+        );
+        assert_eq!(
+            edge_detection.thick_outline,
+            Some(ThickOutline {
+                radius_px: 4.0,
+                ..default()
+            })
+        );
+        assert_eq!(edge_detection.color_filter, FilterMode::Nearest);
+        assert_eq!(
+            edge_detection.normal_threshold,
+            EdgeDetection::default().normal_threshold
+        );
+
+        let serialized = ron::ser::to_string(&EdgeDetection::BLUEPRINT).unwrap();
+        let deserialized: EdgeDetection = ron::de::from_str(&serialized).unwrap();
+        assert_eq!(
+            deserialized.output_mode,
+            EdgeDetection::BLUEPRINT.output_mode
+        );
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()));
+        preset::build(&mut app);
+
+        let preset = app
+            .world_mut()
+            .resource_mut::<Assets<EdgeDetectionPreset>>()
+            .add(EdgeDetectionPreset(EdgeDetection::TOON));
+        let noise_texture = Handle::<Image>::default();
+        let camera = app
+            .world_mut()
+            .spawn(EdgeDetectionPresetHandle(preset.clone()))
+            .id();
+        let styled = app
+            .world_mut()
+            .spawn((
+                EdgeDetectionPresetHandle(preset.clone()),
+                EdgeDetection {
+                    noise_texture: Some(noise_texture.clone()),
+                    ..default()
+                },
+            ))
+            .id();
+        app.update();
+
+        for entity in [camera, styled] {
+            let edge_detection = app.world().get::<EdgeDetection>(entity).unwrap();
+            assert_eq!(edge_detection.posterize, EdgeDetection::TOON.posterize);
+        }
+        let edge_detection = app.world().get::<EdgeDetection>(styled).unwrap();
+        assert_eq!(edge_detection.noise_texture, Some(noise_texture));
+
+        app.world_mut()
+            .resource_mut::<Assets<EdgeDetectionPreset>>()
+            .get_mut(&preset)
+            .unwrap()
+            .depth_threshold = 3.0;
+        app.update();
+        for entity in [camera, styled] {
+            let edge_detection = app.world().get::<EdgeDetection>(entity).unwrap();
+            assert_eq!(edge_detection.depth_threshold, 3.0);
+        }
+    }
+
+    #[test]
+    fn depth_range_of_projections() {
+        let perspective = Projection::from(PerspectiveProjection {
+            near: 0.5,
+            far: 100.0,
+            ..default()
+        });
+        assert_eq!(depth_range(&perspective), Some(99.5));
+
+        let orthographic = Projection::from(OrthographicProjection {
+            near: -10.0,
+            far: 10.0,
+            ..OrthographicProjection::default_3d()
+        });
+        assert_eq!(depth_range(&orthographic), Some(20.0));
+    }
+
+    /// The settings can be tuned remotely through reflection only, like the Bevy Remote Protocol does:
+    /// a component with some of the fields is inserted, then a field is mutated by its path.
+    #[test]
+    fn edge_detection_tuned_through_reflection() {
+        let mut app = RenderTestApp::new();
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+
+        let registry = app.app.world().resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+        let deserialize = |type_id: TypeId, json: &str| {
+            let registration = registry.get(type_id).unwrap();
+            TypedReflectDeserializer::new(registration, &registry)
+                .deserialize(&mut serde_json::Deserializer::from_str(json))
+                .unwrap()
+        };
+        let reflect_component = registry
+            .get_type_data::<ReflectComponent>(TypeId::of::<EdgeDetection>())
+            .unwrap();
+
+        // `bevy/insert`, the missing fields take their default values.
+        let partial = deserialize(
+            TypeId::of::<EdgeDetection>(),
+            r#"{ "depth_threshold": 2.5, "depth_source": "MainPass" }"#,
+        );
+        reflect_component.insert(
+            &mut app.app.world_mut().entity_mut(camera),
+            partial.as_partial_reflect(),
+            &registry,
+        );
+
+        // `bevy/mutate_component`, the value is deserialized as the type at the path.
+        let mut entity = app.app.world_mut().entity_mut(camera);
+        let mut edge_detection = reflect_component.reflect_mut(&mut entity).unwrap();
+        for (path, json) in [
+            (
+                "edge_color",
+                r#"{ "LinearRgba": { "red": 1.0, "green": 0.0, "blue": 0.0, "alpha": 1.0 } }"#,
+            ),
+            ("color_filter", r#""Nearest""#),
+        ] {
+            let field = edge_detection.reflect_path_mut(path).unwrap();
+            let type_id = field.get_represented_type_info().unwrap().type_id();
+            field
+                .try_apply(deserialize(type_id, json).as_partial_reflect())
+                .unwrap();
+        }
+
+        let edge_detection = app.app.world().get::<EdgeDetection>(camera).unwrap();
+        assert_eq!(
+            edge_detection.normal_threshold,
+            EdgeDetection::default().normal_threshold
+        );
+        assert_eq!(edge_detection.color_filter, FilterMode::Nearest);
+
+        app.update();
+
+        let render_entity = app.app.world().get::<RenderEntity>(camera).unwrap().id();
+        let render_world = app.app.sub_app(RenderApp).world();
+        let uniform = render_world
+            .get::<EdgeDetectionUniform>(render_entity)
+            .unwrap();
+        assert_eq!(uniform.depth_threshold, 2.5);
+        assert_eq!(uniform.edge_color, LinearRgba::RED);
+        let extracted = render_world.get::<EdgeDetection>(render_entity).unwrap();
+        assert_eq!(extracted.color_filter, FilterMode::Nearest);
+    }
+
+    /// The edges alone are written with a straight alpha into an sRGB image, and the pixels without edges
+    /// are transparent over an opaque clear color, even once the edges are faded out.
+    #[test]
+    fn edges_only_image_is_transparent() {
+        let mut app = RenderTestApp::new();
+        let targets = [app.image_target(), app.image_target()];
+        for (intensity, target) in [1.0, 0.0].into_iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                clear_color: ClearColorConfig::Custom(Color::WHITE),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    edge_color: Color::srgb(0.0, 0.0, 1.0),
+                    output_mode: OutputMode::EdgesOnly,
+                    intensity,
+                    ..test_edge_detection()
+                });
+            app.wait_for_pipeline(camera);
+        }
+        let RenderTarget::Image(image) = &targets[0] else {
+            unreachable!();
+        };
+        let format = app
+            .app
+            .world()
+            .resource::<Assets<Image>>()
+            .get(&image.handle)
+            .unwrap()
+            .texture_descriptor
+            .format;
+        assert_eq!(format, TextureFormat::Rgba8UnormSrgb);
+
+        let [drawn, faded] = app.read_back(&targets).try_into().unwrap();
+        let alpha = |pixels: &[u8], x: usize, y: usize| pixels[(y * 64 + x) * 4 + 3];
+        for (x, y) in [(0, 0), (63, 0), (0, 63), (63, 63)] {
+            assert_eq!(
+                alpha(&drawn, x, y),
+                0,
+                "the corner {x}, {y} isn't transparent"
+            );
+        }
+        assert!(drawn.chunks_exact(4).any(|pixel| pixel == [0, 0, 255, 255]));
+        assert!(
+            faded.chunks_exact(4).all(|pixel| pixel[3] == 0),
+            "the faded out edges left the scene"
+        );
+    }
+
+    /// A camera rendering its edges alone into an image, composited over the output of another camera
+    /// looking away from the scene.
+    #[test]
+    fn edge_overlay_composites_other_camera() {
+        let mut app = RenderTestApp::new();
+
+        let edges_target = app.image_target();
+        let edges_camera = app.spawn_camera(Camera {
+            target: edges_target.clone(),
+            order: -1,
+            ..default()
+        });
+        app.app
+            .world_mut()
+            .entity_mut(edges_camera)
+            .insert(EdgeDetection {
+                edge_color: Color::srgb(1.0, 0.0, 0.0),
+                output_mode: OutputMode::EdgesOnly,
+                ..test_edge_detection()
+            });
+        app.wait_for_pipeline(edges_camera);
+
+        let RenderTarget::Image(edges_image) = &edges_target else {
+            unreachable!();
+        };
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target: target.clone(),
+            ..default()
+        });
+        app.app
+            .world_mut()
+            .entity_mut(camera)
+            .remove::<EdgeDetection>()
+            .insert((
+                Transform::from_xyz(0.0, 1.0, 3.0).looking_to(Vec3::Z, Vec3::Y),
+                EdgeOverlay::new(edges_image.handle.clone()),
+            ));
+        for _ in 0..1000 {
+            app.update();
+            let render_entity = app.app.world().get::<RenderEntity>(camera).unwrap().id();
+            let render_world = app.app.sub_app(RenderApp).world();
+            let ready = render_world
+                .get::<EdgeOverlayPipelineId>(render_entity)
+                .is_some_and(|pipeline_id| {
+                    render_world
+                        .resource::<PipelineCache>()
+                        .get_render_pipeline(pipeline_id.0)
+                        .is_some()
+                });
+            if ready {
+                break;
+            }
+        }
+
+        let [edges, overlaid] = app.read_back(&[edges_target, target]).try_into().unwrap();
+        let is_edge = |pixel: &[u8]| pixel[0] > 250 && pixel[1] < 5 && pixel[2] < 5;
+
+        // Only the edges are written, the rest is transparent.
+        assert!(edges
+            .chunks_exact(4)
+            .any(|pixel| is_edge(pixel) && pixel[3] == 255));
+        assert!(edges.chunks_exact(4).any(|pixel| pixel[3] == 0));
+        assert!(edges
+            .chunks_exact(4)
+            .all(|pixel| is_edge(pixel) || pixel[3] < 255));
+
+        // The other camera sees none of the scene, only the overlaid edges.
+        let overlaid_edges = overlaid
+            .chunks_exact(4)
+            .filter(|pixel| is_edge(pixel))
+            .count();
+        let edge_count = edges
+            .chunks_exact(4)
+            .filter(|pixel| is_edge(pixel) && pixel[3] == 255)
+            .count();
+        assert!(overlaid_edges > 0);
+        assert_eq!(overlaid_edges, edge_count);
+    }
+
+    /// Two cameras side by side on the same target, each outlining the cube in its half with its own color.
+    #[test]
+    fn split_screen_cameras_keep_their_edges() {
+        let mut app = RenderTestApp::new();
+
+        let target = app.image_target();
+        let colors = [Color::srgb(1.0, 0.0, 0.0), Color::srgb(0.0, 0.0, 1.0)];
+        for (order, edge_color) in colors.into_iter().enumerate() {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                order: order as isize,
+                viewport: Some(Viewport {
+                    physical_position: UVec2::new(32 * order as u32, 0),
+                    physical_size: UVec2::new(32, 64),
+                    ..default()
+                }),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    edge_color,
+                    ..test_edge_detection()
+                });
+            app.wait_for_pipeline(camera);
+        }
+
+        let [pixels] = app.read_back(&[target]).try_into().unwrap();
+        let is_red = |pixel: &[u8]| pixel[0] > 250 && pixel[1] < 5 && pixel[2] < 5;
+        let is_blue = |pixel: &[u8]| pixel[0] < 5 && pixel[1] < 5 && pixel[2] > 250;
+
+        let (mut left, mut right) = (Vec::new(), Vec::new());
+        for row in pixels.chunks_exact(64 * 4) {
+            let (row_left, row_right) = row.split_at(32 * 4);
+            left.extend(row_left.chunks_exact(4));
+            right.extend(row_right.chunks_exact(4));
+        }
+        assert!(left.iter().any(|pixel| is_red(pixel)));
+        assert!(!left.iter().any(|pixel| is_blue(pixel)));
+        assert!(right.iter().any(|pixel| is_blue(pixel)));
+        assert!(!right.iter().any(|pixel| is_red(pixel)));
+    }
+
+    /// The depth edges of an orthographic camera don't depend on its distance, its depth differences don't either.
+    #[test]
+    fn orthographic_edges_independent_of_distance() {
+        let mut app = RenderTestApp::new();
+
+        let world = app.app.world_mut();
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Plane3d::default().mesh().size(8.0, 8.0));
+        world.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d::<StandardMaterial>::default(),
+            Transform::from_xyz(0.0, -0.5, 0.0),
+        ));
+
+        let targets = [app.image_target(), app.image_target()];
+        for (distance, target) in [1.0, 10.0].into_iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app.world_mut().entity_mut(camera).insert((
+                Projection::from(OrthographicProjection {
+                    scaling_mode: ScalingMode::FixedVertical {
+                        viewport_height: 3.0,
+                    },
+                    ..OrthographicProjection::default_3d()
+                }),
+                Transform::from_translation(Vec3::new(0.0, 1.0, 3.0) * distance)
+                    .looking_at(Vec3::ZERO, Vec3::Y),
+                EdgeDetection {
+                    enable_normal: false,
+                    ..test_edge_detection()
+                },
+            ));
+            app.wait_for_pipeline(camera);
+        }
+
+        let [near, far] = app.read_back(&targets).try_into().unwrap();
+        let edges = |pixels: &[u8]| {
+            pixels
+                .chunks_exact(4)
+                .map(|pixel| pixel[..3] == [0; 3])
+                .collect::<Vec<_>>()
+        };
+        let (near, far) = (edges(&near), edges(&far));
+        assert!(near.contains(&true), "the cube wasn't outlined");
+        let different_pixels = near
+            .iter()
+            .zip(&far)
+            .filter(|(near, far)| near != far)
+            .count();
+        assert!(
+            different_pixels <= near.len() / 100,
+            "{different_pixels} edge pixels moved with the distance"
+        );
+    }
+
+    /// The silhouettes against the background are drawn on the side of the cube only, under both projections,
+    /// and not at all with `BackgroundEdgeMode::None`.
+    #[test]
+    fn background_edges() {
+        let orthographic = Projection::from(OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical {
+                viewport_height: 3.0,
+            },
+            ..OrthographicProjection::default_3d()
+        });
+        let settings = [
+            (Projection::default(), 0.0, BackgroundEdgeMode::Silhouette),
+            (Projection::default(), 1.0, BackgroundEdgeMode::Silhouette),
+            (Projection::default(), 1.0, BackgroundEdgeMode::None),
+            (orthographic.clone(), 0.0, BackgroundEdgeMode::Silhouette),
+            (orthographic.clone(), 1.0, BackgroundEdgeMode::Silhouette),
+            (orthographic, 1.0, BackgroundEdgeMode::None),
+        ];
 
-            hdr,
-            multisampled,
-            projection: projection.into(),
+        let mut app = RenderTestApp::new();
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        for ((projection, intensity, background_edges), target) in
+            settings.into_iter().zip(&targets)
+        {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app.world_mut().entity_mut(camera).insert((
+                projection,
+                EdgeDetection {
+                    intensity,
+                    background_edges,
+                    ..test_edge_detection()
+                },
+            ));
+            app.wait_for_pipeline(camera);
+        }
+
+        let pixels = app.read_back(&targets);
+        for [scene, silhouette, none] in pixels
+            .chunks_exact(3)
+            .map(|pixels| [&pixels[0], &pixels[1], &pixels[2]])
+        {
+            let background = &scene[..4];
+            let mut silhouette_edges = 0;
+            let mut none_edges = 0;
+            for ((scene, silhouette), none) in scene
+                .chunks_exact(4)
+                .zip(silhouette.chunks_exact(4))
+                .zip(none.chunks_exact(4))
+            {
+                if scene == background {
+                    assert_eq!(silhouette, scene, "an edge was drawn on the background");
+                    assert_eq!(none, scene, "an edge was drawn on the background");
+                }
+                if silhouette[..3] == [0; 3] {
+                    silhouette_edges += 1;
+                }
+                if none[..3] == [0; 3] {
+                    none_edges += 1;
+                    assert_eq!(silhouette, none, "the edges moved without the background");
+                }
+            }
+            assert!(silhouette_edges > 0, "the silhouette wasn't drawn");
+            assert!(
+                none_edges < silhouette_edges,
+                "the edges against the background were drawn"
+            );
         }
     }
-}
 
-#[derive(Component, Clone, Copy, Debug, Reflect)]
-#[reflect(Component, Default)]
-#[require(DepthPrepass, NormalPrepass)]
-pub struct EdgeDetection {
-    /// Depth threshold, used to detect edges with significant depth changes.
-    /// Areas where the depth variation exceeds this threshold will be marked as edges.
-    pub depth_threshold: f32,
-    /// Normal threshold, used to detect edges with significant normal direction changes.
-    /// Areas where the normal direction variation exceeds this threshold will be marked as edges.
-    pub normal_threshold: f32,
-    /// Color threshold, used to detect edges with significant color changes.
-    /// Areas where the color variation exceeds this threshold will be marked as edges.
-    pub color_threshold: f32,
+    /// The meshes with a `NoEdgeDetection` neither get edges nor outline their neighbors,
+    /// while the other meshes keep their outline.
+    #[test]
+    fn excluded_meshes_are_not_outlined() {
+        let mut app = RenderTestApp::new();
+        let world = app.app.world_mut();
+        let excluded = world
+            .query_filtered::<Entity, With<Mesh3d>>()
+            .single(world)
+            .unwrap();
+        let small_cube = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::from_length(0.3));
+        let red = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                base_color: Color::srgb(1.0, 0.0, 0.0),
+                unlit: true,
+                ..default()
+            });
+        world.spawn((
+            Mesh3d(small_cube),
+            MeshMaterial3d(red),
+            Transform::from_xyz(-0.9, 0.0, 0.0),
+        ));
 
-    /// Thickness of the edges detected based on depth variations.
-    /// This value controls the width of the edges drawn when depth-based edge detection is enabled.
-    /// Higher values result in thicker edges.
-    pub depth_thickness: f32,
-    /// Thickness of the edges detected based on normal direction variations.
-    /// This value controls the width of the edges drawn when normal-based edge detection is enabled.
-    /// Higher values result in thicker edges.
-    pub normal_thickness: f32,
-    /// Thickness of the edges detected based on color variations.
-    /// This value controls the width of the edges drawn when color-based edge detection is enabled.
-    /// Higher values result in thicker edges.
-    pub color_thickness: f32,
+        let targets = [app.image_target(), app.image_target()];
+        let mut camera = Entity::PLACEHOLDER;
+        for (intensity, target) in [0.0, 1.0].into_iter().zip(&targets) {
+            camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    intensity,
+                    ..test_edge_detection()
+                });
+        }
+        assert!(!app.wait_for_pipeline(camera).key.excluded_meshes);
+        let [scene, outlined] = app.read_back(&targets).try_into().unwrap();
 
-    /// Steep angle threshold, used to adjust the depth threshold when viewing surfaces at steep angles.
-    /// When the angle between the view direction and the surface normal is very steep, the depth gradient
-    /// can appear artificially large, causing non-edge regions to be mistakenly detected as edges.
-    /// This threshold defines the angle at which the depth threshold adjustment begins to take effect.
-    ///
-    /// Range: [0.0, 1.0]
-    pub steep_angle_threshold: f32,
-    /// Multiplier applied to the depth threshold when the view angle is steep.
-    /// When the angle between the view direction and the surface normal exceeds the `steep_angle_threshold`,
-    /// the depth threshold is scaled by this multiplier to reduce the likelihood of false edge detection.
-    ///
-    /// A value of 1.0 means no adjustment, while values greater than 1.0 increase the depth threshold,
-    /// making edge detection less sensitive in steep angles.
-    ///
-    /// Range: [0.0, inf)
-    pub steep_angle_multiplier: f32,
+        app.app
+            .world_mut()
+            .entity_mut(excluded)
+            .insert(NoEdgeDetection);
+        assert!(app.wait_for_pipeline(camera).key.excluded_meshes);
+        let [_, excluded] = app.read_back(&targets).try_into().unwrap();
 
-    /// Frequency of UV distortion applied to the edge detection process.
-    /// This controls how often the distortion effect repeats across the UV coordinates.
-    /// Higher values result in more frequent distortion patterns.
-    pub uv_distortion_frequency: Vec2,
+        // Whether a pixel of the scene next to the given one is on the red cube.
+        let near_red = |pixel: usize| {
+            let (x, y) = ((pixel % 64) as i32, (pixel / 64) as i32);
+            (-1..=1).any(|dy| {
+                (-1..=1).any(|dx| {
+                    let (x, y) = ((x + dx).clamp(0, 63), (y + dy).clamp(0, 63));
+                    let scene = &scene[(y * 64 + x) as usize * 4..][..3];
+                    scene[0] > 200 && scene[1] < 50
+                })
+            })
+        };
+        let mut cube_edges = 0;
+        let mut red_edges = 0;
+        for pixel in 0..64 * 64 {
+            let was_edge = outlined[pixel * 4..][..3] == [0; 3];
+            let edge = excluded[pixel * 4..][..3] == [0; 3];
+            if near_red(pixel) {
+                red_edges += usize::from(edge);
+                assert_eq!(edge, was_edge, "the red cube lost its outline at {pixel}");
+            } else {
+                cube_edges += usize::from(was_edge);
+                assert!(!edge, "the excluded cube was outlined at {pixel}");
+            }
+        }
+        assert!(
+            cube_edges > 0,
+            "the cube wasn't outlined before its exclusion"
+        );
+        assert!(red_edges > 0, "the red cube wasn't outlined");
+    }
 
-    /// Strength of UV distortion applied to the edge detection process.
-    /// This controls the intensity of the distortion effect.
-    /// Higher values result in more pronounced distortion.
-    pub uv_distortion_strength: Vec2,
+    /// The edges around the meshes with an `EdgeColorOverride` take its color,
+    /// while the other meshes keep the edge color of the camera.
+    #[test]
+    fn edge_color_overrides_color_the_edges() {
+        let mut app = RenderTestApp::new();
+        let world = app.app.world_mut();
+        let overridden = world
+            .query_filtered::<Entity, With<Mesh3d>>()
+            .single(world)
+            .unwrap();
+        let small_cube = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::from_length(0.3));
+        let red = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                base_color: Color::srgb(1.0, 0.0, 0.0),
+                unlit: true,
+                ..default()
+            });
+        world.spawn((
+            Mesh3d(small_cube),
+            MeshMaterial3d(red),
+            Transform::from_xyz(-0.9, 0.0, 0.0),
+        ));
 
-    /// Edge color, used to draw the detected edges.
-    /// Typically a high-contrast color (e.g., red or black) to visually highlight the edges.
-    pub edge_color: Color,
+        let targets = [app.image_target(), app.image_target()];
+        let mut camera = Entity::PLACEHOLDER;
+        for (intensity, target) in [0.0, 1.0].into_iter().zip(&targets) {
+            camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    intensity,
+                    ..test_edge_detection()
+                });
+        }
+        assert!(!app.wait_for_pipeline(camera).key.edge_color_overrides);
+        let [scene, outlined] = app.read_back(&targets).try_into().unwrap();
 
-    /// Whether to enable depth-based edge detection.
-    /// If `true`, edges will be detected based on depth variations.
-    pub enable_depth: bool,
-    /// Whether to enable normal-based edge detection.
-    /// If `true`, edges will be detected based on normal direction variations.
-    pub enable_normal: bool,
-    /// Whether to enable color-based edge detection.
-    /// If `true`, edges will be detected based on color variations.
-    pub enable_color: bool,
-}
+        app.app
+            .world_mut()
+            .entity_mut(overridden)
+            .insert(EdgeColorOverride(Color::srgb(0.0, 1.0, 0.0)));
+        assert!(app.wait_for_pipeline(camera).key.edge_color_overrides);
+        let [_, overridden] = app.read_back(&targets).try_into().unwrap();
 
-impl Default for EdgeDetection {
-    fn default() -> Self {
-        Self {
-            depth_threshold: 1.0,
-            normal_threshold: 0.8,
-            color_threshold: 0.1,
+        // Whether a pixel of the scene next to the given one is on the red cube.
+        let near_red = |pixel: usize| {
+            let (x, y) = ((pixel % 64) as i32, (pixel / 64) as i32);
+            (-1..=1).any(|dy| {
+                (-1..=1).any(|dx| {
+                    let (x, y) = ((x + dx).clamp(0, 63), (y + dy).clamp(0, 63));
+                    let scene = &scene[(y * 64 + x) as usize * 4..][..3];
+                    scene[0] > 200 && scene[1] < 50
+                })
+            })
+        };
+        let mut green_edges = 0;
+        let mut black_edges = 0;
+        for pixel in 0..64 * 64 {
+            let color = &overridden[pixel * 4..][..3];
+            if outlined[pixel * 4..][..3] != [0; 3] {
+                assert_eq!(
+                    color,
+                    &outlined[pixel * 4..][..3],
+                    "the pixel {pixel} changed without an edge"
+                );
+            } else if near_red(pixel) {
+                black_edges += 1;
+                assert_eq!(color, [0; 3], "the red cube took the override at {pixel}");
+            } else {
+                green_edges += 1;
+                assert!(
+                    color[0] < 50 && color[1] > 200 && color[2] < 50,
+                    "the edge at {pixel} isn't green: {color:?}"
+                );
+            }
+        }
+        assert!(green_edges > 0, "the overridden cube wasn't outlined");
+        assert!(black_edges > 0, "the red cube wasn't outlined");
+    }
 
-            depth_thickness: 1.0,
-            normal_thickness: 1.0,
-            color_thickness: 1.0,
+    /// The depth and normal edges drawn in their own colors, the silhouettes over the creases.
+    #[test]
+    fn channel_edge_colors() {
+        let red = Color::srgb(1.0, 0.0, 0.0);
+        let blue = Color::srgb(0.0, 0.0, 1.0);
+        let settings = [
+            test_edge_detection(),
+            EdgeDetection {
+                depth_edge_color: Some(red),
+                normal_edge_color: Some(blue),
+                ..test_edge_detection()
+            },
+        ];
+
+        let mut app = RenderTestApp::new();
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        let mut camera = Entity::PLACEHOLDER;
+        for (edge_detection, target) in settings.into_iter().zip(&targets) {
+            camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(edge_detection);
+        }
+        assert!(app.wait_for_pipeline(camera).key.channel_edge_colors);
 
-            steep_angle_threshold: 0.00,
-            steep_angle_multiplier: 0.30,
+        let [outlined, colored] = app.read_back(&targets).try_into().unwrap();
+        let mut red_edges = 0;
+        let mut blue_edges = 0;
+        for (edge, color) in outlined.chunks_exact(4).zip(colored.chunks_exact(4)) {
+            if edge[..3] != [0; 3] {
+                continue;
+            }
+            match color[..3] {
+                [r, g, b] if r > 200 && g < 50 && b < 50 => red_edges += 1,
+                [r, g, b] if r < 50 && g < 50 && b > 200 => blue_edges += 1,
+                _ => panic!("the edge isn't red or blue: {color:?}"),
+            }
+        }
+        assert!(red_edges > 0, "the depth edges weren't red");
+        assert!(blue_edges > 0, "the normal edges weren't blue");
+    }
 
-            uv_distortion_frequency: Vec2::splat(1.0),
-            uv_distortion_strength: Vec2::splat(0.004),
+    /// The debug views replace the scene with the strength of a source, black for the disabled ones,
+    /// or with the steep angle adjustment.
+    #[test]
+    fn debug_views() {
+        let settings = [
+            (EdgeDebugView::DepthEdges, true),
+            (EdgeDebugView::DepthEdges, false),
+            (EdgeDebugView::NormalEdges, true),
+            (EdgeDebugView::CombinedMask, true),
+            (EdgeDebugView::SteepAngleFactor, true),
+        ];
 
-            edge_color: Color::BLACK,
+        let mut app = RenderTestApp::new();
+        let targets: Vec<_> = settings.iter().map(|_| app.image_target()).collect();
+        for ((debug_view, enable_depth), target) in settings.into_iter().zip(&targets) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    enable_depth,
+                    steep_angle_multiplier: 1.0,
+                    debug_view,
+                    ..test_edge_detection()
+                });
+            assert_eq!(app.wait_for_pipeline(camera).key.debug_view, debug_view);
+        }
 
-            enable_depth: true,
-            enable_normal: true,
-            enable_color: false,
+        let [depth, no_depth, normal, combined, steep_angle] =
+            app.read_back(&targets).try_into().unwrap();
+        let is_gray = |pixel: &[u8]| pixel[0] == pixel[1] && pixel[1] == pixel[2];
+        let is_white = |pixel: &[u8]| pixel[..3] == [255; 3];
+        for pixels in [&depth, &normal, &combined] {
+            assert!(pixels.chunks_exact(4).all(is_gray));
+            assert!(pixels.chunks_exact(4).any(is_white));
+            // The background far from the cube has no edges.
+            assert_eq!(pixels[..3], [0; 3]);
         }
+        assert!(
+            no_depth.chunks_exact(4).all(|pixel| pixel[..3] == [0; 3]),
+            "the disabled depth source was drawn"
+        );
+        assert_ne!(depth, normal);
+
+        assert_eq!(steep_angle[..3], [0; 3], "the background was drawn");
+        assert!(
+            steep_angle.chunks_exact(4).any(|pixel| !is_gray(pixel)),
+            "the steep angle heatmap wasn't drawn"
+        );
     }
-}
 
-#[derive(Component, Clone, Copy, ShaderType, ExtractComponent)]
-pub struct EdgeDetectionUniform {
-    pub depth_threshold: f32,
-    pub normal_threshold: f32,
-    pub color_threshold: f32,
+    /// The jump flood pipelines are only queued once a view draws a thick outline,
+    /// which is drawn around the edges in the Rg16Uint jump flood textures.
+    #[test]
+    fn thick_outline_pipelines_queued_with_the_first_outline() {
+        let mut app = RenderTestApp::new();
+        if !app
+            .app
+            .world()
+            .resource::<EdgeDetectionSupport>()
+            .thick_outline
+        {
+            return;
+        }
 
-    pub depth_thickness: f32,
-    pub normal_thickness: f32,
-    pub color_thickness: f32,
+        let jump_flood_pipelines = |app: &RenderTestApp| {
+            app.app
+                .sub_app(RenderApp)
+                .world()
+                .resource::<JumpFloodPipeline>()
+                .pipelines
+        };
 
-    pub steep_angle_threshold: f32,
-    pub steep_angle_multiplier: f32,
+        let targets = [app.image_target(), app.image_target()];
+        let edges_camera = app.spawn_camera(Camera {
+            target: targets[0].clone(),
+            ..default()
+        });
+        app.wait_for_pipeline(edges_camera);
+        assert_eq!(jump_flood_pipelines(&app), None);
 
-    pub uv_distortion: Vec4,
+        let outline_camera = app.spawn_camera(Camera {
+            target: targets[1].clone(),
+            ..default()
+        });
+        app.app
+            .world_mut()
+            .entity_mut(outline_camera)
+            .insert(EdgeDetection {
+                thick_outline: Some(ThickOutline {
+                    radius_px: 4.0,
+                    color: Color::srgb(1.0, 0.0, 0.0),
+                }),
+                ..test_edge_detection()
+            });
+        assert!(app.wait_for_pipeline(outline_camera).key.thick_outline);
+        assert!(jump_flood_pipelines(&app).is_some());
+        for _ in 0..1000 {
+            let render_world = app.app.sub_app(RenderApp).world();
+            if render_world
+                .resource::<JumpFloodPipeline>()
+                .get_passes(render_world.resource::<PipelineCache>())
+                .is_some()
+            {
+                break;
+            }
+            app.update();
+        }
 
-    pub edge_color: LinearRgba,
-}
+        let [edges, outline] = app.read_back(&targets).try_into().unwrap();
+        let is_outline = |pixel: &[u8]| pixel[0] > 200 && pixel[1] < 50 && pixel[2] < 50;
+        assert!(!edges.chunks_exact(4).any(is_outline));
+        assert!(
+            outline.chunks_exact(4).any(is_outline),
+            "the thick outline wasn't drawn"
+        );
+        // The outline is bounded by its radius, the corners of the target are far from the cube.
+        assert_eq!(outline[..4], edges[..4]);
+    }
 
-impl EdgeDetectionUniform {
-    pub fn extract_edge_detection_settings(
-        mut commands: Commands,
-        mut query: Extract<Query<(RenderEntity, &EdgeDetection)>>,
-    ) {
-        if !DEPTH_TEXTURE_SAMPLING_SUPPORTED {
-            info_once!(
-                "Disable edge detection on this platform because depth textures aren't supported correctly"
+    /// Renders the cube with the view of the camera replaced by a hand-built [`ExtractedView`] projecting with
+    /// `clip_from_view`, like the custom extracted views without a [`Projection`], or with the view of the camera.
+    /// Returns the pipeline of the view and its edge coverage.
+    fn hand_built_view_coverage(clip_from_view: Option<Mat4>) -> (EdgeDetectionPipelineId, f32) {
+        let mut app = RenderTestApp::new();
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target,
+            ..default()
+        });
+        app.app
+            .world_mut()
+            .entity_mut(camera)
+            .insert(EdgeCoverage::default());
+
+        if let Some(clip_from_view) = clip_from_view {
+            let world_from_view = GlobalTransform::from(
+                Transform::from_xyz(0.0, 1.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+            );
+            // The extracted views are replaced once the extraction commands are applied.
+            app.app.sub_app_mut(RenderApp).add_systems(
+                Render,
+                (move |mut views: Query<&mut ExtractedView, With<ExtractedCamera>>| {
+                    for mut view in &mut views {
+                        *view = ExtractedView {
+                            retained_view_entity: view.retained_view_entity,
+                            clip_from_view,
+                            world_from_view,
+                            clip_from_world: None,
+                            hdr: false,
+                            viewport: UVec4::new(0, 0, 64, 64),
+                            color_grading: default(),
+                        };
+                    }
+                })
+                .after(RenderSet::ExtractCommands)
+                .before(RenderSet::ManageViews),
             );
-            return;
         }
 
-        for (entity, edge_detection) in query.iter_mut() {
-            let mut entity_commands = commands
-                .get_entity(entity)
-                .expect("Edge Detection entity wasn't synced.");
+        let pipeline_id = app.wait_for_pipeline(camera);
+        let render_entity = app.app.world().get::<RenderEntity>(camera).unwrap().id();
+        assert!(!app
+            .app
+            .sub_app(RenderApp)
+            .world()
+            .entity(render_entity)
+            .contains::<Projection>());
 
-            entity_commands.insert((*edge_detection, EdgeDetectionUniform::from(edge_detection)));
-        }
+        (pipeline_id, app.coverage(camera))
     }
-}
 
-impl From<&EdgeDetection> for EdgeDetectionUniform {
-    fn from(ed: &EdgeDetection) -> Self {
-        Self {
-            depth_threshold: ed.depth_threshold,
-            normal_threshold: ed.normal_threshold,
-            color_threshold: ed.color_threshold,
+    /// The views without a [`Projection`] infer it from their projection matrix in the shader,
+    /// and draw the same bounded edges as the camera instead of noise.
+    #[test]
+    fn views_without_projection_infer_it() {
+        let (_, camera_coverage) = hand_built_view_coverage(None);
+        let perspective = Mat4::perspective_infinite_reverse_rh(FRAC_PI_4, 1.0, 0.1);
+        let (perspective_pipeline, perspective_coverage) =
+            hand_built_view_coverage(Some(perspective));
+        let orthographic = Mat4::orthographic_rh(-1.5, 1.5, -1.5, 1.5, 1000.0, 0.0);
+        let (orthographic_pipeline, orthographic_coverage) =
+            hand_built_view_coverage(Some(orthographic));
 
-            depth_thickness: ed.depth_thickness,
-            normal_thickness: ed.normal_thickness,
-            color_thickness: ed.color_thickness,
+        assert_eq!(perspective_pipeline.key.projection, ProjectionType::None);
+        assert_eq!(orthographic_pipeline.key.projection, ProjectionType::None);
+        assert!(
+            camera_coverage > 0.0,
+            "{camera_coverage}% of edges with the camera"
+        );
+        assert!(
+            (perspective_coverage - camera_coverage).abs() < camera_coverage * 0.1,
+            "{perspective_coverage}% of edges with the hand-built perspective view, {camera_coverage}% with the camera"
+        );
+        // The noise of garbage thresholds would cover most of the view.
+        assert!(
+            orthographic_coverage > 0.0 && orthographic_coverage < 25.0,
+            "{orthographic_coverage}% of edges with the hand-built orthographic view"
+        );
+    }
 
-            steep_angle_threshold: ed.steep_angle_threshold,
-            steep_angle_multiplier: ed.steep_angle_multiplier,
+    /// Renders the cube with `edge_color` edges on an HDR and an LDR camera without tonemapping,
+    /// and returns the pixels of their targets in that order.
+    fn hdr_and_ldr_edges(edge_color: Color) -> [Vec<u8>; 2] {
+        let mut app = RenderTestApp::new();
+        let targets = [app.image_target(), app.image_target()];
+        for (target, hdr) in targets.iter().zip([true, false]) {
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                hdr,
+                ..default()
+            });
+            app.app.world_mut().entity_mut(camera).insert((
+                EdgeDetection {
+                    edge_color,
+                    ..test_edge_detection()
+                },
+                Tonemapping::None,
+                DebandDither::Disabled,
+            ));
+            app.wait_for_pipeline(camera);
+        }
 
-            uv_distortion: Vec4::new(
-                ed.uv_distortion_frequency.x,
-                ed.uv_distortion_frequency.y,
-                ed.uv_distortion_strength.x,
-                ed.uv_distortion_strength.y,
-            ),
+        app.read_back(&targets).try_into().unwrap()
+    }
 
-            edge_color: ed.edge_color.into(),
+    /// The translucent edges are blended in linear space on the LDR cameras like on the HDR ones,
+    /// and the opaque edges are still written as is.
+    #[test]
+    fn soft_edges_blend_alike_on_hdr_and_ldr() {
+        let is_edge = |pixel: &[u8]| pixel[0] > pixel[1].saturating_add(50);
+        let linear = |pixel: &[u8]| LinearRgba::from(Srgba::rgb_u8(pixel[0], pixel[1], pixel[2]));
+
+        let [hdr, ldr] = hdr_and_ldr_edges(Color::srgba(1.0, 0.0, 0.0, 0.5));
+        assert!(hdr.chunks_exact(4).any(is_edge) && ldr.chunks_exact(4).any(is_edge));
+        let max_difference = hdr
+            .chunks_exact(4)
+            .zip(ldr.chunks_exact(4))
+            .map(|(hdr, ldr)| {
+                let (hdr, ldr) = (linear(hdr), linear(ldr));
+                (hdr.red - ldr.red)
+                    .abs()
+                    .max((hdr.green - ldr.green).abs())
+                    .max((hdr.blue - ldr.blue).abs())
+            })
+            .fold(0.0, f32::max);
+        assert!(
+            max_difference < 0.03,
+            "the linear colors of the HDR and the LDR camera differ by up to {max_difference}"
+        );
+
+        let [hdr, ldr] = hdr_and_ldr_edges(Color::srgb(1.0, 0.0, 0.0));
+        for pixels in [hdr, ldr] {
+            assert!(
+                pixels
+                    .chunks_exact(4)
+                    .any(|pixel| pixel[..3] == [255, 0, 0]),
+                "the opaque edges weren't written as is"
+            );
         }
     }
-}
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
-pub struct EdgeDetectionLabel;
+    /// The edge opacity is written into the alpha channel of the target with `write_alpha_mask`, and the alpha
+    /// of the scene is kept with `preserve_scene_alpha`, over a transparent clear color.
+    #[test]
+    fn alpha_around_the_silhouette() {
+        let mut app = RenderTestApp::new();
+        let settings = [
+            test_edge_detection(),
+            EdgeDetection {
+                write_alpha_mask: true,
+                ..test_edge_detection()
+            },
+            EdgeDetection {
+                preserve_scene_alpha: true,
+                ..test_edge_detection()
+            },
+        ];
+        let targets: Vec<_> = settings
+            .iter()
+            .map(|edge_detection| {
+                let target = app.image_target();
+                let camera = app.spawn_camera(Camera {
+                    target: target.clone(),
+                    clear_color: ClearColorConfig::Custom(Color::NONE),
+                    ..default()
+                });
+                app.app
+                    .world_mut()
+                    .entity_mut(camera)
+                    .insert(edge_detection.clone());
+                app.wait_for_pipeline(camera);
+                target
+            })
+            .collect();
 
-// The post process node used for the render graph
-#[derive(Default)]
-pub struct EdgeDetectionNode;
+        let [opaque, alpha_mask, scene_alpha] = app.read_back(&targets).try_into().unwrap();
+        let is_edge = |pixel: &&[u8]| pixel[..3] == [0; 3];
+        assert!(opaque.chunks_exact(4).all(|pixel| pixel[3] == 255));
 
-impl ViewNode for EdgeDetectionNode {
-    type ViewQuery = (
-        &'static Msaa,
-        &'static ViewTarget,
-        &'static ViewPrepassTextures,
-        &'static ViewUniformOffset,
-        &'static DynamicUniformIndex<EdgeDetectionUniform>,
-        &'static EdgeDetectionPipelineId,
-    );
+        // The corners of the target are far from the cube.
+        assert_eq!(alpha_mask[3], 0);
+        let mut edges = alpha_mask.chunks_exact(4).filter(is_edge).peekable();
+        assert!(edges.peek().is_some(), "no edge was drawn");
+        assert!(
+            edges.all(|pixel| pixel[3] == 255),
+            "the opaque edges aren't opaque in the alpha mask"
+        );
+        // The alpha is the mask of the edges, not of the cube.
+        let masked = alpha_mask
+            .chunks_exact(4)
+            .filter(|pixel| pixel[3] > 0)
+            .count();
+        let cube = scene_alpha
+            .chunks_exact(4)
+            .filter(|pixel| pixel[3] > 0)
+            .count();
+        assert!(
+            masked < cube,
+            "{masked} pixels in the alpha mask, {cube} pixels of the cube"
+        );
 
-    fn run(
-        &self,
-        _graph: &mut RenderGraphContext,
-        render_context: &mut RenderContext,
-        (
-            msaa,
-            view_target,
-            prepass_textures,
-            view_uniform_index,
-            ed_uniform_index,
-            edge_detection_pipeline_id,
-        ): QueryItem<Self::ViewQuery>,
-        world: &World,
-    ) -> Result<(), NodeRunError> {
-        let edge_detection_pipeline = world.resource::<EdgeDetectionPipeline>();
+        assert_eq!(scene_alpha[3], 0);
+        assert!(
+            scene_alpha.chunks_exact(4).any(|pixel| pixel[3] == 255),
+            "the alpha of the cube wasn't kept"
+        );
+    }
 
-        let Some(pipeline) = world
-            .resource::<PipelineCache>()
-            .get_render_pipeline(edge_detection_pipeline_id.0)
-        else {
-            return Ok(());
+    /// Deactivating a camera or emptying its target drops the prepared edge detection of its view,
+    /// which is prepared again once the camera renders again, without validation errors.
+    #[test]
+    fn inactive_cameras_and_empty_targets_are_skipped() {
+        let mut app = RenderTestApp::new();
+        let target = app.image_target();
+        let RenderTarget::Image(image_target) = &target else {
+            unreachable!();
         };
+        let image = image_target.handle.clone();
+        let camera = app.spawn_camera(Camera {
+            target: target.clone(),
+            ..default()
+        });
+        app.wait_for_pipeline(camera);
+        let render_entity = app.app.world().get::<RenderEntity>(camera).unwrap().id();
 
-        let (Some(depth_texture), Some(normal_texture)) =
-            (&prepass_textures.depth, &prepass_textures.normal)
-        else {
-            return Ok(());
+        let prepared = |app: &mut RenderTestApp| {
+            for _ in 0..5 {
+                app.update();
+            }
+            let render_world = app.app.sub_app(RenderApp).world();
+            let prepared = render_world
+                .entity(render_entity)
+                .contains::<EdgeDetectionUniform>();
+            assert_eq!(app.pipeline_id(camera).is_some(), prepared);
+            prepared
         };
-
-        let Some(noise_texture) = world
-            .resource::<RenderAssets<GpuImage>>()
-            .get(&edge_detection_pipeline.noise_texture)
-        else {
-            return Ok(());
+        let resize = |app: &mut RenderTestApp, size: u32| {
+            let mut images = app.app.world_mut().resource_mut::<Assets<Image>>();
+            let image = images.get_mut(&image).unwrap();
+            image.resize(Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            });
+            // A zero-sized texture can't be created, so the empty image is only kept in the main world,
+            // where the size of the camera target is read.
+            image.asset_usage = match size {
+                0 => RenderAssetUsages::MAIN_WORLD,
+                _ => RenderAssetUsages::default(),
+            };
         };
+        assert!(prepared(&mut app));
 
-        let Some(view_uniforms_binding) = world.resource::<ViewUniforms>().uniforms.binding()
-        else {
-            return Ok(());
-        };
+        app.app
+            .world_mut()
+            .get_mut::<Camera>(camera)
+            .unwrap()
+            .is_active = false;
+        assert!(!prepared(&mut app));
+        app.app
+            .world_mut()
+            .get_mut::<Camera>(camera)
+            .unwrap()
+            .is_active = true;
+        app.wait_for_pipeline(camera);
+        assert!(prepared(&mut app));
 
-        let Some(ed_uniform_binding) = world
-            .resource::<ComponentUniforms<EdgeDetectionUniform>>()
-            .uniforms()
-            .binding()
-        else {
-            return Ok(());
-        };
+        resize(&mut app, 0);
+        assert!(!prepared(&mut app));
+        resize(&mut app, 64);
+        app.wait_for_pipeline(camera);
+        assert!(prepared(&mut app));
 
-        // This will start a new "post process write", obtaining two texture
-        // views from the view target - a `source` and a `destination`.
-        // `source` is the "current" main texture and you _must_ write into
-        // `destination` because calling `post_process_write()` on the
-        // [`ViewTarget`] will internally flip the [`ViewTarget`]'s main
-        // texture to the `destination` texture. Failing to do so will cause
-        // the current main texture information to be lost.
-        let post_process = view_target.post_process_write();
+        let pixels = app.read_back(&[target]).remove(0);
+        assert!(
+            pixels.chunks_exact(4).any(|pixel| pixel[..3] == [0; 3]),
+            "no edge was drawn once the target was resized back"
+        );
+    }
 
-        // The bind_group gets created each frame.
-        //
-        // Normally, you would create a bind_group in the Queue set,
-        // but this doesn't work with the post_process_write().
-        // The reason it doesn't work is because each post_process_write will alternate the source/destination.
-        // The only way to have the correct source/destination for the bind_group
-        // is to make sure you get it during the node execution.
-        let multisampled = *msaa != Msaa::Off;
-        let bind_group = render_context.render_device().create_bind_group(
-            "edge_detection_bind_group",
-            edge_detection_pipeline.bind_group_layout(multisampled),
-            // It's important for this to match the BindGroupLayout defined in the PostProcessPipeline
-            &BindGroupEntries::sequential((
-                // Make sure to use the source view
-                post_process.source,
-                // Use depth prepass
-                &depth_texture.texture.default_view,
-                // Use normal prepass
-                &normal_texture.texture.default_view,
-                // Use simple texture sampler
-                &edge_detection_pipeline.linear_sampler,
-                // Use noise texture
-                &noise_texture.texture_view,
-                // Use noise texture sampler
-                &edge_detection_pipeline.noise_sampler,
-                // view uniform binding
-                view_uniforms_binding,
-                // Set the uniform binding
-                ed_uniform_binding,
-            )),
+    /// The four tiles of a 2x2 grid of sub views render the same edges as a single view of the whole image.
+    #[test]
+    fn sub_view_tiles_render_like_the_whole_view() {
+        const TILE_SIZE: u32 = 64;
+        let full_size = UVec2::splat(TILE_SIZE * 2);
+        let tiles = [UVec2::ZERO, UVec2::X, UVec2::Y, UVec2::ONE];
+
+        let mut app = RenderTestApp::new();
+        let full_target = app.image_target_with_size(full_size, TextureFormat::bevy_default());
+        let full_camera = app.spawn_camera(Camera {
+            target: full_target.clone(),
+            ..default()
+        });
+        app.wait_for_pipeline(full_camera);
+        let mut targets = vec![full_target];
+        for tile in tiles {
+            let target = app.image_target();
+            let camera = app.spawn_camera(Camera {
+                target: target.clone(),
+                sub_camera_view: Some(SubCameraView {
+                    full_size,
+                    offset: (tile * TILE_SIZE).as_vec2(),
+                    size: UVec2::splat(TILE_SIZE),
+                }),
+                ..default()
+            });
+            app.wait_for_pipeline(camera);
+            targets.push(target);
+        }
+
+        let pixels = app.read_back(&targets);
+        let (full, tile_pixels) = pixels.split_first().unwrap();
+        assert!(
+            full.chunks_exact(4).any(|pixel| pixel[..3] == [0; 3]),
+            "no edge was drawn"
+        );
+        let mut differing = 0;
+        for (tile, tile_pixels) in tiles.into_iter().zip(tile_pixels) {
+            for (index, pixel) in tile_pixels.chunks_exact(4).enumerate() {
+                let position = tile * TILE_SIZE
+                    + UVec2::new(index as u32 % TILE_SIZE, index as u32 / TILE_SIZE);
+                let full_index = (position.y * full_size.x + position.x) as usize * 4;
+                let full_pixel = &full[full_index..full_index + 4];
+                if pixel
+                    .iter()
+                    .zip(full_pixel)
+                    .any(|(tile, full)| tile.abs_diff(*full) > 8)
+                {
+                    differing += 1;
+                }
+            }
+        }
+        // Only a few pixels of the silhouette may be rasterized differently by the projections of the tiles.
+        assert!(
+            differing < full_size.element_product() as usize / 100,
+            "{differing} pixels of the tiles differ from the whole view"
         );
+    }
+
+    /// Renders a flat wall facing the camera, whose left half is a white dielectric and right half a red metal,
+    /// with only the material edges in green. Returns the pipeline of the view and the pixels of the target.
+    fn material_edges_on_a_wall(deferred: bool) -> (EdgeDetectionPipelineId, Vec<u8>) {
+        let mut app = RenderTestApp::new();
+        if deferred {
+            app.app
+                .insert_resource(DefaultOpaqueRendererMethod::deferred());
+        }
+
+        let camera_position = Vec3::new(0.0, 1.0, 3.0);
+        let center = camera_position / 2.0;
+        let wall =
+            Transform::from_translation(center).looking_at(2.0 * center - camera_position, Vec3::Y);
+        let mesh = app
+            .app
+            .world_mut()
+            .resource_mut::<Assets<Mesh>>()
+            .add(Rectangle::new(1.0, 2.0));
+        for (offset, material) in [
+            (
+                -0.5,
+                StandardMaterial {
+                    base_color: Color::WHITE,
+                    metallic: 0.0,
+                    ..default()
+                },
+            ),
+            (
+                0.5,
+                StandardMaterial {
+                    base_color: Color::srgb(1.0, 0.0, 0.0),
+                    metallic: 1.0,
+                    ..default()
+                },
+            ),
+        ] {
+            let material = app
+                .app
+                .world_mut()
+                .resource_mut::<Assets<StandardMaterial>>()
+                .add(material);
+            app.app.world_mut().spawn((
+                Mesh3d(mesh.clone()),
+                MeshMaterial3d(material),
+                wall.with_translation(center + wall.right() * offset),
+            ));
+        }
 
-        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
-            label: Some("edge_detection_pass"),
-            color_attachments: &[Some(RenderPassColorAttachment {
-                view: post_process.destination,
-                resolve_target: None,
-                ops: Operations::default(),
-            })],
-            depth_stencil_attachment: None,
-            timestamp_writes: None,
-            occlusion_query_set: None,
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target: target.clone(),
+            ..default()
         });
+        app.app
+            .world_mut()
+            .entity_mut(camera)
+            .insert(EdgeDetection {
+                enable_depth: false,
+                enable_normal: false,
+                enable_material: true,
+                edge_color: Color::srgb(0.0, 1.0, 0.0),
+                ..test_edge_detection()
+            });
+        if deferred {
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert((DepthPrepass, DeferredPrepass));
+        }
 
-        render_pass.set_render_pipeline(pipeline);
-        render_pass.set_bind_group(
-            0,
-            &bind_group,
-            &[view_uniform_index.offset, ed_uniform_index.index()],
+        let pipeline_id = app.wait_for_pipeline(camera);
+        (pipeline_id, app.read_back(&[target]).remove(0))
+    }
+
+    /// The deferred cameras draw edges where the material changes on a flat wall, along the boundary only,
+    /// and the forward cameras ignore the material edges.
+    #[test]
+    fn material_edges_along_the_boundary() {
+        let (pipeline_id, pixels) = material_edges_on_a_wall(true);
+        assert!(pipeline_id.key.enable_material);
+        let edge_columns: Vec<u32> = pixels
+            .chunks_exact(4)
+            .enumerate()
+            .filter(|(_, pixel)| pixel[..3] == [0, 255, 0])
+            .map(|(index, _)| index as u32 % 64)
+            .collect();
+        // The boundary is a vertical line through the center of the view.
+        assert!(
+            edge_columns.len() >= 32,
+            "{} pixels of material edges",
+            edge_columns.len()
+        );
+        assert!(
+            edge_columns.iter().all(|column| (24..40).contains(column)),
+            "material edges away from the boundary, in the columns {edge_columns:?}"
         );
-        render_pass.draw(0..3, 0..1);
 
-        Ok(())
+        let (pipeline_id, pixels) = material_edges_on_a_wall(false);
+        assert!(!pipeline_id.key.enable_material);
+        assert!(!pixels
+            .chunks_exact(4)
+            .any(|pixel| pixel[..3] == [0, 255, 0]));
+    }
+
+    /// The depth textures of bevy's core 3d pipeline have no stencil aspect, so the stencil source is compiled out
+    /// whatever the depth source.
+    #[test]
+    fn stencil_needs_a_stencil_aspect() {
+        assert!(!CORE_3D_DEPTH_FORMAT.has_stencil_aspect());
+
+        let mut app = RenderTestApp::new();
+        for depth_source in [DepthSource::Prepass, DepthSource::MainPass] {
+            let target = app.image_target();
+            let camera = app.spawn_camera(Camera {
+                target,
+                ..default()
+            });
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    enable_stencil: true,
+                    depth_source,
+                    ..test_edge_detection()
+                });
+
+            let key = app.wait_for_pipeline(camera).key;
+            assert!(!key.enable_stencil, "{depth_source:?}");
+            assert!(!key.layout_key().stencil, "{depth_source:?}");
+        }
+    }
+
+    /// Rounding the corners of thick edges keeps their width, the edges cover about as many pixels.
+    #[test]
+    fn corner_rounding_keeps_the_edge_width() {
+        let mut app = RenderTestApp::new();
+        let target = app.image_target();
+        let camera = app.spawn_camera(Camera {
+            target: target.clone(),
+            ..default()
+        });
+
+        let mut edge_counts = Vec::new();
+        for corner_rounding in [0.0, 4.0] {
+            app.app
+                .world_mut()
+                .entity_mut(camera)
+                .insert(EdgeDetection {
+                    depth_thickness: 4.0,
+                    normal_thickness: 4.0,
+                    corner_rounding,
+                    ..test_edge_detection()
+                });
+            assert_eq!(
+                app.wait_for_pipeline(camera).key.corner_rounding,
+                corner_rounding > 0.0
+            );
+
+            let pixels = app.read_back(slice::from_ref(&target)).remove(0);
+            edge_counts.push(
+                pixels
+                    .chunks_exact(4)
+                    .filter(|pixel| pixel[..3].iter().all(|channel| *channel < 16))
+                    .count() as f32,
+            );
+        }
+        let [sharp, rounded] = edge_counts.try_into().unwrap();
+        assert!(sharp > 0.0, "no edge was drawn");
+        assert!(
+            (rounded - sharp).abs() < sharp * 0.2,
+            "the rounded edges cover {rounded} pixels, the sharp ones {sharp}"
+        );
     }
 }
@@ -0,0 +1,298 @@
+use bevy::{
+    asset::weak_handle,
+    core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    platform::collections::HashMap,
+    prelude::*,
+    render::{
+        render_resource::{
+            binding_types::{texture_2d, texture_2d_multisampled, uniform_buffer},
+            *,
+        },
+        renderer::RenderDevice,
+        texture::{CachedTexture, TextureCache},
+        view::ViewTarget,
+    },
+};
+
+use crate::{EdgeDetectionPipelineId, EdgeDetectionSupport, EdgeDetectionUniform};
+
+pub const EDGE_COMPOSITE_SHADER_HANDLE: Handle<Shader> =
+    weak_handle!("984c0900-86f9-4089-aa0f-598ae993a6f9");
+
+/// The format of the edge mask, the rgb channels hold the edge color and the alpha channel the edge opacity.
+pub const EDGE_MASK_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+
+/// The intermediate texture the edges of a view are drawn into,
+/// when they're post-filtered before being composited onto the screen.
+#[derive(Component)]
+pub struct EdgeDetectionMaskTexture(pub CachedTexture);
+
+/// Creates the edge mask texture of the views that need one.
+/// The views with [`EdgeDetection::checkerboard`](crate::EdgeDetection::checkerboard)
+/// or [`EdgeDetection::half_rate`](crate::EdgeDetection::half_rate) keep their history instead.
+pub fn prepare_edge_detection_mask_textures(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    render_device: Res<RenderDevice>,
+    views: Query<(Entity, &ViewTarget, &EdgeDetectionPipelineId)>,
+) {
+    for (entity, view_target, pipeline_id) in views.iter() {
+        if !pipeline_id.key.edge_mask {
+            commands.entity(entity).remove::<EdgeDetectionMaskTexture>();
+            continue;
+        }
+
+        if pipeline_id.key.checkerboard || pipeline_id.key.half_rate {
+            continue;
+        }
+
+        let texture = texture_cache.get(
+            &render_device,
+            TextureDescriptor {
+                label: Some("edge_detection_mask_texture"),
+                size: pipeline_id.mask_size(view_target),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: EDGE_MASK_TEXTURE_FORMAT,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+        );
+
+        commands
+            .entity(entity)
+            .insert(EdgeDetectionMaskTexture(texture));
+    }
+}
+
+/// The pipeline compositing the edge mask onto the screen.
+#[derive(Resource)]
+pub struct EdgeCompositePipeline {
+    /// The bind group layouts, created on demand for each layout key in use.
+    pub layouts: HashMap<EdgeCompositeLayoutKey, BindGroupLayout>,
+    /// Whether the color attachment and the edge mask are bound as filterable,
+    /// see [`EdgeDetectionPipeline::filterable`](crate::EdgeDetectionPipeline::filterable).
+    pub filterable: bool,
+}
+
+impl FromWorld for EdgeCompositePipeline {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            layouts: HashMap::default(),
+            filterable: world.resource::<EdgeDetectionSupport>().float_filtering,
+        }
+    }
+}
+
+impl EdgeCompositePipeline {
+    /// Returns the bind group layout of `key`.
+    ///
+    /// The layout must have been created by [`EdgeCompositePipeline::prepare_bind_group_layout`].
+    pub fn bind_group_layout(&self, key: EdgeCompositeLayoutKey) -> &BindGroupLayout {
+        &self.layouts[&key]
+    }
+
+    /// Creates the bind group layout of `key` if it doesn't exist yet.
+    pub fn prepare_bind_group_layout(
+        &mut self,
+        render_device: &RenderDevice,
+        key: EdgeCompositeLayoutKey,
+    ) -> &BindGroupLayout {
+        let filterable = self.filterable;
+        self.layouts
+            .entry(key)
+            .or_insert_with(|| Self::create_bind_group_layout(render_device, key, filterable))
+    }
+
+    fn create_bind_group_layout(
+        render_device: &RenderDevice,
+        key: EdgeCompositeLayoutKey,
+        filterable: bool,
+    ) -> BindGroupLayout {
+        let mut entries = BindGroupLayoutEntries::sequential(
+            ShaderStages::FRAGMENT,
+            (
+                // color attachment
+                texture_2d(TextureSampleType::Float { filterable }),
+                // edge mask
+                texture_2d(TextureSampleType::Float { filterable }),
+                // The uniform that will control the effect
+                uniform_buffer::<EdgeDetectionUniform>(true),
+            ),
+        )
+        .to_vec();
+
+        if key.thick_outline {
+            // jump flooded edge mask
+            entries.push(texture_2d(TextureSampleType::Uint).build(3, ShaderStages::FRAGMENT));
+        }
+
+        if key.reproject {
+            // motion vectors
+            let motion_vectors = match key.multisampled {
+                true => texture_2d_multisampled(TextureSampleType::Float { filterable: false }),
+                false => texture_2d(TextureSampleType::Float { filterable: false }),
+            };
+            entries.push(motion_vectors.build(4, ShaderStages::FRAGMENT));
+        }
+
+        render_device
+            .create_bind_group_layout("edge_detection: composite_bind_group_layout", &entries)
+    }
+}
+
+/// The parts of [`EdgeCompositeKey`] that change the bind group layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EdgeCompositeLayoutKey {
+    /// Whether the jump flood texture is bound.
+    pub thick_outline: bool,
+    /// Whether the motion vector prepass texture is bound.
+    pub reproject: bool,
+    /// Whether the motion vector prepass texture is multisampled.
+    pub multisampled: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EdgeCompositeKey {
+    /// The format of the main texture of the view, see [`EdgeDetectionKey::target_format`](crate::EdgeDetectionKey::target_format).
+    pub target_format: TextureFormat,
+    /// Whether we're using HDR.
+    pub hdr: bool,
+    /// Whether the render target holds gamma encoded values, see [`EdgeDetectionKey::gamma_encoded`](crate::EdgeDetectionKey::gamma_encoded).
+    pub gamma_encoded: bool,
+    /// Whether the edge opacity is written into the alpha channel, see [`EdgeDetection::write_alpha_mask`](crate::EdgeDetection::write_alpha_mask).
+    pub write_alpha_mask: bool,
+    /// Whether the alpha of the scene is kept, see [`EdgeDetection::preserve_scene_alpha`](crate::EdgeDetection::preserve_scene_alpha).
+    pub preserve_scene_alpha: bool,
+    /// Whether the scene color is posterized, see [`EdgeDetection::posterize`](crate::EdgeDetection::posterize).
+    pub posterize: bool,
+    /// Whether the scene color is graded beside the edges, see [`EdgeDetection::edge_adjacent_grading`](crate::EdgeDetection::edge_adjacent_grading).
+    pub adjacent_grading: bool,
+    /// Whether the scene is sharpened along the edges instead of drawing them, see [`OutputMode::Sharpen`](crate::OutputMode::Sharpen).
+    pub sharpen: bool,
+    /// Whether only the edges are written, see [`OutputMode::EdgesOnly`](crate::OutputMode::EdgesOnly).
+    pub edges_only: bool,
+    /// Whether the mask holds a debug view replacing the output, see [`EdgeDetection::debug_view`](crate::EdgeDetection::debug_view).
+    pub debug_view: bool,
+    /// Whether the edges are drawn over a flat background, see [`OutputMode::Blueprint`](crate::OutputMode::Blueprint).
+    pub blueprint: bool,
+    /// Whether the edge color is drawn everywhere but on the edges, see [`OutputMode::Inverted`](crate::OutputMode::Inverted).
+    pub inverted: bool,
+    /// Whether the corners of the edges are rounded, see [`EdgeDetection::corner_rounding`](crate::EdgeDetection::corner_rounding).
+    pub corner_rounding: bool,
+    /// Whether a thick outline is drawn around the edges, see [`EdgeDetection::thick_outline`](crate::EdgeDetection::thick_outline).
+    pub thick_outline: bool,
+    /// Whether the edge mask is upsampled to the view, see [`EdgeDetection::resolution_scale`](crate::EdgeDetection::resolution_scale).
+    pub scaled: bool,
+    /// Whether the edge mask is reprojected on some frames, see [`EdgeDetection::half_rate`](crate::EdgeDetection::half_rate).
+    pub reproject: bool,
+    /// Whether the motion vector prepass texture is multisampled, only set with `reproject`.
+    pub multisampled: bool,
+    /// The number of samples of the output, see [`EdgeDetectionKey::output_samples`](crate::EdgeDetectionKey::output_samples).
+    pub samples: u32,
+}
+
+impl EdgeCompositeKey {
+    pub fn layout_key(&self) -> EdgeCompositeLayoutKey {
+        EdgeCompositeLayoutKey {
+            thick_outline: self.thick_outline,
+            reproject: self.reproject,
+            multisampled: self.multisampled,
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for EdgeCompositePipeline {
+    type Key = EdgeCompositeKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let mut shader_defs = vec![];
+
+        if key.gamma_encoded {
+            shader_defs.push("GAMMA_ENCODED_TARGET".into());
+        }
+
+        if key.hdr {
+            shader_defs.push("HDR_TARGET".into());
+        }
+
+        if key.write_alpha_mask {
+            shader_defs.push("WRITE_ALPHA_MASK".into());
+        } else if key.preserve_scene_alpha {
+            shader_defs.push("PRESERVE_SCENE_ALPHA".into());
+        }
+
+        if key.posterize {
+            shader_defs.push("POSTERIZE".into());
+        }
+
+        if key.adjacent_grading {
+            shader_defs.push("ADJACENT_GRADING".into());
+        }
+
+        if key.sharpen {
+            shader_defs.push("SHARPEN".into());
+        }
+
+        if key.edges_only {
+            shader_defs.push("EDGES_ONLY".into());
+        }
+
+        if key.debug_view {
+            shader_defs.push("DEBUG_VIEW".into());
+        }
+
+        if key.blueprint {
+            shader_defs.push("BLUEPRINT".into());
+        }
+
+        if key.inverted {
+            shader_defs.push("INVERTED".into());
+        }
+
+        if key.corner_rounding {
+            shader_defs.push("CORNER_ROUNDING".into());
+        }
+
+        if key.thick_outline {
+            shader_defs.push("THICK_OUTLINE".into());
+        }
+
+        if key.scaled {
+            shader_defs.push("SCALED".into());
+        }
+
+        if key.reproject {
+            shader_defs.push("REPROJECT".into());
+        }
+
+        if key.multisampled {
+            shader_defs.push("MULTISAMPLED".into());
+        }
+
+        RenderPipelineDescriptor {
+            label: Some("edge_detection: composite_pipeline".into()),
+            layout: vec![self.bind_group_layout(key.layout_key()).clone()],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: EDGE_COMPOSITE_SHADER_HANDLE,
+                shader_defs,
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: key.target_format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: default(),
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: key.samples,
+                ..default()
+            },
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
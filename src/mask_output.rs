@@ -0,0 +1,90 @@
+use bevy::{
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        render_resource::*,
+        renderer::RenderDevice,
+        texture::CachedTexture,
+        view::ViewTarget,
+    },
+};
+
+use crate::EdgeDetectionPipelineId;
+
+/// The format of the [`ViewEdgeMaskTexture`], a single channel holding the strength of the edges.
+pub const EDGE_MASK_OUTPUT_FORMAT: TextureFormat = TextureFormat::R8Unorm;
+
+/// Writes the edges of a camera to a [`ViewEdgeMaskTexture`], for other effects to read.
+///
+/// Add it to a camera with [`EdgeDetection`](crate::EdgeDetection) to opt in. The edge detection pass then
+/// also writes the strength of the edges, in `[0.0, 1.0]`, to a texture of the render world view.
+/// It's written before the [`EdgeDetection::intensity`](crate::EdgeDetection::intensity),
+/// the blend mode and the output mode are applied, so it's produced even when the edges aren't drawn.
+///
+/// Without this component, the edge pass doesn't write any mask.
+#[derive(Component, ExtractComponent, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct EdgeDetectionMaskOutput;
+
+/// The edge mask of a view with an [`EdgeDetectionMaskOutput`], in the [`EDGE_MASK_OUTPUT_FORMAT`].
+///
+/// It's written by the edge detection pass, so the render graph nodes running after
+/// [`EdgeDetectionLabel`](crate::EdgeDetectionLabel) can query it on the view to bind the mask.
+/// The texture has the size of the main texture of the view, and is recreated when the view is resized.
+/// It isn't taken from the texture cache, so the pixels skipped by [`EdgeDetection::checkerboard`](crate::EdgeDetection::checkerboard)
+/// and the frames reprojected by [`EdgeDetection::half_rate`](crate::EdgeDetection::half_rate) keep the mask of the previous frame.
+#[derive(Component)]
+pub struct ViewEdgeMaskTexture(pub CachedTexture);
+
+pub(crate) fn build(app: &mut App) {
+    app.register_type::<EdgeDetectionMaskOutput>()
+        .add_plugins(ExtractComponentPlugin::<EdgeDetectionMaskOutput>::default());
+}
+
+/// Creates the edge mask texture of the views with an [`EdgeDetectionMaskOutput`], when they're resized too,
+/// and removes it from the other views.
+pub fn prepare_view_edge_mask_textures(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    views: Query<(
+        Entity,
+        &ViewTarget,
+        &EdgeDetectionPipelineId,
+        Option<&ViewEdgeMaskTexture>,
+    )>,
+) {
+    for (entity, view_target, pipeline_id, mask_output) in views.iter() {
+        if !pipeline_id.key.mask_output {
+            if mask_output.is_some() {
+                commands.entity(entity).remove::<ViewEdgeMaskTexture>();
+            }
+            continue;
+        }
+
+        let size = view_target.main_texture().size();
+        if mask_output.is_some_and(|mask_output| mask_output.0.texture.size() == size) {
+            continue;
+        }
+
+        let texture = render_device.create_texture(&TextureDescriptor {
+            label: Some("edge_detection_mask_output"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: EDGE_MASK_OUTPUT_FORMAT,
+            usage: TextureUsages::RENDER_ATTACHMENT
+                | TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let default_view = texture.create_view(&TextureViewDescriptor::default());
+
+        commands
+            .entity(entity)
+            .insert(ViewEdgeMaskTexture(CachedTexture {
+                texture,
+                default_view,
+            }));
+    }
+}
@@ -0,0 +1,625 @@
+use std::ops::Range;
+
+use bevy::{
+    asset::weak_handle,
+    core_pipeline::{
+        core_3d::graph::Node3d,
+        oit::OrderIndependentTransparencySettings,
+        prepass::{DeferredPrepass, DepthPrepass, MotionVectorPrepass, NormalPrepass},
+    },
+    ecs::query::QueryItem,
+    math::FloatOrd,
+    pbr::{
+        setup_morph_and_skinning_defs, DrawMesh, MeshPipeline, MeshPipelineKey,
+        RenderMeshInstances, SetMeshBindGroup, SetMeshViewBindGroup,
+    },
+    platform::collections::HashSet,
+    prelude::*,
+    render::{
+        camera::ExtractedCamera,
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        mesh::{MeshVertexBufferLayoutRef, RenderMesh},
+        render_asset::RenderAssets,
+        render_graph::{
+            InternedRenderSubGraph, NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel,
+            ViewNode, ViewNodeRunner,
+        },
+        render_phase::{
+            sort_phase_system, AddRenderCommand, CachedRenderPipelinePhaseItem, DrawFunctionId,
+            DrawFunctions, PhaseItem, PhaseItemExtraIndex, SetItemPipeline, SortedPhaseItem,
+            SortedRenderPhasePlugin, ViewSortedRenderPhases,
+        },
+        render_resource::*,
+        renderer::{RenderContext, RenderDevice},
+        sync_world::MainEntity,
+        texture::{CachedTexture, TextureCache},
+        view::{ExtractedView, RenderVisibleEntities, RetainedViewEntity, ViewTarget},
+        Extract, Render, RenderApp, RenderDebugFlags, RenderSet,
+    },
+};
+
+use crate::{EdgeDetection, EdgeDetectionPipelineId, EdgeDetectionSet, EdgeDetectionUniform};
+
+pub const EDGE_MESH_TAG_SHADER_HANDLE: Handle<Shader> =
+    weak_handle!("5b7e2c94-0d13-4a8f-9e61-c4f2a83d7b05");
+
+/// The format of the tags of the [`ViewEdgeMeshTagTextures`].
+///
+/// [`EDGE_MESH_TAG_EXCLUDED`], or the slot of the color of the mesh in the [`EdgeColorPalette`]
+/// offset by [`EDGE_MESH_TAG_FIRST_COLOR`], 0 without any tagged mesh.
+pub const EDGE_MESH_TAG_FORMAT: TextureFormat = TextureFormat::R8Uint;
+
+/// The format of the depth of the [`ViewEdgeMeshTagTextures`], compared with the depth of the scene.
+pub const EDGE_MESH_TAG_DEPTH_FORMAT: TextureFormat = TextureFormat::Depth32Float;
+
+/// The tag of the [`NoEdgeDetection`] meshes.
+pub const EDGE_MESH_TAG_EXCLUDED: u32 = 1;
+
+/// The tag of the [`EdgeColorOverride`] meshes whose color is in the first slot of the [`EdgeColorPalette`].
+pub const EDGE_MESH_TAG_FIRST_COLOR: u32 = 2;
+
+/// The maximum number of distinct [`EdgeColorOverride`] colors.
+pub const MAX_EDGE_COLOR_OVERRIDES: usize = 8;
+
+/// Excludes a mesh from the edge detection of the 3D cameras.
+///
+/// The mesh neither gets edges nor draws its silhouette onto its neighbors, e.g. for a water surface,
+/// foliage cards or the first-person arms of the player. The edges of the other objects are dropped
+/// as far as the thicknesses reach around it, so an object in front of it isn't outlined against it either.
+///
+/// The excluded meshes are drawn into the [`ViewEdgeMeshTagTextures`] with their depth, which is compared
+/// with the depth of the scene, so the parts of them hidden behind other objects don't hide their edges.
+/// Without any excluded mesh, the edge detection pass doesn't sample it at all.
+#[derive(Component, ExtractComponent, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct NoEdgeDetection;
+
+/// Outlines a mesh with its own color on the 3D cameras, instead of the [`EdgeDetection::edge_color`].
+///
+/// The edges around the mesh take the color of the nearest surface in their footprint: the silhouette
+/// between two meshes with different colors is drawn in the color of the one in front, and the silhouette
+/// against the background or an object without an override in the color of the mesh.
+/// The alpha of the color scales the opacity of the edges like the one of the `edge_color`.
+///
+/// At most [`MAX_EDGE_COLOR_OVERRIDES`] distinct colors are used at once, the meshes with other colors
+/// keep the color of the camera. A [`NoEdgeDetection`] on the same mesh takes precedence.
+/// Without any override, the edge detection pass doesn't sample the colors at all.
+#[derive(Component, ExtractComponent, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct EdgeColorOverride(pub Color);
+
+/// The distinct colors of the [`EdgeColorOverride`]s, in the order their meshes were extracted.
+///
+/// It's rebuilt every frame and copied into the [`EdgeDetectionUniform::edge_color_overrides`] of the views.
+#[derive(Resource, Default)]
+pub struct EdgeColorPalette {
+    pub colors: Vec<LinearRgba>,
+}
+
+impl EdgeColorPalette {
+    /// The slot of `color`, if it's in the palette.
+    pub fn slot(&self, color: LinearRgba) -> Option<u32> {
+        self.colors
+            .iter()
+            .position(|&slot| slot == color)
+            .map(|slot| slot as u32)
+    }
+
+    /// The colors of the palette, padded to the size of the uniform array.
+    pub fn uniform(&self) -> [LinearRgba; MAX_EDGE_COLOR_OVERRIDES] {
+        let mut colors = [LinearRgba::NONE; MAX_EDGE_COLOR_OVERRIDES];
+        for (slot, &color) in colors.iter_mut().zip(&self.colors) {
+            *slot = color;
+        }
+        colors
+    }
+}
+
+/// The tags of the [`NoEdgeDetection`] and [`EdgeColorOverride`] meshes of a view, cleared every frame.
+#[derive(Component)]
+pub struct ViewEdgeMeshTagTextures {
+    /// The tags, in the [`EDGE_MESH_TAG_FORMAT`].
+    pub tags: CachedTexture,
+    /// The depth of the tagged meshes, in the [`EDGE_MESH_TAG_DEPTH_FORMAT`], cleared to the far plane.
+    pub depth: CachedTexture,
+}
+
+/// The node drawing the tagged meshes of the views, after the prepasses.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+pub struct EdgeMeshTagLabel;
+
+pub(crate) fn build(app: &mut App) {
+    app.register_type::<NoEdgeDetection>()
+        .register_type::<EdgeColorOverride>()
+        .add_plugins((
+            ExtractComponentPlugin::<NoEdgeDetection>::default(),
+            ExtractComponentPlugin::<EdgeColorOverride>::default(),
+            SortedRenderPhasePlugin::<EdgeMeshTag3d, MeshPipeline>::new(
+                RenderDebugFlags::default(),
+            ),
+        ));
+
+    let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+
+    render_app
+        .init_resource::<EdgeColorPalette>()
+        .init_resource::<SpecializedMeshPipelines<EdgeMeshTagPipeline>>()
+        .init_resource::<DrawFunctions<EdgeMeshTag3d>>()
+        .add_render_command::<EdgeMeshTag3d, DrawEdgeMeshTag>()
+        .add_systems(
+            ExtractSchedule,
+            (
+                extract_edge_mesh_tag_phases,
+                extract_edge_color_palette
+                    .before(EdgeDetectionUniform::extract_edge_detection_settings),
+            )
+                .in_set(EdgeDetectionSet::Extract),
+        )
+        .add_systems(
+            Render,
+            (
+                queue_edge_mesh_tags.in_set(RenderSet::QueueMeshes),
+                sort_phase_system::<EdgeMeshTag3d>.in_set(RenderSet::PhaseSort),
+                prepare_edge_mesh_tag_textures.in_set(EdgeDetectionSet::PrepareResources),
+            ),
+        );
+}
+
+/// Adds the node drawing the tagged meshes to `graph`, between the prepasses and the main passes.
+pub(crate) fn add_node(render_app: &mut SubApp, graph: InternedRenderSubGraph) {
+    render_app
+        .add_render_graph_node::<ViewNodeRunner<EdgeMeshTagNode>>(graph, EdgeMeshTagLabel)
+        .add_render_graph_edges(
+            graph,
+            (
+                Node3d::EndPrepasses,
+                EdgeMeshTagLabel,
+                Node3d::StartMainPass,
+            ),
+        );
+}
+
+pub(crate) fn finish(app: &mut App) {
+    app.sub_app_mut(RenderApp)
+        .init_resource::<EdgeMeshTagPipeline>();
+}
+
+/// A tagged mesh drawn into the [`ViewEdgeMeshTagTextures`] of a view.
+pub struct EdgeMeshTag3d {
+    pub sort_key: FloatOrd,
+    pub entity: (Entity, MainEntity),
+    pub pipeline: CachedRenderPipelineId,
+    pub draw_function: DrawFunctionId,
+    pub batch_range: Range<u32>,
+    pub extra_index: PhaseItemExtraIndex,
+    pub indexed: bool,
+}
+
+impl PhaseItem for EdgeMeshTag3d {
+    #[inline]
+    fn entity(&self) -> Entity {
+        self.entity.0
+    }
+
+    #[inline]
+    fn main_entity(&self) -> MainEntity {
+        self.entity.1
+    }
+
+    #[inline]
+    fn draw_function(&self) -> DrawFunctionId {
+        self.draw_function
+    }
+
+    #[inline]
+    fn batch_range(&self) -> &Range<u32> {
+        &self.batch_range
+    }
+
+    #[inline]
+    fn batch_range_mut(&mut self) -> &mut Range<u32> {
+        &mut self.batch_range
+    }
+
+    #[inline]
+    fn extra_index(&self) -> PhaseItemExtraIndex {
+        self.extra_index.clone()
+    }
+
+    #[inline]
+    fn batch_range_and_extra_index_mut(&mut self) -> (&mut Range<u32>, &mut PhaseItemExtraIndex) {
+        (&mut self.batch_range, &mut self.extra_index)
+    }
+}
+
+impl SortedPhaseItem for EdgeMeshTag3d {
+    type SortKey = FloatOrd;
+
+    #[inline]
+    fn sort_key(&self) -> Self::SortKey {
+        self.sort_key
+    }
+
+    #[inline]
+    fn sort(items: &mut [Self]) {
+        items.sort_by_key(SortedPhaseItem::sort_key);
+    }
+
+    #[inline]
+    fn indexed(&self) -> bool {
+        self.indexed
+    }
+}
+
+impl CachedRenderPipelinePhaseItem for EdgeMeshTag3d {
+    #[inline]
+    fn cached_pipeline(&self) -> CachedRenderPipelineId {
+        self.pipeline
+    }
+}
+
+type DrawEdgeMeshTag = (
+    SetItemPipeline,
+    SetMeshViewBindGroup<0>,
+    SetMeshBindGroup<1>,
+    DrawMesh,
+);
+
+/// The pipeline drawing the tagged meshes, specialized for each mesh layout and tag.
+#[derive(Resource)]
+pub struct EdgeMeshTagPipeline {
+    pub mesh_pipeline: MeshPipeline,
+}
+
+impl FromWorld for EdgeMeshTagPipeline {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            mesh_pipeline: MeshPipeline::from_world(world),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EdgeMeshTagPipelineKey {
+    pub mesh_key: MeshPipelineKey,
+    /// The tag written by the mesh, see [`EDGE_MESH_TAG_FORMAT`].
+    ///
+    /// It's a shader def rather than per-instance data, so the draws of different tags are never batched together.
+    pub tag: u32,
+}
+
+impl SpecializedMeshPipeline for EdgeMeshTagPipeline {
+    type Key = EdgeMeshTagPipelineKey;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayoutRef,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let mut shader_defs = vec![ShaderDefVal::UInt("MESH_TAG".into(), key.tag)];
+        let mut vertex_attributes = vec![Mesh::ATTRIBUTE_POSITION.at_shader_location(0)];
+
+        if let Some(per_object_buffer_batch_size) = self.mesh_pipeline.per_object_buffer_batch_size
+        {
+            shader_defs.push(ShaderDefVal::UInt(
+                "PER_OBJECT_BUFFER_BATCH_SIZE".into(),
+                per_object_buffer_batch_size,
+            ));
+        }
+
+        // The skinned and morphed meshes are bound with their own layouts by `SetMeshBindGroup`.
+        let mesh_layout = setup_morph_and_skinning_defs(
+            &self.mesh_pipeline.mesh_layouts,
+            layout,
+            1,
+            &key.mesh_key,
+            &mut shader_defs,
+            &mut vertex_attributes,
+            self.mesh_pipeline.skins_use_uniform_buffers,
+        );
+
+        Ok(RenderPipelineDescriptor {
+            label: Some("edge_detection: mesh_tag_pipeline".into()),
+            layout: vec![
+                self.mesh_pipeline
+                    .get_view_layout(key.mesh_key.into())
+                    .clone(),
+                mesh_layout,
+            ],
+            push_constant_ranges: vec![],
+            vertex: VertexState {
+                shader: EDGE_MESH_TAG_SHADER_HANDLE,
+                shader_defs: shader_defs.clone(),
+                entry_point: "vertex".into(),
+                buffers: vec![layout.0.get_layout(&vertex_attributes)?],
+            },
+            fragment: Some(FragmentState {
+                shader: EDGE_MESH_TAG_SHADER_HANDLE,
+                shader_defs,
+                entry_point: "fragment".into(),
+                targets: vec![Some(EDGE_MESH_TAG_FORMAT.into())],
+            }),
+            primitive: PrimitiveState {
+                topology: key.mesh_key.primitive_topology(),
+                // The nearest faces are kept by the depth test, whichever side the materials cull.
+                cull_mode: None,
+                ..default()
+            },
+            depth_stencil: Some(DepthStencilState {
+                format: EDGE_MESH_TAG_DEPTH_FORMAT,
+                depth_write_enabled: true,
+                // Reverse z, like the depth of the scene.
+                depth_compare: CompareFunction::GreaterEqual,
+                stencil: default(),
+                bias: default(),
+            }),
+            multisample: default(),
+            zero_initialize_workgroup_memory: false,
+        })
+    }
+}
+
+/// Creates the mesh tag phases of the 3D cameras with an [`EdgeDetection`].
+#[allow(clippy::type_complexity)]
+pub fn extract_edge_mesh_tag_phases(
+    mut phases: ResMut<ViewSortedRenderPhases<EdgeMeshTag3d>>,
+    cameras: Extract<Query<(Entity, &Camera), (With<Camera3d>, With<EdgeDetection>)>>,
+    mut live_entities: Local<HashSet<RetainedViewEntity>>,
+) {
+    live_entities.clear();
+    for (main_entity, camera) in &cameras {
+        if !camera.is_active {
+            continue;
+        }
+
+        let retained_view_entity = RetainedViewEntity::new(main_entity.into(), None, 0);
+        phases.insert_or_clear(retained_view_entity);
+        live_entities.insert(retained_view_entity);
+    }
+
+    phases.retain(|view, _| live_entities.contains(view));
+}
+
+/// Collects the distinct colors of the [`EdgeColorOverride`]s into the [`EdgeColorPalette`].
+#[allow(clippy::type_complexity)]
+pub fn extract_edge_color_palette(
+    mut palette: ResMut<EdgeColorPalette>,
+    overrides: Extract<Query<&EdgeColorOverride, (With<Mesh3d>, Without<NoEdgeDetection>)>>,
+) {
+    palette.colors.clear();
+    for &EdgeColorOverride(color) in &overrides {
+        let color = color.into();
+        if palette.slot(color).is_some() {
+            continue;
+        }
+        if palette.colors.len() == MAX_EDGE_COLOR_OVERRIDES {
+            warn_once!(
+                "More than {MAX_EDGE_COLOR_OVERRIDES} distinct `EdgeColorOverride` colors, the meshes with the others keep the edge color of the cameras"
+            );
+            break;
+        }
+        palette.colors.push(color);
+    }
+}
+
+/// Queues the visible [`NoEdgeDetection`] and [`EdgeColorOverride`] meshes of the views into their mesh tag phase.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn queue_edge_mesh_tags(
+    draw_functions: Res<DrawFunctions<EdgeMeshTag3d>>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<EdgeMeshTagPipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    mesh_tag_pipeline: Res<EdgeMeshTagPipeline>,
+    render_meshes: Res<RenderAssets<RenderMesh>>,
+    render_mesh_instances: Res<RenderMeshInstances>,
+    palette: Res<EdgeColorPalette>,
+    mut phases: ResMut<ViewSortedRenderPhases<EdgeMeshTag3d>>,
+    views: Query<
+        (
+            &ExtractedView,
+            &RenderVisibleEntities,
+            &Msaa,
+            Has<DepthPrepass>,
+            Has<NormalPrepass>,
+            Has<MotionVectorPrepass>,
+            Has<DeferredPrepass>,
+            Has<OrderIndependentTransparencySettings>,
+        ),
+        With<EdgeDetection>,
+    >,
+    tagged: Query<
+        (Has<NoEdgeDetection>, Option<&EdgeColorOverride>),
+        Or<(With<NoEdgeDetection>, With<EdgeColorOverride>)>,
+    >,
+) {
+    if tagged.is_empty() {
+        return;
+    }
+
+    let draw_function = draw_functions.read().id::<DrawEdgeMeshTag>();
+
+    for (
+        view,
+        visible_entities,
+        msaa,
+        depth_prepass,
+        normal_prepass,
+        motion_vector_prepass,
+        deferred_prepass,
+        oit,
+    ) in views.iter()
+    {
+        let Some(phase) = phases.get_mut(&view.retained_view_entity) else {
+            continue;
+        };
+
+        // The view bind group is the one of the mesh pipelines, its layout depends on the prepasses of the view.
+        let mut view_key = MeshPipelineKey::from_msaa_samples(msaa.samples());
+        for (enabled, flag) in [
+            (depth_prepass, MeshPipelineKey::DEPTH_PREPASS),
+            (normal_prepass, MeshPipelineKey::NORMAL_PREPASS),
+            (
+                motion_vector_prepass,
+                MeshPipelineKey::MOTION_VECTOR_PREPASS,
+            ),
+            (deferred_prepass, MeshPipelineKey::DEFERRED_PREPASS),
+            (oit, MeshPipelineKey::OIT_ENABLED),
+        ] {
+            if enabled {
+                view_key |= flag;
+            }
+        }
+
+        let rangefinder = view.rangefinder3d();
+        for &(render_entity, visible_entity) in visible_entities.iter::<Mesh3d>() {
+            let Ok((excluded, color_override)) = tagged.get(render_entity) else {
+                continue;
+            };
+            let tag = match color_override {
+                _ if excluded => EDGE_MESH_TAG_EXCLUDED,
+                Some(&EdgeColorOverride(color)) => match palette.slot(color.into()) {
+                    Some(slot) => EDGE_MESH_TAG_FIRST_COLOR + slot,
+                    None => continue,
+                },
+                None => continue,
+            };
+            let Some(mesh_instance) = render_mesh_instances.render_mesh_queue_data(visible_entity)
+            else {
+                continue;
+            };
+            let Some(mesh) = render_meshes.get(mesh_instance.mesh_asset_id) else {
+                continue;
+            };
+
+            let mut mesh_key = view_key | MeshPipelineKey::from_bits_retain(mesh.key_bits.bits());
+            // `SetMeshBindGroup` binds the lightmap of the mesh, which needs its layout.
+            if mesh_instance.lightmap_slab_index.is_some() {
+                mesh_key |= MeshPipelineKey::LIGHTMAPPED;
+            }
+
+            let pipeline = match pipelines.specialize(
+                &pipeline_cache,
+                &mesh_tag_pipeline,
+                EdgeMeshTagPipelineKey { mesh_key, tag },
+                &mesh.layout,
+            ) {
+                Ok(pipeline) => pipeline,
+                Err(error) => {
+                    error!("{error}");
+                    continue;
+                }
+            };
+
+            phase.add(EdgeMeshTag3d {
+                sort_key: FloatOrd(rangefinder.distance_translation(&mesh_instance.translation)),
+                entity: (render_entity, visible_entity),
+                pipeline,
+                draw_function,
+                batch_range: 0..1,
+                extra_index: PhaseItemExtraIndex::None,
+                indexed: mesh.indexed(),
+            });
+        }
+    }
+}
+
+/// Creates the mesh tag textures of the views with tagged meshes, and removes them from the other views.
+pub fn prepare_edge_mesh_tag_textures(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    render_device: Res<RenderDevice>,
+    views: Query<(Entity, &ViewTarget, &EdgeDetectionPipelineId)>,
+) {
+    for (entity, view_target, pipeline_id) in views.iter() {
+        if !pipeline_id.key.layout_key().mesh_tags {
+            commands.entity(entity).remove::<ViewEdgeMeshTagTextures>();
+            continue;
+        }
+
+        let mut texture = |label, format| {
+            texture_cache.get(
+                &render_device,
+                TextureDescriptor {
+                    label: Some(label),
+                    size: view_target.main_texture().size(),
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: TextureDimension::D2,
+                    format,
+                    usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                },
+            )
+        };
+
+        let tags = texture("edge_detection_mesh_tag_texture", EDGE_MESH_TAG_FORMAT);
+        let depth = texture(
+            "edge_detection_mesh_tag_depth_texture",
+            EDGE_MESH_TAG_DEPTH_FORMAT,
+        );
+        commands
+            .entity(entity)
+            .insert(ViewEdgeMeshTagTextures { tags, depth });
+    }
+}
+
+/// Draws the mesh tag phase of the view into its [`ViewEdgeMeshTagTextures`].
+#[derive(Default)]
+pub struct EdgeMeshTagNode;
+
+impl ViewNode for EdgeMeshTagNode {
+    type ViewQuery = (
+        &'static ExtractedCamera,
+        &'static ExtractedView,
+        &'static ViewEdgeMeshTagTextures,
+    );
+
+    fn run<'w>(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext<'w>,
+        (camera, view, mesh_tag_textures): QueryItem<'w, Self::ViewQuery>,
+        world: &'w World,
+    ) -> Result<(), NodeRunError> {
+        // The textures are cleared even without any tagged mesh in view.
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("edge_detection_mesh_tag_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: &mesh_tag_textures.tags.default_view,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(default()),
+                    store: StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                view: &mesh_tag_textures.depth.default_view,
+                depth_ops: Some(Operations {
+                    load: LoadOp::Clear(0.0),
+                    store: StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        if let Some(viewport) = camera.viewport.as_ref() {
+            render_pass.set_camera_viewport(viewport);
+        }
+
+        let Some(phase) = world
+            .resource::<ViewSortedRenderPhases<EdgeMeshTag3d>>()
+            .get(&view.retained_view_entity)
+        else {
+            return Ok(());
+        };
+
+        if let Err(error) = phase.render(&mut render_pass, world, graph.view_entity()) {
+            error!("Error encountered while rendering the edge mesh tag phase {error:?}");
+        }
+
+        Ok(())
+    }
+}
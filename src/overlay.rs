@@ -0,0 +1,329 @@
+use bevy::{
+    asset::weak_handle,
+    core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        render_asset::RenderAssets,
+        render_graph::{
+            InternedRenderLabel, NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel,
+            ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::GpuImage,
+        view::ViewTarget,
+        Render, RenderApp,
+    },
+};
+
+use crate::{EdgeDetectionPlugin, EdgeDetectionSet, EdgeDetectionSupport};
+
+pub const EDGE_OVERLAY_SHADER_HANDLE: Handle<Shader> =
+    weak_handle!("9c41e7d2-5a38-4f0b-8e16-b3d27a90c5e4");
+
+/// Composites the edges of another camera over the output of this one, e.g. the outlines of a "memory"
+/// of another place drawn over the current view.
+///
+/// The other camera renders its edges alone into `image`, with [`OutputMode::EdgesOnly`](crate::OutputMode::EdgesOnly),
+/// and must render before this one, with a lower [`Camera::order`]. The image is stretched over the whole
+/// target of this camera, whatever its resolution.
+///
+/// The overlay is drawn after the edge detection of this camera, if it has one.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct EdgeOverlay {
+    /// The edges to composite, the target of the other camera.
+    pub image: Handle<Image>,
+    /// How the edges are blended with the output of this camera.
+    pub blend: EdgeOverlayBlend,
+    /// The opacity of the overlay, multiplying the opacity of the edges.
+    ///
+    /// Range: [0.0, 1.0]
+    pub opacity: f32,
+}
+
+impl EdgeOverlay {
+    /// An opaque overlay of the edges in `image`, drawn over the output like regular edges.
+    pub fn new(image: Handle<Image>) -> Self {
+        Self {
+            image,
+            blend: EdgeOverlayBlend::Alpha,
+            opacity: 1.0,
+        }
+    }
+}
+
+/// How the edges of an [`EdgeOverlay`] are blended with the output of the camera,
+/// weighted by the opacity of the edges.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default)]
+pub enum EdgeOverlayBlend {
+    /// The edges are drawn over the output, like the edges of the camera itself.
+    #[default]
+    Alpha,
+    /// The edge color is added to the output, so the edges glow.
+    Add,
+    /// The output is multiplied by the edge color, so the edges darken it.
+    Multiply,
+    /// The inverse of the output is multiplied by the inverse of the edge color, so the edges lighten it.
+    Screen,
+}
+
+impl EdgeOverlayBlend {
+    /// The value of the blend mode in the shader, matching the `BLEND_*` constants.
+    fn index(self) -> u32 {
+        match self {
+            Self::Alpha => 0,
+            Self::Add => 1,
+            Self::Multiply => 2,
+            Self::Screen => 3,
+        }
+    }
+}
+
+/// The settings of an [`EdgeOverlay`] in the shader.
+#[derive(Component, Clone, Copy, ShaderType)]
+pub struct EdgeOverlayUniform {
+    pub opacity: f32,
+    pub blend: u32,
+    // WebGL2 requires the uniform buffers to be padded to 16 bytes.
+    pub _webgl2_padding: Vec2,
+}
+
+impl ExtractComponent for EdgeOverlay {
+    type QueryData = &'static EdgeOverlay;
+    type QueryFilter = With<Camera3d>;
+    type Out = (EdgeOverlay, EdgeOverlayUniform);
+
+    fn extract_component(overlay: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        let uniform = EdgeOverlayUniform {
+            opacity: overlay.opacity.clamp(0.0, 1.0),
+            blend: overlay.blend.index(),
+            _webgl2_padding: Vec2::ZERO,
+        };
+        Some((overlay.clone(), uniform))
+    }
+}
+
+/// The overlay node, after the edge detection at [`EdgeDetectionPlugin::placement`](crate::EdgeDetectionPlugin::placement).
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+pub struct EdgeOverlayLabel;
+
+pub(crate) fn build(app: &mut App) {
+    app.register_type::<EdgeOverlay>()
+        .add_plugins(ExtractComponentPlugin::<EdgeOverlay>::default())
+        .add_plugins(UniformComponentPlugin::<EdgeOverlayUniform>::default());
+
+    let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+
+    render_app
+        .init_resource::<SpecializedRenderPipelines<EdgeOverlayPipeline>>()
+        .add_systems(
+            Render,
+            prepare_edge_overlay_pipelines.in_set(EdgeDetectionSet::Prepare),
+        );
+}
+
+/// Adds the overlay node to the graph of `plugin`, between the nodes of its
+/// [`EdgeDetectionPlacement::PostProcess`](crate::EdgeDetectionPlacement::PostProcess), and after `after`.
+pub(crate) fn add_node(
+    render_app: &mut SubApp,
+    plugin: &EdgeDetectionPlugin,
+    after: Option<InternedRenderLabel>,
+) {
+    render_app
+        .add_render_graph_node::<ViewNodeRunner<EdgeOverlayNode>>(plugin.graph, EdgeOverlayLabel);
+
+    for &node in plugin.after.iter().chain(&after) {
+        render_app.add_render_graph_edge(plugin.graph, node, EdgeOverlayLabel);
+    }
+    for &node in &plugin.before {
+        render_app.add_render_graph_edge(plugin.graph, EdgeOverlayLabel, node);
+    }
+}
+
+pub(crate) fn finish(app: &mut App) {
+    app.sub_app_mut(RenderApp)
+        .init_resource::<EdgeOverlayPipeline>();
+}
+
+/// The pipeline compositing an [`EdgeOverlay`], specialized for the format of the main texture of the view.
+#[derive(Resource)]
+pub struct EdgeOverlayPipeline {
+    pub layout: BindGroupLayout,
+    pub sampler: Sampler,
+}
+
+impl FromWorld for EdgeOverlayPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let filterable = world.resource::<EdgeDetectionSupport>().float_filtering;
+        let render_device = world.resource::<RenderDevice>();
+
+        // The images are stretched with a bilinear filter, or sampled at the nearest texels without float filtering.
+        let (sampler_binding, filter) = match filterable {
+            true => (SamplerBindingType::Filtering, FilterMode::Linear),
+            false => (SamplerBindingType::NonFiltering, FilterMode::Nearest),
+        };
+
+        let layout = render_device.create_bind_group_layout(
+            "edge_detection: overlay_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    // screen texture
+                    texture_2d(TextureSampleType::Float { filterable }),
+                    // overlay texture
+                    texture_2d(TextureSampleType::Float { filterable }),
+                    // overlay sampler
+                    sampler(sampler_binding),
+                    // overlay uniform
+                    uniform_buffer::<EdgeOverlayUniform>(true),
+                ),
+            ),
+        );
+
+        let sampler = render_device.create_sampler(&SamplerDescriptor {
+            label: Some("edge detection overlay sampler"),
+            mag_filter: filter,
+            min_filter: filter,
+            ..default()
+        });
+
+        Self { layout, sampler }
+    }
+}
+
+impl SpecializedRenderPipeline for EdgeOverlayPipeline {
+    type Key = TextureFormat;
+
+    fn specialize(&self, format: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("edge_detection: overlay_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: EDGE_OVERLAY_SHADER_HANDLE,
+                shader_defs: vec![],
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: default(),
+            depth_stencil: None,
+            multisample: default(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
+
+/// The overlay pipeline of a view with an [`EdgeOverlay`].
+#[derive(Component)]
+pub struct EdgeOverlayPipelineId(pub CachedRenderPipelineId);
+
+/// Specializes the overlay pipelines of the views with an [`EdgeOverlay`].
+pub fn prepare_edge_overlay_pipelines(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<EdgeOverlayPipeline>>,
+    overlay_pipeline: Res<EdgeOverlayPipeline>,
+    views: Query<(Entity, &ViewTarget), With<EdgeOverlay>>,
+) {
+    for (entity, view_target) in views.iter() {
+        let id = pipelines.specialize(
+            &pipeline_cache,
+            &overlay_pipeline,
+            view_target.main_texture_format(),
+        );
+        commands.entity(entity).insert(EdgeOverlayPipelineId(id));
+    }
+}
+
+/// Composites the [`EdgeOverlay`] of the view over its main texture.
+#[derive(Default)]
+pub struct EdgeOverlayNode;
+
+impl ViewNode for EdgeOverlayNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static EdgeOverlay,
+        &'static EdgeOverlayPipelineId,
+        &'static DynamicUniformIndex<EdgeOverlayUniform>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, overlay, pipeline_id, uniform_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let Some(pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(pipeline_id.0)
+        else {
+            return Ok(());
+        };
+
+        // The other camera may not have rendered into the image yet.
+        let Some(image) = world
+            .resource::<RenderAssets<GpuImage>>()
+            .get(&overlay.image)
+        else {
+            return Ok(());
+        };
+
+        let Some(uniforms) = world
+            .resource::<ComponentUniforms<EdgeOverlayUniform>>()
+            .uniforms()
+            .binding()
+        else {
+            return Ok(());
+        };
+
+        let overlay_pipeline = world.resource::<EdgeOverlayPipeline>();
+        let post_process = view_target.post_process_write();
+
+        let bind_group = render_context.render_device().create_bind_group(
+            "edge_detection_overlay_bind_group",
+            &overlay_pipeline.layout,
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &image.texture_view,
+                &overlay_pipeline.sampler,
+                uniforms,
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("edge_detection_overlay_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[uniform_index.index()]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,803 @@
+use bevy::{
+    prelude::*,
+    render::{
+        diagnostic::RecordDiagnostics,
+        extract_component::ComponentUniforms,
+        render_asset::RenderAssets,
+        render_resource::*,
+        renderer::RenderContext,
+        texture::GpuImage,
+        view::{ViewTarget, ViewUniforms},
+    },
+};
+
+use crate::{
+    EdgeAaPipeline, EdgeCompositePipeline, EdgeDetectionAaTexture, EdgeDetectionDilationTexture,
+    EdgeDetectionJumpFloodTextures, EdgeDetectionKey, EdgeDetectionLayoutKey,
+    EdgeDetectionPipeline, EdgeDetectionPipelineId, EdgeDetectionTemporalHistory,
+    EdgeDetectionUniform, EdgeDilationPipeline, EdgeTemporalPipeline, JumpFloodPasses,
+    JumpFloodPipeline, ThickOutline, ViewEdgeMeshTagTextures, EDGE_DETECTION_OUTPUT_BINDING,
+    EDGE_DETECTION_WORKGROUP_SIZE, EDGE_SOURCE_FIRST_BINDING, MAX_EDGE_SOURCES,
+};
+
+/// The textures and offsets the edge detection pass of a view is recorded with, see [`EdgeDetectionPass::record`].
+///
+/// The edges are drawn over the main texture of the [`ViewTarget`] passed along with the inputs.
+///
+/// The optional inputs are only used if the pipeline key of the view needs them.
+pub struct EdgeDetectionInputs<'a> {
+    /// The depth texture of the [`EdgeDetection::depth_source`](crate::EdgeDetection::depth_source),
+    /// needed by [`EdgeDetectionKey::depth_texture`].
+    pub depth: Option<&'a TextureView>,
+    /// The stencil aspect of the depth texture, needed by [`EdgeDetectionKey::enable_stencil`].
+    pub stencil: Option<&'a TextureView>,
+    /// The normal prepass texture, needed unless [`EdgeDetectionKey::geometric_normals`].
+    pub normal: Option<&'a TextureView>,
+    /// The motion vector prepass texture, needed by the motion emphasis and smear.
+    pub motion_vectors: Option<&'a TextureView>,
+    /// The deferred G-buffer, needed by [`EdgeDetectionKey::enable_material`] and [`EdgeDetectionKey::deferred_normals`].
+    pub deferred: Option<&'a TextureView>,
+    /// The noise texture distorting the edges, see [`EdgeDetection::noise_texture`](crate::EdgeDetection::noise_texture).
+    /// `None` uses the embedded noise.
+    pub noise: Option<&'a TextureView>,
+    /// The screen-space shadow mask, needed by [`EdgeDetectionKey::enable_shadow`].
+    pub shadow_mask: Option<&'a TextureView>,
+    /// The screen-space mask of the edges, needed by [`EdgeDetectionKey::screen_mask`].
+    pub screen_mask: Option<&'a TextureView>,
+    /// The tags of the excluded and color overridden meshes, needed by [`EdgeDetectionKey::excluded_meshes`]
+    /// and [`EdgeDetectionKey::edge_color_overrides`].
+    pub mesh_tags: Option<&'a ViewEdgeMeshTagTextures>,
+    /// The ambient visibility of the view, needed by [`EdgeDetectionKey::ambient_occlusion`].
+    pub ambient_occlusion: Option<&'a TextureView>,
+    /// The edge pixel counter, needed by [`EdgeDetectionKey::edge_coverage`]. It's cleared by the pass.
+    pub edge_coverage: Option<&'a Buffer>,
+    /// The gradient histograms, needed by [`EdgeDetectionKey::auto_calibrate`]. They're cleared by the pass.
+    pub calibration: Option<&'a Buffer>,
+    /// The textures of the [`EdgeSource`](crate::EdgeSource)s, needed by the bits of [`EdgeDetectionKey::edge_sources`].
+    pub edge_sources: [Option<&'a TextureView>; MAX_EDGE_SOURCES],
+    /// The intermediate texture the edges are drawn into, needed by [`EdgeDetectionKey::edge_mask`].
+    pub mask: Option<&'a TextureView>,
+    /// The texture the strength of the edges is written to, needed by [`EdgeDetectionKey::mask_output`],
+    /// e.g. the [`ViewEdgeMaskTexture`](crate::ViewEdgeMaskTexture) of the view.
+    pub mask_output: Option<&'a TextureView>,
+    /// The texture the edge mask is anti-aliased into, needed by [`EdgeDetectionKey::edge_aa`].
+    pub aa: Option<&'a EdgeDetectionAaTexture>,
+    /// The texture the edge mask is dilated into, needed by [`EdgeDetectionKey::dilation`].
+    pub dilation: Option<&'a EdgeDetectionDilationTexture>,
+    /// The textures the edge mask is jump flooded in, needed by [`EdgeDetectionKey::thick_outline`].
+    pub jump_flood: Option<&'a EdgeDetectionJumpFloodTextures>,
+    /// The thick outline of the view, needed by [`EdgeDetectionKey::thick_outline`].
+    pub thick_outline: Option<ThickOutline>,
+    /// Whether the edges of the previous frame are reprojected instead of being detected, with [`EdgeDetectionKey::half_rate`].
+    /// See [`EdgeDetectionHalfRateHistory::reproject`](crate::EdgeDetectionHalfRateHistory::reproject).
+    pub reproject: bool,
+    /// The history the edge mask is blended with, needed by [`EdgeDetectionKey::temporal`].
+    /// It's blended with the `depth` and `motion_vectors` textures.
+    pub temporal: Option<&'a EdgeDetectionTemporalHistory>,
+    /// The filtering of the `source` texture, see [`EdgeDetection::color_filter`](crate::EdgeDetection::color_filter).
+    pub color_filter: FilterMode,
+    /// The offset of the view in the [`ViewUniforms`], see [`ViewUniformOffset`](bevy::render::view::ViewUniformOffset).
+    pub view_offset: u32,
+    /// The offset of the view in the [`EdgeDetectionUniform`] buffer,
+    /// see [`DynamicUniformIndex`](bevy::render::extract_component::DynamicUniformIndex).
+    pub uniform_offset: u32,
+}
+
+/// Records the edge detection of a view into a render context, for render graphs other than the
+/// stock [`Core3d`](bevy::core_pipeline::core_3d::graph::Core3d) one where [`EdgeDetectionNode`](crate::EdgeDetectionNode) runs.
+///
+/// The view must have an [`EdgeDetection`](crate::EdgeDetection), so its pipelines, uniform and intermediate textures are prepared
+/// by the plugin. The pass must be recorded after the prepasses of the view, and the main, `depth` and `normal`
+/// textures must not be written by the same render pass: the main and prepass textures are bound as
+/// sampled textures, the other main texture and the intermediate textures as color attachments.
+///
+/// The pass flips the main texture with [`ViewTarget::post_process_write`] itself, once it has checked its inputs,
+/// so the scene is never lost: when [`EdgeDetectionPass::record`] returns `false`, the main texture is left as is.
+///
+/// ```no_run
+/// use bevy::{
+///     core_pipeline::prepass::ViewPrepassTextures,
+///     pbr::ScreenSpaceAmbientOcclusionResources,
+///     prelude::*,
+///     render::{
+///         extract_component::DynamicUniformIndex,
+///         render_asset::RenderAssets,
+///         render_graph::{Node, NodeRunError, RenderGraphContext},
+///         renderer::RenderContext,
+///         storage::GpuShaderStorageBuffer,
+///         texture::{FallbackImage, GpuImage},
+///         view::{ViewTarget, ViewUniformOffset},
+///     },
+/// };
+/// use bevy_edge_detection::{
+///     EdgeCalibrationBuffer, EdgeCoverageBuffer, EdgeDetection, EdgeDetectionAaTexture, EdgeDetectionDilationTexture,
+///     EdgeDetectionHalfRateHistory, EdgeDetectionInputs, EdgeDetectionJumpFloodTextures, EdgeDetectionMaskTexture,
+///     EdgeDetectionPass, EdgeDetectionPipelineId, EdgeDetectionTemporalHistory, EdgeDetectionUniform, ExtractedEdgeSources,
+///     MAX_EDGE_SOURCES, ViewEdgeMaskTexture, ViewEdgeMeshTagTextures,
+/// };
+///
+/// struct CustomEdgeDetectionNode;
+///
+/// impl Node for CustomEdgeDetectionNode {
+///     fn run(
+///         &self,
+///         graph: &mut RenderGraphContext,
+///         render_context: &mut RenderContext,
+///         world: &World,
+///     ) -> Result<(), NodeRunError> {
+///         let view = world.entity(graph.view_entity());
+///         let (
+///             Some(edge_detection),
+///             Some(view_target),
+///             Some(prepass_textures),
+///             Some(view_offset),
+///             Some(uniform_index),
+///             Some(pipeline_id),
+///         ) = (
+///             view.get::<EdgeDetection>(),
+///             view.get::<ViewTarget>(),
+///             view.get::<ViewPrepassTextures>(),
+///             view.get::<ViewUniformOffset>(),
+///             view.get::<DynamicUniformIndex<EdgeDetectionUniform>>(),
+///             view.get::<EdgeDetectionPipelineId>(),
+///         )
+///         else {
+///             return Ok(());
+///         };
+///
+///         let Some(depth) = prepass_textures.depth_view() else {
+///             return Ok(());
+///         };
+///
+///         let Some(pass) = EdgeDetectionPass::get(world, pipeline_id) else {
+///             return Ok(());
+///         };
+///
+///         // The images that aren't loaded yet are replaced by the white fallback, which leaves the edges unchanged.
+///         let images = world.resource::<RenderAssets<GpuImage>>();
+///         let buffers = world.resource::<RenderAssets<GpuShaderStorageBuffer>>();
+///         let fallback = &world.resource::<FallbackImage>().d2.texture_view;
+///         let image_view = |image: AssetId<Image>| {
+///             images
+///                 .get(image)
+///                 .map_or(fallback, |gpu_image| &gpu_image.texture_view)
+///         };
+///
+///         let inputs = EdgeDetectionInputs {
+///             depth: Some(depth),
+///             stencil: None,
+///             normal: prepass_textures.normal_view(),
+///             motion_vectors: prepass_textures.motion_vectors_view(),
+///             deferred: prepass_textures.deferred_view(),
+///             noise: edge_detection
+///                 .noise_texture
+///                 .as_ref()
+///                 .and_then(|image| images.get(image))
+///                 .map(|gpu_image| &gpu_image.texture_view),
+///             shadow_mask: edge_detection.shadow_mask.as_ref().map(|image| image_view(image.id())),
+///             screen_mask: edge_detection.screen_mask.as_ref().map(|image| image_view(image.id())),
+///             mesh_tags: view.get::<ViewEdgeMeshTagTextures>(),
+///             ambient_occlusion: Some(view.get::<ScreenSpaceAmbientOcclusionResources>().map_or(
+///                 fallback,
+///                 |ssao_resources| &ssao_resources.screen_space_ambient_occlusion_texture.default_view,
+///             )),
+///             edge_coverage: view
+///                 .get::<EdgeCoverageBuffer>()
+///                 .and_then(|buffer| buffers.get(&buffer.0))
+///                 .map(|buffer| &buffer.buffer),
+///             calibration: view
+///                 .get::<EdgeCalibrationBuffer>()
+///                 .and_then(|buffer| buffers.get(&buffer.buffer))
+///                 .map(|buffer| &buffer.buffer),
+///             edge_sources: view
+///                 .get::<ExtractedEdgeSources>()
+///                 .map_or([None; MAX_EDGE_SOURCES], |edge_sources| {
+///                     edge_sources.0.map(|image| image.map(image_view))
+///                 }),
+///             mask: view
+///                 .get::<EdgeDetectionMaskTexture>()
+///                 .map(|mask_texture| &mask_texture.0.default_view),
+///             mask_output: view
+///                 .get::<ViewEdgeMaskTexture>()
+///                 .map(|mask_output_texture| &mask_output_texture.0.default_view),
+///             aa: view.get::<EdgeDetectionAaTexture>(),
+///             dilation: view.get::<EdgeDetectionDilationTexture>(),
+///             jump_flood: view.get::<EdgeDetectionJumpFloodTextures>(),
+///             thick_outline: edge_detection.thick_outline,
+///             reproject: view
+///                 .get::<EdgeDetectionHalfRateHistory>()
+///                 .is_some_and(|history| history.reproject),
+///             temporal: view.get::<EdgeDetectionTemporalHistory>(),
+///             color_filter: edge_detection.color_filter,
+///             view_offset: view_offset.offset,
+///             uniform_offset: uniform_index.index(),
+///         };
+///         // The main texture is only flipped once the inputs are checked, the view keeps its scene otherwise.
+///         if !pass.record(render_context, view_target, &inputs) {
+///             warn_once!("The inputs of the edge detection are missing, e.g. the stencil aspect.");
+///         }
+///
+///         Ok(())
+///     }
+/// }
+/// ```
+pub struct EdgeDetectionPass<'w> {
+    key: EdgeDetectionKey,
+    pipeline: &'w RenderPipeline,
+    composite_pipeline: Option<&'w RenderPipeline>,
+    compute_pipeline: Option<&'w ComputePipeline>,
+    target_size: UVec2,
+    edge_detection_pipeline: &'w EdgeDetectionPipeline,
+    composite_layouts: &'w EdgeCompositePipeline,
+    // The post-filters needed by the key, resolved when getting the pass so they can't fail once the edges are drawn.
+    jump_flood: Option<JumpFloodPasses<'w>>,
+    aa: Option<(&'w EdgeAaPipeline, &'w RenderPipeline)>,
+    dilation: Option<(&'w EdgeDilationPipeline, &'w RenderPipeline)>,
+    temporal: Option<(&'w EdgeTemporalPipeline, &'w RenderPipeline)>,
+    noise_texture: &'w TextureView,
+    view_uniforms: BindingResource<'w>,
+    ed_uniforms: BindingResource<'w>,
+}
+
+impl<'w> EdgeDetectionPass<'w> {
+    /// Gets the resources of the pass of a view, or `None` if its pipelines or the noise texture aren't ready yet.
+    pub fn get(world: &'w World, pipeline_id: &EdgeDetectionPipelineId) -> Option<Self> {
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let pipeline = pipeline_cache.get_render_pipeline(pipeline_id.id)?;
+
+        // When the edges are post-filtered, they're drawn into the mask and composited by a second pass.
+        let composite_pipeline = match pipeline_id.composite_id {
+            Some(composite_id) => Some(pipeline_cache.get_render_pipeline(composite_id)?),
+            None => None,
+        };
+
+        // The compute variant replaces the render pipeline once it's compiled.
+        let compute_pipeline = pipeline_id
+            .compute_id
+            .and_then(|compute_id| pipeline_cache.get_compute_pipeline(compute_id));
+
+        let key = pipeline_id.key;
+        let jump_flood = match key.thick_outline {
+            true => Some(
+                world
+                    .resource::<JumpFloodPipeline>()
+                    .get_passes(pipeline_cache)?,
+            ),
+            false => None,
+        };
+
+        let aa = match key.edge_aa {
+            true => {
+                let aa_pipeline = world.resource::<EdgeAaPipeline>();
+                Some((aa_pipeline, aa_pipeline.get_pipeline(pipeline_cache)?))
+            }
+            false => None,
+        };
+
+        let dilation = match key.dilation {
+            true => {
+                let dilation_pipeline = world.resource::<EdgeDilationPipeline>();
+                Some((
+                    dilation_pipeline,
+                    dilation_pipeline.get_pipeline(pipeline_cache)?,
+                ))
+            }
+            false => None,
+        };
+
+        let temporal = match key.temporal {
+            true => {
+                let temporal_pipeline = world.resource::<EdgeTemporalPipeline>();
+                Some((
+                    temporal_pipeline,
+                    temporal_pipeline.get_pipeline(pipeline_cache, key.multisampled)?,
+                ))
+            }
+            false => None,
+        };
+
+        let edge_detection_pipeline = world.resource::<EdgeDetectionPipeline>();
+        let noise_texture = &world
+            .resource::<RenderAssets<GpuImage>>()
+            .get(&edge_detection_pipeline.noise_texture)?
+            .texture_view;
+
+        let view_uniforms = world.resource::<ViewUniforms>().uniforms.binding()?;
+        let ed_uniforms = world
+            .resource::<ComponentUniforms<EdgeDetectionUniform>>()
+            .uniforms()
+            .binding()?;
+
+        Some(Self {
+            key,
+            pipeline,
+            composite_pipeline,
+            compute_pipeline,
+            target_size: pipeline_id.target_size,
+            edge_detection_pipeline,
+            composite_layouts: world.resource::<EdgeCompositePipeline>(),
+            jump_flood,
+            aa,
+            dilation,
+            temporal,
+            noise_texture,
+            view_uniforms,
+            ed_uniforms,
+        })
+    }
+
+    /// Returns whether all the inputs needed by the pipeline key are there, see [`EdgeDetectionInputs`].
+    pub fn has_inputs(&self, view_target: &ViewTarget, inputs: &EdgeDetectionInputs) -> bool {
+        let key = self.key;
+        let layout_key = key.layout_key();
+
+        // The output is rendered into the multisampled main texture, so the transparent pass resolving it keeps the edges.
+        let missing_input = (key.output_samples() > 1
+            && view_target.sampled_main_texture_view().is_none())
+            || (layout_key.depth && inputs.depth.is_none())
+            || (key.enable_stencil && inputs.stencil.is_none())
+            || (layout_key.normal && inputs.normal.is_none())
+            || ((layout_key.motion_vectors || key.half_rate || key.temporal)
+                && inputs.motion_vectors.is_none())
+            || (layout_key.material && inputs.deferred.is_none())
+            || (key.enable_shadow && inputs.shadow_mask.is_none())
+            || (key.screen_mask && inputs.screen_mask.is_none())
+            || (layout_key.mesh_tags && inputs.mesh_tags.is_none())
+            || (key.ambient_occlusion && inputs.ambient_occlusion.is_none())
+            || (key.edge_coverage && inputs.edge_coverage.is_none())
+            || (key.auto_calibrate && inputs.calibration.is_none())
+            || (0..MAX_EDGE_SOURCES).any(|index| {
+                key.edge_sources & (1 << index) != 0 && inputs.edge_sources[index].is_none()
+            })
+            || (key.edge_mask && inputs.mask.is_none())
+            || (key.mask_output && inputs.mask_output.is_none())
+            || (key.edge_aa && inputs.aa.is_none())
+            || (key.dilation && inputs.dilation.is_none())
+            || (key.temporal && (inputs.temporal.is_none() || inputs.depth.is_none()))
+            || (key.thick_outline
+                && (inputs.jump_flood.is_none() || inputs.thick_outline.is_none()));
+
+        !missing_input
+    }
+
+    /// Records the edge detection pass, and the temporal, dilation, anti-aliasing, jump flood and composite passes
+    /// when the edges are post-filtered.
+    ///
+    /// The main texture of the `view_target` is flipped with [`ViewTarget::post_process_write`] once the inputs are
+    /// checked, and the scene and its edges are always written to the new main texture from there on.
+    /// Returns `false` without recording anything or flipping the main texture if an input needed by the pipeline
+    /// key is missing, see [`Self::has_inputs`].
+    ///
+    /// The passes are measured by the `edge_detection` span of the [`EdgeDetectionAdaptiveQuality::GPU_TIME`](crate::EdgeDetectionAdaptiveQuality::GPU_TIME)
+    /// diagnostic when the [`RenderDiagnosticsPlugin`](bevy::render::diagnostic::RenderDiagnosticsPlugin) is added,
+    /// and each of them by a child span, e.g. `render/edge_detection/detection/elapsed_gpu`: `detection`, `temporal`,
+    /// `dilation`, `aa`, `jump_flood` and `composite`. The spans of the passes also record the pipeline statistics
+    /// on the adapters supporting them.
+    pub fn record(
+        &self,
+        render_context: &mut RenderContext,
+        view_target: &ViewTarget,
+        inputs: &EdgeDetectionInputs,
+    ) -> bool {
+        if !self.has_inputs(view_target, inputs) {
+            return false;
+        }
+
+        // This will start a new "post process write", obtaining two texture
+        // views from the view target - a `source` and a `destination`.
+        // `source` is the "current" main texture and you _must_ write into
+        // `destination` because calling `post_process_write()` on the
+        // [`ViewTarget`] will internally flip the [`ViewTarget`]'s main
+        // texture to the `destination` texture. Failing to do so will cause
+        // the current main texture information to be lost.
+        let post_process = view_target.post_process_write();
+        let targets = EdgeDetectionTargets {
+            source: post_process.source,
+            destination: post_process.destination,
+            sampled_destination: view_target
+                .sampled_main_texture_view()
+                .filter(|_| self.key.output_samples() > 1),
+        };
+
+        let diagnostics = render_context.diagnostic_recorder();
+        let time_span = diagnostics.time_span(render_context.command_encoder(), "edge_detection");
+        self.record_passes(render_context, inputs, &targets);
+        time_span.end(render_context.command_encoder());
+
+        true
+    }
+
+    fn record_passes(
+        &self,
+        render_context: &mut RenderContext,
+        inputs: &EdgeDetectionInputs,
+        targets: &EdgeDetectionTargets,
+    ) {
+        let key = self.key;
+
+        // The output is rendered into the multisampled main texture and resolved before the transparent pass.
+        let (output, resolve_target) = match targets.sampled_destination {
+            Some(sampled_destination) => (sampled_destination, Some(&**targets.destination)),
+            None => (targets.destination, None),
+        };
+
+        if let Some(compute_pipeline) = self.compute_pipeline {
+            self.record_compute_detection(render_context, inputs, targets, compute_pipeline);
+            return;
+        }
+
+        let composite = self.composite_pipeline.zip(inputs.mask);
+        let (target, target_resolve) = match composite {
+            Some((_, mask)) => (mask, None),
+            None => (output, resolve_target),
+        };
+
+        // With half rate, the edges of the previous frame are only reprojected by the composite pass.
+        if !(key.half_rate && inputs.reproject) {
+            self.record_detection(render_context, inputs, targets, target, target_resolve);
+        }
+
+        let Some((composite_pipeline, mask)) = composite else {
+            return;
+        };
+
+        let composite_key = key.composite_key();
+
+        // The post-filters are only there when the key needs them, and their inputs have been checked by `record`.
+        // The edges are stabilized before they're post-filtered, so the history holds the edges as detected.
+        let mask = match (
+            self.temporal,
+            inputs.temporal,
+            inputs.depth,
+            inputs.motion_vectors,
+        ) {
+            (
+                Some((temporal_pipeline, pipeline)),
+                Some(history),
+                Some(depth),
+                Some(motion_vectors),
+            ) => temporal_pipeline.run(
+                render_context,
+                pipeline,
+                mask,
+                depth,
+                motion_vectors,
+                self.ed_uniforms.clone(),
+                inputs.uniform_offset,
+                history,
+                key.multisampled,
+            ),
+            _ => mask,
+        };
+
+        // The edges are grown before they're anti-aliased, so the dilated lines get smooth borders.
+        let mask = match (self.dilation, inputs.dilation) {
+            (Some((dilation_pipeline, pipeline)), Some(dilation)) => dilation_pipeline.run(
+                render_context,
+                pipeline,
+                mask,
+                self.ed_uniforms.clone(),
+                inputs.uniform_offset,
+                dilation,
+            ),
+            _ => mask,
+        };
+
+        let mask = match (self.aa, inputs.aa) {
+            (Some((aa_pipeline, pipeline)), Some(aa)) => {
+                aa_pipeline.run(render_context, pipeline, mask, aa)
+            }
+            _ => mask,
+        };
+
+        let mut composite_entries =
+            BindGroupEntries::sequential((targets.source, mask, self.ed_uniforms.clone())).to_vec();
+
+        if let (Some(jump_flood_passes), Some(jump_flood), Some(thick_outline)) =
+            (&self.jump_flood, inputs.jump_flood, &inputs.thick_outline)
+        {
+            let jump_flood_view =
+                jump_flood_passes.run(render_context, mask, jump_flood, thick_outline);
+            composite_entries.push(BindGroupEntry {
+                binding: 3,
+                resource: jump_flood_view.into_binding(),
+            });
+        }
+
+        if let Some(motion_vectors) = inputs.motion_vectors.filter(|_| composite_key.reproject) {
+            composite_entries.push(BindGroupEntry {
+                binding: 4,
+                resource: motion_vectors.into_binding(),
+            });
+        }
+
+        let composite_bind_group = render_context.render_device().create_bind_group(
+            "edge_detection_composite_bind_group",
+            self.composite_layouts
+                .bind_group_layout(composite_key.layout_key()),
+            &composite_entries,
+        );
+
+        let diagnostics = render_context.diagnostic_recorder();
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("edge_detection_composite_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: output,
+                resolve_target,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        let pass_span = diagnostics.pass_span(&mut render_pass, "composite");
+
+        render_pass.set_render_pipeline(composite_pipeline);
+        render_pass.set_bind_group(0, &composite_bind_group, &[inputs.uniform_offset]);
+        render_pass.draw(0..3, 0..1);
+        pass_span.end(&mut render_pass);
+    }
+
+    /// Records the edge detection pass itself, into the output or the edge mask.
+    fn record_detection(
+        &self,
+        render_context: &mut RenderContext,
+        inputs: &EdgeDetectionInputs,
+        targets: &EdgeDetectionTargets,
+        target: &TextureView,
+        resolve_target: Option<&WgpuTextureView>,
+    ) {
+        let key = self.key;
+        // The render pipeline is specialized without the compute variant's output.
+        let layout_key = EdgeDetectionLayoutKey {
+            compute: false,
+            ..key.layout_key()
+        };
+
+        let entries = self.detection_entries(render_context, inputs, targets);
+        let bind_group = render_context.render_device().create_bind_group(
+            "edge_detection_bind_group",
+            self.edge_detection_pipeline.bind_group_layout(layout_key),
+            &entries,
+        );
+
+        // The pixels skipped by the checkerboard keep the edges of the previous frame.
+        let load = match key.checkerboard {
+            true => LoadOp::Load,
+            false => LoadOp::Clear(default()),
+        };
+
+        let ops = Operations {
+            load,
+            store: StoreOp::Store,
+        };
+        let mut color_attachments = vec![Some(RenderPassColorAttachment {
+            view: target,
+            resolve_target,
+            ops,
+        })];
+        if let Some(mask_output) = inputs.mask_output.filter(|_| key.mask_output) {
+            color_attachments.push(Some(RenderPassColorAttachment {
+                view: mask_output,
+                resolve_target: None,
+                ops,
+            }));
+        }
+
+        let diagnostics = render_context.diagnostic_recorder();
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("edge_detection_pass"),
+            color_attachments: &color_attachments,
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        let pass_span = diagnostics.pass_span(&mut render_pass, "detection");
+
+        render_pass.set_render_pipeline(self.pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[inputs.view_offset, inputs.uniform_offset]);
+        render_pass.draw(0..3, 0..1);
+        pass_span.end(&mut render_pass);
+    }
+
+    /// Records the compute variant of the edge detection, which writes the output straight to the destination.
+    fn record_compute_detection(
+        &self,
+        render_context: &mut RenderContext,
+        inputs: &EdgeDetectionInputs,
+        targets: &EdgeDetectionTargets,
+        compute_pipeline: &ComputePipeline,
+    ) {
+        let mut entries = self.detection_entries(render_context, inputs, targets);
+        entries.push(BindGroupEntry {
+            binding: EDGE_DETECTION_OUTPUT_BINDING,
+            resource: targets.destination.into_binding(),
+        });
+        let bind_group = render_context.render_device().create_bind_group(
+            "edge_detection_bind_group",
+            self.edge_detection_pipeline
+                .bind_group_layout(self.key.layout_key()),
+            &entries,
+        );
+
+        let diagnostics = render_context.diagnostic_recorder();
+        let mut compute_pass =
+            render_context
+                .command_encoder()
+                .begin_compute_pass(&ComputePassDescriptor {
+                    label: Some("edge_detection_compute_pass"),
+                    timestamp_writes: None,
+                });
+
+        let pass_span = diagnostics.pass_span(&mut compute_pass, "detection");
+
+        compute_pass.set_pipeline(compute_pipeline);
+        compute_pass.set_bind_group(0, &bind_group, &[inputs.view_offset, inputs.uniform_offset]);
+        compute_pass.dispatch_workgroups(
+            self.target_size.x.div_ceil(EDGE_DETECTION_WORKGROUP_SIZE),
+            self.target_size.y.div_ceil(EDGE_DETECTION_WORKGROUP_SIZE),
+            1,
+        );
+        pass_span.end(&mut compute_pass);
+    }
+
+    /// Returns the bindings of the edge detection pass shared by its render and compute variants,
+    /// and clears the counters it writes to.
+    fn detection_entries<'a>(
+        &'a self,
+        render_context: &mut RenderContext,
+        inputs: &'a EdgeDetectionInputs,
+        targets: &'a EdgeDetectionTargets,
+    ) -> Vec<BindGroupEntry<'a>> {
+        let key = self.key;
+        let layout_key = key.layout_key();
+
+        // Without float filtering, the prepass textures are sampled at the nearest texels.
+        let texture_sampler = match self.edge_detection_pipeline.filterable {
+            true => &self.edge_detection_pipeline.linear_sampler,
+            false => &self.edge_detection_pipeline.nearest_sampler,
+        };
+
+        // It's important for this to match the BindGroupLayout defined in the EdgeDetectionPipeline
+        let mut entries = BindGroupEntries::with_indices((
+            // view uniform binding, first like in the mesh view bindings
+            (0, self.view_uniforms.clone()),
+            // Use simple texture sampler
+            (3, texture_sampler),
+            // Use noise texture
+            (4, inputs.noise.unwrap_or(self.noise_texture)),
+            // Use noise texture sampler
+            (5, &self.edge_detection_pipeline.noise_sampler),
+            // Make sure to use the source view
+            (6, targets.source),
+            // Set the uniform binding
+            (7, self.ed_uniforms.clone()),
+        ))
+        .to_vec();
+
+        // Use depth texture, unless the platform can't sample it
+        if let Some(depth) = inputs.depth.filter(|_| layout_key.depth) {
+            entries.push(BindGroupEntry {
+                binding: 1,
+                resource: depth.into_binding(),
+            });
+        }
+
+        // Use normal prepass, unless the normals are reconstructed from the depth
+        if let Some(normal) = inputs.normal.filter(|_| layout_key.normal) {
+            entries.push(BindGroupEntry {
+                binding: 2,
+                resource: normal.into_binding(),
+            });
+        }
+
+        let screen_sampler = match inputs.color_filter {
+            FilterMode::Linear if self.edge_detection_pipeline.filterable => {
+                &self.edge_detection_pipeline.linear_sampler
+            }
+            _ => &self.edge_detection_pipeline.nearest_sampler,
+        };
+        entries.push(BindGroupEntry {
+            binding: 11,
+            resource: screen_sampler.into_binding(),
+        });
+
+        if let Some(stencil) = inputs.stencil.filter(|_| key.enable_stencil) {
+            entries.push(BindGroupEntry {
+                binding: 8,
+                resource: stencil.into_binding(),
+            });
+        }
+
+        if let Some(motion_vectors) = inputs.motion_vectors.filter(|_| layout_key.motion_vectors) {
+            entries.push(BindGroupEntry {
+                binding: 9,
+                resource: motion_vectors.into_binding(),
+            });
+        }
+
+        if let Some(edge_coverage) = inputs.edge_coverage.filter(|_| key.edge_coverage) {
+            // The counter is reset every frame, the previous count has been copied for the readback.
+            render_context
+                .command_encoder()
+                .clear_buffer(edge_coverage, 0, None);
+
+            entries.push(BindGroupEntry {
+                binding: 10,
+                resource: edge_coverage.as_entire_binding(),
+            });
+        }
+
+        if let Some(calibration) = inputs.calibration.filter(|_| key.auto_calibrate) {
+            // The histograms are only built on the calibration frames, and read back right after them.
+            render_context
+                .command_encoder()
+                .clear_buffer(calibration, 0, None);
+
+            entries.push(BindGroupEntry {
+                binding: 13,
+                resource: calibration.as_entire_binding(),
+            });
+        }
+
+        if let Some(ambient_occlusion) = inputs.ambient_occlusion.filter(|_| key.ambient_occlusion)
+        {
+            entries.push(BindGroupEntry {
+                binding: 12,
+                resource: ambient_occlusion.into_binding(),
+            });
+        }
+
+        for (index, edge_source) in inputs.edge_sources.iter().enumerate() {
+            if let Some(edge_source) = edge_source.filter(|_| key.edge_sources & (1 << index) != 0)
+            {
+                entries.push(BindGroupEntry {
+                    binding: EDGE_SOURCE_FIRST_BINDING + index as u32,
+                    resource: edge_source.into_binding(),
+                });
+            }
+        }
+
+        if let Some(deferred) = inputs.deferred.filter(|_| key.layout_key().material) {
+            entries.push(BindGroupEntry {
+                binding: 18,
+                resource: deferred.into_binding(),
+            });
+        }
+
+        if let Some(shadow_mask) = inputs.shadow_mask.filter(|_| key.enable_shadow) {
+            entries.push(BindGroupEntry {
+                binding: 19,
+                resource: shadow_mask.into_binding(),
+            });
+        }
+
+        if let Some(screen_mask) = inputs.screen_mask.filter(|_| key.screen_mask) {
+            entries.push(BindGroupEntry {
+                binding: 23,
+                resource: screen_mask.into_binding(),
+            });
+        }
+
+        if let Some(mesh_tags) = inputs.mesh_tags.filter(|_| layout_key.mesh_tags) {
+            entries.push(BindGroupEntry {
+                binding: 20,
+                resource: mesh_tags.depth.default_view.into_binding(),
+            });
+            entries.push(BindGroupEntry {
+                binding: 21,
+                resource: mesh_tags.tags.default_view.into_binding(),
+            });
+        }
+
+        entries
+    }
+}
+
+/// The main textures of the view the pass reads from and writes to, after the flip of [`ViewTarget::post_process_write`].
+struct EdgeDetectionTargets<'a> {
+    source: &'a TextureView,
+    destination: &'a TextureView,
+    /// The multisampled main texture resolved into `destination`, when the output is multisampled.
+    sampled_destination: Option<&'a TextureView>,
+}
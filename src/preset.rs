@@ -0,0 +1,148 @@
+use std::{error::Error, fmt, io};
+
+use bevy::{
+    asset::{io::Reader, ron, AssetEvents, AssetLoader, LoadContext},
+    prelude::*,
+};
+
+use crate::{EdgeDetection, EdgeDetectionSet};
+
+/// [`EdgeDetection`] settings loaded from a `.edgepreset.ron` file, e.g. to tune the edges of each level
+/// without recompiling. Apply one to a camera with an [`EdgeDetectionPresetHandle`].
+///
+/// The file holds the fields of [`EdgeDetection`] in RON, e.g. `(depth_threshold: 2.0, enable_color: true)`.
+/// The missing fields take their default values, and the unknown ones are ignored, so older files keep loading
+/// as the settings change. The textures and the [`EdgeDetectionPlacement::Between`](crate::EdgeDetectionPlacement::Between)
+/// placement can't be written in a preset.
+#[derive(Asset, TypePath, Clone, Debug, Default, Deref, DerefMut)]
+pub struct EdgeDetectionPreset(pub EdgeDetection);
+
+/// Applies an [`EdgeDetectionPreset`] to the camera, inserting its [`EdgeDetection`] once it's loaded,
+/// and again when the preset is reloaded, e.g. with the `file_watcher` feature of bevy.
+///
+/// The preset replaces the settings of the camera, but the [`EdgeDetection::noise_texture`] and
+/// [`EdgeDetection::shadow_mask`] and [`EdgeDetection::screen_mask`] of the camera are kept.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct EdgeDetectionPresetHandle(pub Handle<EdgeDetectionPreset>);
+
+/// Loads the `.edgepreset.ron` files into [`EdgeDetectionPreset`]s.
+#[derive(Default)]
+pub struct EdgeDetectionPresetLoader;
+
+/// An error loading an [`EdgeDetectionPreset`].
+#[derive(Debug)]
+pub enum EdgeDetectionPresetLoaderError {
+    Io(io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for EdgeDetectionPresetLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to read the edge detection preset: {error}"),
+            Self::Ron(error) => write!(f, "failed to parse the edge detection preset: {error}"),
+        }
+    }
+}
+
+impl Error for EdgeDetectionPresetLoaderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Ron(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for EdgeDetectionPresetLoaderError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for EdgeDetectionPresetLoaderError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        Self::Ron(error)
+    }
+}
+
+impl AssetLoader for EdgeDetectionPresetLoader {
+    type Asset = EdgeDetectionPreset;
+    type Settings = ();
+    type Error = EdgeDetectionPresetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<EdgeDetectionPreset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(EdgeDetectionPreset(ron::de::from_bytes(&bytes)?))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["edgepreset.ron"]
+    }
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.init_asset::<EdgeDetectionPreset>()
+        .init_asset_loader::<EdgeDetectionPresetLoader>()
+        .register_type::<EdgeDetectionPresetHandle>()
+        .add_systems(
+            PostUpdate,
+            apply_edge_detection_presets
+                .after(AssetEvents)
+                .before(EdgeDetectionSet::Configure),
+        );
+}
+
+/// Applies the [`EdgeDetectionPreset`]s to the cameras, when their handle changes or the preset is (re)loaded.
+pub fn apply_edge_detection_presets(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<EdgeDetectionPreset>>,
+    presets: Res<Assets<EdgeDetectionPreset>>,
+    mut cameras: Query<(
+        Entity,
+        Ref<EdgeDetectionPresetHandle>,
+        Option<&mut EdgeDetection>,
+    )>,
+) {
+    let loaded: Vec<_> = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for (entity, handle, edge_detection) in cameras.iter_mut() {
+        if !handle.is_changed() && !loaded.contains(&handle.0.id()) {
+            continue;
+        }
+        // Applied by the load event if it isn't loaded yet.
+        let Some(preset) = presets.get(&handle.0) else {
+            continue;
+        };
+
+        match edge_detection {
+            Some(mut edge_detection) => {
+                let noise_texture = edge_detection.noise_texture.take();
+                let shadow_mask = edge_detection.shadow_mask.take();
+                let screen_mask = edge_detection.screen_mask.take();
+                *edge_detection = EdgeDetection {
+                    noise_texture,
+                    shadow_mask,
+                    screen_mask,
+                    ..preset.0.clone()
+                };
+            }
+            None => {
+                commands.entity(entity).insert(preset.0.clone());
+            }
+        }
+    }
+}
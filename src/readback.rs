@@ -0,0 +1,237 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+    image::TextureFormatPixelInfo,
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        render_resource::*,
+        renderer::{render_system, RenderDevice, RenderQueue},
+        sync_world::MainEntity,
+        Render, RenderApp, RenderSet,
+    },
+};
+
+use crate::{EdgeDetectionMaskOutput, ViewEdgeMaskTexture, EDGE_MASK_OUTPUT_FORMAT};
+
+/// The most copies of the edge mask of a camera waiting to be mapped, the frames after it are skipped
+/// until one is read.
+pub const EDGE_MASK_READBACKS_IN_FLIGHT: usize = 3;
+
+/// Reads the edge mask of a camera back to the CPU, e.g. for the gameplay to check what's outlined on the screen,
+/// or to test the edges. The masks are sent as [`EdgeMaskReadback`] events.
+///
+/// The [`ViewEdgeMaskTexture`] of the [`EdgeDetectionMaskOutput`] it requires is copied once the frame is submitted,
+/// and mapped asynchronously, so the GPU never waits for the CPU and the events lag a few frames behind.
+/// While [`EDGE_MASK_READBACKS_IN_FLIGHT`] copies are waiting to be mapped, the frames aren't copied,
+/// and the copies made before the camera was resized are dropped.
+///
+/// Removing it stops the readback, but keeps the [`EdgeDetectionMaskOutput`].
+#[derive(Component, ExtractComponent, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+#[require(EdgeDetectionMaskOutput)]
+pub struct EdgeDetectionReadback;
+
+/// The edge mask of a camera with an [`EdgeDetectionReadback`], a few frames after it was rendered.
+#[derive(Event, Clone, Debug)]
+pub struct EdgeMaskReadback {
+    /// The camera.
+    pub entity: Entity,
+    /// The size of the mask, the size of the main texture of the camera.
+    pub width: u32,
+    pub height: u32,
+    /// The strength of the edges, from 0 without an edge to 255, one byte per pixel in rows from the top left.
+    pub data: Vec<u8>,
+}
+
+impl EdgeMaskReadback {
+    /// Returns the strength of the edge at `position`, in `[0.0, 1.0]`, or `None` outside of the mask.
+    pub fn get(&self, position: UVec2) -> Option<f32> {
+        if position.x >= self.width || position.y >= self.height {
+            return None;
+        }
+        let index = (position.y * self.width + position.x) as usize;
+        self.data
+            .get(index)
+            .map(|&strength| strength as f32 / 255.0)
+    }
+}
+
+/// The [`EdgeMaskReadback`]s mapped by the render world, and sent in the main world at the start of each frame.
+#[derive(Resource, Clone, Default)]
+struct MappedEdgeMaskReadbacks(Arc<Mutex<Vec<EdgeMaskReadback>>>);
+
+/// The buffers the edge mask of a view with an [`EdgeDetectionReadback`] is copied into, shared with the callbacks
+/// mapping them.
+#[derive(Component, Clone, Default)]
+pub struct EdgeMaskReadbackBuffers(Arc<Mutex<EdgeMaskReadbackPool>>);
+
+#[derive(Default)]
+struct EdgeMaskReadbackPool {
+    /// The size of the mask the buffers hold.
+    size: Extent3d,
+    /// The buffers being mapped.
+    in_flight: usize,
+    /// The buffers read, to copy the next masks into.
+    free: Vec<Buffer>,
+}
+
+pub(crate) fn build(app: &mut App) {
+    let mapped = MappedEdgeMaskReadbacks::default();
+    app.register_type::<EdgeDetectionReadback>()
+        .add_event::<EdgeMaskReadback>()
+        .add_plugins(ExtractComponentPlugin::<EdgeDetectionReadback>::default())
+        .insert_resource(mapped.clone())
+        .add_systems(First, send_edge_mask_readbacks);
+
+    let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+
+    // After the frame is submitted, like the readbacks of bevy.
+    render_app.insert_resource(mapped).add_systems(
+        Render,
+        read_back_edge_masks
+            .in_set(RenderSet::Render)
+            .after(render_system),
+    );
+}
+
+fn send_edge_mask_readbacks(
+    mapped: Res<MappedEdgeMaskReadbacks>,
+    mut readbacks: EventWriter<EdgeMaskReadback>,
+) {
+    let mut mapped = mapped.0.lock().unwrap();
+    if !mapped.is_empty() {
+        readbacks.write_batch(mapped.drain(..));
+    }
+}
+
+/// Copies the edge masks of the views with an [`EdgeDetectionReadback`] into buffers, and maps them.
+#[allow(clippy::type_complexity)]
+pub fn read_back_edge_masks(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mapped: Res<MappedEdgeMaskReadbacks>,
+    views: Query<
+        (
+            Entity,
+            &MainEntity,
+            &ViewEdgeMaskTexture,
+            Option<&EdgeMaskReadbackBuffers>,
+        ),
+        With<EdgeDetectionReadback>,
+    >,
+    stopped: Query<
+        Entity,
+        (
+            With<EdgeMaskReadbackBuffers>,
+            Without<EdgeDetectionReadback>,
+        ),
+    >,
+) {
+    for entity in stopped.iter() {
+        commands.entity(entity).remove::<EdgeMaskReadbackBuffers>();
+    }
+
+    let mut encoder = None;
+    let mut copies = Vec::new();
+    for (entity, main_entity, mask, buffers) in views.iter() {
+        let buffers = buffers.cloned().unwrap_or_else(|| {
+            let buffers = EdgeMaskReadbackBuffers::default();
+            commands.entity(entity).insert(buffers.clone());
+            buffers
+        });
+
+        let texture = &mask.0.texture;
+        let size = texture.size();
+        let row_size = size.width as usize * EDGE_MASK_OUTPUT_FORMAT.pixel_size();
+        // The rows of the copy are aligned to 256 bytes.
+        let bytes_per_row = RenderDevice::align_copy_bytes_per_row(row_size);
+
+        let buffer = {
+            let mut pool = buffers.0.lock().unwrap();
+            if pool.size != size {
+                // The view was resized, the copies in flight are dropped once they're mapped.
+                pool.size = size;
+                pool.free.clear();
+            }
+            // Skipped rather than waiting for the GPU.
+            if pool.in_flight >= EDGE_MASK_READBACKS_IN_FLIGHT {
+                continue;
+            }
+            pool.in_flight += 1;
+            pool.free.pop().unwrap_or_else(|| {
+                render_device.create_buffer(&BufferDescriptor {
+                    label: Some("edge_detection_mask_readback_buffer"),
+                    size: (bytes_per_row * size.height as usize) as u64,
+                    usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+                    mapped_at_creation: false,
+                })
+            })
+        };
+
+        encoder
+            .get_or_insert_with(|| {
+                render_device.create_command_encoder(&CommandEncoderDescriptor {
+                    label: Some("edge_detection_mask_readback"),
+                })
+            })
+            .copy_texture_to_buffer(
+                texture.as_image_copy(),
+                TexelCopyBufferInfo {
+                    buffer: &buffer,
+                    layout: TexelCopyBufferLayout {
+                        offset: 0,
+                        bytes_per_row: Some(bytes_per_row as u32),
+                        rows_per_image: None,
+                    },
+                },
+                size,
+            );
+        copies.push((
+            main_entity.id(),
+            buffers,
+            buffer,
+            size,
+            bytes_per_row,
+            row_size,
+        ));
+    }
+
+    let Some(encoder) = encoder else {
+        return;
+    };
+    render_queue.submit([encoder.finish()]);
+
+    for (entity, buffers, buffer, size, bytes_per_row, row_size) in copies {
+        let mapped = mapped.clone();
+        let slice = buffer.slice(..);
+        let buffer = buffer.clone();
+        slice.map_async(MapMode::Read, move |result| {
+            let mut pool = buffers.0.lock().unwrap();
+            pool.in_flight -= 1;
+            if result.is_err() || pool.size != size {
+                return;
+            }
+
+            let data = buffer
+                .slice(..)
+                .get_mapped_range()
+                .chunks(bytes_per_row)
+                .flat_map(|row| &row[..row_size])
+                .copied()
+                .collect();
+            buffer.unmap();
+            pool.free.push(buffer);
+
+            mapped.0.lock().unwrap().push(EdgeMaskReadback {
+                entity,
+                width: size.width,
+                height: size.height,
+                data,
+            });
+        });
+    }
+}
@@ -0,0 +1,295 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+    core_pipeline::{
+        core_3d::{CORE_3D_DEPTH_FORMAT, DEPTH_TEXTURE_SAMPLING_SUPPORTED},
+        prepass::{MOTION_VECTOR_PREPASS_FORMAT, NORMAL_PREPASS_FORMAT},
+    },
+    prelude::*,
+    render::{
+        render_resource::{
+            CachedPipelineState, DownlevelFlags, PipelineCache, PipelineCacheError, TextureFormat,
+            TextureFormatFeatureFlags, TextureSampleType, TextureUsages,
+        },
+        renderer::RenderAdapter,
+        settings::WgpuFeatures,
+        view::ViewTarget,
+        Render, RenderApp, RenderSet,
+    },
+};
+
+use crate::{
+    EdgeDetectionPipelineId, EDGE_MASK_TEXTURE_FORMAT, JUMP_FLOOD_TEXTURE_FORMAT,
+    NON_FILTERABLE_ENV_VAR,
+};
+
+/// What the adapter supports of the edge detection, and the fallbacks used for the rest.
+///
+/// It's filled in from the capabilities of the adapter when the plugin is finished, and inserted in both the main
+/// and the render world, e.g. to tell in a settings menu why the edges look different on a platform.
+#[derive(Resource, Clone, Debug, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Debug, PartialEq)]
+pub struct EdgeDetectionSupport {
+    /// Whether the depth textures can be sampled. Without it, e.g. on WebGL2, no depth nor normal is read,
+    /// and only the color-based sources draw edges, which may need lower thresholds to outline the same shapes.
+    pub depth_sampling: bool,
+    /// Whether the textures sampled by the edge detection can be filtered, the color attachment, the depth, the prepass
+    /// textures and the edge mask. The 32-bit depth needs [`WgpuFeatures::FLOAT32_FILTERABLE`] for it.
+    /// Without it, their nearest texels are loaded instead. See [`NON_FILTERABLE_ENV_VAR`].
+    pub float_filtering: bool,
+    /// The highest MSAA sample count the adapter supports on the depth and color targets of the cameras.
+    /// The multisampled textures are read at any sample count.
+    pub max_samples: u32,
+    /// Whether the adapter runs compute shaders. The passes of the edge detection are render passes,
+    /// unless [`EdgeDetectionPlugin::use_compute`](crate::EdgeDetectionPlugin::use_compute) is set.
+    pub compute_shaders: bool,
+    /// Whether the compute shaders can write the HDR main textures as storage textures, which the compute variant
+    /// of the edge detection writes its output to. Without it, the fragment pass is used instead.
+    pub compute_output: bool,
+    /// Whether the fragment shaders can write to storage buffers, which [`EdgeCoverage`](crate::EdgeCoverage)
+    /// and [`EdgeDetectionAutoCalibrate`](crate::EdgeDetectionAutoCalibrate) count into.
+    /// Without it, they're skipped and never measure anything.
+    pub fragment_storage: bool,
+    /// Whether the jump flood textures of the [`ThickOutline`](crate::ThickOutline) can be rendered to and sampled.
+    /// Without it, the edges are drawn without their thick outline.
+    pub thick_outline: bool,
+}
+
+/// A fallback of the edge detection in use on the adapter, see [`EdgeDetectionSupport::fallbacks`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum EdgeDetectionFallback {
+    /// The depth textures can't be sampled, only the color-based sources draw edges.
+    ColorOnly,
+    /// The float textures can't be filtered, their nearest texels are loaded.
+    NearestSampling,
+    /// The fragment shaders can't write to storage buffers, the edge coverage and the calibration are skipped.
+    NoFragmentStorage,
+    /// The jump flood textures can't be rendered to, the thick outlines are skipped.
+    NoThickOutline,
+}
+
+impl EdgeDetectionSupport {
+    /// Reads the capabilities of the adapter of the render world.
+    pub fn new(render_world: &World) -> Self {
+        let adapter = render_world.resource::<RenderAdapter>();
+        let downlevel = adapter.get_downlevel_capabilities();
+
+        let max_samples = [16, 8, 4, 2]
+            .into_iter()
+            .find(|&samples| {
+                [
+                    ViewTarget::TEXTURE_FORMAT_HDR,
+                    TextureFormat::bevy_default(),
+                    CORE_3D_DEPTH_FORMAT,
+                ]
+                .into_iter()
+                .all(|format| {
+                    adapter
+                        .get_texture_format_features(format)
+                        .flags
+                        .sample_count_supported(samples)
+                })
+            })
+            .unwrap_or(1);
+
+        let compute_shaders = downlevel.flags.contains(DownlevelFlags::COMPUTE_SHADERS);
+
+        Self {
+            depth_sampling: DEPTH_TEXTURE_SAMPLING_SUPPORTED,
+            float_filtering: float_filtering_supported(adapter),
+            max_samples,
+            compute_shaders,
+            compute_output: compute_shaders
+                && adapter
+                    .get_texture_format_features(ViewTarget::TEXTURE_FORMAT_HDR)
+                    .allowed_usages
+                    .contains(TextureUsages::STORAGE_BINDING),
+            fragment_storage: downlevel
+                .flags
+                .contains(DownlevelFlags::FRAGMENT_WRITABLE_STORAGE),
+            thick_outline: adapter
+                .get_texture_format_features(JUMP_FLOOD_TEXTURE_FORMAT)
+                .allowed_usages
+                .contains(TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING),
+        }
+    }
+
+    /// Whether the edge detection can render into the main textures of `format` on the adapter: a float color format
+    /// it can render to and sample, filtered with [`EdgeDetectionSupport::float_filtering`].
+    /// The views of the other formats, e.g. integer or compressed ones, skip the edge detection.
+    pub fn target_format_supported(&self, adapter: &RenderAdapter, format: TextureFormat) -> bool {
+        let features = adapter.get_texture_format_features(format);
+        matches!(
+            format.sample_type(None, None),
+            Some(TextureSampleType::Float { .. })
+        ) && features
+            .allowed_usages
+            .contains(TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING)
+            && (!self.float_filtering
+                || features
+                    .flags
+                    .contains(TextureFormatFeatureFlags::FILTERABLE))
+    }
+
+    /// The fallbacks in use, empty if the adapter supports everything.
+    pub fn fallbacks(&self) -> impl Iterator<Item = EdgeDetectionFallback> {
+        [
+            (!self.depth_sampling).then_some(EdgeDetectionFallback::ColorOnly),
+            (!self.float_filtering).then_some(EdgeDetectionFallback::NearestSampling),
+            (!self.fragment_storage).then_some(EdgeDetectionFallback::NoFragmentStorage),
+            (!self.thick_outline).then_some(EdgeDetectionFallback::NoThickOutline),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+/// Whether the edges are drawn in this app, and why not, in the main world.
+///
+/// E.g. a settings menu can gray out its outline toggle when the edge detection can't run.
+/// See [`EdgeDetectionSupport`] for the fallbacks of the platform once it runs.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Default, Debug, PartialEq)]
+pub enum EdgeDetectionStatus {
+    /// The app has no renderer, e.g. a headless app without wgpu backends, so nothing is drawn.
+    #[default]
+    NoRenderer,
+    /// No edge detection pipeline is compiled yet, because no camera has an [`EdgeDetection`](crate::EdgeDetection),
+    /// or its shaders are still loading.
+    Pending,
+    /// The pipeline of a camera failed to compile, with the error logged by the pipeline cache.
+    Failed,
+    /// The edges are drawn. Without `depth_sampling`, only the color-based sources draw them,
+    /// see [`EdgeDetectionSupport::depth_sampling`].
+    ///
+    /// The compiled pipelines are kept, so it stays active when the cameras stop detecting edges.
+    Active { depth_sampling: bool },
+}
+
+impl EdgeDetectionStatus {
+    /// Whether the edges are drawn.
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Active { .. })
+    }
+}
+
+/// The [`EdgeDetectionStatus`] written by the render world, and copied to the main world at the start of each frame.
+#[derive(Resource, Clone, Default)]
+struct SharedEdgeDetectionStatus(Arc<Mutex<EdgeDetectionStatus>>);
+
+/// Updates the [`EdgeDetectionStatus`] from the pipelines of the views, once they're processed.
+fn update_edge_detection_status(
+    views: Query<&EdgeDetectionPipelineId>,
+    pipeline_cache: Res<PipelineCache>,
+    support: Res<EdgeDetectionSupport>,
+    shared: Res<SharedEdgeDetectionStatus>,
+) {
+    let mut failed = false;
+    for pipeline_id in views.iter() {
+        match pipeline_cache.get_render_pipeline_state(pipeline_id.id) {
+            CachedPipelineState::Ok(_) => {
+                *shared.0.lock().unwrap() = EdgeDetectionStatus::Active {
+                    depth_sampling: support.depth_sampling,
+                };
+                return;
+            }
+            CachedPipelineState::Err(
+                PipelineCacheError::ShaderNotLoaded(_)
+                | PipelineCacheError::ShaderImportNotYetAvailable,
+            ) => {}
+            CachedPipelineState::Err(_) => failed = true,
+            _ => {}
+        }
+    }
+
+    if failed {
+        *shared.0.lock().unwrap() = EdgeDetectionStatus::Failed;
+    }
+}
+
+fn sync_edge_detection_status(
+    shared: Res<SharedEdgeDetectionStatus>,
+    mut status: ResMut<EdgeDetectionStatus>,
+) {
+    status.set_if_neq(*shared.0.lock().unwrap());
+}
+
+/// The formats the edge detection samples through its filtering sampler: the HDR color attachment, the depth,
+/// the prepass textures and the edge mask.
+const FILTERED_FORMATS: [TextureFormat; 5] = [
+    ViewTarget::TEXTURE_FORMAT_HDR,
+    CORE_3D_DEPTH_FORMAT,
+    NORMAL_PREPASS_FORMAT,
+    MOTION_VECTOR_PREPASS_FORMAT,
+    EDGE_MASK_TEXTURE_FORMAT,
+];
+
+/// Whether the filtering of `format` depends on the adapter. Core WebGPU filters all the float formats but the
+/// 32-bit ones, the depth included, which need [`WgpuFeatures::FLOAT32_FILTERABLE`].
+pub(crate) fn needs_float32_filtering(format: TextureFormat) -> bool {
+    matches!(
+        format,
+        TextureFormat::Depth32Float | TextureFormat::Depth32FloatStencil8
+    ) || format.sample_type(None, None) == Some(TextureSampleType::Float { filterable: false })
+}
+
+/// Returns whether all the `formats` can be filtered with the `features` of the adapter.
+pub(crate) fn formats_filterable(
+    formats: impl IntoIterator<Item = TextureFormat>,
+    features: WgpuFeatures,
+) -> bool {
+    features.contains(WgpuFeatures::FLOAT32_FILTERABLE)
+        || !formats.into_iter().any(needs_float32_filtering)
+}
+
+/// Returns whether the float textures sampled by the edge detection can be filtered on the adapter,
+/// see [`FILTERED_FORMATS`]. Some WebGPU, downlevel and mobile configurations can't filter the 32-bit depth,
+/// and fail to create the pipelines filtering it.
+fn float_filtering_supported(adapter: &RenderAdapter) -> bool {
+    if std::env::var_os(NON_FILTERABLE_ENV_VAR).is_some() {
+        info!("Edge detection float texture filtering disabled by {NON_FILTERABLE_ENV_VAR}");
+        return false;
+    }
+
+    let supported = formats_filterable(FILTERED_FORMATS, adapter.features());
+
+    if !supported {
+        info!(
+            "Edge detection float texture filtering isn't supported by the adapter, sampling the nearest texels instead"
+        );
+    }
+    supported
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.register_type::<EdgeDetectionSupport>()
+        .register_type::<EdgeDetectionStatus>()
+        .init_resource::<EdgeDetectionStatus>();
+}
+
+/// Inserts the [`EdgeDetectionSupport`] in the render world, before the pipelines reading it are created,
+/// and copies it to the main world. Sets up the [`EdgeDetectionStatus`], left as
+/// [`EdgeDetectionStatus::NoRenderer`] without a render world.
+pub(crate) fn finish(app: &mut App) {
+    let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+
+    let shared = SharedEdgeDetectionStatus(Arc::new(Mutex::new(EdgeDetectionStatus::Pending)));
+    render_app.insert_resource(shared.clone()).add_systems(
+        Render,
+        update_edge_detection_status.in_set(RenderSet::Cleanup),
+    );
+
+    let support = EdgeDetectionSupport::new(render_app.world());
+    if !support.depth_sampling {
+        info!(
+            "Edge detection only draws the color edges on this platform because depth textures aren't supported correctly"
+        );
+    }
+    render_app.insert_resource(support.clone());
+    app.insert_resource(support)
+        .insert_resource(EdgeDetectionStatus::Pending)
+        .insert_resource(shared)
+        .add_systems(First, sync_edge_detection_status);
+}
@@ -0,0 +1,31 @@
+use bevy::{
+    prelude::*,
+    render::{camera::TemporalJitter, view::ExtractedView},
+};
+
+use crate::{EdgeDetectionPipelineId, EdgeDetectionPlacement, EdgeDetectionUniform};
+
+/// Sets the [`EdgeDetectionUniform::jitter`] of the views with a [`TemporalJitter`], once the TAA has jittered
+/// their projection for this frame.
+///
+/// The prepass textures are rendered with the jittered projection, while the edges drawn after the TAA resolve
+/// are composited over its unjittered output, so they're detected at the unjittered positions. The edges drawn
+/// with [`EdgeDetectionPlacement::BeforeTaa`] are resolved along with the rest of the frame, and keep the jitter.
+pub fn prepare_edge_detection_jitter(
+    mut views: Query<(
+        &ExtractedView,
+        &EdgeDetectionPipelineId,
+        &mut EdgeDetectionUniform,
+        Option<&TemporalJitter>,
+    )>,
+) {
+    for (view, pipeline_id, mut uniform, temporal_jitter) in views.iter_mut() {
+        // The orthographic projections aren't jittered, see `TemporalJitter::jitter_projection`.
+        let jittered = view.clip_from_view.w_axis.w != 1.0
+            && pipeline_id.placement != EdgeDetectionPlacement::BeforeTaa;
+
+        uniform.jitter = temporal_jitter
+            .filter(|_| jittered)
+            .map_or(Vec2::ZERO, |temporal_jitter| temporal_jitter.offset);
+    }
+}
@@ -0,0 +1,307 @@
+use bevy::{
+    asset::weak_handle,
+    core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    prelude::*,
+    render::{
+        diagnostic::RecordDiagnostics,
+        render_resource::{
+            binding_types::{
+                texture_2d, texture_2d_multisampled, texture_depth_2d,
+                texture_depth_2d_multisampled, uniform_buffer,
+            },
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        texture::CachedTexture,
+        view::ViewTarget,
+    },
+};
+
+use crate::{
+    EdgeDetection, EdgeDetectionPipelineId, EdgeDetectionSupport, EdgeDetectionUniform,
+    EDGE_MASK_TEXTURE_FORMAT,
+};
+
+pub const EDGE_TEMPORAL_SHADER_HANDLE: Handle<Shader> =
+    weak_handle!("4a7d2e90-6b1c-4f38-8e53-c0f9a1b6d274");
+
+/// The format of the depths kept in the [`EdgeDetectionTemporalHistory`], to reject the history on disocclusion.
+pub const EDGE_TEMPORAL_DEPTH_FORMAT: TextureFormat = TextureFormat::R32Float;
+
+/// The largest [`EdgeDetection::temporal_blend`], so the history still converges to the edges of the scene.
+pub const MAX_TEMPORAL_BLEND: f32 = 0.95;
+
+/// The history of a view with [`EdgeDetection::temporal_blend`]: the stabilized edge masks and the depths
+/// of the last two frames. The textures of the current frame are written from the ones of the previous frame,
+/// and they're swapped every frame.
+///
+/// The textures aren't taken from the texture cache, so they keep their content between frames.
+#[derive(Component)]
+pub struct EdgeDetectionTemporalHistory {
+    pub edges: [CachedTexture; 2],
+    pub depths: [CachedTexture; 2],
+    /// The index of the textures written this frame.
+    pub current: usize,
+}
+
+/// Creates the history of the views with [`EdgeDetection::temporal_blend`], and swaps it every frame.
+/// The history is ignored on the frames it's (re)created, e.g. on the first frame or on resize,
+/// or reset with [`EdgeDetection::reset_history`].
+#[allow(clippy::type_complexity)]
+pub fn prepare_edge_detection_temporal_history(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    mut views: Query<(
+        Entity,
+        &ViewTarget,
+        &EdgeDetection,
+        &EdgeDetectionPipelineId,
+        &mut EdgeDetectionUniform,
+        Option<&mut EdgeDetectionTemporalHistory>,
+    )>,
+) {
+    for (entity, view_target, edge_detection, pipeline_id, mut uniform, history) in views.iter_mut()
+    {
+        if !pipeline_id.key.temporal {
+            if history.is_some() {
+                commands
+                    .entity(entity)
+                    .remove::<EdgeDetectionTemporalHistory>();
+            }
+            continue;
+        }
+
+        let size = pipeline_id.mask_size(view_target);
+        let history = history.filter(|history| history.edges[0].texture.size() == size);
+
+        if let Some(mut history) = history {
+            history.current ^= 1;
+            if edge_detection.reset_history {
+                uniform.temporal_blend = 0.0;
+            }
+            continue;
+        }
+
+        let texture = |label, format| {
+            let texture = render_device.create_texture(&TextureDescriptor {
+                label: Some(label),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+            let default_view = texture.create_view(&TextureViewDescriptor::default());
+            CachedTexture {
+                texture,
+                default_view,
+            }
+        };
+
+        // The new textures hold nothing yet, the edges of this frame are kept as is.
+        uniform.temporal_blend = 0.0;
+        commands
+            .entity(entity)
+            .insert(EdgeDetectionTemporalHistory {
+                edges: [
+                    texture(
+                        "edge_detection_temporal_history_a",
+                        EDGE_MASK_TEXTURE_FORMAT,
+                    ),
+                    texture(
+                        "edge_detection_temporal_history_b",
+                        EDGE_MASK_TEXTURE_FORMAT,
+                    ),
+                ],
+                depths: [
+                    texture(
+                        "edge_detection_temporal_depth_a",
+                        EDGE_TEMPORAL_DEPTH_FORMAT,
+                    ),
+                    texture(
+                        "edge_detection_temporal_depth_b",
+                        EDGE_TEMPORAL_DEPTH_FORMAT,
+                    ),
+                ],
+                current: 0,
+            });
+    }
+}
+
+/// The pipelines blending the edge mask with its reprojected history. They aren't specialized,
+/// there's one for the single sampled prepass textures and one for the multisampled ones.
+#[derive(Resource)]
+pub struct EdgeTemporalPipeline {
+    /// The bind group layouts, indexed by whether the prepass textures are multisampled.
+    pub layouts: [BindGroupLayout; 2],
+    /// The pipelines, indexed by whether the prepass textures are multisampled.
+    pub pipelines: [CachedRenderPipelineId; 2],
+}
+
+impl FromWorld for EdgeTemporalPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let filterable = world.resource::<EdgeDetectionSupport>().float_filtering;
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = |multisampled: bool| {
+            // Without float filtering, the depth is loaded as a float, like in the edge detection pass.
+            let (depth, motion_vectors) = match multisampled {
+                true => (
+                    match filterable {
+                        true => texture_depth_2d_multisampled(),
+                        false => {
+                            texture_2d_multisampled(TextureSampleType::Float { filterable: false })
+                        }
+                    },
+                    texture_2d_multisampled(TextureSampleType::Float { filterable: false }),
+                ),
+                false => (
+                    match filterable {
+                        true => texture_depth_2d(),
+                        false => texture_2d(TextureSampleType::Float { filterable: false }),
+                    },
+                    texture_2d(TextureSampleType::Float { filterable: false }),
+                ),
+            };
+
+            render_device.create_bind_group_layout(
+                "edge_detection: temporal_bind_group_layout",
+                &BindGroupLayoutEntries::sequential(
+                    ShaderStages::FRAGMENT,
+                    (
+                        // edge mask
+                        texture_2d(TextureSampleType::Float { filterable: false }),
+                        // edge mask of the previous frame
+                        texture_2d(TextureSampleType::Float { filterable: false }),
+                        // depth of the previous frame
+                        texture_2d(TextureSampleType::Float { filterable: false }),
+                        // depth
+                        depth,
+                        // motion vectors
+                        motion_vectors,
+                        // The uniform holding the blend
+                        uniform_buffer::<EdgeDetectionUniform>(true),
+                    ),
+                ),
+            )
+        };
+        let layouts = [layout(false), layout(true)];
+
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let pipeline = |multisampled: bool| {
+            let mut shader_defs = vec![];
+            if multisampled {
+                shader_defs.push("MULTISAMPLED".into());
+            }
+            if !filterable {
+                shader_defs.push("NON_FILTERABLE".into());
+            }
+
+            pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
+                label: Some("edge_detection: temporal_pipeline".into()),
+                layout: vec![layouts[multisampled as usize].clone()],
+                vertex: fullscreen_shader_vertex_state(),
+                fragment: Some(FragmentState {
+                    shader: EDGE_TEMPORAL_SHADER_HANDLE,
+                    shader_defs,
+                    entry_point: "fragment".into(),
+                    targets: vec![
+                        Some(ColorTargetState {
+                            format: EDGE_MASK_TEXTURE_FORMAT,
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        }),
+                        Some(ColorTargetState {
+                            format: EDGE_TEMPORAL_DEPTH_FORMAT,
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        }),
+                    ],
+                }),
+                primitive: default(),
+                depth_stencil: None,
+                multisample: default(),
+                push_constant_ranges: vec![],
+                zero_initialize_workgroup_memory: false,
+            })
+        };
+        let pipelines = [pipeline(false), pipeline(true)];
+
+        Self { layouts, pipelines }
+    }
+}
+
+impl EdgeTemporalPipeline {
+    /// Returns the compiled pipeline for the prepass textures of a view, or `None` if it isn't ready to run yet.
+    pub fn get_pipeline<'w>(
+        &self,
+        pipeline_cache: &'w PipelineCache,
+        multisampled: bool,
+    ) -> Option<&'w RenderPipeline> {
+        pipeline_cache.get_render_pipeline(self.pipelines[multisampled as usize])
+    }
+
+    /// Blends the edge mask with the history reprojected along the motion vectors, into the
+    /// current textures of the `history`, with the pipeline from [`Self::get_pipeline`],
+    /// and returns the view of the blended edges.
+    #[allow(clippy::too_many_arguments)]
+    pub fn run<'a>(
+        &self,
+        render_context: &mut RenderContext,
+        pipeline: &RenderPipeline,
+        mask_view: &TextureView,
+        depth: &TextureView,
+        motion_vectors: &TextureView,
+        ed_uniforms: BindingResource,
+        uniform_offset: u32,
+        history: &'a EdgeDetectionTemporalHistory,
+        multisampled: bool,
+    ) -> &'a TextureView {
+        let index = multisampled as usize;
+
+        let (current, previous) = (history.current, history.current ^ 1);
+        let bind_group = render_context.render_device().create_bind_group(
+            "edge_detection_temporal_bind_group",
+            &self.layouts[index],
+            &BindGroupEntries::sequential((
+                mask_view,
+                &history.edges[previous].default_view,
+                &history.depths[previous].default_view,
+                depth,
+                motion_vectors,
+                ed_uniforms,
+            )),
+        );
+
+        let output_view = &history.edges[current].default_view;
+        let diagnostics = render_context.diagnostic_recorder();
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("edge_detection_temporal_pass"),
+            color_attachments: &[
+                Some(RenderPassColorAttachment {
+                    view: output_view,
+                    resolve_target: None,
+                    ops: Operations::default(),
+                }),
+                Some(RenderPassColorAttachment {
+                    view: &history.depths[current].default_view,
+                    resolve_target: None,
+                    ops: Operations::default(),
+                }),
+            ],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        let pass_span = diagnostics.pass_span(&mut render_pass, "temporal");
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[uniform_offset]);
+        render_pass.draw(0..3, 0..1);
+        pass_span.end(&mut render_pass);
+
+        output_view
+    }
+}
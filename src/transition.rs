@@ -0,0 +1,426 @@
+use std::time::Duration;
+
+use bevy::{math::bounding::Aabb3d, prelude::*};
+
+use crate::{
+    AdjacentGrading, EdgeColorMode, EdgeDetection, EdgeDetectionSet, EdgeRegionOverride,
+    EdgeRegionSettings, HatchingSettings, OutputMode, PosterizeSettings, RevealMode,
+    RevealSettings, ScanSettings, ThickOutline,
+};
+
+impl EdgeDetection {
+    /// Interpolates the settings toward `other`, from `self` at `t` = 0.0 to `other` at `t` = 1.0.
+    ///
+    /// The numbers and vectors are interpolated linearly, and the colors in the perceptual Oklab space.
+    /// The features enabled on both sides, e.g. a [`ThickOutline`] or [`OutputMode::Blueprint`], interpolate their
+    /// settings too. Everything else switches over at `t` = 0.5: the booleans, the counts, the features enabled
+    /// on one side only and the textures, so the shaders are only specialized again once, halfway.
+    pub fn lerp(&self, other: &EdgeDetection, t: f32) -> EdgeDetection {
+        let lerp = |a: f32, b: f32| a.lerp(b, t);
+        let lerp_vec2 = |a: Vec2, b: Vec2| a.lerp(b, t);
+        let mix = |a: Color, b: Color| mix_color(a, b, t);
+
+        EdgeDetection {
+            depth_threshold: lerp(self.depth_threshold, other.depth_threshold),
+            normalized_depth_threshold: snap(
+                &self.normalized_depth_threshold,
+                &other.normalized_depth_threshold,
+                t,
+            ),
+            normal_threshold: lerp(self.normal_threshold, other.normal_threshold),
+            normal_threshold_concave: lerp_option(
+                &self.normal_threshold_concave,
+                &other.normal_threshold_concave,
+                t,
+                |a, b| lerp(*a, *b),
+            ),
+            normal_threshold_convex: lerp_option(
+                &self.normal_threshold_convex,
+                &other.normal_threshold_convex,
+                t,
+                |a, b| lerp(*a, *b),
+            ),
+            color_threshold: lerp(self.color_threshold, other.color_threshold),
+            material_threshold: lerp(self.material_threshold, other.material_threshold),
+            shadow_threshold: lerp(self.shadow_threshold, other.shadow_threshold),
+            depth_threshold_softness: lerp(
+                self.depth_threshold_softness,
+                other.depth_threshold_softness,
+            ),
+            normal_threshold_softness: lerp(
+                self.normal_threshold_softness,
+                other.normal_threshold_softness,
+            ),
+            color_threshold_softness: lerp(
+                self.color_threshold_softness,
+                other.color_threshold_softness,
+            ),
+
+            depth_thickness: lerp(self.depth_thickness, other.depth_thickness),
+            normal_thickness: lerp(self.normal_thickness, other.normal_thickness),
+            color_thickness: lerp(self.color_thickness, other.color_thickness),
+            stencil_thickness: lerp(self.stencil_thickness, other.stencil_thickness),
+            material_thickness: lerp(self.material_thickness, other.material_thickness),
+            shadow_thickness: lerp(self.shadow_thickness, other.shadow_thickness),
+            kernel_radius: snap(&self.kernel_radius, &other.kernel_radius, t),
+            units: snap(&self.units, &other.units, t),
+
+            fov_compensation: snap(&self.fov_compensation, &other.fov_compensation, t),
+            reference_fov: lerp(self.reference_fov, other.reference_fov),
+
+            steep_angle_threshold: lerp(self.steep_angle_threshold, other.steep_angle_threshold),
+            steep_angle_multiplier: lerp(self.steep_angle_multiplier, other.steep_angle_multiplier),
+            one_sided_depth_edges: snap(
+                &self.one_sided_depth_edges,
+                &other.one_sided_depth_edges,
+                t,
+            ),
+            background_edges: snap(&self.background_edges, &other.background_edges, t),
+            slope_thickness_boost: lerp(self.slope_thickness_boost, other.slope_thickness_boost),
+            thickness_attenuation: lerp(self.thickness_attenuation, other.thickness_attenuation),
+            thickness_reference_distance: lerp(
+                self.thickness_reference_distance,
+                other.thickness_reference_distance,
+            ),
+            corner_emphasis: lerp(self.corner_emphasis, other.corner_emphasis),
+            ao_influence: lerp(self.ao_influence, other.ao_influence),
+
+            operator: snap(&self.operator, &other.operator, t),
+
+            depth_source: snap(&self.depth_source, &other.depth_source, t),
+            normal_source: snap(&self.normal_source, &other.normal_source, t),
+
+            color_filter: snap(&self.color_filter, &other.color_filter, t),
+            color_space: snap(&self.color_space, &other.color_space, t),
+
+            all_samples: snap(&self.all_samples, &other.all_samples, t),
+
+            motion_emphasis: lerp(self.motion_emphasis, other.motion_emphasis),
+            motion_smear_strength: lerp(self.motion_smear_strength, other.motion_smear_strength),
+
+            corner_rounding: lerp(self.corner_rounding, other.corner_rounding),
+            dilation: snap(&self.dilation, &other.dilation, t),
+            edge_aa: snap(&self.edge_aa, &other.edge_aa, t),
+
+            checkerboard: snap(&self.checkerboard, &other.checkerboard, t),
+            half_rate: snap(&self.half_rate, &other.half_rate, t),
+            temporal_blend: lerp(self.temporal_blend, other.temporal_blend),
+            reset_history: snap(&self.reset_history, &other.reset_history, t),
+            resolution_scale: lerp(self.resolution_scale, other.resolution_scale),
+
+            thick_outline: lerp_option(&self.thick_outline, &other.thick_outline, t, |a, b| {
+                ThickOutline {
+                    radius_px: lerp(a.radius_px, b.radius_px),
+                    color: mix(a.color, b.color),
+                }
+            }),
+
+            uv_distortion_frequency: lerp_vec2(
+                self.uv_distortion_frequency,
+                other.uv_distortion_frequency,
+            ),
+            uv_distortion_strength: lerp_vec2(
+                self.uv_distortion_strength,
+                other.uv_distortion_strength,
+            ),
+            uv_distortion_speed: lerp_vec2(self.uv_distortion_speed, other.uv_distortion_speed),
+            uv_distortion_fps: lerp_option(
+                &self.uv_distortion_fps,
+                &other.uv_distortion_fps,
+                t,
+                |a, b| lerp(*a, *b),
+            ),
+
+            noise_texture: snap(&self.noise_texture, &other.noise_texture, t),
+            noise_uv_scale: lerp_vec2(self.noise_uv_scale, other.noise_uv_scale),
+            noise_rotation: lerp(self.noise_rotation, other.noise_rotation),
+            boil_fps: lerp(self.boil_fps, other.boil_fps),
+
+            distortion_octaves: snap(&self.distortion_octaves, &other.distortion_octaves, t),
+            distortion_lacunarity: lerp(self.distortion_lacunarity, other.distortion_lacunarity),
+            distortion_gain: lerp(self.distortion_gain, other.distortion_gain),
+            noise_lod_bias: lerp(self.noise_lod_bias, other.noise_lod_bias),
+
+            strokes: snap(&self.strokes, &other.strokes, t),
+            stroke_scatter: lerp(self.stroke_scatter, other.stroke_scatter),
+            hatching: lerp_option(&self.hatching, &other.hatching, t, |a, b| {
+                HatchingSettings {
+                    scale: lerp(a.scale, b.scale),
+                    coverage: lerp(a.coverage, b.coverage),
+                }
+            }),
+
+            aspect_corrected: snap(&self.aspect_corrected, &other.aspect_corrected, t),
+
+            exclusion_rects: lerp_vec(&self.exclusion_rects, &other.exclusion_rects, t, |a, b| {
+                lerp_rect(*a, *b, t)
+            }),
+            exclusion_feather: lerp(self.exclusion_feather, other.exclusion_feather),
+            region_overrides: lerp_vec(
+                &self.region_overrides,
+                &other.region_overrides,
+                t,
+                |a, b| EdgeRegionOverride {
+                    rect: lerp_rect(a.rect, b.rect, t),
+                    settings: EdgeRegionSettings {
+                        depth_threshold: lerp(
+                            a.settings.depth_threshold,
+                            b.settings.depth_threshold,
+                        ),
+                        normal_threshold: lerp(
+                            a.settings.normal_threshold,
+                            b.settings.normal_threshold,
+                        ),
+                        color_threshold: lerp(
+                            a.settings.color_threshold,
+                            b.settings.color_threshold,
+                        ),
+                        edge_color: mix(a.settings.edge_color, b.settings.edge_color),
+                    },
+                },
+            ),
+
+            scan: lerp_option(&self.scan, &other.scan, t, |a, b| ScanSettings {
+                center: a.center.lerp(b.center, t),
+                radius: lerp(a.radius, b.radius),
+                edge_falloff: lerp(a.edge_falloff, b.edge_falloff),
+            }),
+
+            reveal: lerp_option(&self.reveal, &other.reveal, t, |a, b| RevealSettings {
+                progress: lerp(a.progress, b.progress),
+                mode: match (a.mode, b.mode) {
+                    (RevealMode::Radial { origin: a }, RevealMode::Radial { origin: b }) => {
+                        RevealMode::Radial {
+                            origin: lerp_vec2(a, b),
+                        }
+                    }
+                    (a, b) => snap(&a, &b, t),
+                },
+                softness: lerp(a.softness, b.softness),
+            }),
+
+            mask_volume: lerp_option(&self.mask_volume, &other.mask_volume, t, |a, b| Aabb3d {
+                min: a.min.lerp(b.min, t),
+                max: a.max.lerp(b.max, t),
+            }),
+            mask_volume_margin: lerp(self.mask_volume_margin, other.mask_volume_margin),
+
+            fade_start: lerp(self.fade_start, other.fade_start),
+            fade_end: lerp(self.fade_end, other.fade_end),
+
+            depth_range_min: lerp(self.depth_range_min, other.depth_range_min),
+            depth_range_max: lerp_distance(self.depth_range_max, other.depth_range_max, t),
+            depth_range_feather: lerp(self.depth_range_feather, other.depth_range_feather),
+
+            posterize: lerp_option(&self.posterize, &other.posterize, t, |a, b| {
+                PosterizeSettings {
+                    levels: snap(&a.levels, &b.levels, t),
+                    preserve_hue: snap(&a.preserve_hue, &b.preserve_hue, t),
+                }
+            }),
+            edge_adjacent_grading: lerp_option(
+                &self.edge_adjacent_grading,
+                &other.edge_adjacent_grading,
+                t,
+                |a, b| AdjacentGrading {
+                    width_px: lerp(a.width_px, b.width_px),
+                    desaturate: lerp(a.desaturate, b.desaturate),
+                    lighten: lerp(a.lighten, b.lighten),
+                },
+            ),
+
+            write_alpha_mask: snap(&self.write_alpha_mask, &other.write_alpha_mask, t),
+            preserve_scene_alpha: snap(&self.preserve_scene_alpha, &other.preserve_scene_alpha, t),
+
+            placement: snap(&self.placement, &other.placement, t),
+
+            edge_color: mix(self.edge_color, other.edge_color),
+            depth_edge_color: lerp_option(
+                &self.depth_edge_color,
+                &other.depth_edge_color,
+                t,
+                |a, b| mix(*a, *b),
+            ),
+            normal_edge_color: lerp_option(
+                &self.normal_edge_color,
+                &other.normal_edge_color,
+                t,
+                |a, b| mix(*a, *b),
+            ),
+            color_edge_color: lerp_option(
+                &self.color_edge_color,
+                &other.color_edge_color,
+                t,
+                |a, b| mix(*a, *b),
+            ),
+            edge_color_far: lerp_option(&self.edge_color_far, &other.edge_color_far, t, |a, b| {
+                mix(*a, *b)
+            }),
+            color_gradient_start: lerp(self.color_gradient_start, other.color_gradient_start),
+            color_gradient_end: lerp(self.color_gradient_end, other.color_gradient_end),
+            edge_emissive_strength: lerp(self.edge_emissive_strength, other.edge_emissive_strength),
+            edge_color_mode: match (self.edge_color_mode, other.edge_color_mode) {
+                (
+                    EdgeColorMode::FromObject { darken, saturate },
+                    EdgeColorMode::FromObject {
+                        darken: other_darken,
+                        saturate: other_saturate,
+                    },
+                ) => EdgeColorMode::FromObject {
+                    darken: lerp(darken, other_darken),
+                    saturate: lerp(saturate, other_saturate),
+                },
+                (a, b) => snap(&a, &b, t),
+            },
+            output_mode: match (self.output_mode, other.output_mode) {
+                (
+                    OutputMode::Sharpen { amount },
+                    OutputMode::Sharpen {
+                        amount: other_amount,
+                    },
+                ) => OutputMode::Sharpen {
+                    amount: lerp(amount, other_amount),
+                },
+                (
+                    OutputMode::Blueprint { background },
+                    OutputMode::Blueprint {
+                        background: other_background,
+                    },
+                ) => OutputMode::Blueprint {
+                    background: mix(background, other_background),
+                },
+                (a, b) => snap(&a, &b, t),
+            },
+            debug_view: snap(&self.debug_view, &other.debug_view, t),
+            blend_mode: snap(&self.blend_mode, &other.blend_mode, t),
+            intensity: lerp(self.intensity, other.intensity),
+            stencil_edge_color: mix(self.stencil_edge_color, other.stencil_edge_color),
+            shadow_edge_color: mix(self.shadow_edge_color, other.shadow_edge_color),
+
+            enable_depth: snap(&self.enable_depth, &other.enable_depth, t),
+            enable_normal: snap(&self.enable_normal, &other.enable_normal, t),
+            geometric_normals_only: snap(
+                &self.geometric_normals_only,
+                &other.geometric_normals_only,
+                t,
+            ),
+            enable_color: snap(&self.enable_color, &other.enable_color, t),
+            enable_stencil: snap(&self.enable_stencil, &other.enable_stencil, t),
+            enable_material: snap(&self.enable_material, &other.enable_material, t),
+            material_attributes: snap(&self.material_attributes, &other.material_attributes, t),
+            shadow_mask: snap(&self.shadow_mask, &other.shadow_mask, t),
+            screen_mask: snap(&self.screen_mask, &other.screen_mask, t),
+        }
+    }
+}
+
+/// Returns `a` before `t` = 0.5, `b` after.
+fn snap<T: Clone>(a: &T, b: &T, t: f32) -> T {
+    if t < 0.5 {
+        a.clone()
+    } else {
+        b.clone()
+    }
+}
+
+/// Interpolates two distances, snapping if either is infinite, which doesn't interpolate.
+fn lerp_distance(a: f32, b: f32, t: f32) -> f32 {
+    match a.is_finite() && b.is_finite() {
+        true => a.lerp(b, t),
+        false => snap(&a, &b, t),
+    }
+}
+
+/// Interpolates the values set on both sides with `lerp`, and snaps the others.
+fn lerp_option<T: Clone>(
+    a: &Option<T>,
+    b: &Option<T>,
+    t: f32,
+    lerp: impl Fn(&T, &T) -> T,
+) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(lerp(a, b)),
+        _ => snap(a, b, t),
+    }
+}
+
+/// Interpolates the items pairwise with `lerp` if there are as many on both sides, and snaps them otherwise.
+fn lerp_vec<T: Clone>(a: &[T], b: &[T], t: f32, lerp: impl Fn(&T, &T) -> T) -> Vec<T> {
+    if a.len() != b.len() {
+        return snap(&a, &b, t).to_vec();
+    }
+    a.iter().zip(b).map(|(a, b)| lerp(a, b)).collect()
+}
+
+fn lerp_rect(a: Rect, b: Rect, t: f32) -> Rect {
+    Rect {
+        min: a.min.lerp(b.min, t),
+        max: a.max.lerp(b.max, t),
+    }
+}
+
+/// Mixes the colors in the Oklab space, where the steps of the mix look even.
+fn mix_color(a: Color, b: Color, t: f32) -> Color {
+    Oklaba::from(a).mix(&Oklaba::from(b), t).into()
+}
+
+/// Tweens the [`EdgeDetection`] of a camera from `from` to `to`, e.g. from subtle to dramatic edges over half
+/// a second, see [`EdgeDetection::lerp`]. The component removes itself once the camera has the settings of `to`.
+///
+/// The settings are written to the `EdgeDetection` of the camera every frame. The shaders are only specialized
+/// again if some feature is enabled on one side only, halfway through.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct EdgeDetectionTransition {
+    /// The settings at the start of the transition, e.g. a clone of the current ones of the camera.
+    pub from: EdgeDetection,
+    /// The settings at the end of the transition.
+    pub to: EdgeDetection,
+    /// How long the transition takes.
+    pub duration: Duration,
+    /// The easing of the transition, from 0.0 at the start to 1.0 once it's over.
+    pub curve: EaseFunction,
+    /// The time elapsed since the transition started.
+    pub elapsed: Duration,
+}
+
+impl EdgeDetectionTransition {
+    /// A transition from `from` to `to` over `duration`, with a cubic ease in and out.
+    pub fn new(from: EdgeDetection, to: EdgeDetection, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            curve: EaseFunction::CubicInOut,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+pub(crate) fn build(app: &mut App) {
+    app.register_type::<EdgeDetectionTransition>().add_systems(
+        Update,
+        advance_edge_detection_transitions.in_set(EdgeDetectionSet::Configure),
+    );
+}
+
+/// Advances the [`EdgeDetectionTransition`]s, writing the interpolated settings to the cameras,
+/// and removes them once they're over.
+pub fn advance_edge_detection_transitions(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut EdgeDetectionTransition, &mut EdgeDetection)>,
+    time: Res<Time>,
+) {
+    for (entity, mut transition, mut edge_detection) in query.iter_mut() {
+        transition.elapsed += time.delta();
+        if transition.elapsed >= transition.duration {
+            *edge_detection = transition.to.clone();
+            commands.entity(entity).remove::<EdgeDetectionTransition>();
+            continue;
+        }
+
+        let progress = transition.elapsed.as_secs_f32() / transition.duration.as_secs_f32();
+        let t = transition.curve.sample_clamped(progress);
+        *edge_detection = transition.from.lerp(&transition.to, t);
+    }
+}